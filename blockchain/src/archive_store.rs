@@ -0,0 +1,266 @@
+use std::{borrow::Cow, collections::BTreeSet};
+
+use nimiq_account::{Accounts, DataStoreReadOps};
+use nimiq_database::{
+    declare_table,
+    mdbx::{MdbxDatabase, MdbxReadTransaction, MdbxWriteTransaction},
+    traits::{Database, ReadCursor, ReadTransaction, WriteTransaction},
+};
+use nimiq_database_value::{AsDatabaseBytes, FromDatabaseBytes};
+use nimiq_database_value_derive::DbSerializable;
+use nimiq_keys::Address;
+use nimiq_primitives::{key_nibbles::KeyNibbles, trie::trie_diff::TrieDiff};
+use nimiq_serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// (`KeyNibbles`, `u32` (block number)) -> value of the key before the block was applied
+declare_table!(AccountsArchiveTable, "AccountsArchive", ArchiveKey => ArchivedValue);
+// `()` -> first block number whose accounts state can be reconstructed
+declare_table!(ArchiveStartTable, "AccountsArchiveStart", () => u32);
+// `()` -> last block number whose changes were recorded
+declare_table!(ArchiveHeadTable, "AccountsArchiveHead", () => u32);
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ArchiveError {
+    #[error("The accounts archive is not enabled")]
+    Disabled,
+    #[error("The accounts state at block {0} is not archived")]
+    NotArchived(u32),
+    #[error("Block {0} is beyond the head of the chain")]
+    FutureBlock(u32),
+}
+
+/// The key of an archived value. It is encoded as the serialized trie key followed by the block
+/// number in big endian, such that all changes of a trie key are stored consecutively and ordered
+/// by block number.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ArchiveKey {
+    key: KeyNibbles,
+    block_number: u32,
+}
+
+impl AsDatabaseBytes for ArchiveKey {
+    fn as_key_bytes(&self) -> Cow<[u8]> {
+        let bytes = [
+            &self.key.serialize_to_vec()[..],
+            &self.block_number.to_be_bytes()[..],
+        ]
+        .concat();
+        Cow::Owned(bytes)
+    }
+}
+
+impl FromDatabaseBytes for ArchiveKey {
+    fn from_key_bytes(bytes: &[u8]) -> Self
+    where
+        Self: Sized,
+    {
+        let (key, block_number) = bytes.split_at(bytes.len() - 4);
+        ArchiveKey {
+            key: KeyNibbles::deserialize_from_vec(key).unwrap(),
+            block_number: u32::from_be_bytes(block_number.try_into().unwrap()),
+        }
+    }
+}
+
+/// The value of a trie key before a block was applied. `None` if the key didn't exist.
+#[derive(Debug, Serialize, Deserialize, DbSerializable)]
+pub struct ArchivedValue(Option<Vec<u8>>);
+
+/// The archive store keeps the previous values of all accounts trie entries that were changed by
+/// a block. This allows to reconstruct the accounts state at any block since the archive was
+/// started: The value of a key after block `n` is the value recorded for the first change of the
+/// key after block `n`, or its current value if it didn't change since.
+#[derive(Debug)]
+pub struct ArchiveStore {
+    /// Database handle.
+    db: MdbxDatabase,
+    /// A database of the previous values of trie keys indexed by the key and the block number.
+    archive_table: AccountsArchiveTable,
+    /// A database of the first block whose state can be reconstructed.
+    start_table: ArchiveStartTable,
+    /// A database of the last block whose changes were recorded.
+    head_table: ArchiveHeadTable,
+}
+
+impl ArchiveStore {
+    pub fn new(db: MdbxDatabase) -> Self {
+        let store = ArchiveStore {
+            db,
+            archive_table: AccountsArchiveTable,
+            start_table: ArchiveStartTable,
+            head_table: ArchiveHeadTable,
+        };
+
+        store.db.create_regular_table(&store.archive_table);
+        store.db.create_regular_table(&store.start_table);
+        store.db.create_regular_table(&store.head_table);
+
+        store
+    }
+
+    /// Returns the first block whose accounts state can be reconstructed, if any block was
+    /// archived yet.
+    pub fn first_block_number(&self, txn: &MdbxReadTransaction) -> Option<u32> {
+        txn.get(&self.start_table, &())
+    }
+
+    /// Returns the last block whose changes were recorded.
+    fn last_block_number(&self, txn: &MdbxReadTransaction) -> Option<u32> {
+        txn.get(&self.head_table, &())
+    }
+
+    /// Records the changes of a block. The diff must contain the values of the changed keys
+    /// before the block was applied (i.e. a backward diff). If the archive wasn't started yet or
+    /// the changes of the previous block weren't recorded (e.g. because archiving was disabled in
+    /// the meantime), the archive is restarted with the state before the block.
+    pub fn put_block(&self, txn: &mut MdbxWriteTransaction, block_number: u32, diff: &TrieDiff) {
+        if self.first_block_number(txn).is_none()
+            || self.last_block_number(txn) != Some(block_number - 1)
+        {
+            self.clear(txn);
+            txn.put(&self.start_table, &(), &(block_number - 1));
+        }
+        txn.put(&self.head_table, &(), &block_number);
+
+        for (key, value) in diff.0.iter() {
+            let archive_key = ArchiveKey {
+                key: key.clone(),
+                block_number,
+            };
+            txn.put_reserve(
+                &self.archive_table,
+                &archive_key,
+                &ArchivedValue(value.clone()),
+            );
+        }
+    }
+
+    /// Removes the changes of a reverted block. The diff must contain all keys changed by the block.
+    /// If the state the archive started with is reverted as well, the archive is cleared.
+    pub fn remove_block(&self, txn: &mut MdbxWriteTransaction, block_number: u32, diff: &TrieDiff) {
+        if self.last_block_number(txn) != Some(block_number) {
+            return;
+        }
+        if self
+            .first_block_number(txn)
+            .map_or(true, |first_block_number| {
+                first_block_number >= block_number
+            })
+        {
+            self.clear(txn);
+            return;
+        }
+
+        for key in diff.0.keys() {
+            let archive_key = ArchiveKey {
+                key: key.clone(),
+                block_number,
+            };
+            txn.remove(&self.archive_table, &archive_key);
+        }
+        txn.put(&self.head_table, &(), &(block_number - 1));
+    }
+
+    /// Removes all archived changes, e.g. when the accounts trie is replaced.
+    pub fn clear(&self, txn: &mut MdbxWriteTransaction) {
+        txn.clear_table(&self.archive_table);
+        txn.clear_table(&self.start_table);
+        txn.clear_table(&self.head_table);
+    }
+
+    /// Returns the value of the given trie key after the block with the given number was applied.
+    /// The block number must be within the archived range and the accounts trie must be complete.
+    pub(crate) fn get<T: Deserialize>(
+        &self,
+        accounts: &Accounts,
+        txn: &MdbxReadTransaction,
+        key: &KeyNibbles,
+        block_number: u32,
+    ) -> Option<T> {
+        let mut cursor = txn.cursor(&self.archive_table);
+        let archive_key = ArchiveKey {
+            key: key.clone(),
+            block_number: block_number + 1,
+        };
+
+        match cursor.set_lowerbound_key(&archive_key) {
+            Some((found_key, ArchivedValue(value))) if found_key.key == *key => {
+                value.map(|value| {
+                    T::deserialize_from_vec(&value)
+                        .expect("Corrupted store: Invalid archived value")
+                })
+            }
+            _ => accounts.tree.get(txn, key).expect("Tree must be complete"),
+        }
+    }
+
+    /// Returns the trie keys within the given range (inclusive) that were changed by a block after
+    /// the block with the given number. Both keys must have the same length.
+    pub(crate) fn keys_changed_after(
+        &self,
+        txn: &MdbxReadTransaction,
+        start_key: &KeyNibbles,
+        end_key: &KeyNibbles,
+        block_number: u32,
+    ) -> BTreeSet<KeyNibbles> {
+        let mut cursor = txn.cursor(&self.archive_table);
+        let mut entry = cursor.set_lowerbound_key(&ArchiveKey {
+            key: start_key.clone(),
+            block_number: 0,
+        });
+
+        // Archive keys are ordered by the length of the trie key first, so all keys of the range
+        // are stored consecutively.
+        let mut keys = BTreeSet::new();
+        while let Some((archive_key, _)) = entry {
+            if archive_key.key.len() != start_key.len() || archive_key.key > *end_key {
+                break;
+            }
+            if archive_key.block_number > block_number {
+                keys.insert(archive_key.key);
+            }
+            entry = cursor.next();
+        }
+
+        keys
+    }
+}
+
+/// A read-only data store over the archived state of an account at a given block.
+pub struct ArchivedDataStore<'a, 'txn, 'env> {
+    archive_store: &'a ArchiveStore,
+    accounts: &'a Accounts,
+    txn: &'txn MdbxReadTransaction<'env>,
+    prefix: KeyNibbles,
+    block_number: u32,
+}
+
+impl<'a, 'txn, 'env> ArchivedDataStore<'a, 'txn, 'env> {
+    pub(crate) fn new(
+        archive_store: &'a ArchiveStore,
+        accounts: &'a Accounts,
+        txn: &'txn MdbxReadTransaction<'env>,
+        address: &Address,
+        block_number: u32,
+    ) -> Self {
+        ArchivedDataStore {
+            archive_store,
+            accounts,
+            txn,
+            prefix: KeyNibbles::from(address),
+            block_number,
+        }
+    }
+}
+
+impl<'a, 'txn, 'env> DataStoreReadOps for ArchivedDataStore<'a, 'txn, 'env> {
+    fn get<T: Deserialize>(&self, key: &KeyNibbles) -> Option<T> {
+        self.archive_store.get(
+            self.accounts,
+            self.txn,
+            &(&self.prefix + key),
+            self.block_number,
+        )
+    }
+}
@@ -0,0 +1,114 @@
+use nimiq_database::{
+    declare_table,
+    mdbx::{MdbxDatabase, MdbxReadTransaction, MdbxWriteTransaction},
+    traits::{Database, ReadCursor, ReadTransaction, WriteTransaction},
+};
+use nimiq_database_value_derive::DbSerializable;
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::compact_filter::{batch_filter_key, CompactFilter};
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_transaction::historic_transaction::{HistoricTransaction, HistoricTransactionData};
+use thiserror::Error;
+
+// `u32` (batch number) -> filter of the batch and its header
+declare_table!(BatchFilterTable, "BatchFilters", u32 => BatchFilter);
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BatchFilterError {
+    #[error("Batch filters are not enabled")]
+    Disabled,
+    #[error("There is no filter for batch {0}")]
+    MissingFilter(u32),
+}
+
+/// The compact filter over all addresses touched by the transactions and inherents of a batch,
+/// together with its filter header.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DbSerializable)]
+pub struct BatchFilter {
+    pub filter: CompactFilter,
+    /// The header of the filter, which commits to the filters of all previous batches.
+    pub header: Blake2bHash,
+}
+
+/// Returns the addresses a historic transaction is included in the filter for. These are the
+/// same addresses the history store index uses.
+pub fn batch_filter_addresses(hist_tx: &HistoricTransaction) -> Vec<&Address> {
+    match &hist_tx.data {
+        HistoricTransactionData::Basic(tx) => {
+            let tx = tx.get_raw_transaction();
+            vec![&tx.sender, &tx.recipient]
+        }
+        HistoricTransactionData::Reward(ev) => vec![&ev.reward_address],
+        HistoricTransactionData::Equivocation(_)
+        | HistoricTransactionData::Penalize(_)
+        | HistoricTransactionData::Jail(_) => vec![],
+    }
+}
+
+/// The batch filter store keeps a compact filter per finalized batch. Filters are stored for
+/// every batch since genesis without gaps, such that each filter header commits to all previous
+/// filters.
+#[derive(Debug)]
+pub struct BatchFilterStore {
+    /// Database handle.
+    db: MdbxDatabase,
+    /// A database of the batch filters indexed by batch number.
+    filter_table: BatchFilterTable,
+}
+
+impl BatchFilterStore {
+    pub fn new(db: MdbxDatabase) -> Self {
+        let store = BatchFilterStore {
+            db,
+            filter_table: BatchFilterTable,
+        };
+
+        store.db.create_regular_table(&store.filter_table);
+
+        store
+    }
+
+    /// Returns the filter of the given batch.
+    pub fn get(&self, txn: &MdbxReadTransaction, batch_number: u32) -> Option<BatchFilter> {
+        txn.get(&self.filter_table, &batch_number)
+    }
+
+    /// Returns the number of the last batch a filter was stored for.
+    pub fn last_batch_number(&self, txn: &MdbxReadTransaction) -> Option<u32> {
+        let mut cursor = txn.cursor(&self.filter_table);
+        cursor.last().map(|(batch_number, _)| batch_number)
+    }
+
+    /// Computes and stores the filter of the given batch from its historic transactions. The
+    /// filter of the previous batch must already be stored, unless this is the first batch.
+    pub fn put_batch(
+        &self,
+        txn: &mut MdbxWriteTransaction,
+        batch_number: u32,
+        hist_txs: &[HistoricTransaction],
+    ) -> BatchFilter {
+        let prev_header = match batch_number.checked_sub(1) {
+            Some(prev_batch_number) => self
+                .get(txn, prev_batch_number)
+                .map(|prev_filter| prev_filter.header)
+                .unwrap_or_default(),
+            None => Blake2bHash::default(),
+        };
+
+        let filter = CompactFilter::new(
+            &batch_filter_key(batch_number),
+            hist_txs
+                .iter()
+                .flat_map(batch_filter_addresses)
+                .map(|address| address.as_bytes()),
+        );
+        let batch_filter = BatchFilter {
+            header: filter.header(&prev_header),
+            filter,
+        };
+
+        txn.put_reserve(&self.filter_table, &batch_number, &batch_filter);
+        batch_filter
+    }
+}
@@ -186,6 +186,11 @@ impl Blockchain {
             .expect("Failed to revert - missing revert info");
 
         // Revert the block from AccountsTree.
+        // The keys changed by the revert are the keys the block changed, so we record them to
+        // remove the block from the archive.
+        if self.archive_store.is_some() {
+            txn.start_recording();
+        }
         let block_state = BlockState::new(block.block_number(), block.header.timestamp);
         let result = accounts.revert(
             txn,
@@ -198,6 +203,10 @@ impl Blockchain {
         if let Err(e) = result {
             panic!("Failed to revert {block} - {e:?}");
         }
+        if let Some(ref archive_store) = self.archive_store {
+            let recorded_diff = txn.stop_recording().into_forward_diff();
+            archive_store.remove_block(txn.raw(), block.block_number(), &recorded_diff);
+        }
 
         let total_size = self
             .history_store
@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use nimiq_account::{Account, Staker, StakingContract, StakingContractStore};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::mdbx::MdbxReadTransaction;
+use nimiq_keys::Address;
+use nimiq_primitives::{key_nibbles::KeyNibbles, policy::Policy};
+
+use crate::{
+    archive_store::{ArchiveError, ArchiveStore, ArchivedDataStore},
+    Blockchain,
+};
+
+/// Implements methods to query the accounts state at past blocks.
+impl Blockchain {
+    /// Returns the archive store if the accounts state after the block with the given number can
+    /// be reconstructed.
+    fn archive_store_at(
+        &self,
+        block_number: u32,
+        txn: &MdbxReadTransaction,
+    ) -> Result<&ArchiveStore, ArchiveError> {
+        let archive_store = self.archive_store.as_ref().ok_or(ArchiveError::Disabled)?;
+
+        if block_number > self.block_number() {
+            return Err(ArchiveError::FutureBlock(block_number));
+        }
+        match archive_store.first_block_number(txn) {
+            Some(first_block_number) if block_number >= first_block_number => Ok(archive_store),
+            _ => Err(ArchiveError::NotArchived(block_number)),
+        }
+    }
+
+    /// Returns the account at the given address as it was after the block with the given number.
+    pub fn get_archived_account(
+        &self,
+        address: &Address,
+        block_number: u32,
+        txn: &MdbxReadTransaction,
+    ) -> Result<Account, ArchiveError> {
+        let archive_store = self.archive_store_at(block_number, txn)?;
+        Ok(archive_store
+            .get(
+                &self.state.accounts,
+                txn,
+                &KeyNibbles::from(address),
+                block_number,
+            )
+            .unwrap_or_default())
+    }
+
+    /// Returns the staking contract as it was after the block with the given number.
+    pub fn get_archived_staking_contract(
+        &self,
+        block_number: u32,
+        txn: &MdbxReadTransaction,
+    ) -> Result<StakingContract, ArchiveError> {
+        match self.get_archived_account(&Policy::STAKING_CONTRACT_ADDRESS, block_number, txn)? {
+            Account::Staking(staking_contract) => Ok(staking_contract),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the data store of the staking contract as it was after the block with the given
+    /// number.
+    pub fn get_archived_staking_contract_store<'a, 'txn, 'env>(
+        &'a self,
+        block_number: u32,
+        txn: &'txn MdbxReadTransaction<'env>,
+    ) -> Result<ArchivedDataStore<'a, 'txn, 'env>, ArchiveError> {
+        let archive_store = self.archive_store_at(block_number, txn)?;
+        Ok(ArchivedDataStore::new(
+            archive_store,
+            &self.state.accounts,
+            txn,
+            &Policy::STAKING_CONTRACT_ADDRESS,
+            block_number,
+        ))
+    }
+
+    /// Returns the stakers that delegated their stake to the given validator as they were after
+    /// the block with the given number.
+    /// IMPORTANT: This is a very expensive operation, iterating over all existing stakers in the
+    /// contract and all stakers that changed since the given block.
+    pub fn get_archived_stakers_for_validator(
+        &self,
+        validator_address: &Address,
+        block_number: u32,
+        txn: &MdbxReadTransaction,
+    ) -> Result<Vec<Staker>, ArchiveError> {
+        let archive_store = self.archive_store_at(block_number, txn)?;
+
+        let prefix = KeyNibbles::from(&Policy::STAKING_CONTRACT_ADDRESS);
+        let start_key = &prefix + &StakingContractStore::staker_key(&Address::START_ADDRESS);
+        let end_key = &prefix + &StakingContractStore::staker_key(&Address::END_ADDRESS);
+
+        // A staker existed after the given block if it exists now and didn't change since, or if
+        // it changed since and its archived value exists.
+        let changed_keys =
+            archive_store.keys_changed_after(txn, &start_key, &end_key, block_number);
+        let mut stakers = BTreeMap::new();
+        for staker in self
+            .state
+            .accounts
+            .tree
+            .iter_nodes::<Staker>(txn, &start_key, &end_key)
+        {
+            let key = &prefix + &StakingContractStore::staker_key(&staker.address);
+            if !changed_keys.contains(&key) {
+                stakers.insert(staker.address.clone(), staker);
+            }
+        }
+        for key in changed_keys.iter() {
+            if let Some(staker) =
+                archive_store.get::<Staker>(&self.state.accounts, txn, key, block_number)
+            {
+                stakers.insert(staker.address.clone(), staker);
+            }
+        }
+
+        Ok(stakers
+            .into_values()
+            .filter(|staker| staker.delegation.as_ref() == Some(validator_address))
+            .collect())
+    }
+}
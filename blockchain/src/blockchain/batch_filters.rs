@@ -0,0 +1,144 @@
+use std::cmp::Ordering;
+
+use nimiq_database::mdbx::{MdbxReadTransaction, MdbxWriteTransaction};
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::policy::Policy;
+use parking_lot::RwLock;
+
+use crate::{
+    batch_filter_store::{BatchFilter, BatchFilterError},
+    interface::HistoryInterface,
+    Blockchain,
+};
+
+/// The number of batches whose filters are computed in a single database transaction when
+/// backfilling.
+const BACKFILL_CHUNK_SIZE: u32 = 32;
+
+/// Implements methods to compute and query the compact filters of finalized batches.
+impl Blockchain {
+    /// Computes the filters of the batches up to and including the given one that don't have a
+    /// filter yet. Must be called when a macro block is committed, after its history was added to
+    /// the history store. Does nothing if batch filters are disabled.
+    ///
+    /// To bound the work done while pushing, this only covers the batches of a single epoch. If
+    /// more filters are missing, e.g. because batch filters were just enabled, nothing is done
+    /// and the filters are left to [`Blockchain::backfill_batch_filters`] instead.
+    pub(crate) fn put_batch_filters(&self, txn: &mut MdbxWriteTransaction, batch_number: u32) {
+        let Some(first_batch) = self.next_batch_filter(txn) else {
+            return;
+        };
+        if batch_number.saturating_sub(first_batch) >= Policy::batches_per_epoch() as u32 {
+            log::warn!(
+                first_batch,
+                batch_number,
+                "Not computing batch filters while pushing, too many are missing. They need to be backfilled"
+            );
+            return;
+        }
+
+        for batch in first_batch..=batch_number {
+            self.put_batch_filter(txn, batch);
+        }
+    }
+
+    /// Computes the filters of all finalized batches that don't have a filter yet, in chunks of
+    /// [`BACKFILL_CHUNK_SIZE`] batches. Each chunk is computed in its own database transaction and
+    /// blocks from being pushed only while it is computed. Meant to be run on a separate thread
+    /// when the node starts. Returns the number of filters computed.
+    pub fn backfill_batch_filters(blockchain: &RwLock<Self>) -> u32 {
+        let mut num_filters = 0;
+        loop {
+            let this = blockchain.upgradable_read();
+            let mut txn = this.write_transaction();
+            let last_batch = Policy::batch_at(this.state.macro_info.head.block_number());
+            let first_batch = match this.next_batch_filter(&txn) {
+                Some(first_batch) if first_batch <= last_batch => first_batch,
+                _ => {
+                    txn.abort();
+                    if num_filters > 0 {
+                        log::info!(num_filters, "Finished backfilling batch filters");
+                    }
+                    return num_filters;
+                }
+            };
+
+            let last_chunk_batch = last_batch.min(first_batch + BACKFILL_CHUNK_SIZE - 1);
+            for batch in first_batch..=last_chunk_batch {
+                this.put_batch_filter(&mut txn, batch);
+            }
+            txn.commit();
+
+            num_filters += last_chunk_batch - first_batch + 1;
+            log::debug!(
+                batch = last_chunk_batch,
+                last_batch,
+                "Backfilled batch filters"
+            );
+        }
+    }
+
+    /// Returns the first batch without a filter, or `None` if batch filters are disabled.
+    fn next_batch_filter(&self, txn: &MdbxReadTransaction) -> Option<u32> {
+        let batch_filter_store = self.batch_filter_store.as_ref()?;
+        Some(match batch_filter_store.last_batch_number(txn) {
+            Some(last_batch) => last_batch + 1,
+            None => Policy::batch_at(self.genesis_block_number) + 1,
+        })
+    }
+
+    /// Computes the filter of the given batch from its history. The filter of the previous batch
+    /// must already be stored, unless this is the first batch.
+    fn put_batch_filter(&self, txn: &mut MdbxWriteTransaction, batch: u32) {
+        let Some(ref batch_filter_store) = self.batch_filter_store else {
+            return;
+        };
+
+        let first_block = Policy::first_block_of_batch(batch).unwrap();
+        let macro_block = Policy::macro_block_of(batch).unwrap();
+        let hist_txs: Vec<_> = (first_block..=macro_block)
+            .flat_map(|block_number| {
+                self.history_store
+                    .get_block_transactions(block_number, Some(txn))
+            })
+            .collect();
+
+        batch_filter_store.put_batch(txn, batch, &hist_txs);
+    }
+
+    /// Returns the filters of `count` consecutive batches starting at `start_batch`, together
+    /// with the header of the filter preceding `start_batch`.
+    pub fn get_batch_filters(
+        &self,
+        start_batch: u32,
+        count: u32,
+        txn: &MdbxReadTransaction,
+    ) -> Result<(Blake2bHash, Vec<BatchFilter>), BatchFilterError> {
+        let batch_filter_store = self
+            .batch_filter_store
+            .as_ref()
+            .ok_or(BatchFilterError::Disabled)?;
+
+        let first_batch = Policy::batch_at(self.genesis_block_number) + 1;
+        let prev_header = match start_batch.cmp(&first_batch) {
+            Ordering::Less => return Err(BatchFilterError::MissingFilter(start_batch)),
+            Ordering::Equal => Blake2bHash::default(),
+            Ordering::Greater => {
+                batch_filter_store
+                    .get(txn, start_batch - 1)
+                    .ok_or(BatchFilterError::MissingFilter(start_batch - 1))?
+                    .header
+            }
+        };
+
+        let filters = (start_batch..start_batch.saturating_add(count))
+            .map(|batch| {
+                batch_filter_store
+                    .get(txn, batch)
+                    .ok_or(BatchFilterError::MissingFilter(batch))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok((prev_header, filters))
+    }
+}
@@ -18,6 +18,7 @@ use tokio::sync::broadcast::{channel as broadcast, Sender as BroadcastSender};
 #[cfg(feature = "metrics")]
 use crate::chain_metrics::BlockchainMetrics;
 use crate::{
+    archive_store::ArchiveStore, batch_filter_store::BatchFilterStore,
     blockchain_state::BlockchainState, chain_store::ChainStore, history::HistoryStore,
     history_store_proxy::HistoryStoreProxy, interface::HistoryInterface,
     reward::genesis_parameters, HistoryStoreIndex,
@@ -46,6 +47,12 @@ pub struct Blockchain {
     pub chain_store: ChainStore,
     /// The history store is a database containing all of the history trees and transactions.
     pub history_store: Arc<HistoryStoreProxy>,
+    /// The archive store is a database containing the previous states of the accounts. It is only
+    /// present if the archive is enabled.
+    pub archive_store: Option<ArchiveStore>,
+    /// The batch filter store is a database containing the compact address filters of all
+    /// finalized batches. It is only present if the full history is kept.
+    pub batch_filter_store: Option<BatchFilterStore>,
     /// The current state of the blockchain.
     pub state: BlockchainState,
     /// A reference to a "function" to test whether a given transaction is known and valid.
@@ -72,6 +79,8 @@ pub struct BlockchainConfig {
     pub max_epochs_stored: u32,
     /// Enables/Disables indices in the history store.
     pub index_history: bool,
+    /// Enables/Disables the archive of previous accounts states.
+    pub archive_accounts: bool,
 }
 
 impl Default for BlockchainConfig {
@@ -80,6 +89,7 @@ impl Default for BlockchainConfig {
             keep_history: true,
             max_epochs_stored: Policy::MIN_EPOCHS_STORED,
             index_history: true,
+            archive_accounts: false,
         }
     }
 }
@@ -277,6 +287,13 @@ impl Blockchain {
             }
         };
 
+        let archive_store = config
+            .archive_accounts
+            .then(|| ArchiveStore::new(env.clone()));
+        let batch_filter_store = config
+            .keep_history
+            .then(|| BatchFilterStore::new(env.clone()));
+
         let (tx, _rx) = broadcast(BROADCAST_MAX_CAPACITY);
         let (tx_fork, _rx_fork) = broadcast(BROADCAST_MAX_CAPACITY);
         let (tx_log, _rx_log) = broadcast(BROADCAST_MAX_CAPACITY);
@@ -291,6 +308,8 @@ impl Blockchain {
             log_notifier: tx_log,
             chain_store,
             history_store,
+            archive_store,
+            batch_filter_store,
             state: BlockchainState {
                 accounts,
                 main_chain,
@@ -344,6 +363,13 @@ impl Blockchain {
         chain_store.set_head(&mut txn, &head_hash);
         txn.commit();
 
+        let archive_store = config
+            .archive_accounts
+            .then(|| ArchiveStore::new(env.clone()));
+        let batch_filter_store = config
+            .keep_history
+            .then(|| BatchFilterStore::new(env.clone()));
+
         let (tx, _rx) = broadcast(BROADCAST_MAX_CAPACITY);
         let (tx_fork, _rx_fork) = broadcast(BROADCAST_MAX_CAPACITY);
         let (tx_log, _rx_log) = broadcast(BROADCAST_MAX_CAPACITY);
@@ -358,6 +384,8 @@ impl Blockchain {
             log_notifier: tx_log,
             chain_store,
             history_store,
+            archive_store,
+            batch_filter_store,
             state: BlockchainState {
                 accounts,
                 macro_info: main_chain.clone(),
@@ -18,6 +18,7 @@ use nimiq_transaction::{
     inherent::Inherent,
     Transaction,
 };
+use nimiq_trie::WriteTransactionProxy;
 use parking_lot::{RwLockUpgradableReadGuard, RwLockWriteGuard};
 
 use crate::{interface::HistoryInterface, Blockchain};
@@ -321,13 +322,21 @@ impl Blockchain {
 
             // Commit block to AccountsTree and create the receipts.
             let block_state = BlockState::new(block_numbers[i], block_timestamps[i]);
+            let mut trie_txn: WriteTransactionProxy = (&mut txn).into();
+            if this.archive_store.is_some() {
+                trie_txn.start_recording();
+            }
             let receipts = this.state.accounts.commit_batch(
-                &mut (&mut txn).into(),
+                &mut trie_txn,
                 &txns,
                 &block_inherents[i],
                 &block_state,
                 &mut BlockLogger::empty(),
             );
+            if let Some(ref archive_store) = this.archive_store {
+                let recorded_diff = trie_txn.stop_recording().into_backward_diff();
+                archive_store.put_block(&mut txn, block_numbers[i], &recorded_diff);
+            }
 
             // Check if the receipts contain an error.
             if let Err(e) = receipts {
@@ -412,6 +421,9 @@ impl Blockchain {
             return Err(PushError::InvalidBlock(BlockError::AccountsHashMismatch));
         }
 
+        // Compute the filters of the batches whose history was added.
+        this.put_batch_filters(&mut txn, block.batch_number());
+
         // Give up database transactions and push lock before creating notifications.
         txn.commit();
 
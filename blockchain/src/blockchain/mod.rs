@@ -1,12 +1,17 @@
 mod abstract_blockchain;
 pub mod accounts;
+pub mod archive;
+pub mod batch_filters;
 #[allow(clippy::module_inception)]
 pub mod blockchain;
 pub mod history_sync;
 pub mod inherents;
 pub mod push;
 pub(super) mod rebranch_utils;
+pub mod slot_schedule;
 pub mod slots;
+pub mod snapshot;
+pub mod validator_rewards;
 pub mod verify;
 pub mod wrappers;
 pub mod zkp_sync;
@@ -309,6 +309,7 @@ impl Blockchain {
 
         if is_macro_block {
             this.chain_store.finalize_batch(&mut txn);
+            this.put_batch_filters(&mut txn, Policy::batch_at(block_number));
         }
 
         if is_election_block {
@@ -445,6 +446,9 @@ impl Blockchain {
         let new_head_hash = &fork_chain[0].0;
         let new_head_info = &fork_chain[0].1;
         this.chain_store.set_head(&mut write_txn, new_head_hash);
+        if new_head_info.head.is_macro() {
+            this.put_batch_filters(&mut write_txn, new_head_info.head.batch_number());
+        }
         write_txn.commit();
 
         if let Block::Macro(ref macro_block) = new_head_info.head {
@@ -573,9 +577,19 @@ impl Blockchain {
                 self.metrics.note_invalid_block();
             })?;
             if is_complete {
-                let recorded_diff = txn.stop_recording().into_forward_diff();
-                self.chain_store
-                    .put_accounts_diff(txn.raw(), &block.hash(), &recorded_diff);
+                let recorded_diff = txn.stop_recording();
+                if let Some(ref archive_store) = self.archive_store {
+                    archive_store.put_block(
+                        txn.raw(),
+                        block.block_number(),
+                        &recorded_diff.clone().into_backward_diff(),
+                    );
+                }
+                self.chain_store.put_accounts_diff(
+                    txn.raw(),
+                    &block.hash(),
+                    &recorded_diff.into_forward_diff(),
+                );
             }
         }
 
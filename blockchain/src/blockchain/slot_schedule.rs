@@ -0,0 +1,95 @@
+use std::ops::Range;
+
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_keys::Address;
+use nimiq_primitives::policy::Policy;
+
+use crate::Blockchain;
+
+/// A block that is still to be produced, with its expected timestamp assuming no delays.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScheduledBlock {
+    pub block_number: u32,
+    pub timestamp: u64,
+}
+
+/// The upcoming slots of a validator in the epoch of the next block. The parts of the schedule
+/// that can't be determined yet are marked as unknown.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlotSchedule {
+    /// The address of the validator.
+    pub validator_address: Address,
+    /// The epoch of the next block. If the head is an election block, this is the epoch elected
+    /// by it.
+    pub epoch_number: u32,
+    /// The slots the validator holds during the epoch.
+    pub slots: Range<u16>,
+    /// The next block, which is the first block of the schedule.
+    pub first_block: ScheduledBlock,
+    /// The last micro block of the epoch. The election block is not included. If the next block
+    /// is the election block, no micro blocks remain and this precedes the first block.
+    pub last_block: ScheduledBlock,
+    /// The blocks the validator is known to propose, unless it skips them. The proposer of a
+    /// block is selected using the VRF seed of its predecessor, so only the proposer of the next
+    /// block is known in advance.
+    pub proposer_blocks: Vec<ScheduledBlock>,
+    /// The first block of the schedule whose proposer is not known yet. The proposers of all
+    /// blocks from this one to the last block are unknown. `None` if the proposers of all blocks
+    /// of the schedule are known.
+    pub proposers_unknown_from: Option<ScheduledBlock>,
+    /// The first block of the following epoch. Its validators are elected by the election block
+    /// of this epoch, so the slots of the validator from this block on are unknown until that
+    /// election block is final. Once it is, the schedule covers the elected epoch.
+    pub slots_unknown_from: ScheduledBlock,
+}
+
+/// Implements methods to schedule the slots of validators.
+impl Blockchain {
+    /// Returns the upcoming slots of the given validator, or `None` if it doesn't hold any slots
+    /// in the epoch of the next block. The expected timestamps are derived from the timestamp of
+    /// the head and `Policy::BLOCK_SEPARATION_TIME`.
+    pub fn get_slot_schedule(&self, validator_address: &Address) -> Option<SlotSchedule> {
+        // The validators are updated by the election block, so the current validators are the ones
+        // of the epoch of the next block.
+        let slots = self
+            .current_validators()?
+            .get_validator_by_address(validator_address)?
+            .slots
+            .clone();
+
+        let head_block_number = self.block_number();
+        let head_timestamp = self.timestamp();
+        let scheduled_block = |block_number: u32| ScheduledBlock {
+            block_number,
+            timestamp: head_timestamp
+                + u64::from(block_number - head_block_number) * Policy::BLOCK_SEPARATION_TIME,
+        };
+
+        let next_block_number = head_block_number + 1;
+        let epoch_number = Policy::epoch_at(next_block_number);
+        let election_block_number = Policy::election_block_of(epoch_number)?;
+
+        let last_block_number = election_block_number - 1;
+
+        let proposer_blocks = self
+            .get_proposer_at(next_block_number, 0)
+            .ok()
+            .filter(|slot| slot.validator.address == *validator_address)
+            .map(|_| scheduled_block(next_block_number))
+            .into_iter()
+            .collect();
+        let proposers_unknown_from =
+            (next_block_number < last_block_number).then(|| scheduled_block(next_block_number + 1));
+
+        Some(SlotSchedule {
+            validator_address: validator_address.clone(),
+            epoch_number,
+            slots,
+            first_block: scheduled_block(next_block_number),
+            last_block: scheduled_block(last_block_number),
+            proposer_blocks,
+            proposers_unknown_from,
+            slots_unknown_from: scheduled_block(election_block_number + 1),
+        })
+    }
+}
@@ -0,0 +1,398 @@
+use std::io::{self, Read, Write};
+
+use nimiq_account::BlockLogger;
+use nimiq_block::Block;
+use nimiq_blockchain_interface::{
+    AbstractBlockchain, BlockchainError, ChunksPushError, ChunksPushResult, Direction, PushError,
+    PushResult,
+};
+use nimiq_database::traits::WriteTransaction;
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::{
+    key_nibbles::KeyNibbles,
+    networks::NetworkId,
+    policy::Policy,
+    trie::trie_chunk::{TrieChunk, TrieChunkWithStart},
+};
+use nimiq_serde::{Deserialize, DeserializeError, Serialize};
+use nimiq_transaction::historic_transaction::HistoricTransaction;
+use parking_lot::RwLock;
+use thiserror::Error;
+
+use crate::{interface::HistoryInterface, Blockchain};
+
+/// The version of the snapshot format.
+pub const SNAPSHOT_VERSION: u8 = 1;
+
+/// The maximum number of trie items in a single accounts chunk of a snapshot.
+const SNAPSHOT_CHUNK_SIZE: usize = 10_000;
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Malformed snapshot: {0}")]
+    Malformed(#[from] DeserializeError),
+    #[error("Unsupported snapshot version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Snapshot was created for network {0}")]
+    WrongNetwork(NetworkId),
+    #[error("Snapshots can only be imported into an empty database")]
+    DatabaseNotEmpty,
+    #[error("Accounts are incomplete")]
+    AccountsIncomplete,
+    #[error("History is not available")]
+    HistoryUnavailable,
+    #[error("Invalid snapshot: {0}")]
+    InvalidSnapshot(&'static str),
+    #[error("Election block {0} of the snapshot is not the trusted one")]
+    UntrustedElectionBlock(Blake2bHash),
+    #[error("Blockchain error: {0}")]
+    Blockchain(#[from] BlockchainError),
+    #[error("Failed to push block: {0}")]
+    Push(#[from] PushError),
+    #[error("Failed to commit accounts: {0}")]
+    Chunks(#[from] ChunksPushError),
+}
+
+/// The first entry of a snapshot. It describes the macro block the snapshot was taken at.
+///
+/// A snapshot consists of the header, followed by the accounts trie in chunks and, if
+/// `num_epochs` is not zero, the history of each epoch up to the macro block.
+/// Every entry is prefixed with its serialized length as a big-endian u32.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub version: u8,
+    pub network_id: NetworkId,
+    /// The election block of the epoch the macro block belongs to.
+    /// It is the macro block itself if that is an election block.
+    pub election_block: Block,
+    /// The macro block the snapshot was taken at.
+    pub macro_block: Block,
+    /// The number of epochs with history in the snapshot.
+    pub num_epochs: u32,
+}
+
+/// A chunk of the accounts trie at the snapshot's macro block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SnapshotChunk {
+    start_key: KeyNibbles,
+    chunk: TrieChunk,
+}
+
+/// A macro block together with the history of its epoch up to that block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SnapshotEpoch {
+    block: Block,
+    history: Vec<HistoricTransaction>,
+}
+
+fn write_entry<W: Write, T: Serialize>(writer: &mut W, entry: &T) -> Result<(), SnapshotError> {
+    let bytes = entry.serialize_to_vec();
+    writer.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+fn read_entry<R: Read, T: Deserialize>(reader: &mut R) -> Result<T, SnapshotError> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let mut bytes = vec![0u8; u32::from_be_bytes(len) as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(T::deserialize_from_vec(&bytes)?)
+}
+
+/// Implements methods to export and import offline snapshots of the node state.
+impl Blockchain {
+    /// Exports a snapshot of the chain at the current macro head.
+    ///
+    /// The micro blocks after the macro head are reverted in a transaction that is aborted
+    /// afterwards, such that the accounts trie and the history are consistent with the macro block.
+    /// The history of all epochs is only included if `include_history` is set, which requires
+    /// a node that keeps the full history.
+    pub fn export_snapshot<W: Write>(
+        &self,
+        writer: &mut W,
+        include_history: bool,
+    ) -> Result<SnapshotHeader, SnapshotError> {
+        if include_history && !self.config.keep_history {
+            return Err(SnapshotError::HistoryUnavailable);
+        }
+
+        let mut txn = self.write_transaction();
+
+        if !self.state.accounts.is_complete(Some(&txn)) {
+            txn.abort();
+            return Err(SnapshotError::AccountsIncomplete);
+        }
+
+        // Revert the accounts and history to the state at the macro head.
+        let mut block = self
+            .chain_store
+            .get_block(&self.state.head_hash, true, Some(&txn))?;
+        while !block.is_macro() {
+            self.revert_accounts(
+                &self.state.accounts,
+                &mut (&mut txn).into(),
+                &block,
+                &mut BlockLogger::empty(),
+            )?;
+            block = self
+                .chain_store
+                .get_block(block.parent_hash(), true, Some(&txn))?;
+        }
+        let macro_block = block;
+
+        let election_block =
+            self.chain_store
+                .get_block(&self.state.election_head_hash, true, Some(&txn))?;
+
+        // Collect all election blocks since genesis, followed by the macro head.
+        let mut epoch_blocks = vec![];
+        if include_history {
+            epoch_blocks = self.chain_store.get_macro_blocks(
+                &self.state.election_head_hash,
+                u32::MAX,
+                true,
+                Direction::Backward,
+                true,
+                Some(&txn),
+            )?;
+            epoch_blocks.retain(|block| block.block_number() > Policy::genesis_block_number());
+            epoch_blocks.reverse();
+            if election_block.block_number() > Policy::genesis_block_number() {
+                epoch_blocks.push(election_block.clone());
+            }
+            if !macro_block.is_election() {
+                epoch_blocks.push(macro_block.clone());
+            }
+        }
+
+        let header = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            network_id: self.network_id,
+            election_block,
+            macro_block,
+            num_epochs: epoch_blocks.len() as u32,
+        };
+        write_entry(writer, &header)?;
+
+        let mut start_key = KeyNibbles::ROOT;
+        loop {
+            let chunk =
+                self.state
+                    .accounts
+                    .get_chunk(start_key.clone(), SNAPSHOT_CHUNK_SIZE, Some(&txn));
+            let end_key = chunk.end_key.clone();
+            write_entry(writer, &SnapshotChunk { start_key, chunk })?;
+
+            match end_key {
+                Some(end_key) => start_key = end_key,
+                None => break,
+            }
+        }
+
+        for block in epoch_blocks {
+            let history = self
+                .history_store
+                .get_epoch_transactions(block.epoch_number(), Some(&txn));
+            write_entry(writer, &SnapshotEpoch { block, history })?;
+        }
+
+        txn.abort();
+
+        info!(
+            block = %header.macro_block,
+            num_epochs = header.num_epochs,
+            "Exported snapshot"
+        );
+
+        Ok(header)
+    }
+
+    /// Imports a snapshot into a blockchain that only contains the genesis block.
+    ///
+    /// Nodes that keep the history rebuild the chain from the history of each epoch, verifying
+    /// the history root and state root of every macro block. Other nodes adopt the election block
+    /// like one proven by a ZKP and restore the accounts trie from the chunks, which are verified
+    /// against the state root of the macro block.
+    ///
+    /// As nodes without history can't check the election block against the genesis block, they
+    /// only adopt it if its hash is `trusted_election_hash`, which must be obtained from a trusted
+    /// source (e.g. a node that verified the chain). If given, the hash is checked by nodes that
+    /// keep the history as well.
+    pub fn import_snapshot<R: Read>(
+        blockchain: &RwLock<Self>,
+        reader: &mut R,
+        trusted_election_hash: Option<&Blake2bHash>,
+    ) -> Result<SnapshotHeader, SnapshotError> {
+        let header: SnapshotHeader = read_entry(reader)?;
+        if header.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(header.version));
+        }
+        if let Some(trusted_election_hash) = trusted_election_hash {
+            let election_hash = header.election_block.hash();
+            if election_hash != *trusted_election_hash {
+                return Err(SnapshotError::UntrustedElectionBlock(election_hash));
+            }
+        }
+
+        let keep_history = {
+            let this = blockchain.read();
+            if header.network_id != this.network_id {
+                return Err(SnapshotError::WrongNetwork(header.network_id));
+            }
+            if this.block_number() != Policy::genesis_block_number() {
+                return Err(SnapshotError::DatabaseNotEmpty);
+            }
+            this.config.keep_history
+        };
+
+        if !header.election_block.is_election() || !header.macro_block.is_macro() {
+            return Err(SnapshotError::InvalidSnapshot("expected macro blocks"));
+        }
+        if !header.macro_block.is_election()
+            && header
+                .macro_block
+                .unwrap_macro_ref()
+                .header
+                .parent_election_hash
+                != header.election_block.hash()
+        {
+            return Err(SnapshotError::InvalidSnapshot(
+                "macro block does not belong to the election block",
+            ));
+        }
+
+        if keep_history {
+            if header.num_epochs == 0
+                && header.macro_block.block_number() > Policy::genesis_block_number()
+            {
+                return Err(SnapshotError::HistoryUnavailable);
+            }
+            Self::import_snapshot_history(blockchain, &header, reader)?;
+        } else {
+            Self::import_snapshot_accounts(blockchain, &header, reader, trusted_election_hash)?;
+        }
+
+        info!(
+            block = %header.macro_block,
+            num_epochs = header.num_epochs,
+            "Imported snapshot"
+        );
+
+        Ok(header)
+    }
+
+    /// Rebuilds the chain by pushing each epoch with its history. This verifies the history root
+    /// and the state root of every macro block.
+    fn import_snapshot_history<R: Read>(
+        blockchain: &RwLock<Self>,
+        header: &SnapshotHeader,
+        reader: &mut R,
+    ) -> Result<(), SnapshotError> {
+        // The accounts trie is rebuilt from the history, so we skip the chunks.
+        loop {
+            let chunk: SnapshotChunk = read_entry(reader)?;
+            if chunk.chunk.end_key.is_none() {
+                break;
+            }
+        }
+
+        for _ in 0..header.num_epochs {
+            let epoch: SnapshotEpoch = read_entry(reader)?;
+            if !epoch.block.is_macro() {
+                return Err(SnapshotError::InvalidSnapshot("expected macro blocks"));
+            }
+            match Blockchain::push_history_sync(
+                blockchain.upgradable_read(),
+                epoch.block,
+                &epoch.history,
+            )? {
+                PushResult::Extended => {}
+                _ => return Err(SnapshotError::InvalidSnapshot("epochs are not consecutive")),
+            }
+        }
+
+        if blockchain.read().head_hash() != header.macro_block.hash() {
+            return Err(SnapshotError::InvalidSnapshot(
+                "history does not end at the macro block",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Adopts the election block if it is the trusted one, pushes the macro block and restores
+    /// the accounts trie from the chunks. Every chunk is verified against the state root of the
+    /// macro block.
+    fn import_snapshot_accounts<R: Read>(
+        blockchain: &RwLock<Self>,
+        header: &SnapshotHeader,
+        reader: &mut R,
+        trusted_election_hash: Option<&Blake2bHash>,
+    ) -> Result<(), SnapshotError> {
+        let network_id = blockchain.read().network_id;
+
+        if header.election_block.block_number() > Policy::genesis_block_number() {
+            // The hash was checked against the trusted one before.
+            if trusted_election_hash.is_none() {
+                return Err(SnapshotError::UntrustedElectionBlock(
+                    header.election_block.hash(),
+                ));
+            }
+            header
+                .election_block
+                .verify(network_id)
+                .map_err(PushError::from)?;
+
+            let this = blockchain.upgradable_read();
+            let genesis_block =
+                this.chain_store
+                    .get_block_at(Policy::genesis_block_number(), true, None)?;
+            Blockchain::set_election_head(
+                this,
+                header.election_block.clone(),
+                genesis_block,
+                "import_snapshot",
+            );
+        }
+
+        if !header.macro_block.is_election() {
+            Blockchain::push_macro(blockchain.upgradable_read(), header.macro_block.clone())?;
+        }
+
+        let block_hash = header.macro_block.hash();
+        if blockchain.read().head_hash() != block_hash {
+            return Err(SnapshotError::InvalidSnapshot(
+                "macro block does not extend the election block",
+            ));
+        }
+
+        loop {
+            let SnapshotChunk { start_key, chunk } = read_entry(reader)?;
+            let is_last = chunk.end_key.is_none();
+
+            let result = blockchain
+                .read()
+                .commit_chunks(vec![TrieChunkWithStart { chunk, start_key }], &block_hash)?;
+            if result != ChunksPushResult::Chunks(1, 0) {
+                return Err(SnapshotError::InvalidSnapshot(
+                    "accounts chunks are not consecutive",
+                ));
+            }
+
+            if is_last {
+                break;
+            }
+        }
+
+        // All chunks have been verified against the state root, so the trie must be complete now.
+        let state_root = blockchain.read().state.accounts.get_root_hash(None);
+        if state_root.as_ref() != Some(header.macro_block.state_root()) {
+            return Err(SnapshotError::AccountsIncomplete);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use std::ops::Range;
+
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError};
+use nimiq_database::mdbx::MdbxReadTransaction;
+use nimiq_keys::Address;
+use nimiq_primitives::{coin::Coin, policy::Policy};
+use nimiq_transaction::historic_transaction::{HistoricTransactionData, JailEvent, PenalizeEvent};
+use thiserror::Error;
+
+use crate::{interface::HistoryInterface, ArchiveError, Blockchain};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ValidatorRewardsError {
+    #[error("Invalid epoch: {0}")]
+    InvalidEpoch(u32),
+    #[error("The rewards of epoch {0} have not been paid out yet")]
+    NotPaidOut(u32),
+    #[error("The history of epoch {0} is not available")]
+    HistoryNotAvailable(u32),
+    #[error("Validator {0} held no slots in epoch {1}")]
+    NotElected(Address, u32),
+    #[error("{0}")]
+    Blockchain(#[from] BlockchainError),
+    #[error("{0}")]
+    Archive(#[from] ArchiveError),
+}
+
+/// The reward a validator received for a batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchReward {
+    /// The number of the batch that was rewarded.
+    pub batch_number: u32,
+    /// The number of the macro block that paid out the reward, i.e. the macro block of the
+    /// following batch.
+    pub payout_block_number: u32,
+    /// The reward paid to the reward address of the validator. This is zero if all slots of the
+    /// validator were punished or if the reward address couldn't accept the reward.
+    pub reward: Coin,
+}
+
+/// The share of the rewards of a validator that corresponds to a staker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StakerReward {
+    /// The address of the staker.
+    pub address: Address,
+    /// The active balance of the staker after the election block.
+    pub active_balance: Coin,
+    /// The share of the rewards proportional to the active balance of the staker.
+    pub reward: Coin,
+}
+
+/// An account of the rewards a validator received for the slots it held during an epoch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorEpochRewards {
+    /// The address of the validator.
+    pub validator_address: Address,
+    /// The epoch the rewards were earned in.
+    pub epoch_number: u32,
+    /// The election block at which the validators of the epoch were selected. Stakes are taken
+    /// from the state after this block.
+    pub election_block_number: u32,
+    /// The slots the validator held during the epoch.
+    pub slots: Range<u16>,
+    /// The rewards per batch of the epoch.
+    pub batches: Vec<BatchReward>,
+    /// The sum of the rewards of all batches.
+    pub total_reward: Coin,
+    /// The penalties of the validator that were included in the epoch or before its last reward
+    /// was paid out. Each penalty forfeits the reward of the penalized slot for the batch of the
+    /// offense.
+    pub penalties: Vec<PenalizeEvent>,
+    /// The jail events of the validator that were included in the epoch or before its last reward
+    /// was paid out. A jailed validator forfeits the rewards of all its slots.
+    pub jails: Vec<JailEvent>,
+    /// The total stake of the validator after the election block, including its deposit.
+    pub total_stake: Coin,
+    /// The deposit of the validator after the election block.
+    pub deposit: Coin,
+    /// The shares of the stakers delegating to the validator after the election block. The share
+    /// of the deposit and the remainder of the rounding stay with the validator.
+    pub stakers: Vec<StakerReward>,
+}
+
+/// Implements methods to account for the rewards of validators.
+impl Blockchain {
+    /// Returns the rewards the given validator received for the given epoch, together with the
+    /// split of the rewards between its stakers pro-rata to their active balance after the
+    /// election block of the previous epoch.
+    /// This requires the history of the epoch and the accounts state after the election block to
+    /// be available, i.e. an accounts archive.
+    /// IMPORTANT: This is a very expensive operation, iterating over all historic transactions of
+    /// the epoch and all stakers in the staking contract.
+    pub fn get_validator_rewards_for_epoch(
+        &self,
+        validator_address: &Address,
+        epoch_number: u32,
+        txn: &MdbxReadTransaction,
+    ) -> Result<ValidatorEpochRewards, ValidatorRewardsError> {
+        let (Some(election_block_number), Some(last_block)) = (
+            epoch_number
+                .checked_sub(1)
+                .and_then(Policy::election_block_of),
+            Policy::election_block_of(epoch_number),
+        ) else {
+            return Err(ValidatorRewardsError::InvalidEpoch(epoch_number));
+        };
+
+        // The reward of a batch is paid out by the macro block of the following batch.
+        let first_batch = Policy::batch_at(election_block_number) + 1;
+        let last_batch = Policy::batch_at(last_block);
+        let last_payout_block = Policy::macro_block_of(last_batch + 1)
+            .ok_or(ValidatorRewardsError::InvalidEpoch(epoch_number))?;
+        if last_payout_block > self.block_number() {
+            return Err(ValidatorRewardsError::NotPaidOut(epoch_number));
+        }
+
+        let (first_history_block, _) = self.history_store.history_store_range(Some(txn));
+        if Policy::epoch_at(first_history_block) > epoch_number {
+            return Err(ValidatorRewardsError::HistoryNotAvailable(epoch_number));
+        }
+
+        let validators = self.get_validators_for_epoch(epoch_number, Some(txn))?;
+        let slots = validators
+            .get_validator_by_address(validator_address)
+            .ok_or_else(|| {
+                ValidatorRewardsError::NotElected(validator_address.clone(), epoch_number)
+            })?
+            .slots
+            .clone();
+
+        // Collect the rewards and punishments from the history of the epoch and the first batch
+        // of the next one, which pays out the reward of the last batch.
+        let mut hist_txs = self
+            .history_store
+            .get_epoch_transactions(epoch_number, Some(txn));
+        for block_number in last_block + 1..=last_payout_block {
+            hist_txs.extend(
+                self.history_store
+                    .get_block_transactions(block_number, Some(txn)),
+            );
+        }
+
+        let mut batches: Vec<_> = (first_batch..=last_batch)
+            .map(|batch_number| BatchReward {
+                batch_number,
+                payout_block_number: Policy::macro_block_of(batch_number + 1).unwrap(),
+                reward: Coin::ZERO,
+            })
+            .collect();
+        let mut penalties = vec![];
+        let mut jails = vec![];
+        for hist_tx in hist_txs {
+            match hist_tx.data {
+                HistoricTransactionData::Reward(ev)
+                    if ev.validator_address == *validator_address =>
+                {
+                    let batch_number = Policy::batch_at(hist_tx.block_number) - 1;
+                    if let Some(batch) = batch_number
+                        .checked_sub(first_batch)
+                        .and_then(|index| batches.get_mut(index as usize))
+                    {
+                        batch.reward += ev.value;
+                    }
+                }
+                HistoricTransactionData::Penalize(ev)
+                    if ev.validator_address == *validator_address =>
+                {
+                    penalties.push(ev);
+                }
+                HistoricTransactionData::Jail(ev) if ev.validator_address == *validator_address => {
+                    jails.push(ev);
+                }
+                _ => {}
+            }
+        }
+        let total_reward = batches
+            .iter()
+            .fold(Coin::ZERO, |total, batch| total + batch.reward);
+
+        // Split the rewards pro-rata to the stakes after the election block.
+        let staking_contract = self.get_archived_staking_contract(election_block_number, txn)?;
+        let data_store = self.get_archived_staking_contract_store(election_block_number, txn)?;
+        let validator = staking_contract
+            .get_validator(&data_store, validator_address)
+            .ok_or_else(|| {
+                ValidatorRewardsError::NotElected(validator_address.clone(), epoch_number)
+            })?;
+        let stakers = self
+            .get_archived_stakers_for_validator(validator_address, election_block_number, txn)?
+            .into_iter()
+            .map(|staker| StakerReward {
+                reward: pro_rata_share(total_reward, staker.active_balance, validator.total_stake),
+                address: staker.address,
+                active_balance: staker.active_balance,
+            })
+            .collect();
+
+        Ok(ValidatorEpochRewards {
+            validator_address: validator_address.clone(),
+            epoch_number,
+            election_block_number,
+            slots,
+            batches,
+            total_reward,
+            penalties,
+            jails,
+            total_stake: validator.total_stake,
+            deposit: validator.deposit,
+            stakers,
+        })
+    }
+}
+
+/// Returns the share of the reward corresponding to the given stake, rounded down.
+fn pro_rata_share(reward: Coin, stake: Coin, total_stake: Coin) -> Coin {
+    if total_stake.is_zero() {
+        return Coin::ZERO;
+    }
+    let share = u128::from(u64::from(reward)) * u128::from(u64::from(stake))
+        / u128::from(u64::from(total_stake));
+    Coin::from_u64_unchecked(share as u64)
+}
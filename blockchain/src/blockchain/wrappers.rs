@@ -2,7 +2,10 @@ use std::ops::RangeFrom;
 #[cfg(feature = "metrics")]
 use std::sync::Arc;
 
-use nimiq_account::{Account, BlockState, DataStore, ReservedBalance, StakingContract};
+use nimiq_account::{
+    Account, AccountsError, BlockState, DataStore, ReservedBalance, SimulatedTransaction,
+    StakingContract,
+};
 use nimiq_block::Block;
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, ChainInfo, Direction};
 use nimiq_database::{mdbx::MdbxReadTransaction as DBTransaction, traits::WriteTransaction};
@@ -164,6 +167,27 @@ impl Blockchain {
         }
     }
 
+    /// Simulates the given transaction as if it was included in the next block, without changing
+    /// the accounts. Returns `None` if the accounts tree is incomplete.
+    pub fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Option<Result<SimulatedTransaction, AccountsError>> {
+        if !self.accounts_complete() {
+            return None;
+        }
+
+        let block_state = BlockState::new(
+            self.block_number() + 1,
+            self.timestamp().max(self.time.now()),
+        );
+        Some(
+            self.state
+                .accounts
+                .simulate_transaction(transaction, &block_state),
+        )
+    }
+
     /// The given account must correspond to the sender of the given transaction.
     pub fn reserve_balance(
         &self,
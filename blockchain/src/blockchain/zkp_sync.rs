@@ -56,7 +56,6 @@ impl Blockchain {
             .unwrap();
         let genesis_macro_block = genesis_block.unwrap_macro_ref();
         let genesis_hash_blake2s = genesis_macro_block.hash_blake2s();
-        let genesis_hash_blake2b = genesis_macro_block.hash();
 
         // Verify the zk proof.
         if !trusted_proof {
@@ -72,17 +71,40 @@ impl Blockchain {
             }
         }
 
+        read_txn.close();
+
         // At this point we know that the block is correct. We just have to push it.
+        Ok(Self::set_election_head(
+            this,
+            block,
+            genesis_block,
+            "push_zkp",
+        ))
+    }
+
+    /// Makes the given election block the new head of the chain, discarding the current chain
+    /// and marking the accounts as incomplete. The block must already have been verified.
+    /// Only the genesis block is kept in the chain store.
+    pub(crate) fn set_election_head(
+        this: RwLockUpgradableReadGuard<Self>,
+        block: Block,
+        genesis_block: Block,
+        kind: &'static str,
+    ) -> PushResult {
+        let block_hash_blake2b = block.hash();
+        let genesis_hash_blake2b = genesis_block.hash();
 
         // Create the chain info for the new block.
         let chain_info = ChainInfo::new(block, true);
 
-        read_txn.close();
         let mut txn = this.write_transaction();
 
         this.state
             .accounts
             .reinitialize_as_incomplete(&mut (&mut txn).into());
+        if let Some(ref archive_store) = this.archive_store {
+            archive_store.clear(&mut txn);
+        }
 
         // Since it's a macro block, we have to clear the ChainStore. If we are syncing for the first
         // time, this should be empty. But we clear it just in case it's not our first time.
@@ -139,7 +161,7 @@ impl Blockchain {
         debug!(
             block = %this.state.main_chain.head,
             num_transactions,
-            kind = "push_zkp",
+            kind,
             "Accepted block",
         );
 
@@ -154,7 +176,7 @@ impl Blockchain {
 
         // We don't have any block logs, so we do not notify the block log stream.
 
-        Ok(PushResult::Extended)
+        PushResult::Extended
     }
 
     /// Pushes an election block backwards into the chain.
@@ -259,6 +281,9 @@ impl Blockchain {
         this.state
             .accounts
             .reinitialize_as_incomplete(&mut (&mut txn).into());
+        if let Some(ref archive_store) = this.archive_store {
+            archive_store.clear(&mut txn);
+        }
 
         let is_election_block = Policy::is_election_block_at(block_number);
 
@@ -0,0 +1,138 @@
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::{account::AccountType, coin::Coin};
+use nimiq_transaction::historic_transaction::{HistoricTransaction, HistoricTransactionData};
+
+/// The position in the history of an address after which a query continues. The history of an
+/// address is always traversed from the most recent to the least recent transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddressHistoryCursor {
+    /// Continue with the transactions preceding the transaction with the given hash.
+    Transaction(Blake2bHash),
+    /// Continue with the transactions included in blocks preceding the given block number.
+    BlockNumber(u32),
+}
+
+/// The direction of a transaction from the point of view of the queried address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionDirection {
+    /// The address is the sender of the transaction.
+    Sent,
+    /// The address is the recipient of the transaction or of the reward.
+    Received,
+}
+
+/// The types of transactions that are part of the history of an address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressTransactionType {
+    /// A transaction between basic accounts.
+    Basic,
+    /// A transaction creating or spending from a vesting contract.
+    Vesting,
+    /// A transaction creating or resolving an HTLC.
+    Htlc,
+    /// A transaction interacting with the staking contract.
+    Staking,
+    /// A reward paid out to a validator.
+    Reward,
+}
+
+impl AddressTransactionType {
+    /// Returns the type of the given historic transaction. Basic transactions are classified by
+    /// the contract they interact with, if any. Returns `None` for punishments and equivocations,
+    /// as those are not part of the history of an address.
+    pub fn of(hist_tx: &HistoricTransaction) -> Option<Self> {
+        match &hist_tx.data {
+            HistoricTransactionData::Basic(tx) => {
+                let tx = tx.get_raw_transaction();
+                let involves = |account_type| {
+                    tx.sender_type == account_type || tx.recipient_type == account_type
+                };
+                Some(if involves(AccountType::Staking) {
+                    AddressTransactionType::Staking
+                } else if involves(AccountType::HTLC) {
+                    AddressTransactionType::Htlc
+                } else if involves(AccountType::Vesting) {
+                    AddressTransactionType::Vesting
+                } else {
+                    AddressTransactionType::Basic
+                })
+            }
+            HistoricTransactionData::Reward(_) => Some(AddressTransactionType::Reward),
+            HistoricTransactionData::Penalize(_)
+            | HistoricTransactionData::Jail(_)
+            | HistoricTransactionData::Equivocation(_) => None,
+        }
+    }
+}
+
+/// Restricts the transactions returned by a query of the history of an address. All criteria
+/// need to be met for a transaction to be returned.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AddressHistoryFilter {
+    /// Only return transactions sent or only transactions received by the address.
+    pub direction: Option<TransactionDirection>,
+    /// Only return transactions of one of the given types. All types are returned if empty.
+    pub types: Vec<AddressTransactionType>,
+    /// Only return transactions included in this block or a later one.
+    pub min_block_number: Option<u32>,
+    /// Only return transactions included in this block or an earlier one.
+    pub max_block_number: Option<u32>,
+    /// Only return transactions (and rewards) with at least this value.
+    pub min_value: Option<Coin>,
+}
+
+impl AddressHistoryFilter {
+    /// Returns whether the filter accepts all transactions, in which case the transactions don't
+    /// need to be fetched to evaluate it.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns whether the given historic transaction of the given address passes the filter.
+    pub fn matches(&self, address: &Address, hist_tx: &HistoricTransaction) -> bool {
+        if self
+            .min_block_number
+            .is_some_and(|block_number| hist_tx.block_number < block_number)
+            || self
+                .max_block_number
+                .is_some_and(|block_number| hist_tx.block_number > block_number)
+        {
+            return false;
+        }
+
+        let (sender, recipient, value) = match &hist_tx.data {
+            HistoricTransactionData::Basic(tx) => {
+                let tx = tx.get_raw_transaction();
+                (Some(&tx.sender), &tx.recipient, tx.value)
+            }
+            HistoricTransactionData::Reward(ev) => (None, &ev.reward_address, ev.value),
+            HistoricTransactionData::Penalize(_)
+            | HistoricTransactionData::Jail(_)
+            | HistoricTransactionData::Equivocation(_) => return false,
+        };
+
+        let direction_matches = match self.direction {
+            Some(TransactionDirection::Sent) => sender == Some(address),
+            Some(TransactionDirection::Received) => recipient == address,
+            None => true,
+        };
+        let type_matches = self.types.is_empty()
+            || AddressTransactionType::of(hist_tx).is_some_and(|ty| self.types.contains(&ty));
+        let value_matches = !self.min_value.is_some_and(|min_value| value < min_value);
+
+        direction_matches && type_matches && value_matches
+    }
+}
+
+/// A page of the history of an address.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AddressHistoryPage {
+    /// The hashes of the transactions, from the most recent to the least recent one.
+    pub hashes: Vec<Blake2bHash>,
+    /// The hash of the transaction after which the next page starts. `None` if the history of
+    /// the address has been exhausted. A page may contain fewer transactions than requested (even
+    /// none) if only few of the scanned transactions passed the filter, so paging continues as
+    /// long as this is set.
+    pub next: Option<Blake2bHash>,
+}
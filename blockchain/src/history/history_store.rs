@@ -133,6 +133,36 @@ impl HistoryStore {
         hist_txs
     }
 
+    /// Gets the historic transactions of an epoch at the given leaf indices, without proving
+    /// them. Returns `None` if any of the leaves is missing.
+    pub fn get_epoch_leaves(
+        &self,
+        epoch_number: u32,
+        leaf_indices: Range<u32>,
+        txn_option: Option<&MdbxReadTransaction>,
+    ) -> Option<Vec<HistoricTransaction>> {
+        let mut hist_txs = Vec::with_capacity(leaf_indices.len());
+        let txn = txn_option.or_new(&self.db);
+
+        // Get consecutive transactions with fast cursor.
+        let mut cursor = txn.dup_cursor(&self.hist_tx_table);
+
+        for (i, leaf_index) in leaf_indices.enumerate() {
+            let (epoch, hist_tx) = if i == 0 {
+                (epoch_number, cursor.set_subkey(&epoch_number, &leaf_index)?)
+            } else {
+                cursor.next_duplicate()?
+            };
+
+            if epoch != epoch_number || hist_tx.index != leaf_index {
+                return None;
+            }
+            hist_txs.push(hist_tx.value);
+        }
+
+        Some(hist_txs)
+    }
+
     /// Internal method to remove leaves from the history tree.
     /// Returns the root and leaf indices.
     pub(crate) fn remove_leaves_from_history(
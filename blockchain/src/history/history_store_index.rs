@@ -22,6 +22,7 @@ use nimiq_transaction::{
 };
 
 use super::{
+    address_history::{AddressHistoryCursor, AddressHistoryFilter, AddressHistoryPage},
     interface::HistoryInterface,
     utils::{EpochBasedIndex, OrderedHash},
 };
@@ -32,6 +33,10 @@ declare_table!(TxHashTable, "LeafIndexByTxHash", RawTransactionHash => EpochBase
 // `Address` -> `EpochBasedIndex` -> `Blake2bHash`
 declare_table!(AddressTable, "TxHashesByAddress", Address => EpochBasedIndex => Blake2bHash);
 
+/// The maximum number of entries of the history of an address that are considered for a single
+/// page. Pages of sparse matches are cut short and continue with the next page instead.
+const MAX_SCANNED_ADDRESS_HISTORY_ENTRIES: usize = 10_000;
+
 #[derive(Debug)]
 /// A struct that contains databases to store history indices.
 pub struct HistoryStoreIndex {
@@ -224,6 +229,113 @@ impl HistoryStoreIndex {
             }
         }
     }
+
+    /// Returns a page of the history of the given address like
+    /// [`get_tx_hashes_by_address_paginated`](HistoryInterface::get_tx_hashes_by_address_paginated),
+    /// but considers at most `max_scanned` entries of the history.
+    fn scan_address_history(
+        &self,
+        address: &Address,
+        max: u16,
+        start_after: Option<&AddressHistoryCursor>,
+        filter: &AddressHistoryFilter,
+        max_scanned: usize,
+        txn_option: Option<&MdbxReadTransaction>,
+    ) -> Option<AddressHistoryPage> {
+        let txn = txn_option.or_new(&self.db);
+
+        let mut page = AddressHistoryPage::default();
+        if max == 0 {
+            return Some(page);
+        }
+
+        // Starting after a block number is equivalent to an upper bound on the block number.
+        let mut filter = filter.clone();
+        if let Some(AddressHistoryCursor::BlockNumber(block_number)) = start_after {
+            let Some(max_block_number) = block_number.checked_sub(1) else {
+                return Some(page);
+            };
+            filter.max_block_number = Some(
+                filter
+                    .max_block_number
+                    .map_or(max_block_number, |n| n.min(max_block_number)),
+            );
+        }
+        let min_epoch = filter.min_block_number.map(Policy::epoch_at);
+        let max_epoch = filter.max_block_number.map(Policy::epoch_at);
+
+        // Seek to the first transaction hash to consider. Without a cursor, this is the last
+        // transaction hash at the given address, or the last one of the latest epoch allowed by
+        // the filter.
+        let mut cursor = txn.dup_cursor(&self.address_table);
+        let mut entry = match (start_after, max_epoch) {
+            (Some(AddressHistoryCursor::Transaction(hash)), _) => {
+                let index = self.get_leaf_indices_by_tx_hash(hash, Some(&txn))?;
+                cursor.set_subkey(address, &index)?;
+                cursor.prev_duplicate().map(|(_, v)| v)
+            }
+            (_, Some(max_epoch)) => {
+                let upper_bound = EpochBasedIndex::new(max_epoch.saturating_add(1), 0);
+                if cursor
+                    .set_lowerbound_subkey(address, &upper_bound)
+                    .is_some()
+                {
+                    cursor.prev_duplicate().map(|(_, v)| v)
+                } else {
+                    cursor
+                        .set_key(address)
+                        .and_then(|_| cursor.last_duplicate())
+                }
+            }
+            (_, None) => cursor
+                .set_key(address)
+                .and_then(|_| cursor.last_duplicate()),
+        };
+
+        let mut num_scanned = 0;
+        let mut last_scanned = None;
+        while let Some(ordered_hash) = entry {
+            // Transactions are ordered by epoch, so there are no matches past the lower bound.
+            if min_epoch.is_some_and(|min_epoch| ordered_hash.index.epoch_number < min_epoch) {
+                break;
+            }
+
+            // There are more transactions to consider, but the page is either full or the
+            // remaining ones are left to the next page to bound the work done per call.
+            if page.hashes.len() == max as usize || num_scanned == max_scanned {
+                page.next = last_scanned;
+                break;
+            }
+            num_scanned += 1;
+
+            let is_match = if filter.is_empty() {
+                true
+            } else if max_epoch.is_some_and(|max_epoch| ordered_hash.index.epoch_number > max_epoch)
+            {
+                false
+            } else {
+                let hist_tx = self
+                    .history_store
+                    .get_historic_tx(
+                        ordered_hash.index.epoch_number,
+                        ordered_hash.index.index,
+                        Some(&txn),
+                    )
+                    .expect("Indexed transactions must be part of the history store");
+                filter.matches(address, &hist_tx)
+            };
+
+            if is_match {
+                page.hashes.push(ordered_hash.value.clone());
+            }
+            last_scanned = Some(ordered_hash.value);
+
+            // Get previous transaction hash.
+            entry = cursor.prev_duplicate().map(|(_, v)| v);
+        }
+
+        Some(page)
+    }
 }
 
 impl HistoryInterface for HistoryStoreIndex {
@@ -557,34 +669,38 @@ impl HistoryIndexInterface for HistoryStoreIndex {
         max: u16,
         txn_option: Option<&MdbxReadTransaction>,
     ) -> Vec<Blake2bHash> {
-        if max == 0 {
-            return vec![];
-        }
-
-        let txn = txn_option.or_new(&self.db);
-
-        let mut tx_hashes = vec![];
-
-        // Seek to the first transaction hash at the given address. If there's none, stop here.
-        let mut cursor = txn.dup_cursor(&self.address_table);
-
-        if cursor.set_key(address).is_none() {
-            return tx_hashes;
-        }
-
-        // Then go to the last transaction hash at the given address and add it to the transaction
-        // hashes list.
-        tx_hashes.push(cursor.last_duplicate().expect("This shouldn't panic since we already verified before that there is at least one transactions at this address!").value);
-
-        while tx_hashes.len() < max as usize {
-            // Get previous transaction hash.
-            match cursor.prev_duplicate() {
-                Some((_, v)) => tx_hashes.push(v.value),
-                None => break,
-            };
-        }
+        self.get_tx_hashes_by_address_paginated(
+            address,
+            max,
+            None,
+            &AddressHistoryFilter::default(),
+            txn_option,
+        )
+        .map(|page| page.hashes)
+        .unwrap_or_default()
+    }
 
-        tx_hashes
+    /// Returns a page of the transaction (and reward inherent) hashes corresponding to the given
+    /// address that pass the given filter, from most recent to least recent, up to the maximum
+    /// number given. If a cursor is given, the page starts after the position it refers to.
+    /// Returns `None` if the cursor refers to a transaction that is not part of the history of
+    /// the address.
+    fn get_tx_hashes_by_address_paginated(
+        &self,
+        address: &Address,
+        max: u16,
+        start_after: Option<&AddressHistoryCursor>,
+        filter: &AddressHistoryFilter,
+        txn_option: Option<&MdbxReadTransaction>,
+    ) -> Option<AddressHistoryPage> {
+        self.scan_address_history(
+            address,
+            max,
+            start_after,
+            filter,
+            MAX_SCANNED_ADDRESS_HISTORY_ENTRIES,
+            txn_option,
+        )
     }
 
     /// Returns a proof for transactions with the given hashes. The proof also includes the extended
@@ -627,6 +743,7 @@ mod tests {
     };
 
     use super::*;
+    use crate::{AddressTransactionType, TransactionDirection};
 
     #[test]
     fn prove_num_leaves_works() {
@@ -1237,6 +1354,208 @@ mod tests {
         assert_eq!(query_4.len(), 0);
     }
 
+    #[test]
+    fn get_tx_hashes_by_address_paginated_works() {
+        // Initialize History Store.
+        let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+        let history_store = HistoryStoreIndex::new(env.clone(), NetworkId::UnitAlbatross);
+
+        // Create historic transactions.
+        let hist_txs = gen_hist_txs();
+
+        // Add historic transactions to History Store.
+        let mut txn = env.write_transaction();
+        history_store.add_to_history(&mut txn, Policy::genesis_block_number() + 0, &hist_txs[..3]);
+        history_store.add_to_history(&mut txn, Policy::genesis_block_number() + 2, &hist_txs[3..]);
+
+        let hashes: Vec<Blake2bHash> = hist_txs
+            .iter()
+            .map(|hist_tx| hist_tx.tx_hash().into())
+            .collect();
+        let sender =
+            Address::from_user_friendly_address("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF")
+                .unwrap();
+        let reward_address =
+            Address::from_user_friendly_address("NQ04 B79B R4FF 4NGU A9H0 2PT9 9ART 5A88 J73T")
+                .unwrap();
+        let query = |address: &Address,
+                     max: u16,
+                     start_after: Option<AddressHistoryCursor>,
+                     filter: &AddressHistoryFilter| {
+            history_store.get_tx_hashes_by_address_paginated(
+                address,
+                max,
+                start_after.as_ref(),
+                filter,
+                Some(&txn),
+            )
+        };
+
+        // Page through the history of the sender.
+        let filter = AddressHistoryFilter::default();
+        let page_1 = query(&sender, 2, None, &filter).unwrap();
+        assert_eq!(page_1.hashes, vec![hashes[6].clone(), hashes[5].clone()]);
+        assert_eq!(page_1.next, Some(hashes[5].clone()));
+
+        let page_2 = query(
+            &sender,
+            2,
+            Some(AddressHistoryCursor::Transaction(hashes[5].clone())),
+            &filter,
+        )
+        .unwrap();
+        assert_eq!(page_2.hashes, vec![hashes[3].clone(), hashes[1].clone()]);
+        assert_eq!(page_2.next, Some(hashes[1].clone()));
+
+        let page_3 = query(
+            &sender,
+            2,
+            Some(AddressHistoryCursor::Transaction(hashes[1].clone())),
+            &filter,
+        )
+        .unwrap();
+        assert_eq!(page_3.hashes, vec![hashes[0].clone()]);
+        assert_eq!(page_3.next, None);
+
+        // A page that ends with the last transaction doesn't refer to a next page.
+        let page = query(&sender, 5, None, &filter).unwrap();
+        assert_eq!(page.hashes.len(), 5);
+        assert_eq!(page.next, None);
+
+        // Start after a block number.
+        let page = query(
+            &sender,
+            99,
+            Some(AddressHistoryCursor::BlockNumber(
+                Policy::genesis_block_number() + 1,
+            )),
+            &filter,
+        )
+        .unwrap();
+        assert_eq!(page.hashes, vec![hashes[1].clone(), hashes[0].clone()]);
+
+        // A cursor that is not part of the history of the address is rejected.
+        assert!(query(
+            &sender,
+            99,
+            Some(AddressHistoryCursor::Transaction(hashes[2].clone())),
+            &filter,
+        )
+        .is_none());
+
+        // Filter by direction.
+        let received = AddressHistoryFilter {
+            direction: Some(TransactionDirection::Received),
+            ..Default::default()
+        };
+        assert!(query(&sender, 99, None, &received)
+            .unwrap()
+            .hashes
+            .is_empty());
+        assert_eq!(
+            query(&Address::burn_address(), 99, None, &received)
+                .unwrap()
+                .hashes
+                .len(),
+            5
+        );
+
+        // Filter by transaction type.
+        let rewards = AddressHistoryFilter {
+            types: vec![AddressTransactionType::Reward],
+            ..Default::default()
+        };
+        assert!(query(&sender, 99, None, &rewards)
+            .unwrap()
+            .hashes
+            .is_empty());
+        assert_eq!(
+            query(&reward_address, 99, None, &rewards).unwrap().hashes,
+            vec![hashes[7].clone(), hashes[4].clone(), hashes[2].clone()]
+        );
+
+        // Filter by block range and value.
+        let filter = AddressHistoryFilter {
+            min_block_number: Some(Policy::genesis_block_number() + 1),
+            max_block_number: Some(Policy::genesis_block_number() + 1),
+            ..Default::default()
+        };
+        assert_eq!(
+            query(&sender, 99, None, &filter).unwrap().hashes,
+            vec![hashes[3].clone()]
+        );
+
+        let filter = AddressHistoryFilter {
+            min_value: Some(Coin::from_u64_unchecked(3)),
+            ..Default::default()
+        };
+        let page = query(&sender, 2, None, &filter).unwrap();
+        assert_eq!(page.hashes, vec![hashes[6].clone(), hashes[5].clone()]);
+        let page = query(
+            &sender,
+            2,
+            page.next.map(AddressHistoryCursor::Transaction),
+            &filter,
+        )
+        .unwrap();
+        assert_eq!(page.hashes, vec![hashes[3].clone()]);
+        assert_eq!(page.next, None);
+    }
+
+    #[test]
+    fn scan_address_history_stops_after_max_scanned() {
+        // Initialize History Store.
+        let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+        let history_store = HistoryStoreIndex::new(env.clone(), NetworkId::UnitAlbatross);
+
+        // Create historic transactions.
+        let hist_txs = gen_hist_txs();
+
+        // Add historic transactions to History Store.
+        let mut txn = env.write_transaction();
+        history_store.add_to_history(&mut txn, Policy::genesis_block_number() + 0, &hist_txs[..3]);
+        history_store.add_to_history(&mut txn, Policy::genesis_block_number() + 2, &hist_txs[3..]);
+
+        let hashes: Vec<Blake2bHash> = hist_txs
+            .iter()
+            .map(|hist_tx| hist_tx.tx_hash().into())
+            .collect();
+        let sender =
+            Address::from_user_friendly_address("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF")
+                .unwrap();
+
+        // Only the oldest transactions of the sender match. Each scan stops after two
+        // transactions and the next one continues after the last scanned transaction.
+        let filter = AddressHistoryFilter {
+            max_block_number: Some(Policy::genesis_block_number()),
+            ..Default::default()
+        };
+        let scan = |start_after: Option<Blake2bHash>| {
+            history_store
+                .scan_address_history(
+                    &sender,
+                    99,
+                    start_after.map(AddressHistoryCursor::Transaction).as_ref(),
+                    &filter,
+                    2,
+                    Some(&txn),
+                )
+                .unwrap()
+        };
+
+        let page_1 = scan(None);
+        assert!(page_1.hashes.is_empty());
+        assert_eq!(page_1.next, Some(hashes[5].clone()));
+
+        let page_2 = scan(page_1.next);
+        assert_eq!(page_2.hashes, vec![hashes[1].clone()]);
+        assert_eq!(page_2.next, Some(hashes[1].clone()));
+
+        let page_3 = scan(page_2.next);
+        assert_eq!(page_3.hashes, vec![hashes[0].clone()]);
+        assert_eq!(page_3.next, None);
+    }
+
     #[test]
     fn prove_works() {
         // Initialize History Store.
@@ -13,7 +13,7 @@ use nimiq_transaction::{
     EquivocationLocator,
 };
 
-use crate::HistoryTreeChunk;
+use crate::{AddressHistoryCursor, AddressHistoryFilter, AddressHistoryPage, HistoryTreeChunk};
 
 /// Defines several methods to interact with a history store.
 pub trait HistoryInterface: std::fmt::Debug {
@@ -203,6 +203,20 @@ pub trait HistoryIndexInterface {
         txn_option: Option<&MdbxReadTransaction>,
     ) -> Vec<Blake2bHash>;
 
+    /// Returns a page of the transaction (and reward inherent) hashes corresponding to the given
+    /// address that pass the given filter, from most recent to least recent, up to the maximum
+    /// number given. If a cursor is given, the page starts after the position it refers to.
+    /// Returns `None` if the cursor refers to a transaction that is not part of the history of
+    /// the address.
+    fn get_tx_hashes_by_address_paginated(
+        &self,
+        address: &Address,
+        max: u16,
+        start_after: Option<&AddressHistoryCursor>,
+        filter: &AddressHistoryFilter,
+        txn_option: Option<&MdbxReadTransaction>,
+    ) -> Option<AddressHistoryPage>;
+
     /// Returns a proof for transactions with the given hashes. The proof also includes the extended
     /// transactions.
     /// The verifier state is used for those cases where the verifier might have an incomplete MMR,
@@ -1,7 +1,12 @@
+pub use address_history::{
+    AddressHistoryCursor, AddressHistoryFilter, AddressHistoryPage, AddressTransactionType,
+    TransactionDirection,
+};
 pub use history_store::HistoryStore;
 pub use history_store_index::HistoryStoreIndex;
 pub use history_tree_chunk::{HistoryTreeChunk, CHUNK_SIZE};
 
+mod address_history;
 mod history_store;
 mod history_store_index;
 pub mod history_store_proxy;
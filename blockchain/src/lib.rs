@@ -1,10 +1,21 @@
 #[macro_use]
 extern crate log;
 
+pub use archive_store::{ArchiveError, ArchiveStore, ArchivedDataStore};
+pub use batch_filter_store::{
+    batch_filter_addresses, BatchFilter, BatchFilterError, BatchFilterStore,
+};
 pub use block_production::{BlockProducer, BlockProducerError};
-pub use blockchain::blockchain::{Blockchain, BlockchainConfig, TransactionVerificationCache};
+pub use blockchain::{
+    blockchain::{Blockchain, BlockchainConfig, TransactionVerificationCache},
+    slot_schedule::{ScheduledBlock, SlotSchedule},
+    snapshot::{SnapshotError, SnapshotHeader, SNAPSHOT_VERSION},
+    validator_rewards::{BatchReward, StakerReward, ValidatorEpochRewards, ValidatorRewardsError},
+};
 pub use history::*;
 
+pub(crate) mod archive_store;
+pub(crate) mod batch_filter_store;
 pub(crate) mod block_production;
 pub(crate) mod blockchain;
 pub(crate) mod blockchain_state;
@@ -0,0 +1,182 @@
+use std::{convert::TryInto, sync::Arc};
+
+use nimiq_block::Block;
+use nimiq_blockchain::{ArchiveError, BlockProducer, Blockchain, BlockchainConfig};
+use nimiq_blockchain_interface::{AbstractBlockchain, PushResult};
+use nimiq_database::mdbx::MdbxDatabase;
+use nimiq_genesis::NetworkId;
+use nimiq_keys::{Address, KeyPair as SchnorrKeyPair, PrivateKey as SchnorrPrivateKey};
+use nimiq_primitives::{coin::Coin, policy::Policy};
+use nimiq_serde::Deserialize;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{signing_key, voting_key};
+use nimiq_transaction::Transaction;
+use nimiq_transaction_builder::TransactionBuilder;
+use nimiq_utils::time::OffsetTime;
+use parking_lot::RwLock;
+
+const ACCOUNT_SECRET_KEY: &str = "6c9320ac201caf1f8eaa5b05f5d67a9e77826f3f6be266a0ecccc20416dc6587";
+const DELEGATION_ADDRESS: &str = "NQ20TSB0DFSMUH9C15GQGAGJTTE4D3MA859E";
+
+fn new_blockchain(archive_accounts: bool) -> Arc<RwLock<Blockchain>> {
+    let time = Arc::new(OffsetTime::new());
+    let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+    let config = BlockchainConfig {
+        archive_accounts,
+        ..Default::default()
+    };
+    Arc::new(RwLock::new(
+        Blockchain::new(env, config, NetworkId::UnitAlbatross, time).unwrap(),
+    ))
+}
+
+fn push_micro_block(
+    producer: &BlockProducer,
+    blockchain: &Arc<RwLock<Blockchain>>,
+    transactions: Vec<Transaction>,
+) {
+    let bc = blockchain.upgradable_read();
+    let block = producer
+        .next_micro_block(
+            &bc,
+            bc.timestamp() + Policy::BLOCK_SEPARATION_TIME,
+            vec![],
+            transactions,
+            vec![0x41],
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(
+        Blockchain::push(bc, Block::Micro(block)),
+        Ok(PushResult::Extended)
+    );
+}
+
+#[test]
+fn it_can_query_archived_accounts() {
+    let blockchain = new_blockchain(true);
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    let genesis_block_number = blockchain.read().get_genesis_block_number();
+
+    let key_pair: SchnorrKeyPair =
+        SchnorrPrivateKey::deserialize_from_vec(&hex::decode(ACCOUNT_SECRET_KEY).unwrap())
+            .unwrap()
+            .into();
+    let sender = Address::from(&key_pair.public);
+    let delegation = Address::from_any_str(DELEGATION_ADDRESS).unwrap();
+    let initial_balance = blockchain
+        .read()
+        .state
+        .accounts
+        .get_complete(&sender, None)
+        .balance();
+
+    push_micro_block(&producer, &blockchain, vec![]);
+
+    let tx = TransactionBuilder::new_create_staker(
+        &key_pair,
+        &key_pair,
+        Some(delegation),
+        100_000_000.try_into().unwrap(),
+        200.try_into().unwrap(),
+        blockchain.read().block_number() + 1,
+        NetworkId::UnitAlbatross,
+    )
+    .unwrap();
+    push_micro_block(&producer, &blockchain, vec![tx]);
+    push_micro_block(&producer, &blockchain, vec![]);
+
+    let bc = blockchain.read();
+    let txn = bc.read_transaction();
+    let final_balance = bc.state.accounts.get_complete(&sender, None).balance();
+    assert_eq!(
+        final_balance,
+        initial_balance - Coin::from_u64_unchecked(100_000_200)
+    );
+
+    // The state before the staker was created.
+    for block_number in genesis_block_number..=genesis_block_number + 1 {
+        assert_eq!(
+            bc.get_archived_account(&sender, block_number, &txn)
+                .unwrap()
+                .balance(),
+            initial_balance
+        );
+        let staking_contract = bc
+            .get_archived_staking_contract(block_number, &txn)
+            .unwrap();
+        let data_store = bc
+            .get_archived_staking_contract_store(block_number, &txn)
+            .unwrap();
+        assert!(staking_contract.get_staker(&data_store, &sender).is_none());
+    }
+
+    // The state after the staker was created.
+    for block_number in genesis_block_number + 2..=genesis_block_number + 3 {
+        assert_eq!(
+            bc.get_archived_account(&sender, block_number, &txn)
+                .unwrap()
+                .balance(),
+            final_balance
+        );
+        let staking_contract = bc
+            .get_archived_staking_contract(block_number, &txn)
+            .unwrap();
+        let data_store = bc
+            .get_archived_staking_contract_store(block_number, &txn)
+            .unwrap();
+        let staker = staking_contract.get_staker(&data_store, &sender).unwrap();
+        assert_eq!(staker.active_balance, Coin::from_u64_unchecked(100_000_000));
+    }
+
+    assert_eq!(
+        bc.get_archived_account(&sender, genesis_block_number + 4, &txn),
+        Err(ArchiveError::FutureBlock(genesis_block_number + 4))
+    );
+}
+
+#[test]
+fn it_requires_the_archive_to_be_enabled() {
+    let blockchain = new_blockchain(false);
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    push_micro_block(&producer, &blockchain, vec![]);
+
+    let bc = blockchain.read();
+    let txn = bc.read_transaction();
+    assert_eq!(
+        bc.get_archived_account(&Address::START_ADDRESS, bc.block_number(), &txn),
+        Err(ArchiveError::Disabled)
+    );
+}
+
+#[test]
+fn it_restarts_the_archive_after_a_gap() {
+    let blockchain = new_blockchain(true);
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    let genesis_block_number = blockchain.read().get_genesis_block_number();
+
+    push_micro_block(&producer, &blockchain, vec![]);
+
+    // Archiving is disabled for a block, so its changes are not recorded.
+    let archive_store = blockchain.write().archive_store.take();
+    push_micro_block(&producer, &blockchain, vec![]);
+    blockchain.write().archive_store = archive_store;
+
+    push_micro_block(&producer, &blockchain, vec![]);
+
+    // Only the state since the gap can be reconstructed.
+    let bc = blockchain.read();
+    let txn = bc.read_transaction();
+    for block_number in genesis_block_number..genesis_block_number + 2 {
+        assert_eq!(
+            bc.get_archived_account(&Address::START_ADDRESS, block_number, &txn),
+            Err(ArchiveError::NotArchived(block_number))
+        );
+    }
+    for block_number in genesis_block_number + 2..=genesis_block_number + 3 {
+        assert!(bc
+            .get_archived_account(&Address::START_ADDRESS, block_number, &txn)
+            .is_ok());
+    }
+}
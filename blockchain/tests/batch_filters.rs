@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use nimiq_blockchain::{
+    batch_filter_addresses, interface::HistoryInterface, BatchFilterError, BlockProducer,
+    Blockchain, BlockchainConfig,
+};
+use nimiq_database::mdbx::MdbxDatabase;
+use nimiq_genesis::NetworkId;
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
+use nimiq_primitives::{compact_filter::batch_filter_key, policy::Policy};
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{produce_macro_blocks_with_txns, signing_key, voting_key};
+use nimiq_utils::time::OffsetTime;
+use parking_lot::RwLock;
+
+fn new_blockchain(keep_history: bool) -> Arc<RwLock<Blockchain>> {
+    let time = Arc::new(OffsetTime::new());
+    let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+    let config = BlockchainConfig {
+        keep_history,
+        ..Default::default()
+    };
+    Arc::new(RwLock::new(
+        Blockchain::new(env, config, NetworkId::UnitAlbatross, time).unwrap(),
+    ))
+}
+
+#[test]
+fn it_computes_batch_filters() {
+    let blockchain = new_blockchain(true);
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks_with_txns(&producer, &blockchain, 2, 1, 0);
+
+    let bc = blockchain.read();
+    let txn = bc.read_transaction();
+    let first_batch = Policy::batch_at(bc.get_genesis_block_number()) + 1;
+
+    let (prev_header, filters) = bc.get_batch_filters(first_batch, 2, &txn).unwrap();
+    assert_eq!(prev_header, Blake2bHash::default());
+    assert_eq!(filters.len(), 2);
+
+    // The headers form a chain.
+    assert_eq!(filters[0].header, filters[0].filter.header(&prev_header));
+    assert_eq!(
+        filters[1].header,
+        filters[1].filter.header(&filters[0].header)
+    );
+    let (prev_header, _) = bc.get_batch_filters(first_batch + 1, 1, &txn).unwrap();
+    assert_eq!(prev_header, filters[0].header);
+
+    // All addresses touched by a batch match its filter.
+    for (batch, batch_filter) in (first_batch..).zip(&filters) {
+        let mut addresses: Vec<Address> = vec![];
+        for block_number in
+            Policy::first_block_of_batch(batch).unwrap()..=Policy::macro_block_of(batch).unwrap()
+        {
+            for hist_tx in bc
+                .history_store
+                .get_block_transactions(block_number, Some(&txn))
+            {
+                addresses.extend(batch_filter_addresses(&hist_tx).into_iter().cloned());
+            }
+        }
+        assert!(!addresses.is_empty());
+
+        let key = batch_filter_key(batch);
+        for address in &addresses {
+            assert_eq!(
+                batch_filter.filter.matches(&key, address.as_bytes()),
+                Ok(true)
+            );
+        }
+        assert_eq!(
+            batch_filter
+                .filter
+                .matches(&key, Address::from([0x77; 20]).as_bytes()),
+            Ok(false)
+        );
+    }
+
+    assert_eq!(
+        bc.get_batch_filters(first_batch + 1, 2, &txn),
+        Err(BatchFilterError::MissingFilter(first_batch + 2))
+    );
+}
+
+#[test]
+fn it_requires_the_full_history() {
+    let blockchain = new_blockchain(false);
+
+    let bc = blockchain.read();
+    let txn = bc.read_transaction();
+    assert_eq!(
+        bc.get_batch_filters(1, 1, &txn),
+        Err(BatchFilterError::Disabled)
+    );
+}
+
+#[test]
+fn it_backfills_missing_batch_filters() {
+    let blockchain = new_blockchain(true);
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    let first_batch = Policy::batch_at(blockchain.read().get_genesis_block_number()) + 1;
+
+    // Batch filters were enabled after more than an epoch of history was stored.
+    let batch_filter_store = blockchain.write().batch_filter_store.take();
+    let num_batches = Policy::batches_per_epoch() as u32 + 1;
+    produce_macro_blocks_with_txns(&producer, &blockchain, num_batches as usize, 1, 0);
+    blockchain.write().batch_filter_store = batch_filter_store;
+
+    // Pushing doesn't compute the filters of all previous batches.
+    produce_macro_blocks_with_txns(&producer, &blockchain, 1, 1, 0);
+    {
+        let bc = blockchain.read();
+        let txn = bc.read_transaction();
+        assert_eq!(
+            bc.get_batch_filters(first_batch, 1, &txn),
+            Err(BatchFilterError::MissingFilter(first_batch))
+        );
+    }
+
+    assert_eq!(
+        Blockchain::backfill_batch_filters(&blockchain),
+        num_batches + 1
+    );
+    assert_eq!(Blockchain::backfill_batch_filters(&blockchain), 0);
+
+    // Once backfilled, pushing computes the filters of new batches again.
+    produce_macro_blocks_with_txns(&producer, &blockchain, 1, 1, 0);
+    let bc = blockchain.read();
+    let txn = bc.read_transaction();
+    let (prev_header, filters) = bc
+        .get_batch_filters(first_batch, num_batches + 2, &txn)
+        .unwrap();
+    assert_eq!(prev_header, Blake2bHash::default());
+    let mut prev_header = prev_header;
+    for filter in &filters {
+        assert_eq!(filter.header, filter.filter.header(&prev_header));
+        prev_header = filter.header.clone();
+    }
+}
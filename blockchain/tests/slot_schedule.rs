@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use nimiq_blockchain::{BlockProducer, Blockchain, BlockchainConfig, ScheduledBlock};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::mdbx::MdbxDatabase;
+use nimiq_genesis::NetworkId;
+use nimiq_keys::Address;
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{
+    produce_macro_blocks, push_micro_block, signing_key, voting_key,
+};
+use nimiq_utils::time::OffsetTime;
+use parking_lot::RwLock;
+
+const VALIDATOR_ADDRESS: &str = "NQ20TSB0DFSMUH9C15GQGAGJTTE4D3MA859E";
+
+fn new_blockchain() -> Arc<RwLock<Blockchain>> {
+    let time = Arc::new(OffsetTime::new());
+    let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+    Arc::new(RwLock::new(
+        Blockchain::new(
+            env,
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            time,
+        )
+        .unwrap(),
+    ))
+}
+
+fn expected_block(bc: &Blockchain, block_number: u32) -> ScheduledBlock {
+    ScheduledBlock {
+        block_number,
+        timestamp: bc.timestamp()
+            + u64::from(block_number - bc.block_number()) * Policy::BLOCK_SEPARATION_TIME,
+    }
+}
+
+#[test]
+fn it_schedules_the_slots_of_a_validator() {
+    let blockchain = new_blockchain();
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    let validator_address = Address::from_any_str(VALIDATOR_ADDRESS).unwrap();
+    push_micro_block(&producer, &blockchain);
+
+    let bc = blockchain.read();
+    let next_block_number = bc.block_number() + 1;
+    let epoch_number = Policy::epoch_at(next_block_number);
+    let schedule = bc.get_slot_schedule(&validator_address).unwrap();
+    assert_eq!(schedule.validator_address, validator_address);
+    assert_eq!(schedule.epoch_number, epoch_number);
+    assert_eq!(schedule.slots, 0..Policy::SLOTS);
+    assert_eq!(schedule.first_block, expected_block(&bc, next_block_number));
+
+    // The election block is not part of the schedule.
+    assert_eq!(
+        schedule.last_block,
+        expected_block(&bc, Policy::election_block_of(epoch_number).unwrap() - 1)
+    );
+
+    // The only validator proposes the next block. The proposers of later blocks are not known yet.
+    assert_eq!(
+        schedule.proposer_blocks,
+        vec![expected_block(&bc, next_block_number)]
+    );
+    assert_eq!(
+        schedule.proposers_unknown_from,
+        Some(expected_block(&bc, next_block_number + 1))
+    );
+
+    // The next epoch isn't elected yet.
+    assert_eq!(
+        schedule.slots_unknown_from,
+        expected_block(&bc, Policy::first_block_of(epoch_number + 1).unwrap())
+    );
+
+    assert!(bc.get_slot_schedule(&Address::from([0x77; 20])).is_none());
+}
+
+#[test]
+fn it_schedules_the_elected_epoch_after_the_election_block() {
+    let blockchain = new_blockchain();
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    let validator_address = Address::from_any_str(VALIDATOR_ADDRESS).unwrap();
+    produce_macro_blocks(&producer, &blockchain, Policy::batches_per_epoch() as usize);
+
+    let bc = blockchain.read();
+    assert!(Policy::is_election_block_at(bc.block_number()));
+    let epoch_number = Policy::epoch_at(bc.block_number()) + 1;
+    let schedule = bc.get_slot_schedule(&validator_address).unwrap();
+    assert_eq!(schedule.epoch_number, epoch_number);
+    assert_eq!(
+        schedule.first_block,
+        expected_block(&bc, Policy::first_block_of(epoch_number).unwrap())
+    );
+    assert_eq!(
+        schedule.last_block,
+        expected_block(&bc, Policy::election_block_of(epoch_number).unwrap() - 1)
+    );
+    assert_eq!(
+        schedule.slots_unknown_from,
+        expected_block(&bc, Policy::first_block_of(epoch_number + 1).unwrap())
+    );
+}
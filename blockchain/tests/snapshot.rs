@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use nimiq_blockchain::{
+    interface::HistoryInterface, BlockProducer, Blockchain, BlockchainConfig, SnapshotError,
+};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::mdbx::MdbxDatabase;
+use nimiq_genesis::NetworkId;
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{
+    fill_micro_blocks_with_txns, produce_macro_blocks, produce_macro_blocks_with_txns, signing_key,
+    voting_key,
+};
+use nimiq_utils::time::OffsetTime;
+use parking_lot::RwLock;
+
+fn new_blockchain(keep_history: bool) -> Arc<RwLock<Blockchain>> {
+    let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+    let config = BlockchainConfig {
+        keep_history,
+        index_history: keep_history,
+        ..Default::default()
+    };
+    Arc::new(RwLock::new(
+        Blockchain::new(
+            env,
+            config,
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ))
+}
+
+/// Produces two epochs and a checkpoint block with transactions, followed by a few micro blocks.
+fn produce_chain() -> Arc<RwLock<Blockchain>> {
+    let blockchain = new_blockchain(true);
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks_with_txns(
+        &producer,
+        &blockchain,
+        (2 * Policy::batches_per_epoch() + 1) as usize,
+        1,
+        1,
+    );
+    fill_micro_blocks_with_txns(&producer, &blockchain, 1, 2);
+    assert!(!blockchain.read().head().is_macro());
+    blockchain
+}
+
+fn export(blockchain: &Arc<RwLock<Blockchain>>, include_history: bool) -> Vec<u8> {
+    let mut snapshot = vec![];
+    blockchain
+        .read()
+        .export_snapshot(&mut snapshot, include_history)
+        .unwrap();
+    snapshot
+}
+
+#[test]
+fn snapshot_with_history_can_be_imported() {
+    let blockchain = produce_chain();
+    let snapshot = export(&blockchain, true);
+
+    // The micro blocks after the macro head are not part of the snapshot.
+    let head = blockchain.read().head().clone();
+    let macro_head = blockchain.read().macro_head().clone();
+    let state_root = macro_head.header.state_root.clone();
+    assert_ne!(head.hash(), macro_head.hash());
+
+    let imported = new_blockchain(true);
+    let header = Blockchain::import_snapshot(&imported, &mut &snapshot[..], None).unwrap();
+    assert_eq!(header.num_epochs, 3);
+
+    let imported = imported.read();
+    assert_eq!(imported.head_hash(), macro_head.hash());
+    assert_eq!(
+        imported.election_head_hash(),
+        blockchain.read().election_head_hash()
+    );
+    assert_eq!(
+        imported.state.accounts.get_root_hash(None),
+        Some(state_root)
+    );
+    let epoch = header.election_block.epoch_number();
+    assert_eq!(
+        imported.history_store.get_epoch_transactions(epoch, None),
+        blockchain
+            .read()
+            .history_store
+            .get_epoch_transactions(epoch, None)
+    );
+}
+
+#[test]
+fn snapshot_can_be_imported_without_history() {
+    let blockchain = produce_chain();
+    let snapshot = export(&blockchain, false);
+    let macro_head = blockchain.read().macro_head().clone();
+
+    let election_hash = blockchain.read().election_head_hash();
+
+    // Nodes without history only adopt a trusted election block.
+    let imported = new_blockchain(false);
+    assert!(matches!(
+        Blockchain::import_snapshot(&imported, &mut &snapshot[..], None),
+        Err(SnapshotError::UntrustedElectionBlock(_))
+    ));
+    assert!(matches!(
+        Blockchain::import_snapshot(&imported, &mut &snapshot[..], Some(&macro_head.hash())),
+        Err(SnapshotError::UntrustedElectionBlock(_))
+    ));
+    assert_eq!(
+        imported.read().block_number(),
+        Policy::genesis_block_number()
+    );
+
+    let header =
+        Blockchain::import_snapshot(&imported, &mut &snapshot[..], Some(&election_hash)).unwrap();
+    assert_eq!(header.num_epochs, 0);
+
+    let imported = imported.read();
+    assert_eq!(imported.head_hash(), macro_head.hash());
+    assert!(imported.state.accounts.is_complete(None));
+    assert_eq!(
+        imported.state.accounts.get_root_hash(None),
+        Some(macro_head.header.state_root)
+    );
+
+    // The snapshot does not contain the history a history node needs.
+    let imported = new_blockchain(true);
+    assert!(matches!(
+        Blockchain::import_snapshot(&imported, &mut &snapshot[..], Some(&election_hash)),
+        Err(SnapshotError::HistoryUnavailable)
+    ));
+}
+
+#[test]
+fn snapshot_can_only_be_imported_into_empty_database() {
+    let blockchain = produce_chain();
+    let snapshot = export(&blockchain, true);
+
+    let imported = new_blockchain(true);
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks(&producer, &imported, 1);
+
+    assert!(matches!(
+        Blockchain::import_snapshot(&imported, &mut &snapshot[..], None),
+        Err(SnapshotError::DatabaseNotEmpty)
+    ));
+}
+
+#[test]
+fn truncated_snapshot_is_rejected() {
+    let blockchain = produce_chain();
+    let snapshot = export(&blockchain, false);
+
+    let imported = new_blockchain(false);
+    assert!(matches!(
+        Blockchain::import_snapshot(
+            &imported,
+            &mut &snapshot[..snapshot.len() - 1],
+            Some(&blockchain.read().election_head_hash()),
+        ),
+        Err(SnapshotError::Io(_))
+    ));
+}
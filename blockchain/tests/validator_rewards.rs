@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use nimiq_blockchain::{BlockProducer, Blockchain, BlockchainConfig, ValidatorRewardsError};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::mdbx::MdbxDatabase;
+use nimiq_genesis::NetworkId;
+use nimiq_keys::Address;
+use nimiq_primitives::{coin::Coin, policy::Policy};
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{produce_macro_blocks, signing_key, voting_key};
+use nimiq_utils::time::OffsetTime;
+use parking_lot::RwLock;
+
+const VALIDATOR_ADDRESS: &str = "NQ20TSB0DFSMUH9C15GQGAGJTTE4D3MA859E";
+const STAKER_ADDRESS: &str = "NQ39VBTNP2HXQ3MFKHF1CCLAG6FS9B8SVY28";
+
+fn new_blockchain() -> Arc<RwLock<Blockchain>> {
+    let time = Arc::new(OffsetTime::new());
+    let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+    let config = BlockchainConfig {
+        archive_accounts: true,
+        ..Default::default()
+    };
+    Arc::new(RwLock::new(
+        Blockchain::new(env, config, NetworkId::UnitAlbatross, time).unwrap(),
+    ))
+}
+
+#[test]
+fn it_can_account_for_validator_rewards() {
+    let blockchain = new_blockchain();
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    let epoch_number = Policy::epoch_at(blockchain.read().get_genesis_block_number()) + 1;
+    let validator_address = Address::from_any_str(VALIDATOR_ADDRESS).unwrap();
+
+    // Complete the epoch and the first batch of the next one, which pays out the last reward.
+    produce_macro_blocks(&producer, &blockchain, Policy::batches_per_epoch() as usize);
+    {
+        let bc = blockchain.read();
+        let txn = bc.read_transaction();
+        assert_eq!(
+            bc.get_validator_rewards_for_epoch(&validator_address, epoch_number, &txn),
+            Err(ValidatorRewardsError::NotPaidOut(epoch_number))
+        );
+    }
+    produce_macro_blocks(&producer, &blockchain, 1);
+
+    let bc = blockchain.read();
+    let txn = bc.read_transaction();
+    let rewards = bc
+        .get_validator_rewards_for_epoch(&validator_address, epoch_number, &txn)
+        .unwrap();
+
+    assert_eq!(rewards.slots, 0..Policy::SLOTS);
+    assert_eq!(rewards.batches.len(), Policy::batches_per_epoch() as usize);
+    for batch in &rewards.batches {
+        assert_eq!(
+            batch.payout_block_number,
+            Policy::macro_block_of(batch.batch_number + 1).unwrap()
+        );
+    }
+    assert!(!rewards.total_reward.is_zero());
+    assert_eq!(
+        rewards.total_reward,
+        rewards
+            .batches
+            .iter()
+            .fold(Coin::ZERO, |total, batch| total + batch.reward)
+    );
+    assert!(rewards.penalties.is_empty());
+    assert!(rewards.jails.is_empty());
+
+    assert_eq!(rewards.stakers.len(), 1);
+    let staker = &rewards.stakers[0];
+    let active_balance = Coin::from_u64_unchecked(100_000);
+    assert_eq!(
+        staker.address,
+        Address::from_any_str(STAKER_ADDRESS).unwrap()
+    );
+    assert_eq!(staker.active_balance, active_balance);
+    assert_eq!(rewards.total_stake, rewards.deposit + active_balance);
+    assert_eq!(
+        u64::from(staker.reward),
+        (u128::from(u64::from(rewards.total_reward)) * u128::from(u64::from(active_balance))
+            / u128::from(u64::from(rewards.total_stake))) as u64
+    );
+
+    assert_eq!(
+        bc.get_validator_rewards_for_epoch(&Address::START_ADDRESS, epoch_number, &txn),
+        Err(ValidatorRewardsError::NotElected(
+            Address::START_ADDRESS,
+            epoch_number
+        ))
+    );
+    assert_eq!(
+        bc.get_validator_rewards_for_epoch(&validator_address, epoch_number + 1, &txn),
+        Err(ValidatorRewardsError::NotPaidOut(epoch_number + 1))
+    );
+}
@@ -4,7 +4,11 @@ use log::info;
 use nimiq::prover::prover_main;
 pub use nimiq::{
     client::Client,
-    config::{command_line::CommandLine, config::ClientConfig, config_file::ConfigFile},
+    config::{
+        command_line::{Command, CommandLine},
+        config::ClientConfig,
+        config_file::ConfigFile,
+    },
     error::Error,
     extras::{
         logging::{initialize_logging, log_error_cause_chain},
@@ -59,6 +63,23 @@ async fn main_inner() -> Result<(), Error> {
     let config = builder.build()?;
     log::debug!("Final configuration: {:#?}", config);
 
+    // Snapshots are exported from and imported into the database before the client is started.
+    match &command_line.command {
+        Some(Command::ExportSnapshot {
+            output,
+            include_history,
+        }) => {
+            return nimiq::snapshot::export_snapshot(&config, output, *include_history);
+        }
+        Some(Command::ImportSnapshot {
+            input,
+            election_block_hash,
+        }) => {
+            nimiq::snapshot::import_snapshot(&config, input, election_block_hash.as_ref())?;
+        }
+        None => {}
+    }
+
     // Clone config for RPC and metrics server
     let rpc_config = config.rpc_server.clone();
     let metrics_config = config.metrics_server.clone();
@@ -68,12 +89,20 @@ async fn main_inner() -> Result<(), Error> {
     let mut client: Client = Client::from_config(config).await?;
 
     // Initialize RPC server
-    if let Some(rpc_config) = rpc_config {
+    let rpc_metrics = rpc_config.map(|rpc_config| {
         use nimiq::extras::rpc_server::initialize_rpc_server;
         let rpc_server = initialize_rpc_server(&client, rpc_config, client.wallet_store())
             .expect("Failed to initialize RPC server");
-        spawn(async move { rpc_server.run().await });
-    }
+        let rpc_metrics = rpc_server.metrics();
+        spawn(async move {
+            // The RPC server is unreachable without its gateway, so we stop the client.
+            if let Err(error) = rpc_server.run().await {
+                log_error_cause_chain(&error);
+                std::process::exit(1);
+            }
+        });
+        rpc_metrics
+    });
 
     // Vector for task monitors (Tokio task metrics)
     let mut nimiq_task_metric = vec![];
@@ -144,6 +173,8 @@ async fn main_inner() -> Result<(), Error> {
             metrics_config.addr,
             client.blockchain(),
             mempool,
+            client.validator_proxy(),
+            rpc_metrics,
             client.consensus_proxy(),
             client.network(),
             &nimiq_task_metric,
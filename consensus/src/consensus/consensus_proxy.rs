@@ -1,5 +1,6 @@
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    cmp,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -18,7 +19,11 @@ use nimiq_network_interface::{
     peer_info::Services,
     request::{OutboundRequestError, RequestError},
 };
-use nimiq_primitives::{key_nibbles::KeyNibbles, policy::Policy};
+use nimiq_primitives::{
+    compact_filter::{batch_filter_key, CompactFilter},
+    key_nibbles::KeyNibbles,
+    policy::Policy,
+};
 use nimiq_transaction::{
     historic_transaction::HistoricTransaction, ControlTransaction, ControlTransactionTopic,
     Transaction, TransactionTopic,
@@ -34,8 +39,9 @@ use crate::{
     consensus::remote_data_store::RemoteDataStore,
     messages::{
         AddressNotification, AddressSubscriptionOperation, AddressSubscriptionTopic,
-        RequestBlocksProof, RequestSubscribeToAddress, RequestTransactionReceiptsByAddress,
-        RequestTransactionsProof, ResponseBlocksProof,
+        RequestBatchFilters, RequestBlocksProof, RequestSubscribeToAddress,
+        RequestTransactionReceiptsByAddress, RequestTransactionsProof, ResponseBlocksProof,
+        MAX_BATCH_FILTERS_PER_REQUEST,
     },
     ConsensusEvent,
 };
@@ -432,6 +438,116 @@ impl<N: Network> ConsensusProxy<N> {
         Ok(transactions)
     }
 
+    /// Returns the batches in `start_batch..start_batch + count` whose compact filter matches any
+    /// of the given addresses. The filters are requested from `min_peers` history nodes and the
+    /// batches matched by the filters of any of them are returned, such that a peer can add
+    /// batches to the result but can't hide any. The result may contain false positives.
+    ///
+    /// The filter headers are not committed to on-chain, so the filters can't be verified. The
+    /// result is only complete if at least one of the peers that responded is honest.
+    pub async fn request_batches_by_addresses(
+        &self,
+        addresses: Vec<Address>,
+        start_batch: u32,
+        count: u32,
+        min_peers: usize,
+    ) -> Result<Vec<u32>, RequestError> {
+        let mut batches = BTreeSet::new();
+        let mut num_responses = 0;
+
+        for peer_id in self
+            .get_peers_for_service(Services::HISTORY, min_peers)
+            .await?
+        {
+            let filters = match self
+                .request_batch_filters(peer_id, start_batch, count)
+                .await
+            {
+                Ok(filters) => filters,
+                Err(error) => {
+                    log::error!(peer = %peer_id, err = %error, "There was an error requesting batch filters from peer");
+                    continue;
+                }
+            };
+
+            batches.extend(
+                filters
+                    .iter()
+                    .zip(start_batch..)
+                    .filter(|(filter, batch)| {
+                        // A malformed filter can't rule out the batch.
+                        filter
+                            .matches_any(
+                                &batch_filter_key(*batch),
+                                addresses.iter().map(Address::as_bytes),
+                            )
+                            .unwrap_or(true)
+                    })
+                    .map(|(_, batch)| batch),
+            );
+            num_responses += 1;
+        }
+
+        if num_responses == 0 {
+            return Err(RequestError::OutboundRequest(
+                OutboundRequestError::NoReceiver,
+            ));
+        }
+        // A single response could omit batches, so we need multiple independent ones.
+        if num_responses < min_peers {
+            return Err(RequestError::OutboundRequest(OutboundRequestError::Other(
+                format!("Only {num_responses} of {min_peers} peers provided batch filters"),
+            )));
+        }
+
+        Ok(batches.into_iter().collect())
+    }
+
+    /// Requests the filters of the given batches from a peer, split into multiple requests if
+    /// necessary. Verifies that the responses form a consistent chain of filter headers.
+    async fn request_batch_filters(
+        &self,
+        peer_id: N::PeerId,
+        start_batch: u32,
+        count: u32,
+    ) -> Result<Vec<CompactFilter>, RequestError> {
+        let mut filters = Vec::with_capacity(count as usize);
+        let mut last_header = None;
+        let end_batch = start_batch.saturating_add(count);
+
+        for batch in (start_batch..end_batch).step_by(MAX_BATCH_FILTERS_PER_REQUEST as usize) {
+            let count = cmp::min(MAX_BATCH_FILTERS_PER_REQUEST, end_batch - batch);
+            let response = self
+                .network
+                .request::<RequestBatchFilters>(
+                    RequestBatchFilters {
+                        start_batch: batch,
+                        count,
+                    },
+                    peer_id,
+                )
+                .await?
+                .map_err(|error| {
+                    RequestError::OutboundRequest(OutboundRequestError::Other(error.to_string()))
+                })?;
+
+            if response.filters.len() != count as usize
+                || last_header
+                    .as_ref()
+                    .is_some_and(|last_header| *last_header != response.prev_header)
+            {
+                return Err(RequestError::OutboundRequest(OutboundRequestError::Other(
+                    "Inconsistent batch filters".to_string(),
+                )));
+            }
+
+            last_header = Some(response.last_header());
+            filters.extend(response.filters);
+        }
+
+        Ok(filters)
+    }
+
     /// Gets a set of accounts given their addresses. The returned type is a
     /// BTreeMap of addresses to an optional `Account`. If an account was not
     /// found, then `None` is returned in its corresponding entry.
@@ -41,7 +41,7 @@ use crate::{
 #[cfg(feature = "full")]
 use crate::{
     messages::{
-        RequestBatchSet, RequestBlocksProof, RequestHistoryChunk,
+        RequestBatchFilters, RequestBatchSet, RequestBlocksProof, RequestHistoryChunk,
         RequestTransactionReceiptsByAddress, RequestTransactionsProof, RequestTrieProof,
     },
     sync::live::{diff_queue::RequestTrieDiff, state_queue::RequestChunk},
@@ -293,6 +293,12 @@ impl<N: Network> Consensus<N> {
 
                 let stream = network.receive_requests::<RequestBlocksProof>();
                 spawn(Box::pin(request_handler(network, stream, blockchain)));
+
+                // Only spawn this handler if batch filters are kept.
+                if blockchain.read().batch_filter_store.is_some() {
+                    let stream = network.receive_requests::<RequestBatchFilters>();
+                    spawn(Box::pin(request_handler(network, stream, blockchain)));
+                }
             }
             BlockchainProxy::Light(_) => {}
         }
@@ -8,7 +8,7 @@ use nimiq_block::BlockInclusionProof;
 #[cfg(feature = "full")]
 use nimiq_blockchain::interface::{HistoryIndexInterface, HistoryInterface};
 #[cfg(feature = "full")]
-use nimiq_blockchain::{Blockchain, CHUNK_SIZE};
+use nimiq_blockchain::{BatchFilterError, Blockchain, CHUNK_SIZE};
 #[cfg(feature = "full")]
 use nimiq_blockchain_interface::BlockchainError;
 use nimiq_blockchain_interface::{AbstractBlockchain, Direction};
@@ -705,3 +705,32 @@ impl<N: Network> Handle<N, Arc<RwLock<Blockchain>>> for RequestBlocksProof {
         })
     }
 }
+
+#[cfg(feature = "full")]
+impl<N: Network> Handle<N, Arc<RwLock<Blockchain>>> for RequestBatchFilters {
+    fn handle(
+        &self,
+        _peer_id: N::PeerId,
+        blockchain: &Arc<RwLock<Blockchain>>,
+    ) -> Result<ResponseBatchFilters, ResponseBatchFiltersError> {
+        if self.count > MAX_BATCH_FILTERS_PER_REQUEST {
+            return Err(ResponseBatchFiltersError::TooManyFilters);
+        }
+
+        let blockchain = blockchain.read();
+        let txn = blockchain.read_transaction();
+        match blockchain.get_batch_filters(self.start_batch, self.count, &txn) {
+            Ok((prev_header, filters)) => Ok(ResponseBatchFilters {
+                prev_header,
+                filters: filters
+                    .into_iter()
+                    .map(|batch_filter| batch_filter.filter)
+                    .collect(),
+            }),
+            Err(BatchFilterError::MissingFilter(batch)) => {
+                Err(ResponseBatchFiltersError::MissingFilter(batch))
+            }
+            Err(BatchFilterError::Disabled) => Err(ResponseBatchFiltersError::Other),
+        }
+    }
+}
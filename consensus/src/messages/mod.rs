@@ -18,7 +18,9 @@ use nimiq_network_interface::{
     network::Topic,
     request::{RequestCommon, RequestMarker},
 };
-use nimiq_primitives::{key_nibbles::KeyNibbles, trie::trie_proof::TrieProof};
+use nimiq_primitives::{
+    compact_filter::CompactFilter, key_nibbles::KeyNibbles, trie::trie_proof::TrieProof,
+};
 use nimiq_serde::{Deserialize, Serialize, SerializedMaxSize};
 use nimiq_transaction::{
     historic_transaction::HistoricTransaction, history_proof::HistoryTreeProof,
@@ -179,6 +181,10 @@ pub const MAX_REQUEST_TRANSACTIONS_BY_ADDRESS: u32 = 1000;
 pub const MAX_REQUEST_TRIE_PROOF: u32 = 1000;
 /// The max number of Block proof requests per peer.
 pub const MAX_REQUEST_BLOCKS_PROOF: u32 = 1000;
+/// The max number of Batch filters requests per peer.
+pub const MAX_REQUEST_BATCH_FILTERS: u32 = 1000;
+/// The max number of filters returned in response to a single Batch filters request.
+pub const MAX_BATCH_FILTERS_PER_REQUEST: u32 = 100;
 /// The max number of Subscribe to address requests per peer.
 pub const MAX_REQUEST_SUBSCRIBE_BY_ADDRESS: u32 = 10;
 /// The max number of Address notifications per peer.
@@ -607,6 +613,52 @@ impl RequestCommon for RequestBlocksProof {
     const MAX_REQUESTS: u32 = MAX_REQUEST_BLOCKS_PROOF;
 }
 
+/// Request the compact address filters of `count` consecutive batches starting at `start_batch`.
+/// Filters are only served by history nodes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestBatchFilters {
+    pub start_batch: u32,
+    pub count: u32,
+}
+
+impl RequestCommon for RequestBatchFilters {
+    type Kind = RequestMarker;
+    const TYPE_ID: u16 = 219;
+    type Response = Result<ResponseBatchFilters, ResponseBatchFiltersError>;
+    const MAX_REQUESTS: u32 = MAX_REQUEST_BATCH_FILTERS;
+}
+
+/// Response to [`RequestBatchFilters`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResponseBatchFilters {
+    /// The header of the filter preceding `start_batch`.
+    pub prev_header: Blake2bHash,
+    /// The filters of the requested batches, in ascending order.
+    pub filters: Vec<CompactFilter>,
+}
+
+impl ResponseBatchFilters {
+    /// Returns the header of the last filter in the response.
+    pub fn last_header(&self) -> Blake2bHash {
+        self.filters
+            .iter()
+            .fold(self.prev_header.clone(), |prev_header, filter| {
+                filter.header(&prev_header)
+            })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Error, Serialize)]
+pub enum ResponseBatchFiltersError {
+    #[error("too many filters requested")]
+    TooManyFilters,
+    #[error("missing filter for batch {0}")]
+    MissingFilter(u32),
+    #[error("unknown error")]
+    #[serde(other)]
+    Other,
+}
+
 /// Operations supported for the transaction address subscription
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 #[repr(u8)]
@@ -4,11 +4,11 @@ use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_hash::Blake2bHash;
 use nimiq_network_interface::{
-    network::{CloseReason, Network},
+    network::{BanReason, CloseReason, Network},
     request::RequestError,
 };
 use nimiq_primitives::policy::Policy;
-use nimiq_utils::WakerExt as _;
+use nimiq_utils::{spawn, WakerExt as _};
 use parking_lot::RwLock;
 
 use crate::{
@@ -77,7 +77,7 @@ impl<TNetwork: Network> HistoryMacroSync<TNetwork> {
                         "Banning peer because requesting macro chain failed: too many epochs returned"
                     );
                     network
-                        .disconnect_peer(peer_id, CloseReason::MaliciousPeer)
+                        .ban_peer(peer_id, BanReason::InvalidMacroChain)
                         .await;
                     return None;
                 }
@@ -107,7 +107,7 @@ impl<TNetwork: Network> HistoryMacroSync<TNetwork> {
                             "Banning peer because requesting macro chain failed: invalid checkpoint",
                         );
                         network
-                            .disconnect_peer(peer_id, CloseReason::MaliciousPeer)
+                            .ban_peer(peer_id, BanReason::InvalidMacroChain)
                             .await;
                         return None;
                     }
@@ -177,7 +177,6 @@ impl<TNetwork: Network> HistoryMacroSync<TNetwork> {
                 let peers_epoch_id =
                     &epoch_ids.ids[our_epoch_number - epoch_ids.first_epoch_number];
                 if our_epoch_id != *peers_epoch_id {
-                    // TODO Actually ban the peer.
                     debug!(
                         our_epoch_number,
                         %our_epoch_id,
@@ -185,6 +184,13 @@ impl<TNetwork: Network> HistoryMacroSync<TNetwork> {
                         peer = %epoch_ids.sender,
                         "Peer is on a different chain"
                     );
+                    let network = Arc::clone(&self.network);
+                    let peer_id = epoch_ids.sender;
+                    spawn(async move {
+                        network
+                            .ban_peer(peer_id, BanReason::InvalidMacroChain)
+                            .await;
+                    });
                     return Some(epoch_ids.sender);
                 }
 
@@ -6,12 +6,13 @@ use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_hash::Blake2bHash;
 use nimiq_network_interface::{
-    network::{CloseReason, Network},
+    network::{BanReason, CloseReason, Network},
     request::{
         InboundRequestError::SenderFutureDropped, RequestError, RequestError::InboundRequest,
     },
 };
 use nimiq_primitives::policy::Policy;
+use nimiq_utils::spawn;
 use nimiq_zkp_component::{
     types::{Error, ZKPRequestEvent},
     zkp_component::ZKPComponentProxy,
@@ -101,7 +102,7 @@ impl<TNetwork: Network> LightMacroSync<TNetwork> {
                         "Banning peer because requesting macro chain failed: too many epochs returned"
                     );
                     network
-                        .disconnect_peer(peer_id, CloseReason::MaliciousPeer)
+                        .ban_peer(peer_id, BanReason::InvalidMacroChain)
                         .await;
                     return None;
                 }
@@ -123,7 +124,7 @@ impl<TNetwork: Network> LightMacroSync<TNetwork> {
                             "Banning peer because requesting macro chain failed: invalid checkpoint"
                         );
                         network
-                            .disconnect_peer(peer_id, CloseReason::MaliciousPeer)
+                            .ban_peer(peer_id, BanReason::InvalidMacroChain)
                             .await;
                         return None;
                     }
@@ -211,7 +212,6 @@ impl<TNetwork: Network> LightMacroSync<TNetwork> {
                 let peers_epoch_id =
                     &epoch_ids.ids[our_epoch_number - epoch_ids.first_epoch_number];
                 if our_epoch_id != *peers_epoch_id {
-                    // TODO Actually ban the peer.
                     debug!(
                         our_epoch_number,
                         %our_epoch_id,
@@ -219,6 +219,13 @@ impl<TNetwork: Network> LightMacroSync<TNetwork> {
                         peer = %epoch_ids.sender,
                         "Peer is on a different chain"
                     );
+                    let network = Arc::clone(&self.network);
+                    let peer_id = epoch_ids.sender;
+                    spawn(async move {
+                        network
+                            .ban_peer(peer_id, BanReason::InvalidMacroChain)
+                            .await;
+                    });
                     return Some(epoch_ids.sender);
                 }
 
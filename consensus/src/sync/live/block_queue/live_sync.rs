@@ -74,7 +74,7 @@ impl<N: Network> LiveSyncQueue<N> for BlockQueue<N> {
             QueuedBlock::Missing(blocks) => {
                 // Pushes multiple blocks.
                 future_results.push_back(
-                    queue::push_multiple_blocks::<N>(blockchain, bls_cache, blocks)
+                    queue::push_multiple_blocks::<N>(network, blockchain, bls_cache, blocks)
                         .map(|(push_result, adopted_blocks, invalid_blocks)| {
                             PushOpResult::Missing(push_result, adopted_blocks, invalid_blocks)
                         })
@@ -5,7 +5,7 @@ use std::{
 };
 
 use futures::{future::BoxFuture, FutureExt, Stream};
-use nimiq_block::Block;
+use nimiq_block::{Block, BlockError};
 #[cfg(feature = "full")]
 use nimiq_blockchain::Blockchain;
 #[cfg(feature = "full")]
@@ -15,7 +15,7 @@ use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_bls::cache::PublicKeyCache;
 use nimiq_hash::Blake2bHash;
 use nimiq_light_blockchain::LightBlockchain;
-use nimiq_network_interface::network::{MsgAcceptance, Network};
+use nimiq_network_interface::network::{BanReason, MsgAcceptance, Network};
 use nimiq_primitives::{
     key_nibbles::KeyNibbles,
     policy::Policy,
@@ -24,6 +24,7 @@ use nimiq_primitives::{
         trie_diff::TrieDiff,
     },
 };
+use nimiq_utils::spawn;
 use parking_lot::Mutex;
 
 use crate::{
@@ -145,7 +146,6 @@ impl Default for QueueConfig {
 struct BlockchainPushResult<N: Network> {
     block_push_result: Option<Result<PushResult, PushError>>,
     push_chunks_result: Result<ChunksPushResult, ChunksPushError>,
-    #[allow(dead_code)] // TODO ban peer related
     chunk_error_peer: Option<<N as Network>::PeerId>,
     block_hash: Blake2bHash,
 }
@@ -229,9 +229,15 @@ pub async fn push_block_and_chunks<N: Network>(
         spawn_blocking(move || blockchain_push(blockchain, bls_cache, Some(block), diff, chunks))
             .await;
 
-    validate_message(network, block_source, &push_results.block_push_result);
+    validate_message(
+        Arc::clone(&network),
+        block_source,
+        &push_results.block_push_result,
+    );
 
-    // TODO Ban peer depending on type of chunk error?
+    if let Some(peer_id) = push_results.chunk_error_peer {
+        network.ban_peer(peer_id, BanReason::InvalidChunk).await;
+    }
 
     (
         push_results.block_push_result.unwrap(),
@@ -265,6 +271,7 @@ pub async fn push_block_only<N: Network>(
 /// This case is different from pushing single blocks in a for loop,
 /// because an invalid block automatically invalidates the remainder of the sequence.
 pub async fn push_multiple_blocks_impl<N: Network>(
+    network: Arc<N>,
     blockchain: BlockchainProxy,
     bls_cache: Arc<Mutex<PublicKeyCache>>,
     blocks: Vec<(BlockAndSource<N>, Option<TrieDiff>, Vec<ChunkAndSource<N>>)>,
@@ -284,7 +291,7 @@ pub async fn push_multiple_blocks_impl<N: Network>(
     let mut push_result = Err(PushError::Orphan);
     let mut push_chunk_result = Ok(ChunksPushResult::EmptyChunks);
     // Try to push blocks, until we encounter an invalid block.
-    for ((block, _), diff, mut chunks) in block_iter.by_ref() {
+    for ((block, block_source), diff, mut chunks) in block_iter.by_ref() {
         log::debug!("Pushing block {} from missing blocks response", block);
 
         let blockchain2 = blockchain.clone();
@@ -302,6 +309,10 @@ pub async fn push_multiple_blocks_impl<N: Network>(
         push_result = push_results.block_push_result.unwrap();
         let block_hash = push_results.block_hash;
 
+        if let Some(peer_id) = push_results.chunk_error_peer {
+            network.ban_peer(peer_id, BanReason::InvalidChunk).await;
+        }
+
         // The chunk result should give precedence to an error. Otherwise, if least one chunk was pushed,
         // the result should reflect that.
         // Errors cannot be overwritten because we will discard the subsequent chunks.
@@ -321,6 +332,11 @@ pub async fn push_multiple_blocks_impl<N: Network>(
         match &push_result {
             Err(e) => {
                 log::warn!("Failed to push missing block {}: {}", block_hash, e);
+                if is_invalid_block(e) {
+                    network
+                        .ban_peer(block_source.peer_id(), BanReason::InvalidBlock)
+                        .await;
+                }
                 invalid_blocks.insert(block_hash);
                 break;
             }
@@ -330,8 +346,6 @@ pub async fn push_multiple_blocks_impl<N: Network>(
         }
     }
 
-    // TODO Ban peer depending on type of chunk error?
-
     // If there are remaining blocks in the iterator, those are invalid.
     for ((block, _), ..) in block_iter {
         invalid_blocks.insert(block.hash());
@@ -345,6 +359,7 @@ pub async fn push_multiple_blocks_impl<N: Network>(
 }
 
 pub async fn push_multiple_blocks_with_chunks<N: Network>(
+    network: Arc<N>,
     blockchain: BlockchainProxy,
     bls_cache: Arc<Mutex<PublicKeyCache>>,
     blocks: Vec<(BlockAndSource<N>, Option<TrieDiff>, Vec<ChunkAndSource<N>>)>,
@@ -354,13 +369,14 @@ pub async fn push_multiple_blocks_with_chunks<N: Network>(
     Vec<Blake2bHash>,
     HashSet<Blake2bHash>,
 ) {
-    push_multiple_blocks_impl(blockchain, bls_cache, blocks).await
+    push_multiple_blocks_impl(network, blockchain, bls_cache, blocks).await
 }
 
 /// Pushes a sequence of blocks to the blockchain.
 /// This case is different from pushing single blocks in a for loop,
 /// because an invalid block automatically invalidates the remainder of the sequence.
 pub async fn push_multiple_blocks<N: Network>(
+    network: Arc<N>,
     blockchain: BlockchainProxy,
     bls_cache: Arc<Mutex<PublicKeyCache>>,
     blocks: Vec<BlockAndSource<N>>,
@@ -373,7 +389,7 @@ pub async fn push_multiple_blocks<N: Network>(
         .into_iter()
         .map(|block| (block, None, vec![]))
         .collect();
-    push_multiple_blocks_impl::<N>(blockchain, bls_cache, blocks)
+    push_multiple_blocks_impl::<N>(network, blockchain, bls_cache, blocks)
         .map(|(push_result, _, adopted_blocks, invalid_blocks)| {
             (push_result, adopted_blocks, invalid_blocks)
         })
@@ -383,6 +399,7 @@ pub async fn push_multiple_blocks<N: Network>(
 /// Pushes the chunks to the current blockchain state.
 #[cfg(feature = "full")]
 pub async fn push_chunks_only<N: Network>(
+    network: Arc<N>,
     blockchain: BlockchainProxy,
     bls_cache: Arc<Mutex<PublicKeyCache>>,
     chunks: Vec<ChunkAndSource<N>>,
@@ -390,7 +407,9 @@ pub async fn push_chunks_only<N: Network>(
     let push_results =
         spawn_blocking(move || blockchain_push(blockchain, bls_cache, None, None, chunks)).await;
 
-    // TODO Ban peer depending on type of chunk error?
+    if let Some(peer_id) = push_results.chunk_error_peer {
+        network.ban_peer(peer_id, BanReason::InvalidChunk).await;
+    }
 
     (push_results.push_chunks_result, push_results.block_hash)
 }
@@ -486,11 +505,68 @@ fn validate_message<N: Network>(
             }
             PushResult::Forked | PushResult::Ignored => MsgAcceptance::Ignore,
         },
-        Err(_) => {
-            // TODO Ban peer
+        Err(error) => {
+            if is_invalid_block(error) {
+                let network = Arc::clone(&network);
+                let peer_id = block_source.peer_id();
+                spawn(async move {
+                    network.ban_peer(peer_id, BanReason::InvalidBlock).await;
+                });
+            }
             MsgAcceptance::Reject
         }
     };
 
     block_source.validate_block(&network, acceptance);
 }
+
+/// Returns whether the push error proves that the block itself is invalid, in which case
+/// the peer that sent it is banned. Errors that depend on our local state (e.g. on the chain
+/// the block is checked against or on the accounts) don't qualify, as an honest peer might
+/// have a different view.
+fn is_invalid_block(error: &PushError) -> bool {
+    match error {
+        PushError::InvalidZKP => true,
+        PushError::InvalidBlock(error) => match error {
+            BlockError::NetworkMismatch
+            | BlockError::InvalidBlockType
+            | BlockError::UnsupportedVersion
+            | BlockError::InvalidSeed
+            | BlockError::ExtraDataTooLarge
+            | BlockError::BodyHashMismatch
+            | BlockError::SizeExceeded
+            | BlockError::MissingJustification
+            | BlockError::MissingBody
+            | BlockError::InvalidForkProof
+            | BlockError::DuplicateForkProof
+            | BlockError::ForkProofsNotOrdered
+            | BlockError::DuplicateTransaction
+            | BlockError::InvalidTransaction(_)
+            | BlockError::InvalidJustification
+            | BlockError::InvalidSkipBlockProof
+            | BlockError::InvalidSkipBlockBody => true,
+            BlockError::InvalidBlockNumber
+            | BlockError::InvalidTimestamp
+            | BlockError::InvalidParentHash
+            | BlockError::InvalidParentElectionHash
+            | BlockError::InvalidInterlink
+            | BlockError::AccountsHashMismatch
+            | BlockError::InvalidHistoryRoot
+            | BlockError::ExpiredTransaction
+            | BlockError::TransactionExecutionMismatch
+            | BlockError::InvalidValidators
+            | BlockError::InvalidRewardTransactions => false,
+        },
+        PushError::Orphan
+        | PushError::InvalidSuccessor
+        | PushError::InvalidPredecessor
+        | PushError::DuplicateTransaction
+        | PushError::InvalidEquivocationProof(_)
+        | PushError::AccountsError(_)
+        | PushError::InvalidFork
+        | PushError::BlockchainError(_)
+        | PushError::MissingAccountsTrieDiff
+        | PushError::EquivocationAlreadyIncluded(_)
+        | PushError::IncompleteAccountsTrie => false,
+    }
+}
@@ -5,8 +5,12 @@ use std::{
 };
 
 use futures::{future::BoxFuture, FutureExt, Stream, StreamExt};
-use nimiq_network_interface::{network::Network, request::RequestError};
+use nimiq_network_interface::{
+    network::{BanReason, Network},
+    request::RequestError,
+};
 use nimiq_primitives::key_nibbles::KeyNibbles;
+use nimiq_utils::spawn;
 use parking_lot::RwLock;
 
 use super::{RequestChunk, ResponseChunk};
@@ -26,7 +30,7 @@ use crate::sync::{peer_list::PeerList, sync_queue::SyncQueue};
 /// The chunks instead are returned by polling the component.
 pub struct ChunkRequestComponent<N: Network> {
     sync_queue:
-        SyncQueue<N, RequestChunk, (ResponseChunk, RequestChunk, N::PeerId), RequestError, ()>,
+        SyncQueue<N, RequestChunk, (ResponseChunk, RequestChunk, N::PeerId), RequestError, Arc<N>>,
     // These peers will be shared across the block request component and this component.
     peers: Arc<RwLock<PeerList<N>>>,
 }
@@ -36,7 +40,7 @@ impl<N: Network> ChunkRequestComponent<N> {
 
     pub fn new(network: Arc<N>, peers: Arc<RwLock<PeerList<N>>>) -> Self {
         let sync_queue = SyncQueue::with_verification(
-            network,
+            Arc::clone(&network),
             vec![],
             Arc::clone(&peers),
             Self::NUM_PENDING_CHUNKS,
@@ -48,7 +52,7 @@ impl<N: Network> ChunkRequestComponent<N> {
                 }
                 .boxed()
             },
-            |request, (response, _, peer_id), _| {
+            |request, (response, _, peer_id), network| {
                 // Verifies the response chunk size.
                 if let ResponseChunk::Chunk(ref chunk) = response {
                     if chunk.chunk.items.len() > request.limit as usize {
@@ -58,13 +62,19 @@ impl<N: Network> ChunkRequestComponent<N> {
                                 request,
                                 chunk.chunk.items.len()
                             );
-                        // TODO: Ban peer
+                        let network = Arc::clone(network);
+                        let peer_id = *peer_id;
+                        spawn(async move {
+                            network
+                                .ban_peer(peer_id, BanReason::InvalidResponse)
+                                .await;
+                        });
                         return false;
                     }
                 }
                 true
             },
-            (),
+            network,
         );
 
         ChunkRequestComponent { sync_queue, peers }
@@ -147,24 +147,26 @@ impl<N: Network> LiveSyncQueue<N> for StateQueue<N> {
             QueuedStateChunks::Missing(blocks) => {
                 // Pushes multiple blocks.
                 future_results.push_back(
-                    queue::push_multiple_blocks_with_chunks::<N>(blockchain, bls_cache, blocks)
-                        .map(
-                            |(push_result, push_chunk_error, adopted_blocks, invalid_blocks)| {
-                                PushOpResult::Missing(
-                                    push_result,
-                                    push_chunk_error,
-                                    adopted_blocks,
-                                    invalid_blocks,
-                                )
-                            },
-                        )
-                        .boxed(),
+                    queue::push_multiple_blocks_with_chunks::<N>(
+                        network, blockchain, bls_cache, blocks,
+                    )
+                    .map(
+                        |(push_result, push_chunk_error, adopted_blocks, invalid_blocks)| {
+                            PushOpResult::Missing(
+                                push_result,
+                                push_chunk_error,
+                                adopted_blocks,
+                                invalid_blocks,
+                            )
+                        },
+                    )
+                    .boxed(),
                 );
             }
             QueuedStateChunks::HeadStateChunk(chunks) => {
                 // Chunks only.
                 future_results.push_back(
-                    queue::push_chunks_only::<N>(blockchain, bls_cache, chunks)
+                    queue::push_chunks_only::<N>(network, blockchain, bls_cache, chunks)
                         .map(|(push_chunk_error, block_hash)| {
                             PushOpResult::HeadChunk(push_chunk_error, block_hash)
                         })
@@ -1,6 +1,6 @@
 use std::{str::FromStr, sync::Arc};
 
-use nimiq_blockchain::{BlockProducer, Blockchain, BlockchainConfig};
+use nimiq_blockchain::{interface::HistoryInterface, BlockProducer, Blockchain, BlockchainConfig};
 use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_bls::cache::PublicKeyCache;
 use nimiq_consensus::{sync::syncer_proxy::SyncerProxy, Consensus};
@@ -124,3 +124,97 @@ async fn test_request_transactions_by_address() {
         Policy::blocks_per_batch() - 1
     );
 }
+
+#[test(tokio::test)]
+async fn test_request_batches_by_addresses() {
+    let mut hub = MockHub::default();
+
+    // Create a history node with a full epoch. The first batch will have one tx per block.
+    let blockchain1 = Arc::new(RwLock::new(
+        Blockchain::new(
+            MdbxDatabase::new_volatile(Default::default()).unwrap(),
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            Arc::new(OffsetTime::new()),
+        )
+        .unwrap(),
+    ));
+
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    fill_micro_blocks_with_txns(&producer, &blockchain1, 1, 1);
+    let num_macro_blocks = (Policy::batches_per_epoch() + 1) as usize;
+    produce_macro_blocks(&producer, &blockchain1, num_macro_blocks);
+
+    let net1 = Arc::new(hub.new_network());
+    let zkp_prover1 =
+        ZKPComponent::new(BlockchainProxy::from(&blockchain1), Arc::clone(&net1), None)
+            .await
+            .proxy();
+    let blockchain1_proxy = BlockchainProxy::from(&blockchain1);
+
+    let syncer1 = SyncerProxy::new_history(
+        blockchain1_proxy.clone(),
+        Arc::clone(&net1),
+        Arc::new(Mutex::new(PublicKeyCache::new(
+            TESTING_BLS_CACHE_MAX_CAPACITY,
+        ))),
+        net1.subscribe_events(),
+    )
+    .await;
+
+    let _consensus1 = Consensus::from_network(
+        blockchain1_proxy.clone(),
+        Arc::clone(&net1),
+        syncer1,
+        zkp_prover1.clone(),
+    );
+
+    // Setup another node that requests the batch filters from the history node.
+    let net2 = Arc::new(hub.new_network());
+    let syncer2 = SyncerProxy::new_history(
+        blockchain1_proxy.clone(),
+        Arc::clone(&net2),
+        Arc::new(Mutex::new(PublicKeyCache::new(
+            TESTING_BLS_CACHE_MAX_CAPACITY,
+        ))),
+        net2.subscribe_events(),
+    )
+    .await;
+    let consensus2 = Consensus::from_network(
+        blockchain1_proxy.clone(),
+        Arc::clone(&net2),
+        syncer2,
+        zkp_prover1,
+    );
+    let consensus_proxy = consensus2.proxy();
+    net1.dial_mock(&net2);
+
+    // The recipient of a transaction in the first batch.
+    let first_batch = Policy::batch_at(blockchain1.read().get_genesis_block_number()) + 1;
+    let recipient = blockchain1
+        .read()
+        .history_store
+        .get_block_transactions(Policy::first_block_of_batch(first_batch).unwrap(), None)
+        .into_iter()
+        .find_map(|hist_tx| match hist_tx.data {
+            HistoricTransactionData::Basic(tx) => Some(tx.get_raw_transaction().recipient.clone()),
+            _ => None,
+        })
+        .unwrap();
+
+    let num_batches = Policy::batches_per_epoch() + 1;
+    let batches = consensus_proxy
+        .request_batches_by_addresses(vec![recipient.clone()], first_batch, num_batches, 1)
+        .await
+        .unwrap();
+    assert!(batches.contains(&first_batch));
+    assert!(batches
+        .iter()
+        .all(|batch| (first_batch..first_batch + num_batches).contains(batch)));
+
+    // A single peer can't satisfy the required number of peers.
+    assert!(consensus_proxy
+        .request_batches_by_addresses(vec![recipient], first_batch, num_batches, 2)
+        .await
+        .is_err());
+}
@@ -6,4 +6,8 @@ pub enum Error {
     CreateDirectory(#[from] std::io::Error),
     #[error("Mdbx error: {0}")]
     Mdbx(#[from] libmdbx::Error),
+    #[error("Database schema version {0} is newer than the latest supported version {1}")]
+    UnsupportedSchemaVersion(u32, u32),
+    #[error("Migration to schema version {0} failed: {1}")]
+    Migration(u32, String),
 }
@@ -1,5 +1,9 @@
 mod error;
 pub mod mdbx;
+/// In-memory implementation of the database traits.
+pub mod memory;
+/// Versioning of the database schema and migrations between versions.
+pub mod migration;
 /// Abstraction for methods related to the database.
 pub mod traits;
 pub mod utils;
@@ -34,7 +34,7 @@ pub struct DatabaseConfig {
 impl Default for DatabaseConfig {
     fn default() -> Self {
         DatabaseConfig {
-            max_tables: Some(20),
+            max_tables: Some(32),
             max_readers: None,
             no_rdahead: true,
             // Default max database size: 2TB
@@ -145,6 +145,13 @@ impl Database for MdbxDatabase {
         self.create_table(table, dup_flags)
     }
 
+    fn is_empty(&self) -> bool {
+        // The names of the tables are stored in the unnamed main table.
+        let txn = self.db.begin_ro_txn().unwrap();
+        let main_table = txn.open_table(None).unwrap();
+        txn.table_stat(&main_table).unwrap().entries() == 0
+    }
+
     fn read_transaction(&self) -> Self::ReadTransaction<'_> {
         MdbxReadTransaction::new_read(self.db.begin_ro_txn().unwrap())
     }
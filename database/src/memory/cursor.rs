@@ -0,0 +1,272 @@
+use std::{cell::RefCell, marker::PhantomData};
+
+use nimiq_database_value::{AsDatabaseBytes, FromDatabaseBytes};
+
+use super::{
+    decode_key, encode_key, MemoryIntoIter, MemoryReadTransaction, RawRow, TableData, Tables,
+};
+use crate::traits::{
+    DupReadCursor, DupSubKey, DupTable, DupTableValue, DupWriteCursor, ReadCursor, Row, Table,
+    WriteCursor,
+};
+
+/// A cursor for navigating the entries within a table of a `MemoryDatabase`.
+///
+/// The cursor remembers the entry it is positioned at. If this entry is removed,
+/// the cursor stays at its position, such that `next` and `prev` continue with its neighbours.
+pub struct MemoryCursor<'txn, T: Table> {
+    tables: &'txn RefCell<Tables>,
+    position: Option<RawRow>,
+    _table: PhantomData<T>,
+}
+
+impl<'txn, T: Table> MemoryCursor<'txn, T> {
+    pub(super) fn new(tables: &'txn RefCell<Tables>) -> Self {
+        MemoryCursor {
+            tables,
+            position: None,
+            _table: PhantomData,
+        }
+    }
+
+    pub(super) fn decode_row((key, value): RawRow) -> Row<T> {
+        (
+            decode_key::<T>(&key),
+            FromDatabaseBytes::from_value_bytes(&value),
+        )
+    }
+
+    /// Moves the cursor relative to its current position.
+    /// The cursor keeps its position if there is no such entry.
+    pub(super) fn move_by(
+        &mut self,
+        f: impl FnOnce(&TableData, &RawRow) -> Option<RawRow>,
+    ) -> Option<RawRow> {
+        let row = {
+            let tables = self.tables.borrow();
+            f(
+                MemoryReadTransaction::open_table::<T>(&tables),
+                self.position.as_ref()?,
+            )?
+        };
+        self.position = Some(row.clone());
+        Some(row)
+    }
+
+    /// Positions the cursor at the entry found by `f`.
+    /// The cursor becomes unpositioned if there is no such entry.
+    pub(super) fn seek(&mut self, f: impl FnOnce(&TableData) -> Option<RawRow>) -> Option<RawRow> {
+        self.position = {
+            let tables = self.tables.borrow();
+            f(MemoryReadTransaction::open_table::<T>(&tables))
+        };
+        self.position.clone()
+    }
+
+    fn update(&mut self, f: impl FnOnce(&mut TableData)) {
+        let mut tables = self.tables.borrow_mut();
+        f(MemoryReadTransaction::open_table_mut::<T>(&mut tables));
+    }
+
+    fn encode_value(value: &T::Value) -> Vec<u8> {
+        AsDatabaseBytes::as_value_bytes(value).into_owned()
+    }
+}
+
+impl<'txn, T: DupTable> MemoryCursor<'txn, T>
+where
+    T::Value: DupTableValue,
+{
+    fn encode_subkey(subkey: &DupSubKey<T>) -> Vec<u8> {
+        let mut enc_key = subkey.as_value_bytes().into_owned();
+        if let Some(new_len) = T::Value::FIXED_SIZE {
+            enc_key.resize(new_len, 0);
+        }
+        enc_key
+    }
+}
+
+impl<'txn, T: Table> Clone for MemoryCursor<'txn, T> {
+    fn clone(&self) -> Self {
+        Self {
+            tables: self.tables,
+            position: self.position.clone(),
+            _table: PhantomData,
+        }
+    }
+}
+
+impl<'txn, T: Table> ReadCursor<'txn, T> for MemoryCursor<'txn, T> {
+    type IntoIter = MemoryIntoIter<'txn, T>;
+
+    fn first(&mut self) -> Option<Row<T>> {
+        self.seek(TableData::first).map(Self::decode_row)
+    }
+
+    fn last(&mut self) -> Option<Row<T>> {
+        self.seek(TableData::last).map(Self::decode_row)
+    }
+
+    fn next(&mut self) -> Option<Row<T>> {
+        if self.position.is_none() {
+            return self.first();
+        }
+        self.move_by(TableData::next).map(Self::decode_row)
+    }
+
+    fn prev(&mut self) -> Option<Row<T>> {
+        if self.position.is_none() {
+            return self.last();
+        }
+        self.move_by(TableData::prev).map(Self::decode_row)
+    }
+
+    fn get_current(&mut self) -> Option<Row<T>> {
+        self.position.clone().map(Self::decode_row)
+    }
+
+    fn set_key(&mut self, key: &T::Key) -> Option<T::Value> {
+        let key = encode_key::<T>(key);
+        let (_, value) = self.seek(|table| table.first_duplicate(&key))?;
+        Some(FromDatabaseBytes::from_value_bytes(&value))
+    }
+
+    fn set_lowerbound_key(&mut self, key: &T::Key) -> Option<Row<T>> {
+        let key = encode_key::<T>(key);
+        self.seek(|table| table.lowerbound_key(&key))
+            .map(Self::decode_row)
+    }
+
+    fn into_iter_start(mut self) -> Self::IntoIter {
+        let first = self.seek(TableData::first);
+        MemoryIntoIter::new(self, first, false)
+    }
+
+    fn into_iter_from(mut self, key: &T::Key) -> Self::IntoIter {
+        let key = encode_key::<T>(key);
+        let first = self.seek(|table| table.lowerbound_key(&key));
+        MemoryIntoIter::new(self, first, false)
+    }
+}
+
+impl<'txn, T: DupTable> DupReadCursor<'txn, T> for MemoryCursor<'txn, T> {
+    fn first_duplicate(&mut self) -> Option<T::Value> {
+        let (_, value) = self.move_by(|table, (key, _)| table.first_duplicate(key))?;
+        Some(FromDatabaseBytes::from_value_bytes(&value))
+    }
+
+    fn last_duplicate(&mut self) -> Option<T::Value> {
+        let (_, value) = self.move_by(|table, (key, _)| table.last_duplicate(key))?;
+        Some(FromDatabaseBytes::from_value_bytes(&value))
+    }
+
+    fn next_duplicate(&mut self) -> Option<Row<T>> {
+        self.move_by(TableData::next_duplicate)
+            .map(Self::decode_row)
+    }
+
+    fn next_no_duplicate(&mut self) -> Option<Row<T>> {
+        if self.position.is_none() {
+            return self.first();
+        }
+        self.move_by(|table, (key, _)| table.next_no_duplicate(key))
+            .map(Self::decode_row)
+    }
+
+    fn prev_duplicate(&mut self) -> Option<Row<T>> {
+        self.move_by(TableData::prev_duplicate)
+            .map(Self::decode_row)
+    }
+
+    fn prev_no_duplicate(&mut self) -> Option<Row<T>> {
+        if self.position.is_none() {
+            return self.last();
+        }
+        self.move_by(|table, (key, _)| table.prev_no_duplicate(key))
+            .map(Self::decode_row)
+    }
+
+    fn set_subkey(&mut self, key: &T::Key, subkey: &DupSubKey<T>) -> Option<T::Value>
+    where
+        T::Value: DupTableValue,
+    {
+        let value = self.set_lowerbound_subkey(key, subkey)?;
+        if value.subkey() == subkey {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    fn set_lowerbound_both(&mut self, key: &T::Key, subkey: &DupSubKey<T>) -> Option<Row<T>>
+    where
+        T::Value: DupTableValue,
+    {
+        let key = encode_key::<T>(key);
+        let data = Self::encode_subkey(subkey);
+        self.seek(|table| table.lowerbound_both(&key, &data))
+            .map(Self::decode_row)
+    }
+
+    fn set_lowerbound_subkey(&mut self, key: &T::Key, subkey: &DupSubKey<T>) -> Option<T::Value>
+    where
+        T::Value: DupTableValue,
+    {
+        let key = encode_key::<T>(key);
+        let data = Self::encode_subkey(subkey);
+        let (_, value) = self.seek(|table| table.lowerbound_value(&key, &data))?;
+        Some(FromDatabaseBytes::from_value_bytes(&value))
+    }
+
+    fn count_duplicates(&mut self) -> usize {
+        let Some((key, _)) = &self.position else {
+            return 0;
+        };
+        let tables = self.tables.borrow();
+        MemoryReadTransaction::open_table::<T>(&tables).count_duplicates(key)
+    }
+
+    fn into_iter_dup_of(mut self, key: &T::Key) -> Self::IntoIter {
+        let key = encode_key::<T>(key);
+        let first = self.seek(|table| table.first_duplicate(&key));
+        MemoryIntoIter::new(self, first, true)
+    }
+}
+
+impl<'txn, T: Table> WriteCursor<'txn, T> for MemoryCursor<'txn, T> {
+    fn put(&mut self, key: &T::Key, value: &T::Value) {
+        let row = (encode_key::<T>(key), Self::encode_value(value));
+        self.update(|table| table.put(row.0.clone(), row.1.clone()));
+        self.position = Some(row);
+    }
+
+    fn append(&mut self, key: &T::Key, value: &T::Value) {
+        let row = (encode_key::<T>(key), Self::encode_value(value));
+        self.update(|table| table.append(row.0.clone(), row.1.clone()));
+        self.position = Some(row);
+    }
+
+    fn remove(&mut self) {
+        let (key, value) = self
+            .position
+            .clone()
+            .expect("Cursor is not positioned at an entry");
+        self.update(|table| table.remove_item(&key, &value));
+    }
+}
+
+impl<'txn, T: DupTable> DupWriteCursor<'txn, T> for MemoryCursor<'txn, T> {
+    fn append_dup(&mut self, key: &T::Key, value: &T::Value) {
+        let row = (encode_key::<T>(key), Self::encode_value(value));
+        self.update(|table| table.append_duplicate(row.0.clone(), row.1.clone()));
+        self.position = Some(row);
+    }
+
+    fn remove_all_dup(&mut self) {
+        let (key, _) = self
+            .position
+            .clone()
+            .expect("Cursor is not positioned at an entry");
+        self.update(|table| table.remove(&key));
+    }
+}
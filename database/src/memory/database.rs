@@ -0,0 +1,306 @@
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Bound,
+    sync::{Arc, Mutex, PoisonError, RwLock},
+};
+
+use log::debug;
+
+use super::{MemoryReadTransaction, MemoryWriteTransaction};
+use crate::traits::{AsDatabaseBytes, Database, DupTable, FromDatabaseBytes, RegularTable, Table};
+
+/// A raw database entry consisting of the encoded key and value.
+pub(super) type RawRow = (Vec<u8>, Vec<u8>);
+
+/// The tables of a database, indexed by their name.
+/// Tables are shared between transactions until they are modified.
+pub(super) type Tables = HashMap<&'static str, Arc<TableData>>;
+
+/// The contents of a single table.
+///
+/// Keys map to an ordered set of values. Regular tables hold exactly one value per key,
+/// dup tables can hold multiple values, which are sorted lexicographically like in MDBX.
+#[derive(Clone, Debug)]
+pub(super) struct TableData {
+    dup: bool,
+    entries: BTreeMap<Vec<u8>, BTreeSet<Vec<u8>>>,
+}
+
+impl TableData {
+    fn new(dup: bool) -> Self {
+        TableData {
+            dup,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    fn first_of(key: &[u8], values: &BTreeSet<Vec<u8>>) -> Option<RawRow> {
+        Some((key.to_vec(), values.first()?.clone()))
+    }
+
+    fn last_of(key: &[u8], values: &BTreeSet<Vec<u8>>) -> Option<RawRow> {
+        Some((key.to_vec(), values.last()?.clone()))
+    }
+
+    pub(super) fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.entries.get(key)?.first().cloned()
+    }
+
+    pub(super) fn first(&self) -> Option<RawRow> {
+        let (key, values) = self.entries.first_key_value()?;
+        Self::first_of(key, values)
+    }
+
+    pub(super) fn last(&self) -> Option<RawRow> {
+        let (key, values) = self.entries.last_key_value()?;
+        Self::last_of(key, values)
+    }
+
+    /// Returns the entry following the given position.
+    pub(super) fn next(&self, position: &RawRow) -> Option<RawRow> {
+        self.next_duplicate(position)
+            .or_else(|| self.next_no_duplicate(&position.0))
+    }
+
+    /// Returns the entry preceding the given position.
+    pub(super) fn prev(&self, position: &RawRow) -> Option<RawRow> {
+        self.prev_duplicate(position)
+            .or_else(|| self.prev_no_duplicate(&position.0))
+    }
+
+    pub(super) fn first_duplicate(&self, key: &[u8]) -> Option<RawRow> {
+        Self::first_of(key, self.entries.get(key)?)
+    }
+
+    pub(super) fn last_duplicate(&self, key: &[u8]) -> Option<RawRow> {
+        Self::last_of(key, self.entries.get(key)?)
+    }
+
+    pub(super) fn next_duplicate(&self, (key, value): &RawRow) -> Option<RawRow> {
+        let next = self
+            .entries
+            .get(key)?
+            .range::<[u8], _>((Bound::Excluded(&value[..]), Bound::Unbounded))
+            .next()?;
+        Some((key.clone(), next.clone()))
+    }
+
+    pub(super) fn prev_duplicate(&self, (key, value): &RawRow) -> Option<RawRow> {
+        let prev = self
+            .entries
+            .get(key)?
+            .range::<[u8], _>((Bound::Unbounded, Bound::Excluded(&value[..])))
+            .next_back()?;
+        Some((key.clone(), prev.clone()))
+    }
+
+    /// Returns the first duplicate of the key following the given key.
+    pub(super) fn next_no_duplicate(&self, key: &[u8]) -> Option<RawRow> {
+        let (key, values) = self
+            .entries
+            .range::<[u8], _>((Bound::Excluded(key), Bound::Unbounded))
+            .next()?;
+        Self::first_of(key, values)
+    }
+
+    /// Returns the last duplicate of the key preceding the given key.
+    pub(super) fn prev_no_duplicate(&self, key: &[u8]) -> Option<RawRow> {
+        let (key, values) = self
+            .entries
+            .range::<[u8], _>((Bound::Unbounded, Bound::Excluded(key)))
+            .next_back()?;
+        Self::last_of(key, values)
+    }
+
+    /// Returns the first entry that has a key >= `key`.
+    pub(super) fn lowerbound_key(&self, key: &[u8]) -> Option<RawRow> {
+        let (key, values) = self
+            .entries
+            .range::<[u8], _>((Bound::Included(key), Bound::Unbounded))
+            .next()?;
+        Self::first_of(key, values)
+    }
+
+    /// Returns the first entry that is >= (`key`, `value`).
+    pub(super) fn lowerbound_both(&self, key: &[u8], value: &[u8]) -> Option<RawRow> {
+        self.lowerbound_value(key, value)
+            .or_else(|| self.next_no_duplicate(key))
+    }
+
+    /// Returns the first entry that has a key == `key` and a value >= `value`.
+    pub(super) fn lowerbound_value(&self, key: &[u8], value: &[u8]) -> Option<RawRow> {
+        let next = self
+            .entries
+            .get(key)?
+            .range::<[u8], _>((Bound::Included(value), Bound::Unbounded))
+            .next()?;
+        Some((key.to_vec(), next.clone()))
+    }
+
+    pub(super) fn count_duplicates(&self, key: &[u8]) -> usize {
+        self.entries.get(key).map_or(0, BTreeSet::len)
+    }
+
+    pub(super) fn put(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        let values = self.entries.entry(key).or_default();
+        if !self.dup {
+            values.clear();
+        }
+        values.insert(value);
+    }
+
+    /// Puts an entry that must not be sorted before the last entry of the table.
+    pub(super) fn append(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        if let Some((last_key, last_value)) = self.last() {
+            assert!(
+                key > last_key || (self.dup && key == last_key && value > last_value),
+                "Appended entry is not sorted after the last entry"
+            );
+        }
+        self.put(key, value);
+    }
+
+    /// Puts a duplicate that must not be sorted before the other duplicates of the key.
+    pub(super) fn append_duplicate(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        if let Some((_, last_value)) = self.last_duplicate(&key) {
+            assert!(
+                value > last_value,
+                "Appended duplicate is not sorted after the last duplicate"
+            );
+        }
+        self.put(key, value);
+    }
+
+    pub(super) fn remove(&mut self, key: &[u8]) {
+        self.entries.remove(key);
+    }
+
+    pub(super) fn remove_item(&mut self, key: &[u8], value: &[u8]) {
+        if let Some(values) = self.entries.get_mut(key) {
+            values.remove(value);
+            if values.is_empty() {
+                self.entries.remove(key);
+            }
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[derive(Debug, Default)]
+struct DatabaseState {
+    /// The committed state of all tables.
+    tables: RwLock<Tables>,
+    /// Ensures that there is only a single write transaction at a time.
+    writer: Mutex<()>,
+}
+
+/// A database that keeps all tables in ordered in-memory maps.
+/// It follows the semantics of the `MdbxDatabase`, but does not need any files.
+///
+/// Read transactions operate on a snapshot of the tables taken when the transaction was created.
+/// Like in MDBX, there can only be one write transaction at a time.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryDatabase {
+    state: Arc<DatabaseState>,
+}
+
+impl MemoryDatabase {
+    /// Creates a new empty database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a table if it does not exist yet.
+    fn create_table<T: Table>(&self, _table: &T, dup: bool) {
+        debug!("Creating table: {}, dup: {}", T::NAME, dup);
+        self.state
+            .tables
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(T::NAME)
+            .or_insert_with(|| Arc::new(TableData::new(dup)));
+    }
+
+    /// Returns a copy of the currently committed tables.
+    pub(super) fn snapshot(&self) -> Tables {
+        self.state
+            .tables
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Makes the tables of a write transaction the committed state.
+    pub(super) fn commit(&self, tables: Tables) {
+        // Tables might have been created concurrently, so we only overwrite the ones we know.
+        self.state
+            .tables
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(tables);
+    }
+}
+
+impl Database for MemoryDatabase {
+    type ReadTransaction<'db> = MemoryReadTransaction<'db>;
+
+    type WriteTransaction<'db> = MemoryWriteTransaction<'db>;
+
+    fn create_regular_table<T: RegularTable>(&self, table: &T) {
+        self.create_table(table, false)
+    }
+
+    fn create_dup_table<T: DupTable>(&self, table: &T) {
+        self.create_table(table, true)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.state
+            .tables
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    }
+
+    fn read_transaction(&self) -> Self::ReadTransaction<'_> {
+        MemoryReadTransaction::new(self.snapshot())
+    }
+
+    fn write_transaction(&self) -> Self::WriteTransaction<'_> {
+        let writer = self
+            .state
+            .writer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        MemoryWriteTransaction::new(self, writer)
+    }
+}
+
+/// Returns whether the key is stored as an integer in MDBX.
+/// MDBX compares these keys numerically, so we store them in big-endian byte order.
+fn is_integer_key<T: Table>() -> bool {
+    let key_type = TypeId::of::<T::Key>();
+    key_type == TypeId::of::<u32>() || key_type == TypeId::of::<u64>()
+}
+
+/// Converts between the native-endian key encoding and the byte order used for sorting.
+fn swap_integer_key<T: Table>(mut bytes: Vec<u8>) -> Vec<u8> {
+    if cfg!(target_endian = "little") && is_integer_key::<T>() {
+        bytes.reverse();
+    }
+    bytes
+}
+
+/// Encodes a key such that the byte order matches the key order of MDBX.
+pub(super) fn encode_key<T: Table>(key: &T::Key) -> Vec<u8> {
+    swap_integer_key::<T>(key.as_key_bytes().into_owned())
+}
+
+/// Decodes a key that was encoded with `encode_key`.
+pub(super) fn decode_key<T: Table>(bytes: &[u8]) -> T::Key {
+    T::Key::from_key_bytes(&swap_integer_key::<T>(bytes.to_vec()))
+}
@@ -0,0 +1,39 @@
+use super::{MemoryCursor, RawRow, TableData};
+use crate::traits::{Row, Table};
+
+/// Iterates over the entries (key, value pairs) of a `MemoryDatabase` table.
+pub struct MemoryIntoIter<'txn, T: Table> {
+    cursor: MemoryCursor<'txn, T>,
+    next: Option<RawRow>,
+    dup_only: bool,
+}
+
+impl<'txn, T: Table> MemoryIntoIter<'txn, T> {
+    /// Creates an iterator starting at `first`, where the cursor is positioned.
+    /// If `dup_only` is set, the iteration ends with the last duplicate of the first key.
+    pub(super) fn new(
+        cursor: MemoryCursor<'txn, T>,
+        first: Option<RawRow>,
+        dup_only: bool,
+    ) -> Self {
+        MemoryIntoIter {
+            cursor,
+            next: first,
+            dup_only,
+        }
+    }
+}
+
+impl<'txn, T: Table> Iterator for MemoryIntoIter<'txn, T> {
+    type Item = Row<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.next.take()?;
+        self.next = if self.dup_only {
+            self.cursor.move_by(TableData::next_duplicate)
+        } else {
+            self.cursor.move_by(TableData::next)
+        };
+        Some(MemoryCursor::<T>::decode_row(row))
+    }
+}
@@ -0,0 +1,7 @@
+mod cursor;
+mod database;
+mod iterators;
+mod transaction;
+
+use self::database::{decode_key, encode_key, RawRow, TableData, Tables};
+pub use self::{cursor::*, database::MemoryDatabase, iterators::*, transaction::*};
@@ -0,0 +1,207 @@
+use std::{
+    cell::{Ref, RefCell},
+    marker::PhantomData,
+    ops::Deref,
+    sync::{Arc, MutexGuard},
+};
+
+use nimiq_database_value::{AsDatabaseBytes, FromDatabaseBytes, IntoDatabaseValue};
+
+use super::{encode_key, MemoryCursor, MemoryDatabase, TableData, Tables};
+use crate::traits::{DupTable, ReadTransaction, RegularTable, Table, WriteTransaction};
+
+/// A transaction on a `MemoryDatabase`.
+/// It holds its own copy of the tables, which share their contents with the database
+/// until they are modified.
+#[derive(Debug)]
+pub struct MemoryReadTransaction<'db> {
+    tables: RefCell<Tables>,
+    _db: PhantomData<&'db MemoryDatabase>,
+}
+
+impl<'db> MemoryReadTransaction<'db> {
+    pub(super) fn new(tables: Tables) -> Self {
+        MemoryReadTransaction {
+            tables: RefCell::new(tables),
+            _db: PhantomData,
+        }
+    }
+
+    pub(super) fn open_table<T: Table>(tables: &Tables) -> &TableData {
+        tables
+            .get(T::NAME)
+            .unwrap_or_else(|| panic!("Table {} does not exist", T::NAME))
+    }
+
+    pub(super) fn open_table_mut<T: Table>(tables: &mut Tables) -> &mut TableData {
+        let table = tables
+            .get_mut(T::NAME)
+            .unwrap_or_else(|| panic!("Table {} does not exist", T::NAME));
+        Arc::make_mut(table)
+    }
+
+    fn table<T: Table>(&self) -> Ref<TableData> {
+        Ref::map(self.tables.borrow(), Self::open_table::<T>)
+    }
+
+    fn update_table<T: Table, R>(&mut self, f: impl FnOnce(&mut TableData) -> R) -> R {
+        f(Self::open_table_mut::<T>(self.tables.get_mut()))
+    }
+}
+
+impl<'db> AsRef<MemoryReadTransaction<'db>> for MemoryReadTransaction<'db> {
+    fn as_ref(&self) -> &MemoryReadTransaction<'db> {
+        self
+    }
+}
+
+impl<'db> ReadTransaction<'db> for MemoryReadTransaction<'db> {
+    type Cursor<'txn, T: Table>
+        = MemoryCursor<'txn, T>
+    where
+        Self: 'txn;
+
+    type DupCursor<'txn, T: DupTable>
+        = MemoryCursor<'txn, T>
+    where
+        Self: 'txn;
+
+    fn get<T: Table>(&self, _table: &T, key: &T::Key) -> Option<T::Value> {
+        let value = self.table::<T>().get(&encode_key::<T>(key))?;
+        Some(FromDatabaseBytes::from_value_bytes(&value))
+    }
+
+    fn cursor<'txn, T: RegularTable>(&'txn self, _table: &T) -> Self::Cursor<'txn, T> {
+        MemoryCursor::new(&self.tables)
+    }
+
+    fn dup_cursor<'txn, T: DupTable>(&'txn self, _table: &T) -> Self::DupCursor<'txn, T> {
+        MemoryCursor::new(&self.tables)
+    }
+}
+
+/// A write transaction on a `MemoryDatabase`.
+/// The changes only become visible to other transactions once it is committed.
+pub struct MemoryWriteTransaction<'db> {
+    txn: MemoryReadTransaction<'db>,
+    db: &'db MemoryDatabase,
+    _writer: MutexGuard<'db, ()>,
+}
+
+impl<'db> MemoryWriteTransaction<'db> {
+    pub(super) fn new(db: &'db MemoryDatabase, writer: MutexGuard<'db, ()>) -> Self {
+        // The snapshot must be taken after acquiring the writer lock,
+        // such that it includes all previously committed changes.
+        Self {
+            txn: MemoryReadTransaction::new(db.snapshot()),
+            db,
+            _writer: writer,
+        }
+    }
+}
+
+impl<'db> ReadTransaction<'db> for MemoryWriteTransaction<'db> {
+    type Cursor<'txn, T: Table>
+        = MemoryCursor<'txn, T>
+    where
+        Self: 'txn;
+
+    type DupCursor<'txn, T: DupTable>
+        = MemoryCursor<'txn, T>
+    where
+        Self: 'txn;
+
+    fn get<T: Table>(&self, table: &T, key: &T::Key) -> Option<T::Value> {
+        self.txn.get(table, key)
+    }
+
+    fn cursor<'txn, T: RegularTable>(&'txn self, table: &T) -> Self::Cursor<'txn, T> {
+        self.txn.cursor(table)
+    }
+
+    fn dup_cursor<'txn, T: DupTable>(&'txn self, table: &T) -> Self::DupCursor<'txn, T> {
+        self.txn.dup_cursor(table)
+    }
+}
+
+impl<'db> WriteTransaction<'db> for MemoryWriteTransaction<'db> {
+    type WriteCursor<'txn, T: Table>
+        = MemoryCursor<'txn, T>
+    where
+        Self: 'txn;
+
+    type DupWriteCursor<'txn, T: DupTable>
+        = MemoryCursor<'txn, T>
+    where
+        Self: 'txn;
+
+    fn put_reserve<T: RegularTable>(&mut self, _table: &T, key: &T::Key, value: &T::Value)
+    where
+        T::Value: IntoDatabaseValue,
+    {
+        let key = encode_key::<T>(key);
+        let mut bytes = vec![0; IntoDatabaseValue::database_byte_size(value)];
+        IntoDatabaseValue::copy_into_database(value, &mut bytes);
+
+        self.txn.update_table::<T, _>(|table| table.put(key, bytes));
+    }
+
+    fn put<T: Table>(&mut self, _table: &T, key: &T::Key, value: &T::Value) {
+        let key = encode_key::<T>(key);
+        let value = AsDatabaseBytes::as_value_bytes(value).into_owned();
+
+        self.txn.update_table::<T, _>(|table| table.put(key, value));
+    }
+
+    fn append<T: Table>(&mut self, _table: &T, key: &T::Key, value: &T::Value) {
+        let key = encode_key::<T>(key);
+        let value = AsDatabaseBytes::as_value_bytes(value).into_owned();
+
+        self.txn
+            .update_table::<T, _>(|table| table.append(key, value));
+    }
+
+    fn remove<T: Table>(&mut self, _table: &T, key: &T::Key) {
+        let key = encode_key::<T>(key);
+
+        self.txn.update_table::<T, _>(|table| table.remove(&key));
+    }
+
+    fn remove_item<T: Table>(&mut self, _table: &T, key: &T::Key, value: &T::Value) {
+        let key = encode_key::<T>(key);
+        let value = AsDatabaseBytes::as_value_bytes(value);
+
+        self.txn
+            .update_table::<T, _>(|table| table.remove_item(&key, &value));
+    }
+
+    fn commit(self) {
+        self.db.commit(self.txn.tables.into_inner());
+    }
+
+    fn cursor<'txn, T: RegularTable>(&'txn self, _table: &T) -> Self::WriteCursor<'txn, T> {
+        MemoryCursor::new(&self.txn.tables)
+    }
+
+    fn dup_cursor<'txn, T: DupTable>(&'txn self, _table: &T) -> Self::DupWriteCursor<'txn, T> {
+        MemoryCursor::new(&self.txn.tables)
+    }
+
+    fn clear_table<T: Table>(&mut self, _table: &T) {
+        self.txn.update_table::<T, _>(TableData::clear);
+    }
+}
+
+impl<'db> Deref for MemoryWriteTransaction<'db> {
+    type Target = MemoryReadTransaction<'db>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.txn
+    }
+}
+
+impl<'db> AsRef<MemoryReadTransaction<'db>> for MemoryWriteTransaction<'db> {
+    fn as_ref(&self) -> &MemoryReadTransaction<'db> {
+        &self.txn
+    }
+}
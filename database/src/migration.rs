@@ -0,0 +1,314 @@
+use std::{error::Error as StdError, time::Instant};
+
+use log::info;
+
+use crate::{
+    declare_table,
+    traits::{Database, ReadTransaction, WriteTransaction},
+    Error,
+};
+
+declare_table!(MetadataTable, "Metadata", String => u32);
+
+/// The metadata key under which the schema version is stored.
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// The schema version of databases that were created before versioning was introduced.
+pub const INITIAL_SCHEMA_VERSION: u32 = 0;
+
+/// The error type returned by a migration step.
+pub type MigrationError = Box<dyn StdError + Send + Sync>;
+
+/// A single step that migrates the database from the previous schema version to `version`.
+///
+/// Steps can be interrupted (e.g. by a shutdown) and are run again on the next start in that case.
+/// They thus must be idempotent.
+pub struct Migration<D: Database> {
+    /// The schema version after this step was applied.
+    pub version: u32,
+    /// A human-readable description of the changes made by this step.
+    pub description: &'static str,
+    /// Applies the changes to the database.
+    pub run: fn(&D) -> Result<(), MigrationError>,
+}
+
+/// An ordered registry of migration steps.
+pub struct Migrations<D: Database> {
+    steps: Vec<Migration<D>>,
+}
+
+impl<D: Database> Default for Migrations<D> {
+    fn default() -> Self {
+        Self { steps: Vec::new() }
+    }
+}
+
+impl<D: Database> Migrations<D> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the migration step to the next schema version.
+    /// Steps must be registered in order, each incrementing the schema version by one.
+    pub fn register(
+        mut self,
+        version: u32,
+        description: &'static str,
+        run: fn(&D) -> Result<(), MigrationError>,
+    ) -> Self {
+        assert_eq!(
+            version,
+            self.latest_version() + 1,
+            "Migrations must be registered in order"
+        );
+        self.steps.push(Migration {
+            version,
+            description,
+            run,
+        });
+        self
+    }
+
+    /// The schema version that databases have after running all migrations.
+    pub fn latest_version(&self) -> u32 {
+        self.steps
+            .last()
+            .map_or(INITIAL_SCHEMA_VERSION, |step| step.version)
+    }
+
+    /// Returns the migration steps that have not been applied to the database yet.
+    pub fn pending(&self, db: &D) -> Result<&[Migration<D>], Error> {
+        let version = schema_version(db);
+        let latest_version = self.latest_version();
+        if version > latest_version {
+            return Err(Error::UnsupportedSchemaVersion(version, latest_version));
+        }
+
+        let applied = (version - INITIAL_SCHEMA_VERSION) as usize;
+        Ok(&self.steps[applied..])
+    }
+
+    /// Brings the database up to the latest schema version and returns that version.
+    ///
+    /// This must be called right after opening the database, before any tables are created.
+    /// New databases, which don't contain any tables yet, are created with the latest schema and
+    /// thus only stamped with the latest schema version, also in a dry run. Databases that
+    /// contain tables but no schema version were created before versioning was introduced
+    /// and are migrated from `INITIAL_SCHEMA_VERSION`.
+    ///
+    /// In a dry run, the pending steps are only logged and the current schema version
+    /// of the database is returned.
+    pub fn run(&self, db: &D, dry_run: bool) -> Result<u32, Error> {
+        if db.is_empty() {
+            let version = self.latest_version();
+            info!(version, "Creating database with the latest schema");
+            set_schema_version(db, version);
+            return Ok(version);
+        }
+
+        let version = schema_version(db);
+        let pending = self.pending(db)?;
+
+        if pending.is_empty() {
+            info!(version, "Database schema is up to date");
+            // Databases that were created before versioning was introduced get a version marker.
+            if !dry_run {
+                set_schema_version(db, version);
+            }
+            return Ok(version);
+        }
+
+        info!(
+            from = version,
+            to = self.latest_version(),
+            steps = pending.len(),
+            dry_run,
+            "Database schema needs to be migrated"
+        );
+
+        if dry_run {
+            for step in pending {
+                info!(
+                    version = step.version,
+                    description = step.description,
+                    "Pending migration"
+                );
+            }
+            return Ok(version);
+        }
+
+        for (i, step) in pending.iter().enumerate() {
+            info!(
+                version = step.version,
+                description = step.description,
+                "Running migration {}/{}",
+                i + 1,
+                pending.len()
+            );
+            let start = Instant::now();
+
+            (step.run)(db).map_err(|e| Error::Migration(step.version, e.to_string()))?;
+            set_schema_version(db, step.version);
+
+            info!(
+                version = step.version,
+                elapsed = ?start.elapsed(),
+                "Finished migration {}/{}",
+                i + 1,
+                pending.len()
+            );
+        }
+
+        Ok(self.latest_version())
+    }
+}
+
+/// Returns the schema version of the database.
+/// Databases without a version marker are considered to be at `INITIAL_SCHEMA_VERSION`.
+pub fn schema_version<D: Database>(db: &D) -> u32 {
+    db.create_regular_table(&MetadataTable);
+
+    let txn = db.read_transaction();
+    let version = txn.get(&MetadataTable, &SCHEMA_VERSION_KEY.to_string());
+    txn.close();
+
+    version.unwrap_or(INITIAL_SCHEMA_VERSION)
+}
+
+fn set_schema_version<D: Database>(db: &D, version: u32) {
+    db.create_regular_table(&MetadataTable);
+
+    let mut txn = db.write_transaction();
+    txn.put(&MetadataTable, &SCHEMA_VERSION_KEY.to_string(), &version);
+    txn.commit();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{memory::MemoryDatabase, traits::ReadCursor};
+
+    declare_table!(LegacyTable, "legacy", u32 => String);
+    declare_table!(NewTable, "new", String => u32);
+
+    fn split_table(db: &MemoryDatabase) -> Result<(), MigrationError> {
+        db.create_regular_table(&NewTable);
+
+        let mut txn = db.write_transaction();
+        let entries: Vec<_> = ReadTransaction::cursor(&txn, &LegacyTable)
+            .into_iter_start()
+            .collect();
+        for (key, value) in entries {
+            txn.put(&NewTable, &value, &key);
+        }
+        txn.commit();
+        Ok(())
+    }
+
+    fn clear_table(db: &MemoryDatabase) -> Result<(), MigrationError> {
+        let mut txn = db.write_transaction();
+        txn.clear_table(&LegacyTable);
+        txn.commit();
+        Ok(())
+    }
+
+    fn fail(_db: &MemoryDatabase) -> Result<(), MigrationError> {
+        Err("failed".into())
+    }
+
+    fn migrations() -> Migrations<MemoryDatabase> {
+        Migrations::new()
+            .register(1, "Index legacy entries by value", split_table)
+            .register(2, "Remove legacy entries", clear_table)
+    }
+
+    fn legacy_db() -> MemoryDatabase {
+        let db = MemoryDatabase::new();
+        db.create_regular_table(&LegacyTable);
+        let mut txn = db.write_transaction();
+        txn.put(&LegacyTable, &1, &"one".to_string());
+        txn.put(&LegacyTable, &2, &"two".to_string());
+        txn.commit();
+        db
+    }
+
+    fn assert_legacy_entries(db: &MemoryDatabase, expected: usize) {
+        let txn = db.read_transaction();
+        assert_eq!(txn.cursor(&LegacyTable).into_iter_start().count(), expected);
+    }
+
+    #[test]
+    fn it_migrates_unversioned_databases() {
+        let db = legacy_db();
+        assert_eq!(schema_version(&db), INITIAL_SCHEMA_VERSION);
+        assert_eq!(migrations().pending(&db).unwrap().len(), 2);
+
+        assert_eq!(migrations().run(&db, false).unwrap(), 2);
+        assert_eq!(schema_version(&db), 2);
+        assert!(migrations().pending(&db).unwrap().is_empty());
+
+        assert_legacy_entries(&db, 0);
+        let txn = db.read_transaction();
+        assert_eq!(txn.get(&NewTable, &"two".to_string()), Some(2));
+    }
+
+    #[test]
+    fn dry_run_does_not_modify_the_database() {
+        let db = legacy_db();
+
+        assert_eq!(migrations().run(&db, true).unwrap(), INITIAL_SCHEMA_VERSION);
+        assert_eq!(schema_version(&db), INITIAL_SCHEMA_VERSION);
+        assert_legacy_entries(&db, 2);
+    }
+
+    #[test]
+    fn it_only_runs_pending_migrations() {
+        let db = legacy_db();
+        Migrations::new()
+            .register(1, "Index legacy entries by value", split_table)
+            .run(&db, false)
+            .unwrap();
+        assert_eq!(schema_version(&db), 1);
+        assert_legacy_entries(&db, 2);
+
+        assert_eq!(migrations().pending(&db).unwrap().len(), 1);
+        assert_eq!(migrations().run(&db, false).unwrap(), 2);
+        assert_legacy_entries(&db, 0);
+    }
+
+    #[test]
+    fn it_stops_at_failed_migrations() {
+        let db = legacy_db();
+        let migrations = Migrations::new()
+            .register(1, "Index legacy entries by value", split_table)
+            .register(2, "Fail", fail)
+            .register(3, "Remove legacy entries", clear_table);
+
+        assert!(matches!(
+            migrations.run(&db, false),
+            Err(Error::Migration(2, _))
+        ));
+        assert_eq!(schema_version(&db), 1);
+        assert_legacy_entries(&db, 2);
+    }
+
+    #[test]
+    fn it_rejects_newer_databases() {
+        let db = legacy_db();
+        migrations().run(&db, false).unwrap();
+
+        let migrations =
+            Migrations::new().register(1, "Index legacy entries by value", split_table);
+        assert!(matches!(
+            migrations.run(&db, false),
+            Err(Error::UnsupportedSchemaVersion(2, 1))
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn migrations_must_be_ordered() {
+        Migrations::<MemoryDatabase>::new().register(2, "Remove legacy entries", clear_table);
+    }
+}
@@ -15,6 +15,9 @@ pub trait Database: Sized {
     /// Creates a table that can store duplicate keys.
     fn create_dup_table<T: DupTable>(&self, table: &T);
 
+    /// Returns whether no tables have been created in the database yet.
+    fn is_empty(&self) -> bool;
+
     /// Creates a read transaction.
     fn read_transaction(&self) -> Self::ReadTransaction<'_>;
 
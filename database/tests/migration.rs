@@ -0,0 +1,130 @@
+use nimiq_database::{
+    declare_table,
+    mdbx::{DatabaseConfig, MdbxDatabase},
+    migration::{schema_version, MigrationError, Migrations, INITIAL_SCHEMA_VERSION},
+    traits::{Database, ReadCursor, ReadTransaction, WriteTransaction},
+};
+use tempfile::TempDir;
+
+declare_table!(AccountTable, "accounts", u32 => String);
+declare_table!(AccountIndexTable, "account_index", String => u32);
+
+/// Indexes the accounts by name. Introduced with schema version 1.
+fn index_accounts(db: &MdbxDatabase) -> Result<(), MigrationError> {
+    db.create_regular_table(&AccountIndexTable);
+
+    let mut txn = db.write_transaction();
+    let entries: Vec<_> = ReadTransaction::cursor(&txn, &AccountTable)
+        .into_iter_start()
+        .collect();
+    for (id, name) in entries {
+        txn.put(&AccountIndexTable, &name, &id);
+    }
+    txn.commit();
+    Ok(())
+}
+
+/// Upper-cases the account names. Introduced with schema version 2.
+fn normalize_names(db: &MdbxDatabase) -> Result<(), MigrationError> {
+    let mut txn = db.write_transaction();
+    let entries: Vec<_> = ReadTransaction::cursor(&txn, &AccountTable)
+        .into_iter_start()
+        .collect();
+    for (id, name) in entries {
+        txn.put(&AccountTable, &id, &name.to_uppercase());
+    }
+    txn.commit();
+    Ok(())
+}
+
+fn migrations() -> Migrations<MdbxDatabase> {
+    Migrations::new()
+        .register(1, "Index accounts by name", index_accounts)
+        .register(2, "Normalize account names", normalize_names)
+}
+
+/// Opens the database in the given directory and brings its schema up to date, like the client
+/// does on start. Afterwards, the tables of the current schema are created.
+fn open(dir: &TempDir, dry_run: bool) -> (MdbxDatabase, u32) {
+    let db = MdbxDatabase::new(dir.path(), DatabaseConfig::default()).unwrap();
+    let version = migrations().run(&db, dry_run).unwrap();
+    db.create_regular_table(&AccountTable);
+    (db, version)
+}
+
+/// Creates a database in the layout used before versioning was introduced.
+fn legacy_db(dir: &TempDir) {
+    let db = MdbxDatabase::new(dir.path(), DatabaseConfig::default()).unwrap();
+    db.create_regular_table(&AccountTable);
+    let mut txn = db.write_transaction();
+    txn.put(&AccountTable, &1, &"alice".to_string());
+    txn.put(&AccountTable, &2, &"bob".to_string());
+    txn.commit();
+}
+
+fn account_name(db: &MdbxDatabase, id: u32) -> Option<String> {
+    db.read_transaction().get(&AccountTable, &id)
+}
+
+#[test]
+fn fresh_databases_are_created_with_the_latest_version() {
+    let dir = TempDir::new().unwrap();
+    let (db, version) = open(&dir, false);
+    assert_eq!(version, 2);
+    assert_eq!(schema_version(&db), 2);
+
+    let mut txn = db.write_transaction();
+    txn.put(&AccountTable, &1, &"alice".to_string());
+    txn.commit();
+    drop(db);
+
+    // Reopening the database must not run any migrations.
+    let (db, version) = open(&dir, false);
+    assert_eq!(version, 2);
+    assert!(migrations().pending(&db).unwrap().is_empty());
+    assert_eq!(account_name(&db, 1), Some("alice".to_string()));
+}
+
+#[test]
+fn legacy_databases_are_migrated() {
+    let dir = TempDir::new().unwrap();
+    legacy_db(&dir);
+
+    let (db, version) = open(&dir, false);
+    assert_eq!(version, 2);
+    assert_eq!(schema_version(&db), 2);
+    assert_eq!(account_name(&db, 2), Some("BOB".to_string()));
+    let txn = db.read_transaction();
+    assert_eq!(txn.get(&AccountIndexTable, &"alice".to_string()), Some(1));
+}
+
+#[test]
+fn dry_run_leaves_legacy_databases_untouched() {
+    let dir = TempDir::new().unwrap();
+    legacy_db(&dir);
+
+    let (db, version) = open(&dir, true);
+    assert_eq!(version, INITIAL_SCHEMA_VERSION);
+    assert_eq!(schema_version(&db), INITIAL_SCHEMA_VERSION);
+    assert_eq!(migrations().pending(&db).unwrap().len(), 2);
+    assert_eq!(account_name(&db, 2), Some("bob".to_string()));
+    drop(db);
+
+    // The migrations are run on the next regular start.
+    let (db, version) = open(&dir, false);
+    assert_eq!(version, 2);
+    assert_eq!(account_name(&db, 2), Some("BOB".to_string()));
+}
+
+#[test]
+fn dry_run_stamps_fresh_databases() {
+    let dir = TempDir::new().unwrap();
+    let (db, version) = open(&dir, true);
+    assert_eq!(version, 2);
+    drop(db);
+
+    // A fresh database created during a dry run is not mistaken for a legacy one later.
+    let (db, version) = open(&dir, false);
+    assert_eq!(version, 2);
+    assert!(migrations().pending(&db).unwrap().is_empty());
+}
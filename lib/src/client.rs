@@ -1,4 +1,4 @@
-use std::{fs, num::NonZeroU8, sync::Arc};
+use std::{fs, num::NonZeroU8, sync::Arc, time::Duration};
 
 use nimiq_block::Block;
 #[cfg(feature = "full-consensus")]
@@ -313,6 +313,21 @@ impl ClientInner {
             None
         };
 
+        let peer_store = if config.network.peer_store {
+            let peer_store = config.storage.peer_store(
+                config.network_id,
+                config.network.peer_store_max_age.map(Duration::from_secs),
+            );
+            if peer_store.is_none() {
+                log::warn!(
+                    "Peer contacts and DHT records can't be persisted with volatile storage"
+                );
+            }
+            peer_store
+        } else {
+            None
+        };
+
         // Setup libp2p network
         let network_config = NetworkConfig::new(
             identity_keypair,
@@ -330,6 +345,7 @@ impl ClientInner {
                 .network
                 .dht_quorum
                 .unwrap_or(NonZeroU8::new(3).unwrap()),
+            peer_store,
         );
 
         log::debug!(
@@ -360,6 +376,7 @@ impl ClientInner {
         #[cfg(feature = "full-consensus")]
         let mut blockchain_config = BlockchainConfig {
             max_epochs_stored: config.consensus.max_epochs_stored,
+            archive_accounts: config.consensus.archive_accounts,
             ..Default::default()
         };
 
@@ -394,6 +411,13 @@ impl ClientInner {
                     }
                 };
 
+                // Compute the filters of batches that were finalized before batch filters were
+                // available, without blocking the start of the client.
+                let backfill_blockchain = Arc::clone(&blockchain);
+                std::thread::Builder::new()
+                    .name("batch-filters".to_string())
+                    .spawn(move || Blockchain::backfill_batch_filters(&backfill_blockchain))?;
+
                 let blockchain_proxy = BlockchainProxy::from(&blockchain);
                 #[cfg(feature = "zkp-prover")]
                 let zkp_component = if let Some(zk_prover_config) = config.zk_prover {
@@ -516,6 +540,9 @@ impl ClientInner {
                     // Load validator address
                     let automatic_reactivate = validator_config.automatic_reactivate;
 
+                    // Load the extra data to include in produced micro blocks
+                    let extra_data = validator_config.extra_data;
+
                     // Load signing key (before we give away ownership of the storage config)
                     let signing_key = config.storage.signing_keypair()?;
 
@@ -538,6 +565,7 @@ impl ClientInner {
                         signing_key,
                         voting_key,
                         fee_key,
+                        extra_data,
                         config.mempool.clone(),
                     );
 
@@ -567,6 +595,7 @@ impl ClientInner {
                 validator_or_mempool = Some(ValidatorOrMempool::Mempool(MempoolTask::new(
                     &consensus,
                     Arc::clone(blockchain),
+                    environment.clone(),
                     config.mempool,
                 )));
             }
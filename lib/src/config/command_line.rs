@@ -1,7 +1,8 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use log::level_filters::{LevelFilter, ParseLevelFilterError};
+use nimiq_hash::Blake2bHash;
 use nimiq_primitives::networks::NetworkId;
 use thiserror::Error;
 
@@ -64,9 +65,59 @@ pub struct CommandLine {
     #[clap(long)]
     pub network: Option<NetworkId>,
 
+    /// Only log the pending database migrations instead of running them.
+    /// The client does not start if there are any.
+    ///
+    /// # Examples
+    ///
+    /// * `nimiq-client --migrations-dry-run`
+    ///
+    #[clap(long, action)]
+    pub migrations_dry_run: bool,
+
     /// Internally used flag to start a zero-knowledge prover process.
     #[clap(long, action)]
     pub prove: bool,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Export a snapshot of the state at the latest macro block and exit.
+    ///
+    /// # Examples
+    ///
+    /// * `nimiq-client export-snapshot --output snapshot.bin --include-history`
+    ///
+    ExportSnapshot {
+        /// The file the snapshot is written to.
+        #[clap(long, short = 'o')]
+        output: PathBuf,
+
+        /// Include the history of all epochs. Requires the history sync mode.
+        #[clap(long, action)]
+        include_history: bool,
+    },
+
+    /// Import a snapshot into an empty database and then start the client,
+    /// which continues to sync from the snapshot's macro block.
+    ///
+    /// # Examples
+    ///
+    /// * `nimiq-client import-snapshot --input snapshot.bin --election-block-hash <hash>`
+    ///
+    ImportSnapshot {
+        /// The snapshot file to import.
+        #[clap(long, short = 'i')]
+        input: PathBuf,
+
+        /// The hash of the snapshot's election block, obtained from a trusted source.
+        /// Required by the full sync mode, which can't verify the election block otherwise.
+        #[clap(long)]
+        election_block_hash: Option<Blake2bHash>,
+    },
 }
 
 impl CommandLine {
@@ -1,3 +1,5 @@
+#[cfg(feature = "rpc-server")]
+use std::collections::HashMap;
 #[cfg(any(feature = "rpc-server", feature = "metrics-server"))]
 use std::net::IpAddr;
 #[cfg(feature = "metrics-server")]
@@ -7,6 +9,7 @@ use std::{
     num::NonZeroU8,
     path::{Path, PathBuf},
     string::ToString,
+    time::Duration,
 };
 
 use derive_builder::Builder;
@@ -20,21 +23,33 @@ use nimiq_keys::{Address, KeyPair, PrivateKey};
 #[cfg(feature = "nimiq-mempool")]
 use nimiq_mempool::{config::MempoolConfig, filter::MempoolRules};
 use nimiq_network_interface::Multiaddr;
-use nimiq_network_libp2p::{Keypair as IdentityKeypair, Libp2pKeyPair};
+use nimiq_network_libp2p::{Keypair as IdentityKeypair, Libp2pKeyPair, PeerStoreConfig};
 use nimiq_primitives::{networks::NetworkId, policy::Policy};
+#[cfg(feature = "rpc-server")]
+use nimiq_rpc_server::access_control::IpAllowList;
+#[cfg(feature = "rpc-server")]
+use nimiq_rpc_server::rate_limiting::{RateLimitConfig, TokenBucketConfig};
 use nimiq_serde::Deserialize;
 #[cfg(feature = "validator")]
 use nimiq_utils::key_rng::SecureGenerate;
 use nimiq_utils::{file_store::FileStore, Sensitive};
+#[cfg(feature = "validator")]
+use nimiq_validator::extra_data::expand_extra_data;
 use nimiq_zkp_circuits::DEFAULT_PROVER_KEYS_PATH;
 use subtle::ConstantTimeEq;
 
-#[cfg(feature = "database-storage")]
-use crate::config::config_file::DatabaseSettings;
-#[cfg(any(feature = "rpc-server", feature = "metrics-server"))]
+#[cfg(feature = "rpc-server")]
+use crate::config::config_file::TokenBucketSettings;
+#[cfg(any(
+    feature = "rpc-server",
+    feature = "metrics-server",
+    feature = "validator"
+))]
 use crate::config::consts;
 #[cfg(feature = "metrics-server")]
 use crate::config::consts::default_bind;
+#[cfg(feature = "database-storage")]
+use crate::{config::config_file::DatabaseSettings, migrations::database_migrations};
 use crate::{
     config::{
         command_line::CommandLine,
@@ -92,6 +107,10 @@ pub struct ConsensusConfig {
     #[builder(default = "true")]
     /// History indices enabled. Only effective for history nodes (default: `true`)
     pub index_history: bool,
+    #[builder(default)]
+    /// Archive the previous values of all accounts. Only effective for full and history nodes
+    /// (default: `false`)
+    pub archive_accounts: bool,
 }
 
 impl Default for ConsensusConfig {
@@ -102,6 +121,7 @@ impl Default for ConsensusConfig {
             max_epochs_stored: Policy::MIN_EPOCHS_STORED,
             full_sync_threshold: 10800,
             index_history: true,
+            archive_accounts: false,
         }
     }
 }
@@ -155,6 +175,14 @@ pub struct NetworkConfig {
     /// Optional quorum value for the network DHT
     #[builder(default)]
     pub dht_quorum: Option<NonZeroU8>,
+
+    /// Optional bool to persist the peer contacts and DHT records across restarts
+    #[builder(default)]
+    pub peer_store: bool,
+
+    /// Optional maximum age in seconds of the peer contacts that are restored on startup
+    #[builder(default)]
+    pub peer_store_max_age: Option<u64>,
 }
 
 /// Configuration for setting TLS for secure WebSocket
@@ -265,8 +293,8 @@ pub struct DatabaseConfig {
     #[builder(default = "1024 * 1024 * 1024 * 1024")]
     size: usize,
 
-    /// Max number of DBs. Recommended: 20
-    #[builder(default = "20")]
+    /// Max number of DBs. Recommended: 32
+    #[builder(default = "32")]
     max_dbs: u32,
 
     /// Max number of threads that can open read transactions.
@@ -275,6 +303,10 @@ pub struct DatabaseConfig {
     /// Recommended: 600
     #[builder(default = "600")]
     max_readers: u32,
+
+    /// Only log the pending schema migrations instead of running them.
+    #[builder(default)]
+    migrations_dry_run: bool,
 }
 #[cfg(feature = "database-storage")]
 impl Default for DatabaseConfig {
@@ -282,8 +314,9 @@ impl Default for DatabaseConfig {
         Self {
             // 1 TB
             size: 1024 * 1024 * 1024 * 1024,
-            max_dbs: 20,
+            max_dbs: 32,
             max_readers: 600,
+            migrations_dry_run: false,
         }
     }
 }
@@ -297,6 +330,7 @@ impl From<Option<DatabaseSettings>> for DatabaseConfig {
                 size: db_settings.size.unwrap_or(default.size),
                 max_dbs: db_settings.max_dbs.unwrap_or(default.max_dbs),
                 max_readers: db_settings.max_readers.unwrap_or(default.max_readers),
+                ..default
             }
         } else {
             default
@@ -337,6 +371,9 @@ impl StorageConfig {
     ///
     /// Returns a `Result` which is either a `Environment` or a `Error`.
     ///
+    /// The schema of the database is migrated to the latest version, unless
+    /// `migrations_dry_run` is set in the database config.
+    ///
     #[cfg(feature = "database-storage")]
     pub fn database(
         &self,
@@ -356,7 +393,7 @@ impl StorageConfig {
             ..Default::default()
         };
 
-        Ok(match self {
+        let db = match self {
             StorageConfig::Volatile => MdbxDatabase::new_volatile(config)?,
             StorageConfig::Filesystem(file_storage) => {
                 let db_path = file_storage.database_parent.join(db_name);
@@ -371,7 +408,20 @@ impl StorageConfig {
                     .to_string();
                 MdbxDatabase::new(db_path, config)?
             }
-        })
+        };
+
+        // Bring the database schema up to date before any component uses the database.
+        let migrations = database_migrations();
+        let schema_version = migrations.run(&db, db_config.migrations_dry_run)?;
+        if schema_version != migrations.latest_version() {
+            return Err(Error::config_error(format!(
+                "Database schema version {schema_version} needs to be migrated to version {}, \
+                 which was skipped in the dry run",
+                migrations.latest_version()
+            )));
+        }
+
+        Ok(db)
     }
 
     #[cfg(feature = "validator")]
@@ -473,6 +523,30 @@ impl StorageConfig {
         })
     }
 
+    /// Returns the configuration for persisting the peer contacts and DHT records of the network
+    /// in the storage directory. Returns `None` for volatile storage.
+    pub(crate) fn peer_store(
+        &self,
+        network_id: NetworkId,
+        max_contact_age: Option<Duration>,
+    ) -> Option<PeerStoreConfig> {
+        match self {
+            StorageConfig::Volatile => None,
+            StorageConfig::Filesystem(file_storage) => {
+                let contacts_file = format!("{network_id}-peer-contacts.dat").to_lowercase();
+                let records_file = format!("{network_id}-dht-records.dat").to_lowercase();
+                let mut config = PeerStoreConfig::new(
+                    file_storage.database_parent.join(contacts_file),
+                    file_storage.database_parent.join(records_file),
+                );
+                if let Some(max_contact_age) = max_contact_age {
+                    config.max_contact_age = max_contact_age;
+                }
+                Some(config)
+            }
+        }
+    }
+
     pub(crate) fn identity_keypair(&self) -> Result<IdentityKeypair, Error> {
         match self {
             StorageConfig::Volatile => Ok(IdentityKeypair::generate_ed25519()),
@@ -512,6 +586,9 @@ pub struct ValidatorConfig {
 
     /// Config if the validator automatically reactivates itself.
     pub automatic_reactivate: bool,
+
+    /// The extra data included in the micro blocks produced by the validator.
+    pub extra_data: Vec<u8>,
 }
 
 /// Credentials for JSON RPC server, metrics server or websocket RPC server
@@ -538,6 +615,27 @@ impl Credentials {
     }
 }
 
+/// A user of the JSON RPC server
+#[cfg(feature = "rpc-server")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcUserConfig {
+    /// The credentials the user authenticates with.
+    pub credentials: Credentials,
+    /// The name of the role of the user.
+    pub role: String,
+}
+
+/// The methods the users of a role are allowed to call on the JSON RPC server
+#[cfg(feature = "rpc-server")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RpcRoleConfig {
+    /// The dispatchers (e.g. `blockchain`) whose methods are allowed.
+    pub dispatchers: Vec<String>,
+    /// The methods that are allowed in addition to the ones of the dispatchers. `*` allows all
+    /// methods.
+    pub methods: Vec<String>,
+}
+
 #[cfg(feature = "rpc-server")]
 #[derive(Builder, Clone, Debug)]
 #[builder(setter(into))]
@@ -556,14 +654,21 @@ pub struct RpcServerConfig {
     #[builder(default = "consts::RPC_DEFAULT_PORT")]
     pub port: u16,
 
-    /// TODO: Use this config setting and add it to the config.example.toml file
+    /// If specified, browsers are only allowed to send cross-origin requests from these origins.
+    /// `*` allows any origin.
+    ///
     #[builder(setter(strip_option))]
     pub corsdomain: Option<Vec<String>>,
 
-    /// If specified, only allow connections from these IP addresses
+    /// If specified, only allow connections from these IP addresses and networks
     ///
     #[builder(setter(strip_option))]
-    pub allow_ips: Option<Vec<IpAddr>>,
+    pub allow_ips: Option<IpAllowList>,
+
+    /// If specified, the RPC server only accepts TLS connections using this certificate
+    ///
+    #[builder(default)]
+    pub tls: Option<TlsConfig>,
 
     /// If specified, only allow these RPC methods
     ///
@@ -573,6 +678,28 @@ pub struct RpcServerConfig {
     /// If specified, require HTTP basic auth with these credentials
     #[builder(setter(strip_option))]
     pub credentials: Option<Credentials>,
+
+    /// If specified, limit the requests of each client to these quotas
+    ///
+    #[builder(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+
+    /// Additional users that may access the server, each with the permissions of their role.
+    /// The user given by `credentials` is allowed to call all methods.
+    ///
+    #[builder(default)]
+    pub users: Vec<RpcUserConfig>,
+
+    /// The roles of the `users` by name
+    ///
+    #[builder(default)]
+    pub roles: HashMap<String, RpcRoleConfig>,
+
+    /// If specified, the maximum size of request bodies checked for access control and rate
+    /// limiting, in bytes
+    ///
+    #[builder(default)]
+    pub max_body_size: Option<usize>,
 }
 
 #[cfg(feature = "metrics-server")]
@@ -718,6 +845,7 @@ impl ClientConfigBuilder {
             control_size_limit,
             filter_rules,
             filter_limit,
+            ..Default::default()
         });
         self
     }
@@ -762,12 +890,15 @@ impl ClientConfigBuilder {
             only_secure_ws_connections: false,
             allow_loopback_addresses: config_file.network.allow_loopback_addresses,
             dht_quorum: config_file.network.dht_quorum,
+            peer_store: config_file.network.peer_store,
+            peer_store_max_age: config_file.network.peer_store_max_age,
         });
 
         // Configure consensus
         let mut consensus = ConsensusConfigBuilder::default()
             .sync_mode(config_file.consensus.sync_mode)
             .index_history(config_file.consensus.index_history)
+            .archive_accounts(config_file.consensus.archive_accounts)
             .build()
             .unwrap();
         if let Some(min_peers) = config_file.consensus.min_peers {
@@ -801,6 +932,13 @@ impl ClientConfigBuilder {
             self.validator(ValidatorConfig {
                 validator_address: Address::from_any_str(&validator_config.validator_address)?,
                 automatic_reactivate: validator_config.automatic_reactivate,
+                extra_data: validator_config
+                    .extra_data
+                    .as_deref()
+                    .map(|template| expand_extra_data(template, consts::CLIENT_VERSION))
+                    .transpose()
+                    .map_err(|e| Error::config_error(e.to_string()))?
+                    .unwrap_or_default(),
             });
 
             if let Some(key_path) = &validator_config.voting_key_file {
@@ -857,15 +995,10 @@ impl ClientConfigBuilder {
                 let allow_ips = if rpc_config.allowip.is_empty() {
                     None
                 } else {
-                    let result = rpc_config
-                        .allowip
-                        .iter()
-                        .map(|s| {
-                            s.parse::<IpAddr>()
-                                .map_err(|e| Error::config_error(format!("Invalid IP: {e}")))
-                        })
-                        .collect::<Result<Vec<IpAddr>, Error>>();
-                    Some(result?)
+                    Some(
+                        IpAllowList::parse(&rpc_config.allowip)
+                            .map_err(|e| Error::config_error(e.to_string()))?,
+                    )
                 };
 
                 let credentials = match (&rpc_config.username, &rpc_config.password) {
@@ -878,13 +1011,77 @@ impl ClientConfigBuilder {
                     }
                 };
 
+                let rate_limit = rpc_config
+                    .rate_limit
+                    .as_ref()
+                    .map(|rate_limit| {
+                        let token_bucket = |settings: &TokenBucketSettings| {
+                            if settings.capacity == 0
+                                || !settings.refill_per_second.is_finite()
+                                || settings.refill_per_second <= 0.0
+                            {
+                                return Err(Error::config_error(
+                                    "RPC: Rate limit capacity and refill rate must be positive.",
+                                ));
+                            }
+                            Ok(TokenBucketConfig {
+                                capacity: settings.capacity,
+                                refill_per_second: settings.refill_per_second,
+                            })
+                        };
+                        Ok::<_, Error>(RateLimitConfig {
+                            per_ip: rate_limit.per_ip.as_ref().map(token_bucket).transpose()?,
+                            per_credential: rate_limit
+                                .per_credential
+                                .as_ref()
+                                .map(token_bucket)
+                                .transpose()?,
+                            method_costs: rate_limit.method_costs.clone(),
+                        })
+                    })
+                    .transpose()?;
+
+                let users = rpc_config
+                    .users
+                    .iter()
+                    .map(|user| {
+                        if !rpc_config.roles.contains_key(&user.role) {
+                            return Err(Error::config_error(format!(
+                                "RPC: Role {} of user {} is not defined.",
+                                user.role, user.username
+                            )));
+                        }
+                        Ok(RpcUserConfig {
+                            credentials: Credentials::new(&user.username, &user.password),
+                            role: user.role.clone(),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let roles = rpc_config
+                    .roles
+                    .iter()
+                    .map(|(name, role)| {
+                        let role = RpcRoleConfig {
+                            dispatchers: role.dispatchers.clone(),
+                            methods: role.methods.clone(),
+                        };
+                        (name.clone(), role)
+                    })
+                    .collect();
+
                 self.rpc_server = Some(Some(RpcServerConfig {
                     bind_to,
                     port: rpc_config.port.unwrap_or(consts::RPC_DEFAULT_PORT),
                     corsdomain: Some(rpc_config.corsdomain.clone()),
                     allow_ips,
+                    tls: rpc_config.tls.as_ref().map(|s| s.clone().into()),
                     allowed_methods: Some(rpc_config.methods.clone()),
                     credentials,
+                    rate_limit,
+                    users,
+                    roles,
+                    max_body_size: rpc_config.max_body_size,
                 }));
             }
         }
@@ -940,6 +1137,14 @@ impl ClientConfigBuilder {
             self.network_id(network_id);
         }
 
+        // Only log the pending database migrations
+        #[cfg(feature = "database-storage")]
+        if command_line.migrations_dry_run {
+            self.database
+                .get_or_insert_with(DatabaseConfig::default)
+                .migrations_dry_run = true;
+        }
+
         // NOTE: We're always return `Ok(_)`, but we might want to introduce errors later.
         Ok(self)
     }
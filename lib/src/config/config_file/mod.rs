@@ -148,6 +148,10 @@ pub struct NetworkSettings {
     pub allow_loopback_addresses: bool,
     #[serde(default)]
     pub dht_quorum: Option<NonZeroU8>,
+    #[serde(default)]
+    pub peer_store: bool,
+    #[serde(default)]
+    pub peer_store_max_age: Option<u64>,
 }
 
 impl NetworkSettings {
@@ -191,6 +195,10 @@ pub struct ConsensusSettings {
     /// History indices enabled. Only effective for history nodes (default: `true`)
     #[serde(default = "default_true")]
     pub index_history: bool,
+    /// Archive the previous values of all accounts. Only effective for full and history nodes
+    /// (default: `false`)
+    #[serde(default)]
+    pub archive_accounts: bool,
 }
 
 impl Default for ConsensusSettings {
@@ -202,6 +210,7 @@ impl Default for ConsensusSettings {
             min_peers: None,
             full_sync_threshold: None,
             index_history: true,
+            archive_accounts: false,
         }
     }
 }
@@ -261,6 +270,46 @@ pub struct RpcServerSettings {
     pub methods: Vec<String>,
     pub username: Option<String>,
     pub password: Option<Sensitive<String>>,
+    pub tls: Option<TlsSettings>,
+    pub rate_limit: Option<RpcRateLimitSettings>,
+    #[serde(default)]
+    pub users: Vec<RpcUserSettings>,
+    #[serde(default)]
+    pub roles: HashMap<String, RpcRoleSettings>,
+    pub max_body_size: Option<usize>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcUserSettings {
+    pub username: String,
+    pub password: Sensitive<String>,
+    pub role: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RpcRoleSettings {
+    #[serde(default)]
+    pub dispatchers: Vec<String>,
+    #[serde(default)]
+    pub methods: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RpcRateLimitSettings {
+    pub per_ip: Option<TokenBucketSettings>,
+    pub per_credential: Option<TokenBucketSettings>,
+    #[serde(default)]
+    pub method_costs: HashMap<String, u32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenBucketSettings {
+    pub capacity: u32,
+    pub refill_per_second: f64,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -345,6 +394,8 @@ pub struct MempoolSettings {
     pub size_limit: Option<usize>,
     pub control_size_limit: Option<usize>,
     pub blacklist_limit: Option<usize>,
+    /// Record the mempool transactions in the database, such that they survive a restart
+    pub journal: Option<bool>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -409,6 +460,7 @@ impl From<MempoolSettings> for MempoolConfig {
                 .blacklist_limit
                 .unwrap_or(MempoolFilter::DEFAULT_BLACKLIST_SIZE),
             filter_rules: mempool.filter.map(MempoolRules::from).unwrap_or_default(),
+            journal: mempool.journal.unwrap_or(false),
         }
     }
 }
@@ -446,6 +498,7 @@ pub struct ValidatorSettings {
     pub fee_key: Option<Sensitive<String>>,
     #[serde(default)]
     pub automatic_reactivate: bool,
+    pub extra_data: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -1,5 +1,8 @@
 use std::net::{IpAddr, Ipv4Addr};
 
+/// The version of the client.
+pub const CLIENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// The default port for `ws` and `wss`.
 pub const WS_DEFAULT_PORT: u16 = 8443;
 
@@ -11,6 +11,10 @@ pub enum Error {
     #[error("MDBX error: {0}")]
     Lmdb(#[from] nimiq_database::Error),
 
+    #[cfg(feature = "full-consensus")]
+    #[error("Snapshot error: {0}")]
+    Snapshot(#[from] nimiq_blockchain::SnapshotError),
+
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
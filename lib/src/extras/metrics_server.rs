@@ -4,25 +4,82 @@ use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_consensus::ConsensusProxy;
 #[cfg(feature = "nimiq-mempool")]
 use nimiq_mempool::mempool::Mempool;
+use nimiq_metrics_server::MetricsCollector;
 pub use nimiq_metrics_server::NimiqTaskMonitor;
+#[cfg(feature = "validator")]
+use nimiq_metrics_server::NumericClosureMetric;
+#[cfg(any(feature = "validator", feature = "rpc-server"))]
+use nimiq_metrics_server::Registry;
 use nimiq_network_interface::network::Network;
+#[cfg(feature = "rpc-server")]
+use nimiq_rpc_server::rpc_metrics::RpcMetrics;
+#[cfg(feature = "validator")]
+use nimiq_validator::validator::ValidatorProxy;
 
 pub fn start_metrics_server<TNetwork: Network>(
     addr: SocketAddr,
     blockchain_proxy: BlockchainProxy,
     #[cfg(feature = "nimiq-mempool")] mempool: Option<Arc<Mempool>>,
+    #[cfg(feature = "validator")] validator: Option<ValidatorProxy>,
+    #[cfg(feature = "rpc-server")] rpc: Option<Arc<RpcMetrics>>,
     consensus_proxy: ConsensusProxy<TNetwork>,
     network: Arc<nimiq_network_libp2p::Network>,
     task_monitors: &[NimiqTaskMonitor],
 ) {
     #[cfg(not(feature = "nimiq-mempool"))]
     let mempool = None;
+
+    // The metrics server doesn't depend on the validator and the RPC server, so we register their
+    // metrics here.
+    #[allow(unused_mut)]
+    let mut collectors: Vec<MetricsCollector> = vec![];
+    #[cfg(feature = "validator")]
+    if let Some(validator) = validator {
+        collectors.push(Box::new(move |registry: &mut Registry| {
+            register_validator_metrics(registry, validator)
+        }));
+    }
+    #[cfg(feature = "rpc-server")]
+    if let Some(rpc) = rpc {
+        collectors.push(Box::new(move |registry: &mut Registry| {
+            rpc.register(registry.sub_registry_with_prefix("rpc"))
+        }));
+    }
+
     nimiq_metrics_server::start_metrics_server(
         addr,
         blockchain_proxy,
         mempool,
+        collectors,
         consensus_proxy,
         network,
         task_monitors,
     );
 }
+
+#[cfg(feature = "validator")]
+fn register_validator_metrics(registry: &mut Registry, validator: ValidatorProxy) {
+    let sub_registry = registry.sub_registry_with_prefix("validator");
+
+    validator.metrics.register(sub_registry);
+
+    let consensus_state = validator.consensus_state.clone();
+    let closure = NumericClosureMetric::new_gauge(Box::new(move || {
+        consensus_state.read().num_equivocation_proofs() as i64
+    }));
+    sub_registry.register(
+        "equivocation_proofs",
+        "Equivocation proofs collected but not yet included in a block",
+        closure,
+    );
+
+    let metrics = validator.metrics;
+    let closure = NumericClosureMetric::new_gauge(Box::new(move || {
+        metrics.time_since_last_produced_block().as_secs() as i64
+    }));
+    sub_registry.register(
+        "seconds_since_last_produced_block",
+        "Seconds since this validator last produced a block",
+        closure,
+    );
+}
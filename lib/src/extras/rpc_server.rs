@@ -1,74 +1,286 @@
-use std::{collections::HashSet, iter::FromIterator, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    iter::FromIterator,
+    net::SocketAddr,
+    sync::Arc,
+};
 
 use nimiq_jsonrpc_server::{
-    AllowListDispatcher, Config, Credentials, ModularDispatcher, Server as _Server,
+    AllowListDispatcher, Config, Credentials, Dispatcher, ModularDispatcher, Server as _Server,
+};
+#[cfg(feature = "metrics-server")]
+use nimiq_rpc_server::rpc_metrics::{MetricsDispatcher, RpcMetrics};
+use nimiq_rpc_server::{
+    access_control::{AccessControl, CorsPolicy, RpcRole, RpcUser, RpcUsers, TlsIdentity},
+    dispatchers::*,
+    gateway::{unused_loopback_addr, RpcGateway, UpstreamSecret, HANDSHAKE_METHOD},
 };
-use nimiq_rpc_server::dispatchers::*;
+use nimiq_utils::spawn;
 use nimiq_wallet::WalletStore;
 
 #[cfg(feature = "rpc-server")]
-use crate::config::config::RpcServerConfig;
-use crate::{client::Client, config::consts::default_bind, error::Error};
+use crate::config::config::{RpcRoleConfig, RpcServerConfig};
+use crate::{
+    client::Client,
+    config::consts::{default_bind, CLIENT_VERSION},
+    error::Error,
+};
 
+#[cfg(not(feature = "metrics-server"))]
 pub type Server = _Server<AllowListDispatcher<ModularDispatcher>>;
+#[cfg(feature = "metrics-server")]
+pub type Server = _Server<MetricsDispatcher<AllowListDispatcher<ModularDispatcher>>>;
+
+/// The JSON-RPC server, optionally running behind an [`RpcGateway`] that enforces the configured
+/// IP allow-list and CORS origins and terminates TLS.
+pub struct RpcServer {
+    server: Server,
+    gateway: Option<RpcGateway>,
+    #[cfg(feature = "metrics-server")]
+    metrics: Arc<RpcMetrics>,
+}
+
+impl RpcServer {
+    /// Returns the metrics of the requests handled by this server.
+    #[cfg(feature = "metrics-server")]
+    pub fn metrics(&self) -> Arc<RpcMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Runs the server and its gateway.
+    ///
+    /// Returns an error if the gateway stops, e.g. because it failed to verify the JSON-RPC server.
+    /// The JSON-RPC server is only reachable through the gateway then, so the caller shouldn't
+    /// continue without it.
+    pub async fn run(self) -> Result<(), Error> {
+        let Some(gateway) = self.gateway else {
+            self.server.run().await;
+            return Ok(());
+        };
+
+        let server = self.server;
+        spawn(async move { server.run().await });
+        gateway.run().await?;
+        Ok(())
+    }
+}
 
 #[cfg(feature = "rpc-server")]
 pub fn initialize_rpc_server(
     client: &Client,
     config: RpcServerConfig,
     wallet_store: Arc<WalletStore>,
-) -> Result<Server, Error> {
+) -> Result<RpcServer, Error> {
     let ip = config.bind_to.unwrap_or_else(default_bind);
     log::info!("Initializing RPC server: {}:{}", ip, config.port);
 
-    // Configure RPC server
-    let basic_auth = config.credentials.map(|credentials| {
-        Credentials::new_from_blake2b(credentials.username, credentials.password_hash.0 .0)
-    });
-
     let allowed_methods = config.allowed_methods.unwrap_or_default();
-    let allowed_methods = if allowed_methods.is_empty() {
+    let mut allowed_methods = if allowed_methods.is_empty() {
         None
     } else {
         Some(HashSet::from_iter(allowed_methods))
     };
 
-    // TODO: Pass this to the rpc server config
-    let _corsdomain = config.corsdomain.unwrap_or_default();
+    let corsdomain = config.corsdomain.unwrap_or_default();
+    let mut access_control = AccessControl {
+        allowed_ips: config.allow_ips,
+        cors: (!corsdomain.is_empty()).then(|| CorsPolicy::new(corsdomain)),
+        tls: config
+            .tls
+            .map(|tls| TlsIdentity::from_pem_files(tls.certificates, tls.private_key))
+            .transpose()?,
+        rate_limit: config.rate_limit,
+        users: None,
+        max_body_size: config.max_body_size,
+    };
 
     let mut dispatcher = ModularDispatcher::default();
+    let mut dispatcher_methods = HashMap::new();
 
     let wallet_dispatcher = WalletDispatcher::new(wallet_store);
     let unlocked_wallets = Arc::clone(&wallet_dispatcher.unlocked_wallets);
 
-    dispatcher.add(BlockchainDispatcher::new(client.blockchain()));
+    add_dispatcher(
+        &mut dispatcher,
+        &mut dispatcher_methods,
+        "blockchain",
+        BlockchainDispatcher::new(client.blockchain()),
+    );
 
-    dispatcher.add(ConsensusDispatcher::new(
-        client.consensus_proxy(),
-        Some(unlocked_wallets),
-    ));
-    dispatcher.add(NetworkDispatcher::new(client.network()));
+    add_dispatcher(
+        &mut dispatcher,
+        &mut dispatcher_methods,
+        "consensus",
+        ConsensusDispatcher::new(client.consensus_proxy(), Some(unlocked_wallets)),
+    );
+    add_dispatcher(
+        &mut dispatcher,
+        &mut dispatcher_methods,
+        "network",
+        NetworkDispatcher::new(client.network()),
+    );
     if let Some(mempool) = client.mempool() {
-        dispatcher.add(MempoolDispatcher::new(mempool));
+        add_dispatcher(
+            &mut dispatcher,
+            &mut dispatcher_methods,
+            "mempool",
+            MempoolDispatcher::new(mempool),
+        );
     }
-    dispatcher.add(PolicyDispatcher {});
+    add_dispatcher(
+        &mut dispatcher,
+        &mut dispatcher_methods,
+        "policy",
+        PolicyDispatcher {},
+    );
     if let Some(validator_proxy) = client.validator_proxy() {
-        dispatcher.add(ValidatorDispatcher::new(
-            validator_proxy,
-            client.consensus_proxy(),
+        add_dispatcher(
+            &mut dispatcher,
+            &mut dispatcher_methods,
+            "validator",
+            ValidatorDispatcher::new(validator_proxy, client.consensus_proxy(), CLIENT_VERSION),
+        );
+    }
+    add_dispatcher(
+        &mut dispatcher,
+        &mut dispatcher_methods,
+        "wallet",
+        wallet_dispatcher,
+    );
+
+    add_dispatcher(
+        &mut dispatcher,
+        &mut dispatcher_methods,
+        "zkpComponent",
+        ZKPComponentDispatcher::new(client.zkp_component()),
+    );
+
+    // Users with roles are authenticated by the gateway.
+    let mut users = Vec::with_capacity(config.users.len());
+    for user in config.users {
+        let role = config
+            .roles
+            .get(&user.role)
+            .ok_or_else(|| Error::config_error(format!("RPC role {} is not defined", user.role)))?;
+        users.push(RpcUser::new(
+            user.credentials.username,
+            user.credentials.password_hash.0,
+            resolve_role(role, &dispatcher_methods)?,
         ));
     }
-    dispatcher.add(wallet_dispatcher);
 
-    dispatcher.add(ZKPComponentDispatcher::new(client.zkp_component()));
+    // If access restrictions are configured, the server is only reachable through the gateway,
+    // which listens on the configured address instead. The server only accepts requests carrying
+    // the secret of the gateway, so local processes can't bypass the gateway, and proves its
+    // identity to the gateway with a handshake.
+    let bind_to: SocketAddr = (ip, config.port).into();
+    let (server_addr, basic_auth, gateway) = if access_control.is_enabled() || !users.is_empty() {
+        // The user given by the credentials is authenticated by the gateway as well, as a user
+        // that is allowed to call all methods.
+        if let Some(credentials) = config.credentials {
+            users.insert(
+                0,
+                RpcUser::new(
+                    credentials.username,
+                    credentials.password_hash.0,
+                    RpcRole::unrestricted(),
+                ),
+            );
+        }
+        access_control.users = (!users.is_empty()).then(|| RpcUsers::new(users));
+
+        let secret = UpstreamSecret::random();
+        dispatcher.add(GatewayDispatcher::new(secret.clone()));
+        if let Some(allowed_methods) = &mut allowed_methods {
+            allowed_methods.insert(HANDSHAKE_METHOD.to_owned());
+        }
+        let basic_auth = Credentials::new_from_blake2b(
+            UpstreamSecret::USERNAME.to_owned(),
+            secret.password_hash().0,
+        );
+        let server_addr = unused_loopback_addr()?;
+        let gateway = RpcGateway::bind(bind_to, server_addr, secret, access_control)?;
+        (server_addr, Some(basic_auth), Some(gateway))
+    } else {
+        let basic_auth = config.credentials.map(|credentials| {
+            Credentials::new_from_blake2b(credentials.username, credentials.password_hash.0 .0)
+        });
+        (bind_to, basic_auth, None)
+    };
 
-    Ok(Server::new(
+    #[cfg(not(feature = "metrics-server"))]
+    let dispatcher = AllowListDispatcher::new(dispatcher, allowed_methods);
+    #[cfg(feature = "metrics-server")]
+    let metrics = Arc::new(RpcMetrics::default());
+    #[cfg(feature = "metrics-server")]
+    let dispatcher = MetricsDispatcher::new(
+        AllowListDispatcher::new(dispatcher, allowed_methods.clone()),
+        Arc::clone(&metrics),
+        allowed_methods,
+    );
+
+    let server = Server::new(
         Config {
-            bind_to: (config.bind_to.unwrap_or_else(default_bind), config.port).into(),
+            bind_to: server_addr,
             enable_websocket: false,
             ip_whitelist: None,
             basic_auth,
         },
-        AllowListDispatcher::new(dispatcher, allowed_methods),
-    ))
+        dispatcher,
+    );
+
+    Ok(RpcServer {
+        server,
+        gateway,
+        #[cfg(feature = "metrics-server")]
+        metrics,
+    })
+}
+
+/// The names of the dispatchers that roles can refer to.
+const DISPATCHER_NAMES: &[&str] = &[
+    "blockchain",
+    "consensus",
+    "mempool",
+    "network",
+    "policy",
+    "validator",
+    "wallet",
+    "zkpComponent",
+];
+
+/// Adds the dispatcher and remembers its methods under the given name, for resolving roles.
+fn add_dispatcher<D: Dispatcher + 'static>(
+    dispatcher: &mut ModularDispatcher,
+    dispatcher_methods: &mut HashMap<&'static str, Vec<String>>,
+    name: &'static str,
+    inner: D,
+) {
+    let methods = inner.method_names().into_iter().map(String::from).collect();
+    dispatcher_methods.insert(name, methods);
+    dispatcher.add(inner);
+}
+
+/// Resolves the methods a role allows. Dispatchers that aren't running on this node (e.g. the
+/// validator dispatcher on a non-validator node) don't add any methods.
+fn resolve_role(
+    role: &RpcRoleConfig,
+    dispatcher_methods: &HashMap<&'static str, Vec<String>>,
+) -> Result<RpcRole, Error> {
+    if role.methods.iter().any(|method| method == "*") {
+        return Ok(RpcRole::unrestricted());
+    }
+
+    let mut methods = role.methods.clone();
+    for name in &role.dispatchers {
+        if !DISPATCHER_NAMES.contains(&name.as_str()) {
+            return Err(Error::config_error(format!(
+                "Unknown RPC dispatcher {name}"
+            )));
+        }
+        if let Some(dispatcher_methods) = dispatcher_methods.get(name.as_str()) {
+            methods.extend(dispatcher_methods.iter().cloned());
+        }
+    }
+    Ok(RpcRole::new(methods))
 }
@@ -0,0 +1,9 @@
+use nimiq_database::{mdbx::MdbxDatabase, migration::Migrations};
+
+/// Returns the ordered migration steps for the client database.
+///
+/// Whenever the layout of a table changes, a step that converts the existing entries is
+/// registered here with the next schema version. The steps are run when the client starts.
+pub fn database_migrations() -> Migrations<MdbxDatabase> {
+    Migrations::new()
+}
@@ -0,0 +1,101 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_genesis::NetworkInfo;
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::policy::Policy;
+use nimiq_utils::time::OffsetTime;
+use parking_lot::RwLock;
+
+use crate::{
+    config::{
+        config::{ClientConfig, StorageConfig},
+        config_file::SyncMode,
+    },
+    error::Error,
+};
+
+/// Opens the consensus database of the client and loads the blockchain from it.
+fn open_blockchain(config: &ClientConfig) -> Result<Blockchain, Error> {
+    let network_info = NetworkInfo::from_network_id(config.network_id);
+    let _ = Policy::get_or_init(Policy {
+        genesis_block_number: network_info.genesis_block().block_number(),
+        ..Default::default()
+    });
+
+    let keep_history = match config.consensus.sync_mode {
+        SyncMode::History => true,
+        SyncMode::Full => false,
+        SyncMode::Light => {
+            return Err(Error::config_error(
+                "Snapshots are not supported by light clients",
+            ))
+        }
+    };
+    if config.storage == StorageConfig::Volatile {
+        return Err(Error::config_error(
+            "Snapshots require a database on the filesystem",
+        ));
+    }
+
+    let environment = config.storage.database(
+        config.network_id,
+        config.consensus.sync_mode,
+        config.database.clone(),
+    )?;
+    let blockchain_config = BlockchainConfig {
+        keep_history,
+        max_epochs_stored: config.consensus.max_epochs_stored,
+        index_history: keep_history && config.consensus.index_history,
+        archive_accounts: config.consensus.archive_accounts,
+    };
+
+    Ok(Blockchain::new(
+        environment,
+        blockchain_config,
+        config.network_id,
+        Arc::new(OffsetTime::new()),
+    )
+    .map_err(nimiq_consensus::Error::BlockchainError)?)
+}
+
+/// Writes a snapshot of the state at the latest macro block of the client's database to `path`.
+pub fn export_snapshot(
+    config: &ClientConfig,
+    path: &Path,
+    include_history: bool,
+) -> Result<(), Error> {
+    let blockchain = open_blockchain(config)?;
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    let header = blockchain.export_snapshot(&mut writer, include_history)?;
+    writer.flush()?;
+
+    log::info!(
+        block = %header.macro_block,
+        path = %path.display(),
+        "Snapshot written"
+    );
+    Ok(())
+}
+
+/// Imports the snapshot at `path` into the client's database, which must not contain any blocks
+/// other than the genesis block. Nodes without history require the hash of the snapshot's election
+/// block from a trusted source.
+pub fn import_snapshot(
+    config: &ClientConfig,
+    path: &Path,
+    trusted_election_hash: Option<&Blake2bHash>,
+) -> Result<(), Error> {
+    let blockchain = RwLock::new(open_blockchain(config)?);
+
+    let mut reader = BufReader::new(File::open(path)?);
+    Blockchain::import_snapshot(&blockchain, &mut reader, trusted_election_hash)?;
+
+    Ok(())
+}
@@ -9,6 +9,7 @@ use log::{debug, warn};
 use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent};
 use nimiq_consensus::{Consensus, ConsensusEvent, ConsensusProxy};
+use nimiq_database::mdbx::MdbxDatabase;
 use nimiq_mempool::{config::MempoolConfig, mempool::Mempool};
 use nimiq_network_interface::network::Network;
 use nimiq_utils::spawn;
@@ -47,11 +48,16 @@ impl<N: Network> MempoolTask<N> {
     pub fn new(
         consensus: &Consensus<N>,
         blockchain: Arc<RwLock<Blockchain>>,
+        env: MdbxDatabase,
         mempool_config: MempoolConfig,
     ) -> Self {
         let consensus_event_rx = consensus.subscribe_events();
 
-        let mempool = Arc::new(Mempool::new(Arc::clone(&blockchain), mempool_config));
+        let mempool = Arc::new(Mempool::with_database(
+            Arc::clone(&blockchain),
+            mempool_config,
+            env,
+        ));
         let mempool_active = false;
 
         let blockchain_event_rx = blockchain.read().notifier_as_stream();
@@ -91,6 +97,7 @@ impl<N: Network> MempoolTask<N> {
         }
 
         let mempool = Arc::clone(&self.mempool);
+        let consensus = self.consensus.clone();
         let network = Arc::clone(&self.consensus.network);
         #[cfg(not(feature = "metrics"))]
         spawn({
@@ -98,6 +105,7 @@ impl<N: Network> MempoolTask<N> {
                 // The mempool is not updated while consensus is lost.
                 // Thus, we need to check all transactions if they are still valid.
                 mempool.cleanup();
+                Self::restore_journal(&mempool, &consensus).await;
                 mempool.start_executors(network, None, None).await;
             }
         });
@@ -109,6 +117,7 @@ impl<N: Network> MempoolTask<N> {
                 // The mempool is not updated while consensus is lost.
                 // Thus, we need to check all transactions if they are still valid.
                 mempool.cleanup();
+                Self::restore_journal(&mempool, &consensus).await;
 
                 mempool
                     .start_executors(network, Some(mempool_monitor), Some(ctrl_mempool_monitor))
@@ -119,6 +128,16 @@ impl<N: Network> MempoolTask<N> {
         self.mempool_active = true;
     }
 
+    /// Restores the transactions recorded in the mempool journal and broadcasts them again.
+    /// Only has an effect the first time consensus is established.
+    async fn restore_journal(mempool: &Mempool, consensus: &ConsensusProxy<N>) {
+        for tx in mempool.restore_journal() {
+            if let Err(error) = consensus.send_transaction(tx).await {
+                warn!(%error, "Failed to broadcast restored mempool transaction");
+            }
+        }
+    }
+
     fn pause(&mut self) {
         if !self.mempool_active {
             return;
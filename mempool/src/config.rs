@@ -14,6 +14,9 @@ pub struct MempoolConfig {
     pub filter_rules: MempoolRules,
     /// Mempool filter limit or size
     pub filter_limit: usize,
+    /// Whether the transactions in the mempool are recorded in the database, such that they are
+    /// restored after a restart
+    pub journal: bool,
 }
 
 impl Default for MempoolConfig {
@@ -23,6 +26,7 @@ impl Default for MempoolConfig {
             control_size_limit: Mempool::DEFAULT_CONTROL_SIZE_LIMIT,
             filter_rules: MempoolRules::default(),
             filter_limit: MempoolFilter::DEFAULT_BLACKLIST_SIZE,
+            journal: false,
         }
     }
 }
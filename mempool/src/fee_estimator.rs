@@ -0,0 +1,204 @@
+use std::collections::VecDeque;
+
+use nimiq_block::Block;
+use nimiq_primitives::{coin::Coin, policy::Policy};
+use nimiq_serde::Serialize;
+use nimiq_transaction::Transaction;
+
+/// A suggested fee for a transaction to be included within a target number of blocks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeEstimate {
+    /// The number of blocks within which the transaction should be included.
+    pub target_blocks: u32,
+    /// The fee per byte a transaction needs to exceed to be included within the target blocks.
+    pub fee_per_byte: f64,
+    /// The total size (in bytes) of the transactions in the mempool that are expected to be
+    /// included within the target blocks ahead of a transaction paying the estimated fee.
+    pub backlog_size: usize,
+}
+
+impl FeeEstimate {
+    /// Returns the fee a transaction of the given serialized size should pay, i.e. the smallest
+    /// fee exceeding the estimated fee per byte. Saturates at [`Coin::MAX`].
+    pub fn fee_for_size(&self, size: usize) -> Coin {
+        Transaction::fee_exceeding(self.fee_per_byte, size).unwrap_or(Coin::MAX)
+    }
+}
+
+/// The fees paid in a micro block.
+#[derive(Clone, Debug)]
+struct BlockFees {
+    block_number: u32,
+    /// The lowest fee per byte of the transactions in the block. Zero if the block is empty.
+    min_fee_per_byte: f64,
+    /// The size (in bytes) of the transactions in the block, including their execution result.
+    size: usize,
+}
+
+impl BlockFees {
+    /// Returns whether the block was (almost) full, so that transactions paying less than the
+    /// lowest fee per byte in the block might have been left out.
+    fn is_full(&self) -> bool {
+        self.size * FeeEstimator::FULL_BLOCK_DENOMINATOR
+            >= Policy::MAX_SIZE_MICRO_BODY * FeeEstimator::FULL_BLOCK_NUMERATOR
+    }
+}
+
+/// Suggests fees for transactions based on the fees paid in recent micro blocks and on the backlog
+/// of transactions in the mempool.
+///
+/// A transaction is included within the target number of blocks if it outbids the transactions
+/// in the mempool that don't fit into those blocks. If recent blocks were full, it additionally
+/// needs to pay the fees that were required to get into those blocks, as transactions might be
+/// arriving faster than the mempool suggests.
+#[derive(Clone, Debug)]
+pub struct FeeEstimator {
+    /// The fees of the most recent micro blocks, ordered by block number.
+    blocks: VecDeque<BlockFees>,
+    /// The number of recent micro blocks that are tracked.
+    max_blocks: usize,
+}
+
+impl FeeEstimator {
+    /// Default number of recent micro blocks that are tracked.
+    pub const DEFAULT_TRACKED_BLOCKS: usize = 60;
+
+    /// A block is considered full if the size of its transactions exceeds this fraction of the
+    /// maximum micro body size.
+    const FULL_BLOCK_NUMERATOR: usize = 9;
+    const FULL_BLOCK_DENOMINATOR: usize = 10;
+
+    /// Creates a new fee estimator that tracks the given number of recent micro blocks.
+    pub fn new(max_blocks: usize) -> Self {
+        Self {
+            blocks: VecDeque::with_capacity(max_blocks),
+            max_blocks,
+        }
+    }
+
+    /// Records the fees paid in the given block. Macro blocks and skip blocks are ignored, as they
+    /// don't contain transactions.
+    pub fn add_block(&mut self, block: &Block) {
+        if let Block::Micro(micro_block) = block {
+            if micro_block.is_skip_block() {
+                return;
+            }
+        }
+        if let Some(transactions) = block.transactions() {
+            self.add_transactions(
+                block.block_number(),
+                transactions.iter().map(|tx| tx.get_raw_transaction()),
+            );
+        }
+    }
+
+    /// Records the fees of the given transactions that were included in the block with the given
+    /// number. Blocks with the same or a higher number that were recorded before are replaced.
+    pub fn add_transactions<'a>(
+        &mut self,
+        block_number: u32,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+    ) {
+        self.revert_block(block_number);
+
+        let mut min_fee_per_byte = None;
+        let mut size = 0;
+        for tx in transactions {
+            let fee_per_byte = tx.fee_per_byte();
+            if min_fee_per_byte.map_or(true, |min_fee_per_byte| fee_per_byte < min_fee_per_byte) {
+                min_fee_per_byte = Some(fee_per_byte);
+            }
+            // One extra byte per transaction encodes its execution result.
+            size += 1 + tx.serialized_size();
+        }
+
+        self.blocks.push_back(BlockFees {
+            block_number,
+            min_fee_per_byte: min_fee_per_byte.unwrap_or(0.0),
+            size,
+        });
+        while self.blocks.len() > self.max_blocks {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// Forgets the fees of the block with the given number and of all blocks following it.
+    pub fn revert_block(&mut self, block_number: u32) {
+        while self
+            .blocks
+            .back()
+            .is_some_and(|block| block.block_number >= block_number)
+        {
+            self.blocks.pop_back();
+        }
+    }
+
+    /// Returns the number of recent blocks that are currently tracked.
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Estimates the fee per byte a transaction needs to pay to be included within the given
+    /// number of blocks. The backlog of the mempool is given as the fee per byte and serialized
+    /// size of its transactions, from the highest to the lowest fee per byte. The estimate is at
+    /// least the given minimum fee per byte accepted by the mempool.
+    pub fn estimate(
+        &self,
+        target_blocks: u32,
+        backlog: impl IntoIterator<Item = (f64, usize)>,
+        min_fee_per_byte: f64,
+    ) -> FeeEstimate {
+        let target_blocks = target_blocks.max(1);
+
+        // The transaction needs to outbid the transactions that don't fit into the target blocks
+        // anymore.
+        let capacity = target_blocks as usize * Policy::MAX_SIZE_MICRO_BODY;
+        let mut backlog_size = 0;
+        let mut backlog_fee_per_byte = 0.0;
+        for (fee_per_byte, size) in backlog {
+            if backlog_size + 1 + size > capacity {
+                backlog_fee_per_byte = fee_per_byte;
+                break;
+            }
+            backlog_size += 1 + size;
+        }
+
+        FeeEstimate {
+            target_blocks,
+            fee_per_byte: backlog_fee_per_byte
+                .max(self.congestion_fee_per_byte(target_blocks))
+                .max(min_fee_per_byte),
+            backlog_size,
+        }
+    }
+
+    /// Returns the fee per byte that was needed to get into recent blocks if it is likely that all
+    /// of the next `target_blocks` blocks will be full, judging by the share of recent blocks
+    /// that were full. Returns zero otherwise.
+    fn congestion_fee_per_byte(&self, target_blocks: u32) -> f64 {
+        let mut full_blocks: Vec<_> = self
+            .blocks
+            .iter()
+            .filter(|block| block.is_full())
+            .map(|block| block.min_fee_per_byte)
+            .collect();
+        if full_blocks.is_empty() {
+            return 0.0;
+        }
+
+        let full_share = full_blocks.len() as f64 / self.blocks.len() as f64;
+        if full_share.powi(i32::try_from(target_blocks).unwrap_or(i32::MAX)) <= 0.5 {
+            return 0.0;
+        }
+
+        // The median of the lowest fees per byte that were included in the full blocks.
+        full_blocks.sort_by(|a, b| a.partial_cmp(b).expect("fees can't be NaN"));
+        full_blocks[full_blocks.len() / 2]
+    }
+}
+
+impl Default for FeeEstimator {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TRACKED_BLOCKS)
+    }
+}
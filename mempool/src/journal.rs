@@ -0,0 +1,125 @@
+use std::{
+    iter,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use nimiq_database::{
+    declare_table,
+    mdbx::MdbxDatabase,
+    traits::{Database, ReadCursor, ReadTransaction, WriteTransaction},
+};
+use nimiq_database_value_derive::DbSerializable;
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_transaction::Transaction;
+
+use crate::mempool_transactions::TxPriority;
+
+declare_table!(MempoolJournalTable, "MempoolJournal", Blake2bHash => JournaledTransaction);
+
+/// A transaction recorded in the mempool journal, along with the priority it was added with.
+#[derive(Clone, Debug, Serialize, Deserialize, DbSerializable)]
+pub(crate) struct JournaledTransaction {
+    pub(crate) transaction: Transaction,
+    pub(crate) priority: TxPriority,
+}
+
+enum JournalUpdate {
+    Add(Blake2bHash, JournaledTransaction),
+    Remove(Blake2bHash),
+}
+
+/// An on-disk record of the transactions in the mempool, such that they survive a restart of the
+/// node.
+///
+/// Updates are written to the database by a background thread, so recording them never waits for
+/// the database while the mempool or blockchain locks are held.
+#[derive(Clone)]
+pub(crate) struct MempoolJournal {
+    // Declared before `_writer`, such that the channel is closed once the last handle is dropped
+    // and the writer thread can be joined.
+    updates: Sender<JournalUpdate>,
+    _writer: Arc<JournalWriter>,
+}
+
+impl MempoolJournal {
+    /// Opens the journal in the given database and returns it along with the transactions that
+    /// were recorded in it.
+    pub(crate) fn open(db: MdbxDatabase) -> (Self, Vec<JournaledTransaction>) {
+        db.create_regular_table(&MempoolJournalTable);
+
+        let txn = db.read_transaction();
+        let transactions = txn
+            .cursor(&MempoolJournalTable)
+            .into_iter_start()
+            .map(|(_, journaled_tx)| journaled_tx)
+            .collect();
+        txn.close();
+
+        let (updates, updates_rx) = mpsc::channel();
+        let handle = thread::Builder::new()
+            .name("mempool-journal".to_owned())
+            .spawn(move || Self::write_updates(db, updates_rx))
+            .expect("Failed to spawn mempool journal thread");
+
+        let journal = Self {
+            updates,
+            _writer: Arc::new(JournalWriter(Some(handle))),
+        };
+        (journal, transactions)
+    }
+
+    /// Records a transaction that was added to the mempool.
+    pub(crate) fn add(&self, transaction: &Transaction, priority: TxPriority) {
+        let journaled_tx = JournaledTransaction {
+            transaction: transaction.clone(),
+            priority,
+        };
+        self.send(JournalUpdate::Add(transaction.hash(), journaled_tx));
+    }
+
+    /// Records that a transaction was removed from the mempool.
+    pub(crate) fn remove(&self, tx_hash: &Blake2bHash) {
+        self.send(JournalUpdate::Remove(tx_hash.clone()));
+    }
+
+    fn send(&self, update: JournalUpdate) {
+        if self.updates.send(update).is_err() {
+            log::error!("Mempool journal thread has stopped, the update is lost");
+        }
+    }
+
+    /// Writes the updates to the database until all journal handles are dropped. All updates that
+    /// are queued at the same time are written in a single database transaction.
+    fn write_updates(db: MdbxDatabase, updates: Receiver<JournalUpdate>) {
+        while let Ok(update) = updates.recv() {
+            let mut txn = db.write_transaction();
+            for update in iter::once(update).chain(updates.try_iter()) {
+                match update {
+                    JournalUpdate::Add(tx_hash, journaled_tx) => {
+                        txn.put(&MempoolJournalTable, &tx_hash, &journaled_tx)
+                    }
+                    JournalUpdate::Remove(tx_hash) => txn.remove(&MempoolJournalTable, &tx_hash),
+                }
+            }
+            txn.commit();
+        }
+    }
+}
+
+/// Waits for the writer thread to finish writing the queued updates when the journal is dropped.
+struct JournalWriter(Option<JoinHandle<()>>);
+
+impl Drop for JournalWriter {
+    fn drop(&mut self) {
+        if let Some(handle) = self.0.take() {
+            if handle.join().is_err() {
+                log::error!("Mempool journal thread panicked");
+            }
+        }
+    }
+}
@@ -15,8 +15,12 @@ pub mod config;
 /// Mempool executor module
 pub mod executor;
 
+/// Fee estimation module
+pub mod fee_estimator;
 /// Mempool filter module
 pub mod filter;
+/// Mempool journal module
+mod journal;
 /// Main mempool module
 pub mod mempool;
 /// Mempool metrics
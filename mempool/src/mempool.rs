@@ -1,5 +1,6 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet, VecDeque},
+    mem,
     sync::{atomic::AtomicU32, Arc},
 };
 
@@ -12,6 +13,7 @@ use nimiq_account::ReservedBalance;
 use nimiq_block::Block;
 use nimiq_blockchain::{Blockchain, TransactionVerificationCache};
 use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::mdbx::MdbxDatabase;
 use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::Address;
 use nimiq_network_interface::network::{Network, Topic};
@@ -21,7 +23,7 @@ use nimiq_transaction::{
     TransactionTopic,
 };
 use nimiq_utils::spawn;
-use parking_lot::RwLock;
+use parking_lot::{Mutex as ParkingMutex, RwLock};
 use tokio_metrics::TaskMonitor;
 
 #[cfg(feature = "metrics")]
@@ -29,9 +31,11 @@ use crate::mempool_metrics::MempoolMetrics;
 use crate::{
     config::MempoolConfig,
     executor::MempoolExecutor,
+    fee_estimator::{FeeEstimate, FeeEstimator},
     filter::{MempoolFilter, MempoolRules},
-    mempool_state::{EvictionReason, MempoolState},
-    mempool_transactions::{MempoolTransactions, TxPriority},
+    journal::{JournaledTransaction, MempoolJournal},
+    mempool_state::{EvictionReason, MempoolState, SenderPendingState},
+    mempool_transactions::{BestTxOrder, MempoolTransactions, TxPriority},
     verify::{verify_tx, VerifyErr},
 };
 
@@ -46,6 +50,9 @@ pub struct Mempool {
     /// Mempool filter
     pub(crate) filter: Arc<RwLock<MempoolFilter>>,
 
+    /// Fee estimator tracking the fees paid in recent blocks
+    fee_estimator: RwLock<FeeEstimator>,
+
     /// Mempool executor handle used to stop the executor
     pub(crate) executor_handle: Mutex<Option<AbortHandle>>,
 
@@ -54,6 +61,12 @@ pub struct Mempool {
 
     /// Total number of ongoing verification tasks
     verification_tasks: Arc<AtomicU32>,
+
+    /// The journal recording the mempool transactions, if the mempool is persistent
+    journal: Option<MempoolJournal>,
+
+    /// The transactions recorded in the journal at startup that still need to be restored
+    journaled_transactions: ParkingMutex<Vec<JournaledTransaction>>,
 }
 
 impl Mempool {
@@ -65,9 +78,37 @@ impl Mempool {
 
     /// Creates a new mempool
     pub fn new(blockchain: Arc<RwLock<Blockchain>>, config: MempoolConfig) -> Self {
+        Self::new_inner(blockchain, config, None)
+    }
+
+    /// Creates a new mempool that records its transactions in the given database if the journal
+    /// is enabled in the config. The transactions recorded by a previous instance can be restored
+    /// with [`Mempool::restore_journal`].
+    pub fn with_database(
+        blockchain: Arc<RwLock<Blockchain>>,
+        config: MempoolConfig,
+        db: MdbxDatabase,
+    ) -> Self {
+        Self::new_inner(blockchain, config, Some(db))
+    }
+
+    fn new_inner(
+        blockchain: Arc<RwLock<Blockchain>>,
+        config: MempoolConfig,
+        db: Option<MdbxDatabase>,
+    ) -> Self {
+        let (journal, journaled_transactions) = match db {
+            Some(db) if config.journal => {
+                let (journal, journaled_transactions) = MempoolJournal::open(db);
+                (Some(journal), journaled_transactions)
+            }
+            _ => (None, vec![]),
+        };
+
         let state = Arc::new(RwLock::new(MempoolState::new(
             config.size_limit,
             config.control_size_limit,
+            journal.clone(),
         )));
 
         Self {
@@ -77,9 +118,12 @@ impl Mempool {
                 config.filter_rules,
                 config.filter_limit,
             ))),
+            fee_estimator: RwLock::new(FeeEstimator::default()),
             executor_handle: Mutex::new(None),
             control_executor_handle: Mutex::new(None),
             verification_tasks: Arc::new(AtomicU32::new(0)),
+            journal,
+            journaled_transactions: ParkingMutex::new(journaled_transactions),
         }
     }
 
@@ -302,6 +346,20 @@ impl Mempool {
         // First remove the transactions that are no longer valid due to age.
         self.prune_expired_transactions(&blockchain, &mut mempool_state);
 
+        // Track the fees paid in the adopted blocks for fee estimation.
+        let mut fee_estimator = self.fee_estimator.write();
+        if let Some(block_number) = reverted_blocks
+            .iter()
+            .map(|(_, block)| block.block_number())
+            .min()
+        {
+            fee_estimator.revert_block(block_number);
+        }
+        for (_, block) in adopted_blocks {
+            fee_estimator.add_block(block);
+        }
+        drop(fee_estimator);
+
         // Now iterate over the transactions in the adopted blocks:
         //  if transaction was known:
         //    remove it from the mempool
@@ -330,6 +388,16 @@ impl Mempool {
 
                     // Check if we know the sender of this transaction.
                     if mempool_state.state_by_sender.contains_key(&tx.sender) {
+                        // If this transaction conflicts with one of ours, e.g. because it is
+                        // a transaction that we replaced, our transaction is obsolete now.
+                        if let Some(conflicting_tx_hash) = mempool_state.find_conflicting(tx) {
+                            mempool_state.remove(
+                                &blockchain,
+                                &conflicting_tx_hash,
+                                EvictionReason::AlreadyIncluded,
+                            );
+                        }
+
                         // This an unknown transaction from a known sender, we need to update our
                         // senders balance and some transactions could become invalid
                         affected_senders.insert(tx.sender.clone());
@@ -437,7 +505,7 @@ impl Mempool {
                 None => {
                     // We don't have the sender account so we can't do any balance tracking.
                     // Remove all transactions from this sender.
-                    for hash in &sender_state.txns {
+                    for hash in sender_state.txns.keys() {
                         mempool_state
                             .regular_transactions
                             .delete(hash)
@@ -447,22 +515,20 @@ impl Mempool {
                 }
             };
 
-            // TODO We should have per sender transactions ordered by fee to try to
-            //  keep the ones with higher fee
-
-            sender_state.txns.retain(|tx_hash| {
-                let tx = match mempool_state.get(tx_hash) {
-                    Some(transaction) => transaction,
-                    None => return false,
+            // Reserve the balance in the per-sender ordering, such that the transactions with a
+            // higher fee are kept if not all of the same validity start height can be afforded.
+            for tx_hash in sender_state.ordered_txns() {
+                let still_valid = match mempool_state.get(&tx_hash) {
+                    Some(tx) => blockchain
+                        .reserve_balance(&sender_account, tx, &mut sender_state.reserved_balance)
+                        .is_ok(),
+                    None => false,
                 };
-                let still_valid = blockchain
-                    .reserve_balance(&sender_account, tx, &mut sender_state.reserved_balance)
-                    .is_ok();
                 if !still_valid {
-                    mempool_state.remove(blockchain, tx_hash, EvictionReason::Invalid);
+                    sender_state.txns.remove(&tx_hash);
+                    mempool_state.remove(blockchain, &tx_hash, EvictionReason::Invalid);
                 }
-                still_valid
-            });
+            }
 
             if !sender_state.txns.is_empty() {
                 mempool_state.state_by_sender.insert(address, sender_state);
@@ -505,8 +571,12 @@ impl Mempool {
         max_bytes: usize,
     ) -> (Vec<Transaction>, usize) {
         let mut state = self.state.write();
-        let (txs, size) =
-            Self::get_transactions_for_block_impl(&mut state.regular_transactions, max_bytes);
+        let state = &mut *state;
+        let (txs, size) = Self::get_transactions_for_block_impl(
+            &mut state.regular_transactions,
+            &state.state_by_sender,
+            max_bytes,
+        );
 
         for tx in &txs {
             state.remove(blockchain, &tx.hash(), EvictionReason::BlockBuilding);
@@ -546,8 +616,12 @@ impl Mempool {
         max_bytes: usize,
     ) -> (Vec<Transaction>, usize) {
         let mut state = self.state.write();
-        let (txs, size) =
-            Self::get_transactions_for_block_impl(&mut state.control_transactions, max_bytes);
+        let state = &mut *state;
+        let (txs, size) = Self::get_transactions_for_block_impl(
+            &mut state.control_transactions,
+            &state.state_by_sender,
+            max_bytes,
+        );
 
         for tx in &txs {
             state.remove(blockchain, &tx.hash(), EvictionReason::BlockBuilding);
@@ -564,10 +638,20 @@ impl Mempool {
 
     fn get_transactions_for_block_impl(
         transactions: &mut MempoolTransactions,
+        state_by_sender: &HashMap<Address, SenderPendingState>,
         max_bytes: usize,
     ) -> (Vec<Transaction>, usize) {
         let mut txs = vec![];
         let mut size = 0_usize;
+        let mut included = HashSet::new();
+
+        // Transactions that have to wait until the transactions preceding them in the per-sender
+        // ordering have been included, by sender.
+        let mut deferred: HashMap<Address, Vec<(Blake2bHash, BestTxOrder)>> = HashMap::new();
+
+        // The validity start heights of the transactions of each sender in the per-sender ordering.
+        // Included transactions are dropped from the front, such that the first one is pending.
+        let mut pending_by_sender: HashMap<Address, VecDeque<(u32, Blake2bHash)>> = HashMap::new();
 
         loop {
             // Get the hash of the highest paying transactions.
@@ -579,6 +663,33 @@ impl Mempool {
             // Get the transaction.
             let tx = transactions.get(&tx_hash).unwrap().clone();
 
+            // Transactions of the same sender are included in the per-sender ordering, so defer
+            // this one if a transaction with a lower validity start height is still pending.
+            let has_pending_predecessor =
+                state_by_sender.get(&tx.sender).is_some_and(|sender_state| {
+                    let pending = pending_by_sender
+                        .entry(tx.sender.clone())
+                        .or_insert_with(|| {
+                            sender_state.ordered_validity_start_heights(|hash| {
+                                transactions.contains_key(hash)
+                            })
+                        });
+                    while pending
+                        .front()
+                        .is_some_and(|(_, hash)| included.contains(hash))
+                    {
+                        pending.pop_front();
+                    }
+                    pending.front().is_some_and(|(validity_start_height, _)| {
+                        *validity_start_height < tx.validity_start_height
+                    })
+                });
+            if has_pending_predecessor {
+                let deferred_tx = transactions.best_transactions.pop().unwrap();
+                deferred.entry(tx.sender).or_default().push(deferred_tx);
+                continue;
+            }
+
             // Calculate size. If we can't fit the transaction in the block, then we stop here.
             // TODO: We can optimize this. There might be a smaller transaction that still fits.
             // We need to account for one extra byte per transaction to encode its final execution status
@@ -591,11 +702,22 @@ impl Mempool {
             // Remove the transaction from best_transactions so that we can advance.
             // The caller needs to clean up the rest of the data structures.
             transactions.best_transactions.pop();
+            included.insert(tx_hash);
+
+            // The deferred transactions of this sender might be next in line now.
+            for (hash, order) in deferred.remove(&tx.sender).into_iter().flatten() {
+                transactions.best_transactions.push(hash, order);
+            }
 
             // Push the transaction to our output vector.
             txs.push(tx);
         }
 
+        // Transactions that are still deferred remain in the mempool.
+        for (hash, order) in deferred.into_values().flatten() {
+            transactions.best_transactions.push(hash, order);
+        }
+
         (txs, size)
     }
 
@@ -619,6 +741,42 @@ impl Mempool {
         )
     }
 
+    /// Re-adds the transactions that were recorded in the journal before the last shutdown.
+    /// They are verified against the current head like any new transaction, so this should only
+    /// be called once consensus is established.
+    ///
+    /// Returns the transactions that were accepted, such that they can be broadcast again.
+    pub fn restore_journal(&self) -> Vec<Transaction> {
+        let journaled_transactions = mem::take(&mut *self.journaled_transactions.lock());
+        let mut restored_transactions = vec![];
+
+        for journaled_tx in journaled_transactions {
+            let tx_hash: Blake2bHash = journaled_tx.transaction.hash();
+            match self.add_transaction(
+                journaled_tx.transaction.clone(),
+                Some(journaled_tx.priority),
+            ) {
+                Ok(()) => restored_transactions.push(journaled_tx.transaction),
+                Err(VerifyErr::Known) => {}
+                Err(error) => {
+                    debug!(%tx_hash, %error, "Dropping journaled transaction");
+                    if let Some(journal) = &self.journal {
+                        journal.remove(&tx_hash);
+                    }
+                }
+            }
+        }
+
+        if !restored_transactions.is_empty() {
+            info!(
+                num_txs = restored_transactions.len(),
+                "Restored transactions from the mempool journal"
+            );
+        }
+
+        restored_transactions
+    }
+
     /// Checks whether a transaction has been filtered
     pub fn is_filtered(&self, hash: &Blake2bHash) -> bool {
         self.filter.read().blacklisted(hash)
@@ -629,6 +787,27 @@ impl Mempool {
         self.filter.read().rules.clone()
     }
 
+    /// Estimates the fee per byte a transaction needs to pay to be included within the given
+    /// number of blocks, based on the backlog of regular transactions in the mempool and the fees
+    /// paid in recent blocks.
+    pub fn estimate_fee(&self, target_blocks: u32) -> FeeEstimate {
+        let min_fee_per_byte = self.filter.read().rules.tx_fee_per_byte;
+
+        let mut backlog: Vec<_> = self
+            .state
+            .read()
+            .regular_transactions
+            .transactions
+            .values()
+            .map(|tx| (tx.fee_per_byte(), tx.serialized_size()))
+            .collect();
+        backlog.sort_by(|(a, _), (b, _)| b.partial_cmp(a).expect("fees can't be NaN"));
+
+        self.fee_estimator
+            .read()
+            .estimate(target_blocks, backlog, min_fee_per_byte)
+    }
+
     /// Checks if a transactions is in the mempool, by its hash.
     pub fn contains_transaction_by_hash(&self, hash: &Blake2bHash) -> bool {
         self.state.read().contains(hash)
@@ -1,6 +1,9 @@
-use std::collections::{HashMap, HashSet};
 #[cfg(feature = "metrics")]
 use std::sync::Arc;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+};
 
 use nimiq_account::ReservedBalance;
 use nimiq_blockchain::Blockchain;
@@ -12,6 +15,7 @@ use nimiq_transaction::Transaction;
 #[cfg(feature = "metrics")]
 use crate::mempool_metrics::MempoolMetrics;
 use crate::{
+    journal::MempoolJournal,
     mempool_transactions::{MempoolTransactions, TxPriority},
     verify::VerifyErr,
 };
@@ -31,10 +35,14 @@ pub(crate) struct MempoolState {
 }
 
 impl MempoolState {
-    pub fn new(regular_txns_limit: usize, control_txns_limit: usize) -> Self {
+    pub fn new(
+        regular_txns_limit: usize,
+        control_txns_limit: usize,
+        journal: Option<MempoolJournal>,
+    ) -> Self {
         MempoolState {
-            regular_transactions: MempoolTransactions::new(regular_txns_limit),
-            control_transactions: MempoolTransactions::new(control_txns_limit),
+            regular_transactions: MempoolTransactions::new(regular_txns_limit, journal.clone()),
+            control_transactions: MempoolTransactions::new(control_txns_limit, journal),
             state_by_sender: HashMap::new(),
             #[cfg(feature = "metrics")]
             metrics: Default::default(),
@@ -72,24 +80,44 @@ impl MempoolState {
             .get_account_if_complete(&tx.sender)
             .ok_or(VerifyErr::NoConsensus)?;
 
-        if let Some(sender_state) = self.state_by_sender.get_mut(&tx.sender) {
-            let reserved_balance = &mut sender_state.reserved_balance;
-            blockchain.reserve_balance(&sender_account, &tx, reserved_balance)?;
-            sender_state.txns.insert(tx.hash());
-        } else {
-            let mut reserved_balance = ReservedBalance::new(tx.sender.clone());
-            blockchain.reserve_balance(&sender_account, &tx, &mut reserved_balance)?;
-
-            let sender_state = SenderPendingState {
-                reserved_balance,
-                txns: HashSet::from([tx.hash()]),
-            };
-            self.state_by_sender.insert(tx.sender.clone(), sender_state);
+        // Staking txns are control txns
+        let is_control_tx =
+            tx.sender_type == AccountType::Staking || tx.recipient_type == AccountType::Staking;
+
+        // A regular transaction replaces a pending one that conflicts with it if it pays a higher
+        // fee per byte, given that the sender can afford it once the old transaction is gone.
+        if !is_control_tx {
+            if let Some(old_tx_hash) = self.find_conflicting(&tx) {
+                let old_tx = self.regular_transactions.get(&old_tx_hash).unwrap();
+                if tx.fee_per_byte() <= old_tx.fee_per_byte() {
+                    return Err(VerifyErr::ReplacementUnderpriced);
+                }
+
+                let mut reserved_balance =
+                    self.state_by_sender[&tx.sender].reserved_balance.clone();
+                blockchain.release_balance(&sender_account, old_tx, &mut reserved_balance)?;
+                blockchain.reserve_balance(&sender_account, &tx, &mut reserved_balance)?;
+
+                self.remove(blockchain, &old_tx_hash, EvictionReason::Replaced);
+            }
+        }
+
+        let sender_state = self
+            .state_by_sender
+            .entry(tx.sender.clone())
+            .or_insert_with(|| SenderPendingState::new(tx.sender.clone()));
+        if let Err(error) =
+            blockchain.reserve_balance(&sender_account, &tx, &mut sender_state.reserved_balance)
+        {
+            if sender_state.txns.is_empty() {
+                self.state_by_sender.remove(&tx.sender);
+            }
+            return Err(error.into());
         }
+        sender_state.insert(tx_hash, &tx);
 
         // If we are adding a staking transaction we insert it into the control txns container
-        // Staking txns are control txns
-        if tx.sender_type == AccountType::Staking || tx.recipient_type == AccountType::Staking {
+        if is_control_tx {
             self.control_transactions.insert(tx, priority);
         } else {
             self.regular_transactions.insert(tx, priority);
@@ -130,7 +158,7 @@ impl MempoolState {
             None => {
                 // We don't know the sender account so we can't do any balance tracking.
                 // Throw away all transactions from this sender.
-                for hash in &sender_state.txns {
+                for hash in sender_state.txns.keys() {
                     self.regular_transactions
                         .delete(hash)
                         .or_else(|| self.control_transactions.delete(hash));
@@ -140,7 +168,7 @@ impl MempoolState {
             }
         };
 
-        if !sender_state.txns.remove(tx_hash) {
+        if sender_state.txns.remove(tx_hash).is_none() {
             return Some(tx);
         }
 
@@ -158,6 +186,30 @@ impl MempoolState {
         Some(tx)
     }
 
+    /// Returns the hash of the pending regular transaction that the given transaction would replace,
+    /// i.e. a transaction from the same sender to the same recipient with the same validity start
+    /// height. If there are several, the one with the lowest fee per byte is returned.
+    ///
+    /// Control transactions are never replaced, since a sender commonly has several transactions
+    /// to the staking contract pending at the same time.
+    pub(crate) fn find_conflicting(&self, tx: &Transaction) -> Option<Blake2bHash> {
+        let sender_state = self.state_by_sender.get(&tx.sender)?;
+        sender_state
+            .txns
+            .keys()
+            .filter_map(|hash| Some((hash, self.regular_transactions.get(hash)?)))
+            .filter(|(_, other)| {
+                other.recipient == tx.recipient
+                    && other.validity_start_height == tx.validity_start_height
+            })
+            .min_by(|(_, a), (_, b)| {
+                a.fee_per_byte()
+                    .partial_cmp(&b.fee_per_byte())
+                    .expect("fees can't be NaN")
+            })
+            .map(|(hash, _)| hash.clone())
+    }
+
     /// Retrieves all expired transaction hashes from both the `regular_transactions` and `control_transactions` vectors
     pub fn get_expired_txns(&mut self, block_number: u32) -> Vec<Blake2bHash> {
         let mut expired_txns = self.control_transactions.get_expired_txns(block_number);
@@ -173,12 +225,91 @@ pub(crate) enum EvictionReason {
     AlreadyIncluded,
     Invalid,
     TooFull,
+    Replaced,
 }
 
 pub(crate) struct SenderPendingState {
     // The balance reserved by transactions that are currently stored in the mempool for this sender.
     pub(crate) reserved_balance: ReservedBalance,
 
-    // Transaction hashes for this sender.
-    pub(crate) txns: HashSet<Blake2bHash>,
+    // Transaction hashes for this sender, along with their position in the per-sender ordering.
+    pub(crate) txns: HashMap<Blake2bHash, SenderTxOrder>,
+
+    // Counter that increases for every transaction added for this sender.
+    tx_counter: u64,
+}
+
+impl SenderPendingState {
+    pub(crate) fn new(sender: Address) -> Self {
+        Self {
+            reserved_balance: ReservedBalance::new(sender),
+            txns: HashMap::new(),
+            tx_counter: 0,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, tx_hash: Blake2bHash, tx: &Transaction) {
+        self.txns.insert(
+            tx_hash,
+            SenderTxOrder {
+                validity_start_height: tx.validity_start_height,
+                fee_per_byte: tx.fee_per_byte(),
+                insertion_order: self.tx_counter,
+            },
+        );
+        self.tx_counter += 1;
+    }
+
+    /// Returns the transaction hashes of this sender in the per-sender ordering.
+    pub(crate) fn ordered_txns(&self) -> Vec<Blake2bHash> {
+        let mut txns: Vec<_> = self.txns.iter().collect();
+        txns.sort_by(|(_, a), (_, b)| a.cmp(b));
+        txns.into_iter().map(|(hash, _)| hash.clone()).collect()
+    }
+
+    /// Returns the validity start heights and hashes of the transactions of this sender for which
+    /// `filter` holds, in the per-sender ordering.
+    pub(crate) fn ordered_validity_start_heights<F: Fn(&Blake2bHash) -> bool>(
+        &self,
+        filter: F,
+    ) -> VecDeque<(u32, Blake2bHash)> {
+        let mut txns: Vec<_> = self.txns.iter().filter(|(hash, _)| filter(hash)).collect();
+        txns.sort_by(|(_, a), (_, b)| a.cmp(b));
+        txns.into_iter()
+            .map(|(hash, order)| (order.validity_start_height, hash.clone()))
+            .collect()
+    }
+}
+
+/// Ordering of the transactions of a single sender, which is the order in which they are included
+/// in blocks and in which their balance is reserved.
+/// Compares by validity start height (lower first), then by fee per byte (higher first), then by
+/// insertion order (lower i.e. older first). The lesser transaction comes first.
+#[derive(PartialEq)]
+pub(crate) struct SenderTxOrder {
+    validity_start_height: u32,
+    fee_per_byte: f64,
+    insertion_order: u64,
+}
+
+impl Eq for SenderTxOrder {}
+
+impl PartialOrd for SenderTxOrder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SenderTxOrder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.validity_start_height
+            .cmp(&other.validity_start_height)
+            .then(
+                self.fee_per_byte
+                    .partial_cmp(&other.fee_per_byte)
+                    .expect("fees can't be NaN")
+                    .reverse(),
+            )
+            .then(self.insertion_order.cmp(&other.insertion_order))
+    }
 }
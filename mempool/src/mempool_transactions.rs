@@ -5,12 +5,14 @@ use std::{
 
 use keyed_priority_queue::KeyedPriorityQueue;
 use nimiq_hash::{Blake2bHash, Hash};
-use nimiq_serde::Serialize;
+use nimiq_serde::{Deserialize, Serialize};
 use nimiq_transaction::Transaction;
 
+use crate::journal::MempoolJournal;
+
 /// TxPriority that is used when adding transactions into the mempool
 /// Higher Priority transactions are returned first from the mempool
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum TxPriority {
     /// Low Priority transactions
     Low = 1,
@@ -116,10 +118,13 @@ pub(crate) struct MempoolTransactions {
 
     // Counter that increases for every added transaction, to order them for removal.
     pub(crate) tx_counter: u64,
+
+    // The journal that records the transactions, if the mempool is persistent.
+    journal: Option<MempoolJournal>,
 }
 
 impl MempoolTransactions {
-    pub fn new(size_limit: usize) -> Self {
+    pub fn new(size_limit: usize, journal: Option<MempoolJournal>) -> Self {
         Self {
             transactions: HashMap::new(),
             best_transactions: KeyedPriorityQueue::new(),
@@ -128,6 +133,7 @@ impl MempoolTransactions {
             total_size_limit: size_limit,
             total_size: 0,
             tx_counter: 0,
+            journal,
         }
     }
 
@@ -201,6 +207,10 @@ impl MempoolTransactions {
         // Update total tx size
         self.total_size += tx.serialized_size();
 
+        if let Some(journal) = &self.journal {
+            journal.add(&tx, priority);
+        }
+
         self.transactions.insert(tx_hash, tx);
 
         true
@@ -215,6 +225,10 @@ impl MempoolTransactions {
 
         self.total_size -= tx.serialized_size();
 
+        if let Some(journal) = &self.journal {
+            journal.remove(tx_hash);
+        }
+
         Some(tx)
     }
 }
@@ -3,7 +3,9 @@ use std::sync::Arc;
 use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_hash::{Blake2bHash, Hash};
-use nimiq_primitives::{account::AccountError, networks::NetworkId, transaction::TransactionError};
+use nimiq_primitives::{
+    account::AccountError, coin::Coin, networks::NetworkId, transaction::TransactionError,
+};
 use nimiq_transaction::Transaction;
 use parking_lot::RwLock;
 use thiserror::Error;
@@ -26,6 +28,8 @@ pub enum VerifyErr {
     Known,
     #[error("Transaction is filtered")]
     Filtered,
+    #[error("Transaction does not pay a higher fee than the pending transaction it would replace")]
+    ReplacementUnderpriced,
     #[error("Can't verify transaction without consensus")]
     NoConsensus,
 }
@@ -66,16 +70,19 @@ pub(crate) fn verify_tx(
     }
 
     // 6. Check if the transaction is going to be filtered.
-    {
+    let filtered = {
         let filter = filter.read();
-        if !filter.accepts_transaction(&transaction) || filter.blacklisted(&hash) {
-            // FIXME add transaction to blacklist
+        if filter.blacklisted(&hash) {
             return Err(VerifyErr::Filtered);
         }
 
-        // TODO We also need to check:
-        //  - filter.accepts_sender_balance()
-        //  - filter.accepts_recipient_balance()
+        !filter.accepts_transaction(&transaction)
+            || !accepts_balances(&filter, &blockchain, &mempool_state, &transaction)?
+    };
+
+    if filtered {
+        filter.write().blacklist(hash);
+        return Err(VerifyErr::Filtered);
     }
 
     // 7. Add transaction to the mempool. Balance checks are performed within put().
@@ -83,3 +90,48 @@ pub(crate) fn verify_tx(
 
     Ok(())
 }
+
+/// Checks the filter rules for the sender and recipient balances.
+///
+/// The balances are computed from the accounts trie at the current head. For the sender, the balance
+/// that is already reserved by transactions pending in the mempool is taken into account as well.
+fn accepts_balances(
+    filter: &MempoolFilter,
+    blockchain: &Blockchain,
+    mempool_state: &MempoolState,
+    transaction: &Transaction,
+) -> Result<bool, VerifyErr> {
+    let sender_account = blockchain
+        .get_account_if_complete(&transaction.sender)
+        .ok_or(VerifyErr::NoConsensus)?;
+    let reserved_balance = mempool_state
+        .state_by_sender
+        .get(&transaction.sender)
+        .map_or(Coin::ZERO, |sender_state| {
+            sender_state.reserved_balance.balance()
+        });
+    let sender_old_balance = sender_account.balance().saturating_sub(reserved_balance);
+
+    // If the sender can't afford the transaction, we let it be rejected when reserving its balance.
+    if let Some(sender_new_balance) = sender_old_balance.checked_sub(transaction.total_value()) {
+        if !filter.accepts_sender_balance(transaction, sender_old_balance, sender_new_balance) {
+            return Ok(false);
+        }
+    }
+
+    // We can't check the recipient balance if its account is missing from an incomplete trie.
+    if let Some(recipient_account) = blockchain.get_account_if_complete(&transaction.recipient) {
+        let recipient_old_balance = recipient_account.balance();
+        if let Some(recipient_new_balance) = recipient_old_balance.checked_add(transaction.value) {
+            if !filter.accepts_recipient_balance(
+                transaction,
+                recipient_old_balance,
+                recipient_new_balance,
+            ) {
+                return Ok(false);
+            }
+        }
+    }
+
+    Ok(true)
+}
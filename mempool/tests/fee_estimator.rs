@@ -0,0 +1,117 @@
+use std::convert::TryFrom;
+
+use nimiq_keys::Address;
+use nimiq_mempool::fee_estimator::FeeEstimator;
+use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
+use nimiq_serde::Serialize;
+use nimiq_test_log::test;
+use nimiq_transaction::Transaction;
+
+fn transaction(fee: u64) -> Transaction {
+    Transaction::new_basic(
+        Address::from([32u8; Address::SIZE]),
+        Address::from([213u8; Address::SIZE]),
+        Coin::try_from(100).unwrap(),
+        Coin::try_from(fee).unwrap(),
+        1,
+        NetworkId::UnitAlbatross,
+    )
+}
+
+/// Returns the transactions of a full micro block, all paying the given fee.
+fn full_block(fee: u64) -> Vec<Transaction> {
+    let tx = transaction(fee);
+    let num_txs = Policy::MAX_SIZE_MICRO_BODY / (tx.serialized_size() + 1);
+    vec![tx; num_txs]
+}
+
+#[test]
+fn it_returns_the_minimum_fee_without_congestion() {
+    let mut estimator = FeeEstimator::default();
+    assert_eq!(estimator.estimate(1, vec![], 0.0).fee_per_byte, 0.0);
+    assert_eq!(estimator.estimate(1, vec![], 1.5).fee_per_byte, 1.5);
+
+    // Blocks that are not full don't require a higher fee.
+    let tx = transaction(1000);
+    for block_number in 1..=10 {
+        estimator.add_transactions(block_number, [&tx]);
+    }
+    assert_eq!(estimator.num_blocks(), 10);
+    let estimate = estimator.estimate(1, vec![(tx.fee_per_byte(), tx.serialized_size())], 0.0);
+    assert_eq!(estimate.fee_per_byte, 0.0);
+    assert_eq!(estimate.backlog_size, tx.serialized_size() + 1);
+}
+
+#[test]
+fn it_outbids_the_mempool_backlog() {
+    let estimator = FeeEstimator::default();
+
+    // A backlog filling three blocks with decreasing fees per byte.
+    let tx_size = transaction(0).serialized_size();
+    let txs_per_block = Policy::MAX_SIZE_MICRO_BODY / (tx_size + 1);
+    let backlog: Vec<_> = (0..3 * txs_per_block)
+        .map(|i| ((3 * txs_per_block - i) as f64, tx_size))
+        .collect();
+
+    for target_blocks in 1..=2 {
+        let estimate = estimator.estimate(target_blocks, backlog.clone(), 0.0);
+        let first_excluded = target_blocks as usize * txs_per_block;
+        assert_eq!(estimate.target_blocks, target_blocks);
+        assert_eq!(estimate.fee_per_byte, backlog[first_excluded].0);
+        assert_eq!(estimate.backlog_size, first_excluded * (tx_size + 1));
+    }
+
+    // The whole backlog fits into three blocks.
+    let estimate = estimator.estimate(3, backlog, 0.0);
+    assert_eq!(estimate.fee_per_byte, 0.0);
+    assert_eq!(estimate.backlog_size, 3 * txs_per_block * (tx_size + 1));
+}
+
+#[test]
+fn it_accounts_for_congested_blocks() {
+    let mut estimator = FeeEstimator::new(4);
+    for (block_number, fee) in [(1, 100), (2, 300), (3, 200)] {
+        estimator.add_transactions(block_number, &full_block(fee));
+    }
+
+    // All recent blocks were full, so the median of their lowest fees is required.
+    let min_fee_per_byte = transaction(200).fee_per_byte();
+    let estimate = estimator.estimate(1, vec![], 0.0);
+    assert_eq!(estimate.fee_per_byte, min_fee_per_byte);
+    let estimate = estimator.estimate(10, vec![], 0.0);
+    assert_eq!(estimate.fee_per_byte, min_fee_per_byte);
+
+    // With a quarter of the blocks not being full, only a short target requires the higher fee.
+    estimator.add_transactions(4, [&transaction(1)]);
+    assert_eq!(estimator.num_blocks(), 4);
+    assert_eq!(
+        estimator.estimate(1, vec![], 0.0).fee_per_byte,
+        min_fee_per_byte
+    );
+    assert_eq!(estimator.estimate(3, vec![], 0.0).fee_per_byte, 0.0);
+
+    // The oldest block is dropped once the limit is exceeded.
+    estimator.add_transactions(5, [&transaction(1)]);
+    assert_eq!(estimator.num_blocks(), 4);
+    assert_eq!(estimator.estimate(1, vec![], 0.0).fee_per_byte, 0.0);
+
+    // Reverting the blocks that weren't full restores the congestion.
+    estimator.revert_block(4);
+    assert_eq!(estimator.num_blocks(), 2);
+    assert_eq!(
+        estimator.estimate(1, vec![], 0.0).fee_per_byte,
+        transaction(300).fee_per_byte()
+    );
+}
+
+#[test]
+fn it_computes_fees_exceeding_the_estimate() {
+    let mut estimator = FeeEstimator::default();
+    estimator.add_transactions(1, &full_block(1000));
+
+    let estimate = estimator.estimate(1, vec![], 0.0);
+    let tx = transaction(1000);
+    let fee = estimate.fee_for_size(tx.serialized_size());
+    assert_eq!(fee, Coin::from_u64_unchecked(1001));
+    assert!(transaction(u64::from(fee)).fee_per_byte() > estimate.fee_per_byte);
+}
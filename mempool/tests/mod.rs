@@ -11,7 +11,10 @@ use nimiq_keys::{
     Address, Ed25519PublicKey as SchnorrPublicKey, KeyPair as SchnorrKeyPair,
     PrivateKey as SchnorrPrivateKey, SecureGenerate,
 };
-use nimiq_mempool::{config::MempoolConfig, mempool::Mempool, mempool_transactions::TxPriority};
+use nimiq_mempool::{
+    config::MempoolConfig, filter::MempoolRules, mempool::Mempool,
+    mempool_transactions::TxPriority, verify::VerifyErr,
+};
 use nimiq_network_mock::{MockHub, MockId, MockNetwork, MockPeerId};
 use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
 use nimiq_serde::{Deserialize, Serialize};
@@ -611,6 +614,384 @@ async fn push_tx_with_insufficient_balance() {
     assert_eq!(txns.len(), 1);
 }
 
+#[test(tokio::test)]
+async fn push_tx_filtered_by_balance_rules() {
+    let mut rng = test_rng(true);
+    let mut genesis_builder = GenesisBuilder::default();
+    genesis_builder.with_network(NetworkId::UnitAlbatross);
+
+    // Generate recipient accounts
+    let recipient_accounts = generate_accounts(vec![0; 4], &mut genesis_builder, false, &mut rng);
+    // Generate sender accounts
+    let sender_accounts = generate_accounts(vec![100; 2], &mut genesis_builder, true, &mut rng);
+
+    // Generate transactions
+    let test_transaction =
+        |sender: usize, recipient: usize, value: u64, fee: u64| TestTransaction {
+            fee,
+            value,
+            recipient: recipient_accounts[recipient].clone(),
+            sender: sender_accounts[sender].clone(),
+        };
+    let (txns, _) = generate_transactions(
+        vec![
+            // Leaves the sender with 30 < 50
+            test_transaction(0, 0, 60, 10),
+            // Leaves the recipient with 15 < 20
+            test_transaction(1, 1, 15, 5),
+            // Leaves the sender with 65 and the recipient with 30
+            test_transaction(1, 2, 30, 5),
+            // Empties the sender account, which is allowed
+            test_transaction(0, 3, 90, 10),
+        ],
+        true,
+    );
+
+    let time = Arc::new(OffsetTime::new());
+    let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+
+    // Add a validator to genesis
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+        None,
+        None,
+        false,
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    // The genesis block number must match the specs we are setting in Policy
+    let genesis_block = genesis_info.block;
+    let genesis_block = match genesis_block {
+        Block::Macro(mut block) => {
+            block.header.block_number = Policy::genesis_block_number();
+            Block::Macro(block)
+        }
+        Block::Micro(_) => panic!(),
+    };
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            time,
+            NetworkId::UnitAlbatross,
+            genesis_block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+
+    let mut filter_rules = MempoolRules::default();
+    filter_rules.sender_balance = Coin::from_u64_unchecked(50);
+    filter_rules.recipient_balance = Coin::from_u64_unchecked(20);
+    let mempool = Mempool::new(
+        blockchain,
+        MempoolConfig {
+            filter_rules,
+            ..Default::default()
+        },
+    );
+
+    let results: Vec<_> = txns
+        .iter()
+        .map(|txn| mempool.add_transaction(txn.clone(), None))
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            Err(VerifyErr::Filtered),
+            Err(VerifyErr::Filtered),
+            Ok(()),
+            Ok(())
+        ]
+    );
+
+    // The rejected transactions are blacklisted
+    assert!(mempool.is_filtered(&txns[0].hash()));
+    assert!(mempool.is_filtered(&txns[1].hash()));
+    assert!(!mempool.is_filtered(&txns[2].hash()));
+    assert!(!mempool.is_filtered(&txns[3].hash()));
+    assert_eq!(mempool.num_transactions(), 2);
+}
+
+#[test(tokio::test)]
+async fn mempool_replace_by_fee() {
+    let mut rng = test_rng(true);
+    let mut genesis_builder = GenesisBuilder::default();
+    genesis_builder.with_network(NetworkId::UnitAlbatross);
+
+    // Generate recipient accounts
+    let recipient_accounts = generate_accounts(vec![0; 3], &mut genesis_builder, false, &mut rng);
+    // Generate sender accounts
+    let sender_accounts = generate_accounts(vec![30; 1], &mut genesis_builder, true, &mut rng);
+
+    // Generate transactions
+    let test_transaction = |recipient: usize, value: u64, fee: u64| TestTransaction {
+        fee,
+        value,
+        recipient: recipient_accounts[recipient].clone(),
+        sender: sender_accounts[0].clone(),
+    };
+    let (txns, txns_len) = generate_transactions(
+        vec![
+            test_transaction(0, 10, 1),
+            // Doesn't pay a higher fee than the transaction it would replace
+            test_transaction(0, 11, 1),
+            // Replaces the first transaction
+            test_transaction(0, 10, 2),
+            // Goes to a different recipient, so it doesn't replace anything
+            test_transaction(1, 10, 1),
+            // Would replace the fourth transaction, but the sender can't afford it
+            test_transaction(1, 20, 2),
+            // Pays a higher fee, but goes to yet another recipient, so it can't evict any of the
+            // pending transactions to make room for itself
+            test_transaction(2, 10, 5),
+        ],
+        true,
+    );
+
+    let time = Arc::new(OffsetTime::new());
+    let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+
+    // Add a validator to genesis
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+        None,
+        None,
+        false,
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    // The genesis block number must match the specs we are setting in Policy
+    let genesis_block = genesis_info.block;
+    let genesis_block = match genesis_block {
+        Block::Macro(mut block) => {
+            block.header.block_number = Policy::genesis_block_number();
+            Block::Macro(block)
+        }
+        Block::Micro(_) => panic!(),
+    };
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            time,
+            NetworkId::UnitAlbatross,
+            genesis_block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+
+    let results: Vec<_> = txns
+        .iter()
+        .map(|txn| mempool.add_transaction(txn.clone(), None))
+        .collect();
+    assert!(matches!(
+        results.as_slice(),
+        [
+            Ok(()),
+            Err(VerifyErr::ReplacementUnderpriced),
+            Ok(()),
+            Ok(()),
+            Err(VerifyErr::InvalidAccount(_)),
+            Err(VerifyErr::InvalidAccount(_)),
+        ]
+    ));
+
+    // The replaced transaction is gone, the others are still pending
+    assert!(!mempool.contains_transaction_by_hash(&txns[0].hash()));
+    assert!(mempool.contains_transaction_by_hash(&txns[2].hash()));
+    assert!(mempool.contains_transaction_by_hash(&txns[3].hash()));
+    assert_eq!(mempool.num_transactions(), 2);
+
+    // Replaced transactions are not filtered
+    assert!(!mempool.is_filtered(&txns[0].hash()));
+
+    let (block_txns, _) = mempool.get_transactions_for_block(txns_len);
+    assert_eq!(block_txns, vec![txns[2].clone(), txns[3].clone()]);
+}
+
+#[test(tokio::test)]
+async fn mempool_get_txn_per_sender_ordering() {
+    let mut rng = test_rng(true);
+    let mut genesis_builder = GenesisBuilder::default();
+    genesis_builder.with_network(NetworkId::UnitAlbatross);
+
+    // Generate recipient accounts
+    let recipient_accounts = generate_accounts(vec![0; 3], &mut genesis_builder, false, &mut rng);
+    // Generate sender accounts
+    let sender_accounts = generate_accounts(vec![100; 2], &mut genesis_builder, true, &mut rng);
+
+    // Generate transactions with different validity start heights
+    let test_transaction = |sender: usize, recipient: usize, fee: u64, block_offset: u32| {
+        TransactionBuilder::new_basic(
+            &sender_accounts[sender].keypair,
+            recipient_accounts[recipient].address.clone(),
+            Coin::from_u64_unchecked(10),
+            Coin::from_u64_unchecked(fee),
+            Policy::genesis_block_number() + block_offset,
+            NetworkId::UnitAlbatross,
+        )
+        .unwrap()
+    };
+    let txns = vec![
+        // Pays the highest fee, but has to wait for the next transaction of the same sender
+        test_transaction(0, 0, 10, 2),
+        test_transaction(0, 1, 1, 1),
+        test_transaction(1, 2, 5, 2),
+    ];
+
+    let time = Arc::new(OffsetTime::new());
+    let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+
+    // Add a validator to genesis
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+        None,
+        None,
+        false,
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    // The genesis block number must match the specs we are setting in Policy
+    let genesis_block = genesis_info.block;
+    let genesis_block = match genesis_block {
+        Block::Macro(mut block) => {
+            block.header.block_number = Policy::genesis_block_number();
+            Block::Macro(block)
+        }
+        Block::Micro(_) => panic!(),
+    };
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            time,
+            NetworkId::UnitAlbatross,
+            genesis_block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+    for txn in &txns {
+        mempool.add_transaction(txn.clone(), None).unwrap();
+    }
+
+    let (block_txns, _) = mempool.get_transactions_for_block(10_000);
+    assert_eq!(
+        block_txns,
+        vec![txns[2].clone(), txns[1].clone(), txns[0].clone()]
+    );
+    assert_eq!(mempool.num_transactions(), 0);
+}
+
+#[test(tokio::test)]
+async fn mempool_journal_restores_transactions() {
+    let mut rng = test_rng(true);
+    let mut genesis_builder = GenesisBuilder::default();
+    genesis_builder.with_network(NetworkId::UnitAlbatross);
+
+    // Generate recipient accounts
+    let recipient_accounts = generate_accounts(vec![0; 3], &mut genesis_builder, false, &mut rng);
+    // Generate sender accounts
+    let sender_accounts = generate_accounts(vec![100; 1], &mut genesis_builder, true, &mut rng);
+
+    // Generate transactions
+    let mempool_transactions = (0..3)
+        .map(|i| TestTransaction {
+            fee: i + 1,
+            value: 10,
+            recipient: recipient_accounts[i as usize].clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let time = Arc::new(OffsetTime::new());
+    let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+
+    // Add a validator to genesis
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+        None,
+        None,
+        false,
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    // The genesis block number must match the specs we are setting in Policy
+    let genesis_block = genesis_info.block;
+    let genesis_block = match genesis_block {
+        Block::Macro(mut block) => {
+            block.header.block_number = Policy::genesis_block_number();
+            Block::Macro(block)
+        }
+        Block::Micro(_) => panic!(),
+    };
+
+    let blockchain = Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env.clone(),
+            BlockchainConfig::default(),
+            time,
+            NetworkId::UnitAlbatross,
+            genesis_block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ));
+    let mempool_config = MempoolConfig {
+        journal: true,
+        ..Default::default()
+    };
+    let mempool =
+        Mempool::with_database(Arc::clone(&blockchain), mempool_config.clone(), env.clone());
+    for txn in &txns {
+        mempool.add_transaction(txn.clone(), None).unwrap();
+    }
+
+    // The transaction with the highest fee leaves the mempool
+    let (block_txns, _) = mempool.get_transactions_for_block(1 + txns[2].serialized_size());
+    assert_eq!(block_txns, vec![txns[2].clone()]);
+    drop(mempool);
+
+    // The remaining transactions are restored by a new mempool
+    let mempool = Mempool::with_database(Arc::clone(&blockchain), mempool_config, env.clone());
+    assert_eq!(mempool.num_transactions(), 0);
+    let mut restored_txns = mempool.restore_journal();
+    restored_txns.sort_by_key(|txn| txn.fee);
+    assert_eq!(restored_txns, vec![txns[0].clone(), txns[1].clone()]);
+    assert_eq!(mempool.num_transactions(), 2);
+
+    // Transactions are only restored once
+    assert!(mempool.restore_journal().is_empty());
+
+    // A mempool without journal doesn't restore anything
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+    assert!(mempool.restore_journal().is_empty());
+}
+
 #[test(tokio::test)]
 async fn multiple_transactions_multiple_senders() {
     let mut rng = test_rng(true);
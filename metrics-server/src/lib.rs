@@ -6,10 +6,10 @@ use nimiq_mempool::mempool::Mempool;
 use nimiq_network_interface::network::Network;
 use nimiq_utils::spawn;
 use parking_lot::RwLock;
+pub use prometheus_client::registry::Registry;
 use prometheus_client::{
     encoding::{EncodeGaugeValue, EncodeMetric, MetricEncoder},
     metrics::MetricType,
-    registry::Registry,
 };
 #[cfg(tokio_unstable)]
 use tokio_metrics::RuntimeMonitor;
@@ -37,7 +37,12 @@ pub struct NimiqTaskMonitor {
     pub monitor: TaskMonitor,
 }
 
-struct NumericClosureMetric<T: EncodeGaugeValue + Sized + Debug> {
+/// Registers the metrics of a component that the metrics server doesn't depend on. It is called
+/// with the registry of the `nimiq` prefix.
+pub type MetricsCollector = Box<dyn FnOnce(&mut Registry) + Send>;
+
+/// A metric whose value is computed by a closure whenever it is collected.
+pub struct NumericClosureMetric<T: EncodeGaugeValue + Sized + Debug> {
     metric_type: MetricType,
     lambda: Box<dyn Fn() -> T + Sync + Send>,
 }
@@ -83,6 +88,7 @@ pub fn start_metrics_server<TNetwork: Network>(
     addr: SocketAddr,
     blockchain_proxy: BlockchainProxy,
     mempool: Option<Arc<Mempool>>,
+    collectors: Vec<MetricsCollector>,
     consensus_proxy: ConsensusProxy<TNetwork>,
     network: Arc<nimiq_network_libp2p::Network>,
     task_monitors: &[NimiqTaskMonitor],
@@ -98,6 +104,10 @@ pub fn start_metrics_server<TNetwork: Network>(
         MempoolMetrics::register(nimiq_registry, mempool);
     }
 
+    for collector in collectors {
+        collector(nimiq_registry);
+    }
+
     // Setup the task metrics
     let task_metrics = Arc::new(RwLock::new(TokioTaskMetrics::new()));
     task_metrics.write().register(
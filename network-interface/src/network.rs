@@ -1,6 +1,7 @@
 use std::{
     fmt::{Debug, Display},
     hash::Hash,
+    time::Duration,
 };
 
 use async_trait::async_trait;
@@ -72,6 +73,113 @@ pub enum CloseReason {
     MaliciousPeer,
 }
 
+/// A peer is banned once its ban score reaches this threshold.
+pub const BAN_SCORE_THRESHOLD: u32 = 100;
+
+/// The ban score of a peer is forgotten if it didn't misbehave for this long and isn't banned.
+pub const BAN_SCORE_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// Reasons for penalizing a misbehaving peer
+pub enum BanReason {
+    /// The peer sent a block that failed verification
+    InvalidBlock,
+    /// The peer sent an invalid macro chain or one that belongs to a different chain
+    InvalidMacroChain,
+    /// The peer sent an accounts chunk that could not be committed
+    InvalidChunk,
+    /// The peer sent a response that violates the limits of the request
+    InvalidResponse,
+}
+
+impl BanReason {
+    /// A stable name of the reason, e.g. for reporting it over RPC.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BanReason::InvalidBlock => "invalidBlock",
+            BanReason::InvalidMacroChain => "invalidMacroChain",
+            BanReason::InvalidChunk => "invalidChunk",
+            BanReason::InvalidResponse => "invalidResponse",
+        }
+    }
+
+    /// The score that is added to the ban score of a peer for this offense.
+    pub fn score(&self) -> u32 {
+        match self {
+            BanReason::InvalidBlock | BanReason::InvalidMacroChain | BanReason::InvalidResponse => {
+                BAN_SCORE_THRESHOLD
+            }
+            // A chunk can also fail to commit if the state of the peer changed in the meantime.
+            BanReason::InvalidChunk => BAN_SCORE_THRESHOLD / 4,
+        }
+    }
+
+    /// How long a peer is banned if this offense brings its ban score to the threshold.
+    pub fn ban_duration(&self) -> Duration {
+        match self {
+            BanReason::InvalidMacroChain => Duration::from_secs(24 * 60 * 60),
+            BanReason::InvalidBlock | BanReason::InvalidResponse => Duration::from_secs(60 * 60),
+            BanReason::InvalidChunk => Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+/// The ban status of a peer that misbehaved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BanInfo {
+    /// The reason of the most recent offense.
+    pub reason: BanReason,
+    /// The accumulated ban score.
+    pub score: u32,
+    /// Time of the most recent offense in seconds since unix epoch.
+    pub last_offense: u64,
+    /// Time in seconds since unix epoch until which the peer is banned.
+    /// `None` if the ban score never reached the threshold.
+    pub banned_until: Option<u64>,
+}
+
+impl BanInfo {
+    pub fn new(reason: BanReason, unix_time: u64) -> Self {
+        Self {
+            reason,
+            score: 0,
+            last_offense: unix_time,
+            banned_until: None,
+        }
+    }
+
+    /// Adds the score of an offense to the ban score. Returns `true` if this offense got the
+    /// peer banned.
+    pub fn penalize(&mut self, reason: BanReason, unix_time: u64) -> bool {
+        if self.is_expired(unix_time) {
+            self.score = 0;
+            self.banned_until = None;
+        }
+
+        self.reason = reason;
+        self.score = self.score.saturating_add(reason.score());
+        self.last_offense = unix_time;
+
+        if self.score >= BAN_SCORE_THRESHOLD && !self.is_banned(unix_time) {
+            self.banned_until = Some(unix_time + reason.ban_duration().as_secs());
+            return true;
+        }
+        false
+    }
+
+    /// Returns whether the peer is currently banned.
+    pub fn is_banned(&self, unix_time: u64) -> bool {
+        self.banned_until
+            .is_some_and(|banned_until| banned_until > unix_time)
+    }
+
+    /// Returns whether the peer is neither banned nor misbehaved within the
+    /// [`BAN_SCORE_RETENTION`] period, such that its ban score can be forgotten.
+    pub fn is_expired(&self, unix_time: u64) -> bool {
+        !self.is_banned(unix_time) && self.last_offense + BAN_SCORE_RETENTION.as_secs() <= unix_time
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum SendError {
     #[error("{0}")]
@@ -122,6 +230,16 @@ pub trait Network: Send + Sync + Unpin + 'static {
     /// Disconnects a peer with a close reason
     async fn disconnect_peer(&self, peer_id: Self::PeerId, close_reason: CloseReason);
 
+    /// Penalizes a misbehaving peer. Once its ban score reaches the [`BAN_SCORE_THRESHOLD`],
+    /// the peer is disconnected and banned for the duration associated with the reason.
+    async fn ban_peer(&self, peer_id: Self::PeerId, reason: BanReason);
+
+    /// Gets the peers that were penalized recently, together with their ban status
+    fn get_banned_peers(&self) -> Vec<(Self::PeerId, BanInfo)>;
+
+    /// Clears the ban list, such that banned peers can connect again
+    async fn clear_banned_peers(&self);
+
     /// Subscribes to network events
     fn subscribe_events(&self) -> SubscribeEvents<Self::PeerId>;
 
@@ -2,7 +2,11 @@ use std::{iter, sync::Arc};
 
 use libp2p::{
     autonat, connection_limits, gossipsub,
-    kad::{self, store::MemoryStore},
+    kad::{
+        self,
+        store::{MemoryStore, RecordStore},
+        Record,
+    },
     ping, request_response,
     swarm::NetworkBehaviour,
     Multiaddr, PeerId, StreamProtocol,
@@ -10,7 +14,7 @@ use libp2p::{
 use parking_lot::RwLock;
 
 use crate::{
-    connection_pool,
+    connection_pool::{self, BanList},
     discovery::{self, peer_contacts::PeerContactBook},
     dispatch::codecs::MessageCodec,
     Config,
@@ -43,6 +47,8 @@ impl Behaviour {
     pub fn new(
         config: Config,
         contacts: Arc<RwLock<PeerContactBook>>,
+        ban_list: Arc<RwLock<BanList>>,
+        records: Vec<Record>,
         peer_score_params: gossipsub::PeerScoreParams,
         force_dht_server_mode: bool,
     ) -> Self {
@@ -50,7 +56,12 @@ impl Behaviour {
         let peer_id = public_key.to_peer_id();
 
         // DHT behaviour
-        let store = MemoryStore::new(peer_id);
+        let mut store = MemoryStore::new(peer_id);
+        for record in records {
+            if let Err(error) = store.put(record) {
+                warn!(%error, "Failed to restore DHT record");
+            }
+        }
         let mut dht = kad::Behaviour::with_config(peer_id, store, config.kademlia);
         if force_dht_server_mode {
             dht.set_mode(Some(kad::Mode::Server));
@@ -82,6 +93,7 @@ impl Behaviour {
         // Connection pool behaviour
         let pool = connection_pool::Behaviour::new(
             Arc::clone(&contacts),
+            ban_list,
             peer_id,
             config.seeds,
             config.discovery.required_services,
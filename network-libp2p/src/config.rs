@@ -1,4 +1,4 @@
-use std::{num::NonZeroU8, time::Duration};
+use std::{num::NonZeroU8, path::PathBuf, time::Duration};
 
 use libp2p::{gossipsub, identity::Keypair, kad, Multiaddr, StreamProtocol};
 use nimiq_hash::Blake2bHash;
@@ -19,6 +19,35 @@ pub struct TlsConfig {
     pub certificates: Vec<Vec<u8>>,
 }
 
+/// Settings for persisting the peer contact book and the DHT records across restarts
+#[derive(Clone, Debug)]
+pub struct PeerStoreConfig {
+    /// File the peer contacts are stored in.
+    pub contacts_path: PathBuf,
+    /// File the DHT records are stored in.
+    pub records_path: PathBuf,
+    /// Peer contacts older than this are not restored from the file.
+    pub max_contact_age: Duration,
+    /// Interval in which the peer contacts and DHT records are written to the files.
+    pub store_interval: Duration,
+}
+
+impl PeerStoreConfig {
+    /// Default maximum age of restored peer contacts (24 hours)
+    pub const DEFAULT_MAX_CONTACT_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+    /// Default interval in which the files are updated (5 minutes)
+    pub const DEFAULT_STORE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+    pub fn new(contacts_path: PathBuf, records_path: PathBuf) -> Self {
+        Self {
+            contacts_path,
+            records_path,
+            max_contact_age: Self::DEFAULT_MAX_CONTACT_AGE,
+            store_interval: Self::DEFAULT_STORE_INTERVAL,
+        }
+    }
+}
+
 /// LibP2P network configuration
 pub struct Config {
     pub keypair: Keypair,
@@ -35,6 +64,7 @@ pub struct Config {
     pub only_secure_ws_connections: bool,
     pub allow_loopback_addresses: bool,
     pub dht_quorum: NonZeroU8,
+    pub peer_store: Option<PeerStoreConfig>,
 }
 
 impl Config {
@@ -51,6 +81,7 @@ impl Config {
         only_secure_ws_connections: bool,
         allow_loopback_addresses: bool,
         dht_quorum: NonZeroU8,
+        peer_store: Option<PeerStoreConfig>,
     ) -> Self {
         // Hardcoding the minimum number of peers in mesh network before adding more
         // TODO: Maybe change this to a mesh limits configuration argument of this function
@@ -100,6 +131,7 @@ impl Config {
             only_secure_ws_connections,
             allow_loopback_addresses,
             dht_quorum,
+            peer_store,
         }
     }
 }
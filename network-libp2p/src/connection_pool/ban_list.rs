@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use instant::SystemTime;
+use libp2p::PeerId;
+use nimiq_network_interface::network::{BanInfo, BanReason};
+
+/// List of peers that misbehaved together with their ban scores.
+/// Entries are removed once they expire, see [`BanInfo::is_expired`].
+#[derive(Debug, Default)]
+pub struct BanList {
+    peers: HashMap<PeerId, BanInfo>,
+}
+
+impl BanList {
+    /// Adds the score of an offense to the ban score of a peer.
+    /// Returns `true` if the peer got banned by this offense.
+    pub fn penalize(&mut self, peer_id: PeerId, reason: BanReason) -> bool {
+        let unix_time = unix_time();
+        self.peers.retain(|_, info| !info.is_expired(unix_time));
+
+        let info = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| BanInfo::new(reason, unix_time));
+        let banned = info.penalize(reason, unix_time);
+
+        if banned {
+            info!(%peer_id, ?reason, banned_until = info.banned_until, "Banned peer");
+        } else {
+            debug!(%peer_id, ?reason, score = info.score, "Penalized peer");
+        }
+        banned
+    }
+
+    /// Returns whether a peer is currently banned.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .is_some_and(|info| info.is_banned(unix_time()))
+    }
+
+    /// Returns the peers that have not expired yet.
+    pub fn peers(&self) -> Vec<(PeerId, BanInfo)> {
+        let unix_time = unix_time();
+        self.peers
+            .iter()
+            .filter(|(_, info)| !info.is_expired(unix_time))
+            .map(|(peer_id, info)| (*peer_id, info.clone()))
+            .collect()
+    }
+
+    /// Removes all peers from the list.
+    pub fn clear(&mut self) {
+        self.peers.clear();
+    }
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use libp2p::PeerId;
+    use nimiq_network_interface::network::{BanReason, BAN_SCORE_THRESHOLD};
+    use nimiq_test_log::test;
+
+    use super::BanList;
+
+    #[test]
+    fn it_bans_peers_once_the_threshold_is_reached() {
+        let mut ban_list = BanList::default();
+        let peer_id = PeerId::random();
+
+        for _ in 1..BAN_SCORE_THRESHOLD / BanReason::InvalidChunk.score() {
+            assert!(!ban_list.penalize(peer_id, BanReason::InvalidChunk));
+        }
+        assert!(!ban_list.is_banned(&peer_id));
+        assert!(ban_list.penalize(peer_id, BanReason::InvalidChunk));
+        assert!(ban_list.is_banned(&peer_id));
+
+        // Further offenses don't ban the peer again.
+        assert!(!ban_list.penalize(peer_id, BanReason::InvalidBlock));
+
+        let peers = ban_list.peers();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].0, peer_id);
+        assert_eq!(peers[0].1.reason, BanReason::InvalidBlock);
+        assert!(peers[0].1.banned_until.is_some());
+    }
+
+    #[test]
+    fn it_bans_peers_immediately_for_invalid_blocks() {
+        let mut ban_list = BanList::default();
+        let peer_id = PeerId::random();
+        let other_peer_id = PeerId::random();
+
+        assert!(ban_list.penalize(peer_id, BanReason::InvalidBlock));
+        assert!(ban_list.is_banned(&peer_id));
+        assert!(!ban_list.is_banned(&other_peer_id));
+
+        ban_list.clear();
+        assert!(!ban_list.is_banned(&peer_id));
+        assert!(ban_list.peers().is_empty());
+    }
+}
@@ -27,7 +27,7 @@ use parking_lot::RwLock;
 use rand::{seq::IteratorRandom, thread_rng};
 use void::Void;
 
-use super::Error;
+use super::{BanList, Error};
 use crate::discovery::peer_contacts::PeerContactBook;
 
 /// Current state of connections and peers for connection limits
@@ -226,6 +226,13 @@ impl<T: Clone + Ord> ConnectionState<T> {
         self.banned.contains(&id)
     }
 
+    /// Unbans all connection IDs marked as banned.
+    fn clear_banned(&mut self) {
+        self.banned.clear();
+        self.unban_deadlines.clear();
+        self.unban_timeout = None;
+    }
+
     /// Marks a connection ID as failed
     ///
     /// If the peers was marked as being dialed, it will be removed from such
@@ -341,6 +348,9 @@ pub struct Behaviour {
     /// services of each of the peers.
     pub contacts: Arc<RwLock<PeerContactBook>>,
 
+    /// Peers that misbehaved. Banned peers are neither dialed nor accepted.
+    ban_list: Arc<RwLock<BanList>>,
+
     /// Local (own) peer ID
     own_peer_id: PeerId,
 
@@ -378,6 +388,7 @@ pub struct Behaviour {
 impl Behaviour {
     pub fn new(
         contacts: Arc<RwLock<PeerContactBook>>,
+        ban_list: Arc<RwLock<BanList>>,
         own_peer_id: PeerId,
         seeds: Vec<Multiaddr>,
         required_services: Services,
@@ -396,6 +407,7 @@ impl Behaviour {
 
         Self {
             contacts,
+            ban_list,
             own_peer_id,
             seeds,
             required_services,
@@ -528,6 +540,14 @@ impl Behaviour {
         }
     }
 
+    /// Unbans all peers and addresses that were banned, either for misbehaving or by closing
+    /// their connection as `MaliciousPeer`.
+    pub fn clear_bans(&mut self) {
+        self.peer_ids.clear_banned();
+        self.addresses.clear_banned();
+        self.waker.wake();
+    }
+
     fn choose_peers_to_dial(&self) -> Vec<PeerId> {
         let num_peers = usize::min(
             self.config.desired_peer_count - self.peer_ids.num_connected(true),
@@ -536,6 +556,7 @@ impl Behaviour {
         let contacts = self.contacts.read();
         let own_contact = contacts.get_own_contact();
         let own_peer_id = own_contact.peer_id();
+        let ban_list = self.ban_list.read();
 
         contacts
             .query(self.required_services)
@@ -543,6 +564,7 @@ impl Behaviour {
                 let peer_id = contact.peer_id();
                 if peer_id != own_peer_id
                     && self.peer_ids.can_dial(peer_id)
+                    && !ban_list.is_banned(peer_id)
                     && contact.addresses().count() > 0
                 {
                     Some(*peer_id)
@@ -564,6 +586,7 @@ impl Behaviour {
         let contacts = self.contacts.read();
         let own_contact = contacts.get_own_contact();
         let own_peer_id = own_contact.peer_id();
+        let ban_list = self.ban_list.read();
 
         contacts
             .query(services)
@@ -571,6 +594,7 @@ impl Behaviour {
                 let peer_id = contact.peer_id();
                 if peer_id != own_peer_id
                     && self.peer_ids.can_dial(peer_id)
+                    && !ban_list.is_banned(peer_id)
                     && contact.addresses().count() > 0
                 {
                     Some(*peer_id)
@@ -954,7 +978,7 @@ impl NetworkBehaviour for Behaviour {
     ) -> Result<THandler<Self>, ConnectionDenied> {
         // Peer IDs checks are performed here since it is in this point where we have
         // this information.
-        if self.peer_ids.is_banned(peer) {
+        if self.peer_ids.is_banned(peer) || self.ban_list.read().is_banned(&peer) {
             debug!(peer_id=%peer, "Peer is banned");
             return Err(ConnectionDenied::new(Error::BannedPeer));
         }
@@ -965,11 +989,16 @@ impl NetworkBehaviour for Behaviour {
     fn handle_established_outbound_connection(
         &mut self,
         _connection_id: ConnectionId,
-        _peer: PeerId,
+        peer: PeerId,
         _addr: &Multiaddr,
         _role_override: Endpoint,
         _port_use: PortUse,
     ) -> Result<THandler<Self>, ConnectionDenied> {
+        if self.ban_list.read().is_banned(&peer) {
+            debug!(peer_id=%peer, "Peer is banned");
+            return Err(ConnectionDenied::new(Error::BannedPeer));
+        }
+
         Ok(dummy::ConnectionHandler)
     }
 
@@ -1,4 +1,6 @@
+pub mod ban_list;
 pub mod behaviour;
+pub use ban_list::BanList;
 pub use behaviour::Behaviour;
 use thiserror::Error;
 
@@ -488,6 +488,15 @@ impl PeerContactBook {
         })
     }
 
+    /// Returns the signed contacts of all known peers except for seeds.
+    pub fn signed_contacts(&self) -> Vec<SignedPeerContact> {
+        self.peer_contacts
+            .values()
+            .filter(|contact| !contact.is_seed())
+            .map(|contact| contact.contact.clone())
+            .collect()
+    }
+
     /// Updates the score of every peer in the contact book with the gossipsub
     /// peer score.
     pub fn update_scores(&self, gossipsub: &gossipsub::Behaviour) {
@@ -12,6 +12,7 @@ mod network;
 mod network_metrics;
 mod network_types;
 mod only_secure_ws_transport;
+mod peer_store;
 mod rate_limiting;
 mod swarm;
 mod utils;
@@ -19,7 +20,7 @@ mod utils;
 pub const DISCOVERY_PROTOCOL: &str = "/nimiq/discovery/0.0.1";
 pub const DHT_PROTOCOL: &str = "/nimiq/kad/0.0.1";
 
-pub use config::{Config, TlsConfig};
+pub use config::{Config, PeerStoreConfig, TlsConfig};
 pub use error::NetworkError;
 pub use libp2p::{
     self,
@@ -15,8 +15,8 @@ use libp2p::{
 };
 use nimiq_network_interface::{
     network::{
-        CloseReason, MsgAcceptance, Network as NetworkInterface, NetworkEvent, SubscribeEvents,
-        Topic,
+        BanInfo, BanReason, CloseReason, MsgAcceptance, Network as NetworkInterface,
+        NetworkEvent, SubscribeEvents, Topic,
     },
     peer_info::{PeerInfo, Services},
     request::{
@@ -37,8 +37,10 @@ use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
 #[cfg(feature = "metrics")]
 use crate::network_metrics::NetworkMetrics;
 use crate::{
+    connection_pool::BanList,
     discovery::peer_contacts::PeerContactBook,
     network_types::{GossipsubId, NetworkAction, ValidateMessage},
+    peer_store::PeerStore,
     rate_limiting::RequestRateLimitData,
     swarm::{new_swarm, swarm_task},
     Config, NetworkError,
@@ -66,6 +68,8 @@ pub struct Network {
     required_services: Services,
     /// Reference to PeerContactBook, used to satisfy rpc requests for it.
     contacts: Arc<RwLock<PeerContactBook>>,
+    /// Peers that misbehaved, shared with the connection pool which refuses banned peers.
+    ban_list: Arc<RwLock<BanList>>,
 }
 
 impl Network {
@@ -77,14 +81,26 @@ impl Network {
     ///
     pub async fn new(config: Config) -> Self {
         let required_services = config.required_services;
-        // TODO: persist to disk
         let own_peer_contact = config.peer_contact.clone();
-        let contacts = Arc::new(RwLock::new(PeerContactBook::new(
+        let mut contacts = PeerContactBook::new(
             own_peer_contact.sign(&config.keypair),
             config.only_secure_ws_connections,
             config.allow_loopback_addresses,
             config.memory_transport,
-        )));
+        );
+
+        // Restore the peer contacts and DHT records from a previous run if they are persisted.
+        let peer_store = config.peer_store.clone().map(PeerStore::new);
+        let records = match peer_store {
+            Some(ref peer_store) => {
+                contacts.insert_all(peer_store.load_contacts());
+                peer_store.load_records()
+            }
+            None => vec![],
+        };
+        let contacts = Arc::new(RwLock::new(contacts));
+        let ban_list = Arc::new(RwLock::new(BanList::default()));
+
         let params = gossipsub::PeerScoreParams {
             ip_colocation_factor_threshold: 20.0,
             ..Default::default()
@@ -98,6 +114,8 @@ impl Network {
         let swarm = new_swarm(
             config,
             Arc::clone(&contacts),
+            Arc::clone(&ban_list),
+            records,
             params.clone(),
             force_dht_server_mode,
         );
@@ -122,6 +140,7 @@ impl Network {
             Arc::clone(&connected_peers),
             update_scores,
             Arc::clone(&contacts),
+            peer_store,
             force_dht_server_mode,
             dht_quorum,
             #[cfg(feature = "metrics")]
@@ -130,6 +149,7 @@ impl Network {
 
         Self {
             contacts,
+            ban_list,
             local_peer_id,
             connected_peers,
             events_tx,
@@ -545,6 +565,25 @@ impl NetworkInterface for Network {
         }
     }
 
+    async fn ban_peer(&self, peer_id: PeerId, reason: BanReason) {
+        let banned = self.ban_list.write().penalize(peer_id, reason);
+        if banned {
+            self.disconnect_peer(peer_id, CloseReason::MaliciousPeer)
+                .await;
+        }
+    }
+
+    fn get_banned_peers(&self) -> Vec<(PeerId, BanInfo)> {
+        self.ban_list.read().peers()
+    }
+
+    async fn clear_banned_peers(&self) {
+        self.ban_list.write().clear();
+        if let Err(error) = self.action_tx.send(NetworkAction::ClearBans).await {
+            error!(%error, "could not send clear bans action to channel");
+        }
+    }
+
     fn subscribe_events(&self) -> SubscribeEvents<PeerId> {
         Box::pin(BroadcastStream::new(self.events_tx.subscribe()))
     }
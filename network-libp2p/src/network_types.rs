@@ -99,6 +99,7 @@ pub(crate) enum NetworkAction {
         peer_id: PeerId,
         reason: CloseReason,
     },
+    ClearBans,
 }
 
 pub(crate) struct ValidateMessage<P: Clone> {
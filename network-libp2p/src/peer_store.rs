@@ -0,0 +1,325 @@
+use std::{io, sync::Arc, time::Duration};
+
+use futures::{future, StreamExt};
+use libp2p::{
+    kad::{
+        store::{MemoryStore, RecordStore},
+        Record, RecordKey,
+    },
+    PeerId,
+};
+use nimiq_time::{interval, Interval};
+use nimiq_utils::{
+    file_store::{Error as FileStoreError, FileStore},
+    spawn,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use web_time::{Instant, SystemTime};
+
+use crate::{
+    discovery::peer_contacts::{PeerContactBook, PeerContactInfo, SignedPeerContact},
+    PeerStoreConfig,
+};
+
+/// A DHT record as it is written to disk.
+/// The expiry is stored as a unix timestamp in seconds since instants can't be persisted.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredRecord {
+    key: Vec<u8>,
+    value: Vec<u8>,
+    publisher: Option<PeerId>,
+    expires: Option<u64>,
+}
+
+/// The peer contacts and DHT records at one point in time, ready to be written to disk.
+pub(crate) struct PeerStoreSnapshot {
+    sequence_number: u64,
+    contacts: Vec<SignedPeerContact>,
+    records: Vec<StoredRecord>,
+}
+
+/// Persists the peer contact book and the DHT records, such that they survive restarts.
+pub(crate) struct PeerStore {
+    config: PeerStoreConfig,
+    interval: Interval,
+    /// The sequence number of the next snapshot.
+    next_sequence_number: u64,
+    /// The sequence number of the last snapshot written to disk. Locked while writing, such that
+    /// writes don't overlap and an older snapshot never overwrites a newer one.
+    written: Arc<Mutex<Option<u64>>>,
+}
+
+impl PeerStore {
+    pub fn new(config: PeerStoreConfig) -> Self {
+        let interval = interval(config.store_interval);
+        Self {
+            config,
+            interval,
+            next_sequence_number: 0,
+            written: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Loads the stored peer contacts. Contacts with an invalid signature and contacts that exceed
+    /// the configured maximum age are skipped.
+    pub fn load_contacts(&self) -> Vec<SignedPeerContact> {
+        let contacts: Vec<SignedPeerContact> = match load(&FileStore::new(
+            &self.config.contacts_path,
+        )) {
+            Ok(contacts) => contacts,
+            Err(error) => {
+                warn!(%error, path = %self.config.contacts_path.display(), "Failed to load peer contacts");
+                return vec![];
+            }
+        };
+
+        let num_stored = contacts.len();
+        let unix_time = unix_time();
+        let contacts: Vec<_> = contacts
+            .into_iter()
+            .filter(|contact| {
+                contact.verify()
+                    && !PeerContactInfo::from(contact.clone())
+                        .exceeds_age(self.config.max_contact_age, unix_time)
+            })
+            .collect();
+
+        debug!(
+            num_contacts = contacts.len(),
+            num_skipped = num_stored - contacts.len(),
+            "Loaded peer contacts"
+        );
+        contacts
+    }
+
+    /// Loads the stored DHT records, skipping the ones that already expired.
+    pub fn load_records(&self) -> Vec<Record> {
+        let records: Vec<StoredRecord> = match load(&FileStore::new(&self.config.records_path)) {
+            Ok(records) => records,
+            Err(error) => {
+                warn!(%error, path = %self.config.records_path.display(), "Failed to load DHT records");
+                return vec![];
+            }
+        };
+
+        let unix_time = unix_time();
+        let now = Instant::now();
+        let records: Vec<_> = records
+            .into_iter()
+            .filter_map(|stored| {
+                let expires = match stored.expires {
+                    Some(expires) => {
+                        let remaining = Duration::from_secs(expires).checked_sub(unix_time)?;
+                        Some(now + remaining)
+                    }
+                    None => None,
+                };
+                let mut record = Record::new(RecordKey::new(&stored.key), stored.value);
+                record.publisher = stored.publisher;
+                record.expires = expires;
+                Some(record)
+            })
+            .collect();
+
+        debug!(num_records = records.len(), "Loaded DHT records");
+        records
+    }
+
+    /// Takes a snapshot of the peer contacts (except for seeds) and the DHT records. This doesn't
+    /// do any I/O, so it can be done while holding the lock on the peer contact book.
+    pub fn snapshot(
+        &mut self,
+        contacts: &PeerContactBook,
+        records: &MemoryStore,
+    ) -> PeerStoreSnapshot {
+        let unix_time = unix_time();
+        let now = Instant::now();
+        let records = records
+            .records()
+            .filter_map(|record| {
+                let expires = match record.expires {
+                    Some(expires) => {
+                        let remaining = expires.checked_duration_since(now)?;
+                        Some((unix_time + remaining).as_secs())
+                    }
+                    None => None,
+                };
+                Some(StoredRecord {
+                    key: record.key.to_vec(),
+                    value: record.value.clone(),
+                    publisher: record.publisher,
+                    expires,
+                })
+            })
+            .collect();
+
+        let sequence_number = self.next_sequence_number;
+        self.next_sequence_number += 1;
+        PeerStoreSnapshot {
+            sequence_number,
+            contacts: contacts.signed_contacts(),
+            records,
+        }
+    }
+
+    /// Writes the snapshot to disk in the background, such that the caller isn't blocked by the
+    /// file I/O.
+    pub fn store(&self, snapshot: PeerStoreSnapshot) {
+        let write = self.write_fn(snapshot);
+        spawn(async move { spawn_blocking(write).await });
+    }
+
+    /// Writes the snapshot to disk and waits until it has been written, e.g. before shutting down.
+    pub async fn flush(&self, snapshot: PeerStoreSnapshot) {
+        spawn_blocking(self.write_fn(snapshot)).await
+    }
+
+    fn write_fn(&self, snapshot: PeerStoreSnapshot) -> impl FnOnce() + Send + 'static {
+        let config = self.config.clone();
+        let written = Arc::clone(&self.written);
+        move || {
+            let mut written = written.lock();
+            if written.is_some_and(|sequence_number| sequence_number > snapshot.sequence_number) {
+                return;
+            }
+            snapshot.write(&config);
+            *written = Some(snapshot.sequence_number);
+        }
+    }
+
+    /// Resolves once the peer store should be written to disk again.
+    /// Never resolves if there is no peer store.
+    pub async fn next_store(store: &mut Option<PeerStore>) {
+        match store {
+            Some(store) => {
+                store.interval.next().await;
+            }
+            None => future::pending().await,
+        }
+    }
+}
+
+impl PeerStoreSnapshot {
+    fn write(&self, config: &PeerStoreConfig) {
+        if let Err(error) = FileStore::new(&config.contacts_path).store(&self.contacts) {
+            warn!(%error, path = %config.contacts_path.display(), "Failed to store peer contacts");
+        }
+        if let Err(error) = FileStore::new(&config.records_path).store(&self.records) {
+            warn!(%error, path = %config.records_path.display(), "Failed to store DHT records");
+        }
+
+        trace!(
+            num_contacts = self.contacts.len(),
+            num_records = self.records.len(),
+            "Stored peer contacts and DHT records"
+        );
+    }
+}
+
+async fn spawn_blocking<F: FnOnce() + Send + 'static>(f: F) {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        tokio::task::spawn_blocking(f).await.unwrap()
+    }
+
+    #[cfg(target_family = "wasm")]
+    {
+        f()
+    }
+}
+
+/// Loads a value from the file store. A missing file is treated as an empty store.
+fn load<T: nimiq_serde::Deserialize + Default>(
+    file_store: &FileStore,
+) -> Result<T, FileStoreError> {
+    match file_store.load() {
+        Err(FileStoreError::Io(error)) if error.kind() == io::ErrorKind::NotFound => {
+            Ok(T::default())
+        }
+        result => result,
+    }
+}
+
+fn unix_time() -> Duration {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use libp2p::{
+        identity::Keypair,
+        kad::{
+            store::{MemoryStore, RecordStore},
+            Record, RecordKey,
+        },
+        multiaddr::multiaddr,
+        PeerId,
+    };
+    use nimiq_network_interface::peer_info::Services;
+    use nimiq_test_log::test;
+    use web_time::Instant;
+
+    use super::{unix_time, PeerStore};
+    use crate::{
+        discovery::peer_contacts::{PeerContact, PeerContactBook, SignedPeerContact},
+        PeerStoreConfig,
+    };
+
+    fn signed_contact(timestamp: Option<u64>) -> SignedPeerContact {
+        let keypair = Keypair::generate_ed25519();
+        PeerContact::new(
+            vec![multiaddr!(Memory(1u64))],
+            keypair.public(),
+            Services::all(),
+            timestamp,
+        )
+        .unwrap()
+        .sign(&keypair)
+    }
+
+    fn peer_store(dir: &tempfile::TempDir) -> PeerStore {
+        PeerStore::new(PeerStoreConfig::new(
+            dir.path().join("peer-contacts.dat"),
+            dir.path().join("dht-records.dat"),
+        ))
+    }
+
+    #[test(tokio::test)]
+    async fn it_restores_contacts_and_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut peer_store = peer_store(&dir);
+        assert!(peer_store.load_contacts().is_empty());
+        assert!(peer_store.load_records().is_empty());
+
+        let now = unix_time().as_secs();
+        let mut contacts = PeerContactBook::new(signed_contact(Some(now)), false, true, true);
+        let recent = signed_contact(Some(now - 60));
+        let old = signed_contact(Some(now - 2 * 24 * 60 * 60));
+        contacts.insert_all([recent.clone(), old, signed_contact(None)]);
+
+        let mut records = MemoryStore::new(PeerId::random());
+        let mut record = Record::new(RecordKey::new(&b"valid"), b"value".to_vec());
+        record.expires = Some(Instant::now() + Duration::from_secs(60 * 60));
+        records.put(record).unwrap();
+        let mut record = Record::new(RecordKey::new(&b"expired"), b"value".to_vec());
+        record.expires = Some(Instant::now() - Duration::from_secs(1));
+        records.put(record).unwrap();
+
+        let snapshot = peer_store.snapshot(&contacts, &records);
+        peer_store.flush(snapshot).await;
+
+        // Seeds and contacts exceeding the maximum age are not restored.
+        assert_eq!(peer_store.load_contacts(), vec![recent]);
+
+        let records = peer_store.load_records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].key, RecordKey::new(&b"valid"));
+        assert_eq!(records[0].value, b"value".to_vec());
+        assert!(records[0].expires.unwrap() > Instant::now());
+    }
+}
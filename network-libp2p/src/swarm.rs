@@ -43,10 +43,12 @@ use tokio::sync::{broadcast, mpsc};
 use crate::network_metrics::NetworkMetrics;
 use crate::{
     behaviour,
+    connection_pool::BanList,
     discovery::{behaviour::Event, peer_contacts::PeerContactBook},
     network_types::{
         DhtBootStrapState, DhtRecord, DhtResults, NetworkAction, TaskState, ValidateMessage,
     },
+    peer_store::PeerStore,
     rate_limiting::RateLimits,
     Config, NetworkError, TlsConfig,
 };
@@ -56,6 +58,8 @@ type NimiqSwarm = Swarm<behaviour::Behaviour>;
 pub(crate) fn new_swarm(
     config: Config,
     contacts: Arc<RwLock<PeerContactBook>>,
+    ban_list: Arc<RwLock<BanList>>,
+    records: Vec<Record>,
     peer_score_params: gossipsub::PeerScoreParams,
     force_dht_server_mode: bool,
 ) -> Swarm<behaviour::Behaviour> {
@@ -68,8 +72,14 @@ pub(crate) fn new_swarm(
     )
     .unwrap();
 
-    let behaviour =
-        behaviour::Behaviour::new(config, contacts, peer_score_params, force_dht_server_mode);
+    let behaviour = behaviour::Behaviour::new(
+        config,
+        contacts,
+        ban_list,
+        records,
+        peer_score_params,
+        force_dht_server_mode,
+    );
 
     // TODO add proper config
     #[cfg(not(target_family = "wasm"))]
@@ -99,6 +109,7 @@ pub(crate) async fn swarm_task(
     connected_peers: Arc<RwLock<HashMap<PeerId, PeerInfo>>>,
     mut update_scores: Interval,
     contacts: Arc<RwLock<PeerContactBook>>,
+    mut peer_store: Option<PeerStore>,
     force_dht_server_mode: bool,
     dht_quorum: NonZeroU8,
     #[cfg(feature = "metrics")] metrics: Arc<NetworkMetrics>,
@@ -152,8 +163,21 @@ pub(crate) async fn swarm_task(
                 _ = update_scores.next() => {
                     swarm.behaviour().update_scores(Arc::clone(&contacts));
                 },
+                _ = PeerStore::next_store(&mut peer_store) => {
+                    if let Some(ref mut peer_store) = peer_store {
+                        let snapshot = peer_store.snapshot(&contacts.read(), swarm.behaviour_mut().dht.store_mut());
+                        peer_store.store(snapshot);
+                    }
+                },
             };
         }
+
+        // Persist the latest peer contacts and DHT records before shutting down.
+        if let Some(ref mut peer_store) = peer_store {
+            let snapshot =
+                peer_store.snapshot(&contacts.read(), swarm.behaviour_mut().dht.store_mut());
+            peer_store.flush(snapshot).await;
+        }
     }
     .instrument(task_span)
     .await
@@ -1122,6 +1146,7 @@ fn perform_action(action: NetworkAction, swarm: &mut NimiqSwarm, state: &mut Tas
         NetworkAction::DisconnectPeer { peer_id, reason } => {
             swarm.behaviour_mut().pool.close_connection(peer_id, reason)
         }
+        NetworkAction::ClearBans => swarm.behaviour_mut().pool.clear_bans(),
     }
 }
 
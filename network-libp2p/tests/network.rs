@@ -9,7 +9,9 @@ use libp2p::{
 };
 use nimiq_bls::KeyPair;
 use nimiq_network_interface::{
-    network::{CloseReason, MsgAcceptance, Network as NetworkInterface, NetworkEvent, Topic},
+    network::{
+        BanReason, CloseReason, MsgAcceptance, Network as NetworkInterface, NetworkEvent, Topic,
+    },
     peer_info::Services,
 };
 use nimiq_network_libp2p::{
@@ -67,6 +69,7 @@ fn network_config(address: Multiaddr) -> Config {
         only_secure_ws_connections: false,
         allow_loopback_addresses: true,
         dht_quorum: NonZeroU8::new(1).unwrap(),
+        peer_store: None,
     }
 }
 
@@ -459,6 +462,30 @@ async fn ban_peer() {
     assert_eq!(net2.get_peers(), &[]);
 }
 
+#[test(tokio::test)]
+async fn ban_misbehaving_peer() {
+    let (net1, net2) = create_connected_networks().await;
+
+    let mut events2 = net2.subscribe_events();
+
+    let net1_peer_id = *net1.local_peer_id();
+
+    net2.ban_peer(net1_peer_id, BanReason::InvalidBlock).await;
+
+    let event2 = helper::get_next_peer_event(&mut events2).await;
+    helper::assert_peer_left(&event2, &net1_peer_id);
+    assert_eq!(net2.get_peers(), &[]);
+
+    let banned_peers = net2.get_banned_peers();
+    assert_eq!(banned_peers.len(), 1);
+    assert_eq!(banned_peers[0].0, net1_peer_id);
+    assert_eq!(banned_peers[0].1.reason, BanReason::InvalidBlock);
+    assert!(banned_peers[0].1.banned_until.is_some());
+
+    net2.clear_banned_peers().await;
+    assert!(net2.get_banned_peers().is_empty());
+}
+
 pub struct TestTopic;
 
 impl Topic for TestTopic {
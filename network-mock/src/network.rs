@@ -1,16 +1,18 @@
 use std::{
+    collections::HashMap,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 
 use async_trait::async_trait;
 use futures::{stream::BoxStream, StreamExt};
 use nimiq_network_interface::{
     network::{
-        CloseReason, MsgAcceptance, Network, NetworkEvent, PubsubId, SubscribeEvents, Topic,
+        BanInfo, BanReason, CloseReason, MsgAcceptance, Network, NetworkEvent, PubsubId,
+        SubscribeEvents, Topic,
     },
     peer_info::{PeerInfo, Services},
     request::{
@@ -77,6 +79,7 @@ pub struct MockNetwork {
     peers: Arc<RwLock<ObservableHashMap<MockPeerId, PeerInfo>>>,
     hub: Arc<Mutex<MockHubInner>>,
     is_connected: Arc<AtomicBool>,
+    bans: Mutex<HashMap<MockPeerId, BanInfo>>,
 }
 
 impl MockNetwork {
@@ -105,6 +108,7 @@ impl MockNetwork {
             peers,
             hub,
             is_connected,
+            bans: Mutex::new(HashMap::new()),
         }
     }
 
@@ -457,6 +461,36 @@ impl Network for MockNetwork {
             .retain(|k, _| k.network_recipient != peer_id.into());
     }
 
+    async fn ban_peer(&self, peer_id: MockPeerId, reason: BanReason) {
+        let unix_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let banned = self
+            .bans
+            .lock()
+            .entry(peer_id)
+            .or_insert_with(|| BanInfo::new(reason, unix_time))
+            .penalize(reason, unix_time);
+
+        if banned {
+            self.disconnect_peer(peer_id, CloseReason::MaliciousPeer)
+                .await;
+        }
+    }
+
+    fn get_banned_peers(&self) -> Vec<(MockPeerId, BanInfo)> {
+        self.bans
+            .lock()
+            .iter()
+            .map(|(peer_id, info)| (*peer_id, info.clone()))
+            .collect()
+    }
+
+    async fn clear_banned_peers(&self) {
+        self.bans.lock().clear();
+    }
+
     fn subscribe_events(&self) -> SubscribeEvents<MockPeerId> {
         Box::pin(
             BroadcastStream::new(self.peers.read().subscribe()).map(|maybe_ev| {
@@ -0,0 +1,187 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use nimiq_hash::Blake2bHash;
+use nimiq_primitives::networks::NetworkId;
+use nimiq_serde::{Deserialize, Serialize};
+
+use crate::types::CheckpointError;
+
+/// The progress of the migration. It is persisted whenever the migration makes progress, such
+/// that a restarted migration resumes where it left off instead of starting over.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationCheckpoint {
+    /// The network the migration is done for.
+    pub network_id: NetworkId,
+    /// The last PoW block whose transactions have been migrated into the PoS history store.
+    pub last_migrated_block: u32,
+    /// The root of the history tree after migrating `last_migrated_block`.
+    pub history_root: Option<Blake2bHash>,
+    /// The current genesis candidate block.
+    pub candidate_block: u32,
+    /// The hash of the genesis config generated for the candidate block, once it is generated.
+    pub genesis_config_hash: Option<Blake2bHash>,
+    /// Whether our validator signaled its readiness for the genesis config of the candidate
+    /// block. The readiness transaction might not have been mined yet.
+    pub reported_ready: bool,
+    /// Whether enough validators signaled their readiness and the genesis file was written.
+    pub completed: bool,
+}
+
+impl MigrationCheckpoint {
+    /// Creates the checkpoint of a migration that didn't start yet.
+    pub fn new(network_id: NetworkId, candidate_block: u32) -> Self {
+        Self {
+            network_id,
+            last_migrated_block: 0,
+            history_root: None,
+            candidate_block,
+            genesis_config_hash: None,
+            reported_ready: false,
+            completed: false,
+        }
+    }
+
+    /// Moves on to the given genesis candidate block, forgetting the genesis config and
+    /// readiness of the previous one.
+    pub fn move_to_candidate(&mut self, candidate_block: u32) {
+        if self.candidate_block != candidate_block {
+            self.candidate_block = candidate_block;
+            self.genesis_config_hash = None;
+            self.reported_ready = false;
+        }
+    }
+}
+
+/// Keeps the migration checkpoint in memory and persists every update of it to a TOML file.
+pub struct CheckpointStore {
+    path: PathBuf,
+    checkpoint: Mutex<MigrationCheckpoint>,
+}
+
+impl CheckpointStore {
+    /// Opens the checkpoint file at the given path. If it doesn't exist yet, a new migration
+    /// starting at the given candidate block is assumed.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        network_id: NetworkId,
+        candidate_block: u32,
+    ) -> Result<Self, CheckpointError> {
+        let path = path.as_ref().to_path_buf();
+        let checkpoint = match fs::read_to_string(&path) {
+            Ok(contents) => {
+                let checkpoint: MigrationCheckpoint = toml::from_str(&contents)?;
+                if checkpoint.network_id != network_id {
+                    return Err(CheckpointError::NetworkMismatch(checkpoint.network_id));
+                }
+                log::info!(
+                    filename = ?path,
+                    last_migrated_block = checkpoint.last_migrated_block,
+                    candidate_block = checkpoint.candidate_block,
+                    "Resuming migration from checkpoint"
+                );
+                checkpoint
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                MigrationCheckpoint::new(network_id, candidate_block)
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(Self {
+            path,
+            checkpoint: Mutex::new(checkpoint),
+        })
+    }
+
+    /// Returns the current checkpoint.
+    pub fn get(&self) -> MigrationCheckpoint {
+        self.checkpoint
+            .lock()
+            .expect("Checkpoint lock is poisoned")
+            .clone()
+    }
+
+    /// Applies the given update to the checkpoint and persists it. The file is replaced
+    /// atomically and synced to disk, such that a crash never leaves a partially written or a
+    /// lost checkpoint behind.
+    pub fn update<F: FnOnce(&mut MigrationCheckpoint)>(&self, f: F) -> Result<(), CheckpointError> {
+        let mut checkpoint = self.checkpoint.lock().expect("Checkpoint lock is poisoned");
+        let mut updated = checkpoint.clone();
+        f(&mut updated);
+        if updated == *checkpoint {
+            return Ok(());
+        }
+
+        let tmp_path = self.path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(toml::to_string(&updated)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+
+        // The rename is only durable once the directory containing the file is synced as well.
+        #[cfg(unix)]
+        if let Some(parent) = self.path.parent() {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            fs::File::open(parent)?.sync_all()?;
+        }
+
+        *checkpoint = updated;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_persists_and_resumes_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.toml");
+
+        let store = CheckpointStore::open(&path, NetworkId::TestAlbatross, 100).unwrap();
+        assert_eq!(
+            store.get(),
+            MigrationCheckpoint::new(NetworkId::TestAlbatross, 100)
+        );
+        assert!(!path.exists());
+
+        store
+            .update(|checkpoint| {
+                checkpoint.last_migrated_block = 99;
+                checkpoint.history_root = Some(Blake2bHash::default());
+                checkpoint.genesis_config_hash = Some(Blake2bHash::default());
+                checkpoint.reported_ready = true;
+            })
+            .unwrap();
+
+        // A restarted migration resumes from the persisted checkpoint.
+        let resumed = CheckpointStore::open(&path, NetworkId::TestAlbatross, 100).unwrap();
+        assert_eq!(resumed.get(), store.get());
+
+        // Moving to the next candidate keeps the history progress only.
+        resumed
+            .update(|checkpoint| checkpoint.move_to_candidate(110))
+            .unwrap();
+        let checkpoint = resumed.get();
+        assert_eq!(checkpoint.candidate_block, 110);
+        assert_eq!(checkpoint.last_migrated_block, 99);
+        assert_eq!(checkpoint.genesis_config_hash, None);
+        assert!(!checkpoint.reported_ready);
+
+        // A checkpoint can't be used for another network.
+        assert!(matches!(
+            CheckpointStore::open(&path, NetworkId::MainAlbatross, 100),
+            Err(CheckpointError::NetworkMismatch(NetworkId::TestAlbatross))
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use nimiq_blockchain::{interface::HistoryInterface, HistoryStore};
 use nimiq_database::{
@@ -22,7 +22,38 @@ use tokio::{
     time::sleep,
 };
 
-use crate::{async_retryer, types::HistoryError};
+use crate::{
+    async_retryer,
+    checkpoint::{CheckpointStore, MigrationCheckpoint},
+    exit_with_error,
+    types::HistoryError,
+};
+
+/// Number of migrated PoW blocks after which the migration checkpoint is updated.
+const CHECKPOINT_INTERVAL: u32 = 100;
+
+/// Number of historic transactions that are read at once when verifying the history root.
+const VERIFY_CHUNK_SIZE: usize = 10_000;
+
+/// The result of recomputing the history root of a migrated history store.
+#[derive(Debug)]
+pub struct HistoryVerification {
+    /// The last PoW block with transactions in the history store.
+    pub last_block: u32,
+    /// The number of historic transactions in the history store.
+    pub num_transactions: usize,
+    /// The history root as stored in the history store.
+    pub stored_root: Blake2bHash,
+    /// The history root recomputed from the historic transactions.
+    pub recomputed_root: Blake2bHash,
+}
+
+impl HistoryVerification {
+    /// Returns whether the recomputed history root matches the stored one.
+    pub fn is_valid(&self) -> bool {
+        self.stored_root == self.recomputed_root
+    }
+}
 
 fn from_pow_network_id(pow_network_id: u8) -> Result<NetworkId, HistoryError> {
     match pow_network_id {
@@ -80,6 +111,8 @@ fn from_pow_transaction(pow_transaction: &PoWTransaction) -> Result<Transaction,
 /// It migrates the history up to the `candidate_block` received in `rx_candidate_block` if the head of the PoW chain
 /// is greater than `candidate_block + block_confirmations` and if not waits for this to happen.
 /// Note that this waiting process is done per block such that the migration can be triggered per confirmed block.
+/// The progress is periodically recorded in the migration checkpoint such that a restarted migration resumes
+/// from the last checkpointed block.
 pub async fn migrate_history(
     mut rx_candidate_block: mpsc::Receiver<u32>,
     tx_migration_completed: watch::Sender<u32>,
@@ -87,8 +120,10 @@ pub async fn migrate_history(
     network_id: NetworkId,
     pow_client: Client,
     block_confirmations: u32,
+    checkpoint: Arc<CheckpointStore>,
 ) {
-    let mut history_store_height = get_history_store_height(env.clone(), network_id).await;
+    let mut history_store_height =
+        get_resume_height(env.clone(), network_id, &checkpoint.get()).await;
     let history_store = HistoryStore::new(env.clone(), network_id);
     let mut pow_head_height = async_retryer(|| pow_client.block_number()).await.unwrap();
 
@@ -133,22 +168,11 @@ pub async fn migrate_history(
                 .await
                 .unwrap();
 
-            if block_height % 100 == 0 {
-                log::info!(block_number = %block.number, target = %candidate_block, "Migrated new PoW history chunk");
-            }
-
             // Get all transactions for this block height
             let mut transactions = vec![];
             let mut network_id = NetworkId::Main;
             match block.transactions {
                 PoWTransactionSequence::BlockHashes(hashes) => {
-                    if hashes.is_empty() {
-                        // Mark that we've migrated up until this block.
-                        history_store_height = block_height;
-                        tx_migration_completed.send(block_height).unwrap();
-                        continue;
-                    }
-
                     for hash in hashes {
                         log::trace!(hash, "Processing transaction");
                         let pow_transaction =
@@ -164,40 +188,91 @@ pub async fn migrate_history(
                         );
                         transactions.push(ExecutedTransaction::Ok(pos_transaction));
                     }
-
-                    // Mark that we've migrated up until this block.
-                    history_store_height = block_height;
-                    tx_migration_completed.send(block_height).unwrap();
                 }
                 PoWTransactionSequence::Transactions(_) => panic!("Unexpected transaction type"),
             }
 
             // Add transactions to the history store
-            let mut txn = env.write_transaction();
-            history_store.add_to_history_for_epoch(
-                &mut txn,
-                0,
-                block_height,
-                &HistoricTransaction::from(
-                    network_id,
+            if !transactions.is_empty() {
+                let mut txn = env.write_transaction();
+                history_store.add_to_history_for_epoch(
+                    &mut txn,
+                    0,
                     block_height,
-                    block.timestamp.into(),
-                    transactions,
-                    vec![],
-                    vec![],
-                ),
-            );
-            txn.commit();
+                    &HistoricTransaction::from(
+                        network_id,
+                        block_height,
+                        block.timestamp.into(),
+                        transactions,
+                        vec![],
+                        vec![],
+                    ),
+                );
+                txn.commit();
+            }
+
+            // Mark that we've migrated up until this block.
+            history_store_height = block_height;
+            tx_migration_completed.send(block_height).unwrap();
+
+            if block_height % CHECKPOINT_INTERVAL == 0 {
+                log::info!(block_number = %block.number, target = %candidate_block, "Migrated new PoW history chunk");
+                checkpoint_history(&history_store, &checkpoint, block_height);
+            }
         }
 
         log::info!(
             candidate_block,
             "Finished migrating PoW history up to the candidate block"
         );
+        checkpoint_history(&history_store, &checkpoint, history_store_height);
         tx_migration_completed.send(candidate_block).unwrap();
     }
 }
 
+/// Records in the migration checkpoint that the PoW history has been migrated up until the given block.
+fn checkpoint_history(
+    history_store: &HistoryStore,
+    checkpoint: &CheckpointStore,
+    block_height: u32,
+) {
+    let history_root = history_store.get_history_tree_root(0, None);
+    checkpoint
+        .update(|checkpoint| {
+            checkpoint.last_migrated_block = block_height;
+            checkpoint.history_root = history_root;
+        })
+        .unwrap_or_else(|error| {
+            exit_with_error(error, "Failed to update the migration checkpoint")
+        });
+}
+
+/// Get the PoW block height from which the history migration resumes.
+/// Blocks without transactions leave no trace in the history store, so the height recorded in the
+/// checkpoint is preferred as long as the history store still has the history root recorded along with it.
+pub async fn get_resume_height(
+    env: MdbxDatabase,
+    network_id: NetworkId,
+    checkpoint: &MigrationCheckpoint,
+) -> u32 {
+    let history_store = HistoryStore::new(env.clone(), network_id);
+    let history_store_height = history_store.get_last_leaf_block_number(None).unwrap_or(1);
+    if checkpoint.last_migrated_block <= history_store_height {
+        return history_store_height;
+    }
+
+    if history_store.get_history_tree_root(0, None) != checkpoint.history_root {
+        log::warn!(
+            history_store_height,
+            checkpoint_height = checkpoint.last_migrated_block,
+            "The history store doesn't match the migration checkpoint, resuming from the history store height"
+        );
+        return history_store_height;
+    }
+
+    checkpoint.last_migrated_block
+}
+
 /// Get the PoS genesis history root by getting all of the transactions from the
 /// PoW chain and building a single history tree.
 pub async fn get_history_root(
@@ -209,6 +284,73 @@ pub async fn get_history_root(
         .ok_or(HistoryError::HistoryRootError)
 }
 
+/// Recomputes the PoS genesis history root from the historic transactions in the history store.
+/// The transactions are read in chunks and added to a new temporary history tree, whose root must
+/// match the one stored in the history store.
+pub fn verify_history_root(
+    env: MdbxDatabase,
+    network_id: NetworkId,
+) -> Result<HistoryVerification, HistoryError> {
+    recompute_history_root(env, network_id, VERIFY_CHUNK_SIZE)
+}
+
+fn recompute_history_root(
+    env: MdbxDatabase,
+    network_id: NetworkId,
+    chunk_size: usize,
+) -> Result<HistoryVerification, HistoryError> {
+    let history_store = HistoryStore::new(env.clone(), network_id);
+    let stored_root = history_store
+        .get_history_tree_root(0, None)
+        .ok_or(HistoryError::HistoryRootError)?;
+    let last_block = history_store
+        .get_last_leaf_block_number(None)
+        .ok_or(HistoryError::HistoryRootError)?;
+    let num_transactions = history_store
+        .length_at(last_block, None)
+        .ok_or(HistoryError::HistoryRootError)? as usize;
+
+    let scratch_env = MdbxDatabase::new_volatile(Default::default())?;
+    let scratch_store = HistoryStore::new(scratch_env.clone(), network_id);
+
+    for chunk_start in (0..num_transactions).step_by(chunk_size) {
+        let chunk_end = num_transactions.min(chunk_start + chunk_size);
+        let hist_txs = history_store
+            .get_epoch_leaves(0, chunk_start as u32..chunk_end as u32, None)
+            .ok_or(HistoryError::HistoryRootError)?;
+
+        let mut txn = scratch_env.write_transaction();
+        for hist_tx in &hist_txs {
+            scratch_store
+                .add_to_history_for_epoch(
+                    &mut txn,
+                    0,
+                    hist_tx.block_number,
+                    std::slice::from_ref(hist_tx),
+                )
+                .ok_or(HistoryError::HistoryRootError)?;
+        }
+        txn.commit();
+
+        log::debug!(
+            verified = chunk_end,
+            total = num_transactions,
+            "Recomputing history root"
+        );
+    }
+
+    let recomputed_root = scratch_store
+        .get_history_tree_root(0, None)
+        .ok_or(HistoryError::HistoryRootError)?;
+
+    Ok(HistoryVerification {
+        last_block,
+        num_transactions,
+        stored_root,
+        recomputed_root,
+    })
+}
+
 /// Get the current block height of the PoS history store
 pub async fn get_history_store_height(env: MdbxDatabase, network_id: NetworkId) -> u32 {
     HistoryStore::new(env.clone(), network_id)
@@ -218,6 +360,9 @@ pub async fn get_history_store_height(env: MdbxDatabase, network_id: NetworkId)
 
 #[cfg(test)]
 mod test {
+    use std::collections::BTreeMap;
+
+    use nimiq_test_log::test;
 
     use super::*;
 
@@ -513,4 +658,120 @@ mod test {
             assert_eq!(txn.hash, pos_transaction.hash::<Blake2bHash>().to_hex())
         }
     }
+
+    /// Migrates the test transactions into a new history store, one PoW block at a time.
+    fn migrated_history_store() -> MdbxDatabase {
+        let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+        let history_store = HistoryStore::new(env.clone(), NetworkId::MainAlbatross);
+
+        let pow_transactions: Vec<PoWTransaction> = serde_json::from_str(TRANSACTIONS).unwrap();
+        let mut blocks = BTreeMap::<_, Vec<PoWTransaction>>::new();
+        for pow_transaction in pow_transactions {
+            blocks
+                .entry(pow_transaction.block_number)
+                .or_default()
+                .push(pow_transaction);
+        }
+
+        for (block_number, pow_transactions) in blocks {
+            let transactions = pow_transactions
+                .iter()
+                .map(|txn| ExecutedTransaction::Ok(from_pow_transaction(txn).unwrap()))
+                .collect();
+            let mut txn = env.write_transaction();
+            history_store
+                .add_to_history_for_epoch(
+                    &mut txn,
+                    0,
+                    block_number,
+                    &HistoricTransaction::from(
+                        NetworkId::Main,
+                        block_number,
+                        pow_transactions[0].timestamp.into(),
+                        transactions,
+                        vec![],
+                        vec![],
+                    ),
+                )
+                .unwrap();
+            txn.commit();
+        }
+
+        env
+    }
+
+    #[test]
+    fn can_recompute_the_history_root() {
+        let env = migrated_history_store();
+        let stored_root = HistoryStore::new(env.clone(), NetworkId::MainAlbatross)
+            .get_history_tree_root(0, None)
+            .unwrap();
+
+        // Chunks that don't evenly divide the transactions.
+        let verification =
+            recompute_history_root(env.clone(), NetworkId::MainAlbatross, 4).unwrap();
+        assert!(verification.is_valid());
+        assert_eq!(verification.last_block, 2815094);
+        assert_eq!(verification.num_transactions, 14);
+        assert_eq!(verification.stored_root, stored_root);
+
+        let verification = verify_history_root(env, NetworkId::MainAlbatross).unwrap();
+        assert!(verification.is_valid());
+        assert_eq!(verification.recomputed_root, stored_root);
+    }
+
+    #[test]
+    fn cannot_verify_an_empty_history_store() {
+        let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+        assert!(matches!(
+            verify_history_root(env, NetworkId::MainAlbatross),
+            Err(HistoryError::HistoryRootError)
+        ));
+    }
+
+    #[test(tokio::test)]
+    async fn resumes_from_the_matching_checkpoint() {
+        let env = migrated_history_store();
+        let history_root =
+            HistoryStore::new(env.clone(), NetworkId::MainAlbatross).get_history_tree_root(0, None);
+        let mut checkpoint = MigrationCheckpoint::new(NetworkId::MainAlbatross, 2815200);
+
+        // The checkpoint is ahead of the history store, because the last blocks had no transactions.
+        checkpoint.last_migrated_block = 2815100;
+        checkpoint.history_root = history_root.clone();
+        assert_eq!(
+            get_resume_height(env.clone(), NetworkId::MainAlbatross, &checkpoint).await,
+            2815100
+        );
+
+        // The history store doesn't match the checkpoint anymore.
+        checkpoint.history_root = Some(Blake2bHash::default());
+        assert_eq!(
+            get_resume_height(env.clone(), NetworkId::MainAlbatross, &checkpoint).await,
+            2815094
+        );
+        checkpoint.history_root = None;
+        assert_eq!(
+            get_resume_height(env.clone(), NetworkId::MainAlbatross, &checkpoint).await,
+            2815094
+        );
+
+        // The checkpoint is behind the history store.
+        checkpoint.last_migrated_block = 2815090;
+        checkpoint.history_root = history_root;
+        assert_eq!(
+            get_resume_height(env, NetworkId::MainAlbatross, &checkpoint).await,
+            2815094
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn resumes_an_empty_history_store_from_the_start() {
+        let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+        let checkpoint = MigrationCheckpoint::new(NetworkId::MainAlbatross, 2815200);
+        assert_eq!(
+            get_resume_height(env, NetworkId::MainAlbatross, &checkpoint).await,
+            1
+        );
+    }
 }
@@ -1,3 +1,4 @@
+pub mod checkpoint;
 pub mod genesis;
 pub mod history;
 pub mod monitor;
@@ -22,6 +23,7 @@ use nimiq_serde::Serialize;
 use tokio::time::sleep;
 
 use crate::{
+    checkpoint::CheckpointStore,
     genesis::get_pos_genesis,
     monitor::{
         check_validators_ready, generate_ready_tx, get_ready_txns, send_tx, ValidatorsReadiness,
@@ -82,6 +84,8 @@ pub fn get_block_windows(network_id: NetworkId) -> Result<&'static BlockWindows,
 
 /// Performs the PoS migration from PoW by parsing transactions and state of the PoW
 /// chain and returning a PoS genesis configuration.
+/// The readiness of our validator is kept in the given checkpoint, such that a restarted
+/// migration doesn't signal readiness for the same genesis config twice.
 pub async fn migrate(
     pow_client: &Client,
     block_windows: &BlockWindows,
@@ -89,6 +93,7 @@ pub async fn migrate(
     env: MdbxDatabase,
     validator_address: &Option<Address>,
     network_id: NetworkId,
+    checkpoint: &CheckpointStore,
 ) -> Result<Option<GenesisConfig>, Error> {
     // First set up the PoW client for accounts migration
     setup_pow_rpc_server(pow_client).await?;
@@ -171,7 +176,6 @@ pub async fn migrate(
         );
     }
 
-    let mut reported_ready = false;
     let mut genesis_config;
 
     // Wait for enough confirmations for the candidate block
@@ -252,6 +256,19 @@ pub async fn migrate(
         "PoS Genesis generation is completed"
     );
 
+    // Only keep the readiness of a previous run if it was reported for the same genesis config
+    checkpoint.update(|checkpoint| {
+        checkpoint.move_to_candidate(candidate_block);
+        if checkpoint.genesis_config_hash.as_ref() != Some(&genesis_config_hash) {
+            checkpoint.genesis_config_hash = Some(genesis_config_hash.clone());
+            checkpoint.reported_ready = false;
+        }
+    })?;
+    let mut reported_ready = checkpoint.get().reported_ready;
+    if reported_ready {
+        log::info!("Our validator already signaled readiness for this genesis config");
+    }
+
     loop {
         let current_height = async_retryer(|| pow_client.block_number()).await.unwrap();
         log::info!(current_height);
@@ -300,6 +317,7 @@ pub async fn migrate(
                 log::info!("We found a ready transaction from our validator in the current window");
             }
             reported_ready = true;
+            checkpoint.update(|checkpoint| checkpoint.reported_ready = true)?;
         }
 
         // Check if we have enough validators ready at this point
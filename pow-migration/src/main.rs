@@ -1,15 +1,18 @@
-use std::{fs, process::exit, time::Duration};
+use std::{fs, process::exit, sync::Arc, time::Duration};
 
 use clap::{Parser, Subcommand};
 use convert_case::{Case, Casing};
 use log::{info, level_filters::LevelFilter};
 use nimiq::config::{config::ClientConfig, config_file::ConfigFile};
+use nimiq_genesis_builder::config::GenesisConfig;
 use nimiq_keys::Address;
 use nimiq_pow_migration::{
-    async_retryer, exit_with_error,
+    async_retryer,
+    checkpoint::CheckpointStore,
+    exit_with_error,
     genesis::write_pos_genesis,
     get_block_windows,
-    history::{get_history_store_height, migrate_history},
+    history::{get_resume_height, migrate_history, verify_history_root},
     launch_pos_client, migrate,
     state::{get_stakers, get_validators},
 };
@@ -30,9 +33,9 @@ struct Args {
     /// Path to the PoS configuration file
     #[arg(short, long)]
     config: String,
-    /// PoW RPC server URL
+    /// PoW RPC server URL (not needed for `verify`)
     #[arg(long)]
-    url: String,
+    url: Option<String>,
     /// Optional PoW RPC server username
     #[arg(short, long)]
     username: Option<String>,
@@ -54,6 +57,9 @@ enum Commands {
     },
     /// Outputs a list of registered validators
     ListValidators,
+    /// Recomputes the history root from the migrated history store and compares it with the
+    /// stored history root, the genesis file and the migration checkpoint
+    Verify,
 }
 
 fn initialize_logging() {
@@ -118,8 +124,72 @@ async fn main() {
             exit_with_error(error, "Error parsing configuration file");
         });
 
-    let url =
-        Url::parse(&args.url).unwrap_or_else(|error| exit_with_error(error, "Invalid RPC URL"));
+    let block_windows = get_block_windows(config.network_id)
+        .unwrap_or_else(|error| exit_with_error(error, "Couldn't get block windows"));
+
+    let genesis_dir = current_exe_dir.join("genesis");
+    let genesis_file =
+        genesis_dir.join(config.network_id.to_string().to_case(Case::Kebab) + ".toml");
+    let checkpoint_file =
+        genesis_dir.join(config.network_id.to_string().to_case(Case::Kebab) + "-checkpoint.toml");
+
+    if let Some(Commands::Verify) = args.command {
+        let env = config
+            .storage
+            .database(
+                config.network_id,
+                config.consensus.sync_mode,
+                config.database,
+            )
+            .unwrap_or_else(|error| exit_with_error(error, "Unable to create DB environment"));
+
+        let verification = verify_history_root(env, config.network_id)
+            .unwrap_or_else(|error| exit_with_error(error, "Could not recompute the history root"));
+        println!(
+            "Migrated history up to PoW block {} with {} transactions",
+            verification.last_block, verification.num_transactions
+        );
+        println!("Stored history root:     {}", verification.stored_root);
+        println!("Recomputed history root: {}", verification.recomputed_root);
+        let mut valid = verification.is_valid();
+
+        if genesis_file.exists() {
+            let genesis_config: GenesisConfig = fs::read_to_string(&genesis_file)
+                .map(|contents| toml::from_str(&contents))
+                .unwrap_or_else(|error| exit_with_error(error, "Could not read genesis file"))
+                .unwrap_or_else(|error| exit_with_error(error, "Could not parse genesis file"));
+            println!("Genesis history root:    {:?}", genesis_config.history_root);
+            valid &= genesis_config.history_root.as_ref() == Some(&verification.recomputed_root);
+        }
+
+        if checkpoint_file.exists() {
+            let checkpoint = CheckpointStore::open(
+                &checkpoint_file,
+                config.network_id,
+                block_windows.election_candidate,
+            )
+            .unwrap_or_else(|error| exit_with_error(error, "Could not read migration checkpoint"))
+            .get();
+            println!("Checkpoint history root: {:?}", checkpoint.history_root);
+            // An unfinished migration might have migrated more blocks since the last checkpoint
+            if checkpoint.completed {
+                valid &= checkpoint.history_root.as_ref() == Some(&verification.recomputed_root);
+            }
+        }
+
+        if !valid {
+            log::error!("The recomputed history root doesn't match");
+            exit(1);
+        }
+        println!("History root verified");
+        return;
+    }
+
+    let url = args.url.as_deref().unwrap_or_else(|| {
+        log::error!("Missing PoW RPC server URL ('--url')");
+        exit(1);
+    });
+    let url = Url::parse(url).unwrap_or_else(|error| exit_with_error(error, "Invalid RPC URL"));
 
     let pow_client = if args.username.is_some() && args.password.is_some() {
         Client::new_with_credentials(url, args.username.unwrap(), args.password.unwrap())
@@ -127,9 +197,6 @@ async fn main() {
         Client::new(url)
     };
 
-    let block_windows = get_block_windows(config.network_id)
-        .unwrap_or_else(|error| exit_with_error(error, "Couldn't get block windows"));
-
     // Check to see if the client already has consensus
     loop {
         let status = async_retryer(|| pow_client.consensus()).await.unwrap();
@@ -235,21 +302,6 @@ async fn main() {
             }
         };
 
-        // Create channels in order to communicate with the PoW-to-PoS history migrator
-        let (tx_candidate_block, rx_candidate_block) = mpsc::channel(16);
-        let (tx_migration_completed, rx_migration_completed) =
-            watch::channel(get_history_store_height(env.clone(), config.network_id).await);
-
-        // Spawn PoW-to-PoS migrator as separate task
-        spawn(migrate_history(
-            rx_candidate_block,
-            tx_migration_completed,
-            env.clone(),
-            config.network_id,
-            pow_client.clone(),
-            block_windows.block_confirmations,
-        ));
-
         // Check that the `nimiq-client` exists
         let pos_client = current_exe_dir.join("nimiq-client");
         if !pos_client.exists() {
@@ -258,16 +310,59 @@ async fn main() {
         };
 
         // Create directory where the genesis file will be written if it doesn't exist
-        let genesis_dir = current_exe_dir.join("genesis");
         if !genesis_dir.exists() {
             fs::create_dir(genesis_dir.clone()).unwrap_or_else(|error| {
                 exit_with_error(error, "Could not create genesis directory")
             });
         }
-        let genesis_file =
-            genesis_dir.join(config.network_id.to_string().to_case(Case::Kebab) + ".toml");
 
-        let mut candidate_block = block_windows.election_candidate;
+        // Resume from the progress of a previous run, if any
+        let checkpoint = Arc::new(
+            CheckpointStore::open(
+                &checkpoint_file,
+                config.network_id,
+                block_windows.election_candidate,
+            )
+            .unwrap_or_else(|error| exit_with_error(error, "Could not open migration checkpoint")),
+        );
+
+        if checkpoint.get().completed && genesis_file.exists() {
+            log::info!(
+                filename = ?genesis_file,
+                "The migration was already completed, using the existing PoS genesis"
+            );
+            launch_pos_client(
+                &pos_client,
+                &genesis_file,
+                &args.config,
+                genesis_env_var_name,
+            )
+            .unwrap_or_else(|error| exit_with_error(error, "Failed to launch POS client"));
+            return;
+        }
+
+        // Create channels in order to communicate with the PoW-to-PoS history migrator
+        let (tx_candidate_block, rx_candidate_block) = mpsc::channel(16);
+        let (tx_migration_completed, rx_migration_completed) = watch::channel(
+            get_resume_height(env.clone(), config.network_id, &checkpoint.get()).await,
+        );
+
+        // Spawn PoW-to-PoS migrator as separate task
+        spawn(migrate_history(
+            rx_candidate_block,
+            tx_migration_completed,
+            env.clone(),
+            config.network_id,
+            pow_client.clone(),
+            block_windows.block_confirmations,
+            Arc::clone(&checkpoint),
+        ));
+
+        // A previous run might already have moved on to a later activation window
+        let mut candidate_block = checkpoint
+            .get()
+            .candidate_block
+            .max(block_windows.election_candidate);
 
         // Eagerly instruct to migrate the PoW history up to the first candidate block
         tx_candidate_block
@@ -321,6 +416,7 @@ async fn main() {
                 env.clone(),
                 &validator_address,
                 config.network_id,
+                &checkpoint,
             )
             .await
             .unwrap_or_else(|error| exit_with_error(error, "Could not migrate"));
@@ -333,6 +429,11 @@ async fn main() {
 
             // We didn't obtain the genesis configuration: select the new candidate block
             candidate_block += block_windows.readiness_window;
+            checkpoint
+                .update(|checkpoint| checkpoint.move_to_candidate(candidate_block))
+                .unwrap_or_else(|error| {
+                    exit_with_error(error, "Failed to update the migration checkpoint")
+                });
 
             // Instruct to migrate the PoW history up until the this next candidate block
             tx_candidate_block
@@ -357,6 +458,11 @@ async fn main() {
             filename = ?genesis_file,
             "Finished writing PoS genesis to file"
         );
+        checkpoint
+            .update(|checkpoint| checkpoint.completed = true)
+            .unwrap_or_else(|error| {
+                exit_with_error(error, "Failed to update the migration checkpoint")
+            });
 
         // Launch PoS client
         launch_pos_client(
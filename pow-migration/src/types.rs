@@ -169,6 +169,26 @@ pub enum HistoryError {
     /// Error calculating history root
     #[error("History root error")]
     HistoryRootError,
+    /// Database error
+    #[error("Database error: {0}")]
+    Database(#[from] nimiq_database::Error),
+}
+
+/// Error types that can be returned
+#[derive(Error, Debug)]
+pub enum CheckpointError {
+    /// I/O error
+    #[error("I/O error: {0}")]
+    IO(#[from] std::io::Error),
+    /// Deserialization error
+    #[error("Deserialization: {0}")]
+    Deserialization(#[from] toml::de::Error),
+    /// Serialization error
+    #[error("Serialization: {0}")]
+    Serialization(#[from] toml::ser::Error),
+    /// The checkpoint belongs to a migration for another network
+    #[error("Checkpoint was created for network ID {0}")]
+    NetworkMismatch(NetworkId),
 }
 
 /// Error types that can be returned
@@ -195,6 +215,9 @@ pub enum Error {
     /// History migration error
     #[error("History migration error: {0}")]
     History(#[from] HistoryError),
+    /// Migration checkpoint error
+    #[error("Migration checkpoint error: {0}")]
+    Checkpoint(#[from] CheckpointError),
     /// Validator key hasn't been imported
     #[error("Validator key hasn't been imported: {0}")]
     ValidatorKey(Address),
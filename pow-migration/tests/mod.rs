@@ -8,6 +8,7 @@ mod pow_migration_test {
     use nimiq_genesis_builder::config::GenesisConfig;
     use nimiq_keys::Address;
     use nimiq_pow_migration::{
+        checkpoint::CheckpointStore,
         migrate,
         types::{BlockWindows, Error},
     };
@@ -29,6 +30,13 @@ mod pow_migration_test {
         candidate_block: u32,
     ) -> Result<Option<GenesisConfig>, Error> {
         let env = MdbxDatabase::new_volatile(Default::default()).unwrap();
+        let checkpoint_dir = tempfile::tempdir().unwrap();
+        let checkpoint = CheckpointStore::open(
+            checkpoint_dir.path().join("checkpoint.toml"),
+            network_id,
+            candidate_block,
+        )
+        .unwrap();
         let client = setup_pow_client();
         let address = Address::from_user_friendly_address(&validator_address)
             .expect("Could not parse provided validator address");
@@ -39,6 +47,7 @@ mod pow_migration_test {
             env,
             &Some(address),
             network_id,
+            &checkpoint,
         )
         .await
     }
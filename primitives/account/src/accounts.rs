@@ -7,6 +7,7 @@ use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::Address;
 use nimiq_primitives::{
     account::{AccountError, AccountType, FailReason},
+    coin::Coin,
     key_nibbles::KeyNibbles,
     trie::{
         error::IncompleteTrie,
@@ -33,6 +34,22 @@ declare_table!(AccountsTrieTable, "AccountsTrie", KeyNibbles => TrieNode);
 /// An alias for the accounts tree.
 pub type AccountsTrie = MerkleRadixTrie<AccountsTrieTable>;
 
+/// The balance of an account before and after a simulated transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub address: Address,
+    pub balance_before: Coin,
+    pub balance_after: Coin,
+}
+
+/// The outcome of a transaction that was committed without changing the accounts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulatedTransaction {
+    pub receipt: TransactionOperationReceipt,
+    pub log: TransactionLog,
+    pub balance_changes: Vec<BalanceChange>,
+}
+
 /// The Accounts struct is simply an wrapper containing a database environment and, more importantly,
 /// a MerkleRadixTrie with accounts as leaf values. This struct basically holds all the accounts in
 /// the blockchain. It also has methods to commit and revert transactions, so we can use it to
@@ -303,6 +320,69 @@ impl Accounts {
         Ok((state_hash, diff_hash, executed_txns))
     }
 
+    /// Commits the given transaction in a throw-away database transaction that is aborted
+    /// afterwards, i.e. the accounts are not changed. Returns the receipt and the logs of the
+    /// transaction together with the balances of the accounts it touches.
+    ///
+    /// The database only allows a single write transaction at a time, so this blocks the pushing
+    /// of blocks (and all other writers) until the simulation is done. Callers serving untrusted
+    /// clients should limit how often it is called.
+    pub fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+        block_state: &BlockState,
+    ) -> Result<SimulatedTransaction, AccountsError> {
+        let mut raw_txn = self.env.write_transaction();
+        let mut txn: WriteTransactionProxy = (&mut raw_txn).into();
+        assert!(self.is_complete(Some(&txn)), "Tree must be complete");
+
+        let mut addresses = vec![transaction.sender.clone()];
+        if transaction.recipient != transaction.sender {
+            addresses.push(transaction.recipient.clone());
+        }
+        let balances_before: Vec<Coin> = addresses
+            .iter()
+            .map(|address| self.get_complete(address, Some(&txn)).balance())
+            .collect();
+
+        let mut block_logger = BlockLogger::empty();
+        let mut receipts = self.commit_batch(
+            &mut txn,
+            &[transaction.clone()],
+            &[],
+            block_state,
+            &mut block_logger,
+        )?;
+
+        let balance_changes = addresses
+            .into_iter()
+            .zip(balances_before)
+            .map(|(address, balance_before)| BalanceChange {
+                balance_after: self.get_complete(&address, Some(&txn)).balance(),
+                address,
+                balance_before,
+            })
+            .collect();
+
+        raw_txn.abort();
+
+        let log = block_logger
+            .build(0)
+            .transaction_logs()
+            .first()
+            .cloned()
+            .expect("A log must have been created for the transaction");
+
+        Ok(SimulatedTransaction {
+            receipt: receipts
+                .transactions
+                .pop()
+                .expect("A receipt must have been created for the transaction"),
+            log,
+            balance_changes,
+        })
+    }
+
     pub fn commit(
         &self,
         txn: &mut WriteTransactionProxy,
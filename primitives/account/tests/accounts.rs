@@ -2,8 +2,9 @@ use std::{convert::TryFrom, time::Instant};
 
 use log::info;
 use nimiq_account::{
-    Account, Accounts, BasicAccount, BlockLogger, BlockState, InherentOperationReceipt, Log,
-    OperationReceipt, TransactionOperationReceipt, TransactionReceipt, VestingContract,
+    Account, Accounts, BalanceChange, BasicAccount, BlockLogger, BlockState,
+    InherentOperationReceipt, Log, OperationReceipt, TransactionOperationReceipt,
+    TransactionReceipt, VestingContract,
 };
 use nimiq_bls::KeyPair as BLSKeyPair;
 use nimiq_database::{
@@ -11,6 +12,7 @@ use nimiq_database::{
     traits::{Database, WriteTransaction},
 };
 use nimiq_genesis_builder::GenesisBuilder;
+use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::{Address, Ed25519PublicKey, KeyPair, PrivateKey, SecureGenerate};
 use nimiq_primitives::{
     account::{AccountType, FailReason},
@@ -148,6 +150,98 @@ fn it_can_commit_and_revert_a_block_body() {
     assert_eq!(hash1, accounts.get_root_hash_assert(None));
 }
 
+#[test]
+fn it_simulates_transactions_without_changing_the_accounts() {
+    let accounts = TestCommitRevert::new();
+
+    let address_sender = Address::from([1u8; Address::SIZE]);
+    let address_recipient = Address::from([2u8; Address::SIZE]);
+
+    let reward = Inherent::Reward {
+        validator_address: Address::burn_address(),
+        target: address_sender.clone(),
+        value: Coin::from_u64_unchecked(10000),
+    };
+    accounts
+        .commit_and_test(
+            &[],
+            &[reward],
+            &BlockState::new(1, 1),
+            &mut BlockLogger::empty(),
+        )
+        .unwrap();
+    let root_hash = accounts.get_root_hash_assert(None);
+
+    let tx = Transaction::new_basic(
+        address_sender.clone(),
+        address_recipient.clone(),
+        Coin::from_u64_unchecked(10),
+        Coin::from_u64_unchecked(1),
+        1,
+        NetworkId::Main,
+    );
+    let simulation = accounts
+        .simulate_transaction(&tx, &BlockState::new(2, 2))
+        .unwrap();
+
+    assert_eq!(
+        simulation.receipt,
+        TransactionOperationReceipt::Ok(TransactionReceipt::default())
+    );
+    assert_eq!(simulation.log.tx_hash, tx.hash::<Blake2bHash>());
+    assert!(!simulation.log.failed);
+    assert_eq!(
+        simulation.log.logs,
+        vec![Log::pay_fee_log(&tx), Log::transfer_log(&tx)]
+    );
+    assert_eq!(
+        simulation.balance_changes,
+        vec![
+            BalanceChange {
+                address: address_sender.clone(),
+                balance_before: Coin::from_u64_unchecked(10000),
+                balance_after: Coin::from_u64_unchecked(10000 - 11),
+            },
+            BalanceChange {
+                address: address_recipient.clone(),
+                balance_before: Coin::ZERO,
+                balance_after: Coin::from_u64_unchecked(10),
+            },
+        ]
+    );
+
+    // Transactions exceeding the balance fail, but still pay the fee.
+    let tx = Transaction::new_basic(
+        address_sender.clone(),
+        address_recipient.clone(),
+        Coin::from_u64_unchecked(20000),
+        Coin::from_u64_unchecked(1),
+        1,
+        NetworkId::Main,
+    );
+    let simulation = accounts
+        .simulate_transaction(&tx, &BlockState::new(2, 2))
+        .unwrap();
+
+    assert!(matches!(
+        simulation.receipt,
+        OperationReceipt::Err(_, FailReason::InsufficientFunds)
+    ));
+    assert!(simulation.log.failed);
+    assert_eq!(
+        simulation.balance_changes[0].balance_after,
+        Coin::from_u64_unchecked(10000 - 1)
+    );
+    assert_eq!(simulation.balance_changes[1].balance_after, Coin::ZERO);
+
+    // The accounts are unchanged.
+    assert_eq!(accounts.get_root_hash_assert(None), root_hash);
+    assert_eq!(
+        accounts.get_complete(&address_sender, None).balance(),
+        Coin::from_u64_unchecked(10000)
+    );
+}
+
 #[test]
 fn it_correctly_rewards_validators() {
     let accounts = TestCommitRevert::new();
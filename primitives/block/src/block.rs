@@ -434,7 +434,7 @@ impl Block {
 
         // Check that the extra data does not exceed the permitted size.
         // This is also checked during deserialization.
-        if self.extra_data().len() > 32 {
+        if self.extra_data().len() > Policy::MAX_EXTRA_DATA_SIZE {
             warn!(
                 header = %self,
                 reason = "too much extra data",
@@ -285,7 +285,7 @@ impl SerializedMaxSize for MacroHeader {
         + /*parent_election_hash*/ Blake2bHash::SIZE
         + /*interlink*/ nimiq_serde::option_max_size(nimiq_serde::seq_max_size(Blake2bHash::SIZE, 32))
         + /*seed*/ VrfSeed::SIZE
-        + /*extra_data*/ nimiq_serde::seq_max_size(u8::SIZE, 32)
+        + /*extra_data*/ nimiq_serde::seq_max_size(u8::SIZE, Policy::MAX_EXTRA_DATA_SIZE)
         + /*state_root*/ Blake2bHash::SIZE
         + /*body_root*/ Blake2sHash::SIZE
         + /*diff_root*/ Blake2bHash::SIZE
@@ -209,7 +209,7 @@ impl SerializedMaxSize for MicroHeader {
         + /*timestamp*/ u64::MAX_SIZE
         + /*parent_hash*/ Blake2bHash::SIZE
         + /*seed*/ VrfSeed::SIZE
-        + /*extra_data*/ nimiq_serde::seq_max_size(u8::SIZE, 32)
+        + /*extra_data*/ nimiq_serde::seq_max_size(u8::SIZE, Policy::MAX_EXTRA_DATA_SIZE)
         + /*state_root*/ Blake2bHash::SIZE
         + /*body_root*/ Blake2sHash::SIZE
         + /*diff_root*/ Blake2bHash::SIZE
@@ -0,0 +1,300 @@
+use std::io::Write;
+
+use nimiq_hash::{Blake2bHash, Blake2bHasher, Hasher};
+use nimiq_serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The number of bits of the remainder in the Golomb-Rice coding.
+pub const FILTER_P: u8 = 19;
+/// The inverse of the false positive rate of a filter, as recommended by BIP158 for `FILTER_P`.
+pub const FILTER_M: u64 = 784_931;
+
+/// Returns the key that is used to hash the items of the filter of the given batch.
+pub fn batch_filter_key(batch_number: u32) -> [u8; 4] {
+    batch_number.to_be_bytes()
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum CompactFilterError {
+    #[error("Compact filter data is malformed")]
+    Malformed,
+}
+
+/// A Golomb-coded set as specified by BIP158. It allows to probabilistically test whether any
+/// of a set of items is contained in the filter, with a false positive rate of `1 / FILTER_M`
+/// per item and without false negatives.
+///
+/// Items are hashed together with a key, which must be the same for creating and querying
+/// the filter.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactFilter {
+    /// The number of distinct items in the filter.
+    pub num_items: u32,
+    /// The Golomb-Rice coded deltas of the sorted hashed items.
+    pub data: Vec<u8>,
+}
+
+impl CompactFilter {
+    /// Creates a filter over the given items. Duplicate items are only included once.
+    pub fn new<I, T>(key: &[u8], items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut items: Vec<Vec<u8>> = items
+            .into_iter()
+            .map(|item| item.as_ref().to_vec())
+            .collect();
+        items.sort_unstable();
+        items.dedup();
+        let hashed_items = hash_items(key, &items, items.len() as u64);
+
+        let mut writer = BitWriter::default();
+        let mut last_value = 0;
+        for value in &hashed_items {
+            golomb_encode(&mut writer, value - last_value);
+            last_value = *value;
+        }
+
+        CompactFilter {
+            num_items: items.len() as u32,
+            data: writer.finish(),
+        }
+    }
+
+    /// Returns whether any of the given items is (probably) contained in the filter.
+    pub fn matches_any<I, T>(&self, key: &[u8], items: I) -> Result<bool, CompactFilterError>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        if self.num_items == 0 {
+            return Ok(false);
+        }
+
+        let items: Vec<T> = items.into_iter().collect();
+        let mut queries = hash_items(key, &items, self.num_items as u64).into_iter();
+        let mut query = match queries.next() {
+            Some(query) => query,
+            None => return Ok(false),
+        };
+
+        let mut reader = BitReader::new(&self.data);
+        let mut value = 0u64;
+        for _ in 0..self.num_items {
+            let delta = golomb_decode(&mut reader).ok_or(CompactFilterError::Malformed)?;
+            value = value
+                .checked_add(delta)
+                .ok_or(CompactFilterError::Malformed)?;
+
+            while query < value {
+                query = match queries.next() {
+                    Some(query) => query,
+                    None => return Ok(false),
+                };
+            }
+            if query == value {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Returns whether the given item is (probably) contained in the filter.
+    pub fn matches(&self, key: &[u8], item: &[u8]) -> Result<bool, CompactFilterError> {
+        self.matches_any(key, [item])
+    }
+
+    /// Returns the header of this filter, which commits to this filter and all previous filters.
+    /// `prev_header` is the header of the previous filter or the default hash for the first one.
+    pub fn header(&self, prev_header: &Blake2bHash) -> Blake2bHash {
+        let mut hasher = Blake2bHasher::default();
+        hasher.write_all(&self.num_items.to_be_bytes()).unwrap();
+        hasher.write_all(&self.data).unwrap();
+        let filter_hash = hasher.finish();
+
+        Blake2bHasher::default()
+            .chain(&filter_hash)
+            .chain(prev_header)
+            .finish()
+    }
+}
+
+/// Hashes the items into the range `[0, num_items * FILTER_M)` and returns them sorted.
+fn hash_items<T: AsRef<[u8]>>(key: &[u8], items: &[T], num_items: u64) -> Vec<u64> {
+    let range = num_items * FILTER_M;
+    let mut hashed_items: Vec<u64> = items
+        .iter()
+        .map(|item| hash_to_range(key, item.as_ref(), range))
+        .collect();
+    hashed_items.sort_unstable();
+    hashed_items
+}
+
+/// Hashes an item to a uniformly distributed value in `[0, range)`.
+fn hash_to_range(key: &[u8], item: &[u8], range: u64) -> u64 {
+    let mut hasher = Blake2bHasher::default();
+    hasher.write_all(key).unwrap();
+    hasher.write_all(item).unwrap();
+    let hash = hasher.finish();
+
+    let value = u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap());
+    ((value as u128 * range as u128) >> 64) as u64
+}
+
+fn golomb_encode(writer: &mut BitWriter, value: u64) {
+    let quotient = value >> FILTER_P;
+    for _ in 0..quotient {
+        writer.write_bit(true);
+    }
+    writer.write_bit(false);
+    writer.write_bits(value, FILTER_P);
+}
+
+fn golomb_decode(reader: &mut BitReader) -> Option<u64> {
+    let mut quotient = 0u64;
+    while reader.read_bit()? {
+        quotient += 1;
+    }
+    let remainder = reader.read_bits(FILTER_P)?;
+    quotient
+        .checked_shl(FILTER_P as u32)
+        .filter(|value| value >> FILTER_P == quotient)
+        .map(|value| value | remainder)
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    num_bits: usize,
+}
+
+impl BitWriter {
+    fn write_bit(&mut self, bit: bool) {
+        if self.num_bits % 8 == 0 {
+            self.bytes.push(0);
+        }
+        if bit {
+            let last = self.bytes.last_mut().unwrap();
+            *last |= 0x80 >> (self.num_bits % 8);
+        }
+        self.num_bits += 1;
+    }
+
+    /// Writes the `num_bits` least significant bits of `value`, most significant bit first.
+    fn write_bits(&mut self, value: u64, num_bits: u8) {
+        for i in (0..num_bits).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, position: 0 }
+    }
+
+    fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.bytes.get(self.position / 8)?;
+        let bit = byte & (0x80 >> (self.position % 8)) != 0;
+        self.position += 1;
+        Some(bit)
+    }
+
+    fn read_bits(&mut self, num_bits: u8) -> Option<u64> {
+        let mut value = 0u64;
+        for _ in 0..num_bits {
+            value = (value << 1) | self.read_bit()? as u64;
+        }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_test_log::test;
+
+    use super::*;
+
+    const KEY: &[u8] = b"key";
+
+    fn items(range: std::ops::Range<u32>) -> Vec<Vec<u8>> {
+        range.map(|i| i.to_be_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn it_matches_contained_items() {
+        let filter = CompactFilter::new(KEY, items(0..100));
+        assert_eq!(filter.num_items, 100);
+
+        for item in items(0..100) {
+            assert_eq!(filter.matches(KEY, &item), Ok(true));
+        }
+        assert_eq!(filter.matches_any(KEY, items(90..200)), Ok(true));
+    }
+
+    #[test]
+    fn it_rejects_other_items() {
+        let filter = CompactFilter::new(KEY, items(0..100));
+
+        // With a false positive rate of 1 / FILTER_M this is extremely unlikely to match.
+        assert_eq!(filter.matches_any(KEY, items(1000..1100)), Ok(false));
+        assert_eq!(filter.matches_any(KEY, Vec::<Vec<u8>>::new()), Ok(false));
+
+        // A different key results in different hashes.
+        let filter = CompactFilter::new(b"other key", items(0..100));
+        assert_eq!(filter.matches_any(KEY, items(0..100)), Ok(false));
+    }
+
+    #[test]
+    fn it_deduplicates_items() {
+        let mut duplicated_items = items(0..10);
+        duplicated_items.extend(items(0..10));
+        let filter = CompactFilter::new(KEY, duplicated_items);
+
+        assert_eq!(filter, CompactFilter::new(KEY, items(0..10)));
+    }
+
+    #[test]
+    fn empty_filters_match_nothing() {
+        let filter = CompactFilter::new(KEY, Vec::<Vec<u8>>::new());
+        assert_eq!(filter.num_items, 0);
+        assert!(filter.data.is_empty());
+        assert_eq!(filter.matches_any(KEY, items(0..100)), Ok(false));
+    }
+
+    #[test]
+    fn it_detects_malformed_filters() {
+        let mut filter = CompactFilter::new(KEY, items(0..100));
+        filter.data.truncate(filter.data.len() / 2);
+        assert_eq!(
+            filter.matches_any(KEY, items(1000..1100)),
+            Err(CompactFilterError::Malformed)
+        );
+    }
+
+    #[test]
+    fn headers_commit_to_previous_filters() {
+        let filter_1 = CompactFilter::new(KEY, items(0..10));
+        let filter_2 = CompactFilter::new(KEY, items(10..20));
+
+        let header_1 = filter_1.header(&Blake2bHash::default());
+        let header_2 = filter_2.header(&header_1);
+        assert_ne!(header_1, filter_2.header(&Blake2bHash::default()));
+        assert_ne!(header_2, filter_1.header(&header_1));
+        assert_eq!(
+            header_2,
+            filter_2.header(&filter_1.header(&Blake2bHash::default()))
+        );
+    }
+}
@@ -2,6 +2,8 @@
 pub mod account;
 #[cfg(feature = "coin")]
 pub mod coin;
+#[cfg(feature = "compact-filter")]
+pub mod compact_filter;
 #[cfg(feature = "key-nibbles")]
 pub mod key_nibbles;
 #[cfg(feature = "networks")]
@@ -63,6 +63,8 @@ impl Policy {
     pub const MAX_MERKLE_PATH_SIZE: usize = 1029;
     /// Maximum size for the total web auth fields.
     pub const MAX_SUPPORTED_WEB_AUTH_SIZE: usize = 512;
+    /// Maximum size for the extra data of blocks.
+    pub const MAX_EXTRA_DATA_SIZE: usize = 32;
 
     /// The current version number of the protocol. Changing this always results in a hard fork.
     pub const VERSION: u16 = 1;
@@ -398,6 +398,31 @@ impl Transaction {
         u64::from(self.fee) as f64 / self.serialized_size() as f64
     }
 
+    /// Returns the smallest fee for which a transaction of the given serialized size pays more
+    /// than the given fee per byte, comparing the same way as [`fee_per_byte`](Self::fee_per_byte).
+    /// Returns `None` if the fee per byte is negative or not finite, or if the fee would exceed
+    /// [`Coin::MAX`].
+    pub fn fee_exceeding(fee_per_byte: f64, size: usize) -> Option<Coin> {
+        if !fee_per_byte.is_finite() || fee_per_byte < 0.0 {
+            return None;
+        }
+
+        let size = size as f64;
+        let fee = (fee_per_byte * size).floor() + 1.0;
+        if fee > u64::from(Coin::MAX) as f64 + 1.0 {
+            return None;
+        }
+
+        // The product might be off by a rounding error, which a single step corrects.
+        let mut fee = fee as u64;
+        if fee > 0 && (fee - 1) as f64 / size > fee_per_byte {
+            fee -= 1;
+        } else if fee as f64 / size <= fee_per_byte {
+            fee += 1;
+        }
+        Coin::try_from(fee).ok()
+    }
+
     pub fn serialize_content(&self) -> Vec<u8> {
         let mut result = Vec::new();
         SerializeContent::serialize_content::<_, Blake2bHash>(self, &mut result).unwrap();
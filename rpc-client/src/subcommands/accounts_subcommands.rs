@@ -52,6 +52,10 @@ pub enum AccountCommand {
         #[clap(short = 'P', long)]
         password: Option<String>,
 
+        /// Locks the account again after this many seconds.
+        #[clap(short, long)]
+        duration: Option<u64>,
+
         /// The account's address.
         address: Address,
     },
@@ -92,6 +96,59 @@ pub enum AccountCommand {
         is_hex: bool,
     },
 
+    /// Imports a BIP39 or legacy mnemonic of 24 words together with its first account.
+    /// The mnemonic and the derived accounts remain locked after this operation.
+    ImportMnemonic {
+        #[clap(short = 'P', long)]
+        password: Option<String>,
+
+        /// The path at which BIP39 accounts are derived. The index of the account is appended to it.
+        /// Defaults to m/44'/242'/0'.
+        #[clap(long)]
+        derivation_path: Option<String>,
+
+        /// The password the mnemonic was encrypted with, if it was exported encrypted.
+        #[clap(long)]
+        encryption_password: Option<String>,
+
+        /// The words of the mnemonic, or the encrypted mnemonic in hexadecimal.
+        mnemonic: String,
+    },
+
+    /// Derives an account from an imported mnemonic and imports it.
+    DeriveAccount {
+        #[clap(short = 'P', long)]
+        password: Option<String>,
+
+        /// The ID of the mnemonic, which is the address of its first account.
+        wallet_id: Address,
+
+        /// The index of the account to derive.
+        index: u32,
+    },
+
+    /// Lists the imported mnemonics and the accounts derived from them.
+    ListMnemonics {},
+
+    /// Lists the accounts that have been derived from an imported mnemonic.
+    ListDerived {
+        /// The ID of the mnemonic, which is the address of its first account.
+        wallet_id: Address,
+    },
+
+    /// Exports an imported mnemonic, optionally encrypted with a separate password.
+    ExportMnemonic {
+        #[clap(short = 'P', long)]
+        password: Option<String>,
+
+        /// Encrypts the exported mnemonic with this password.
+        #[clap(long)]
+        encryption_password: Option<String>,
+
+        /// The ID of the mnemonic, which is the address of its first account.
+        wallet_id: Address,
+    },
+
     /// Queries all accounts in the accounts tree
     GetAll {},
 
@@ -99,6 +156,11 @@ pub enum AccountCommand {
     Get {
         /// The account's address.
         address: Address,
+
+        /// Query the state after the given block instead of the current state. Requires the
+        /// node to keep an accounts archive.
+        #[clap(long)]
+        block_number: Option<u32>,
     },
 }
 
@@ -114,7 +176,7 @@ impl HandleSubcommand for AccountCommand {
                     } else {
                         let account = client
                             .blockchain
-                            .get_account_by_address(address.clone())
+                            .get_account_by_address(address.clone(), None)
                             .await?;
                         println!("{}: {:#?}", address.to_user_friendly_address(), account);
                     }
@@ -134,14 +196,15 @@ impl HandleSubcommand for AccountCommand {
                 client.wallet.lock_account(address).await?;
             }
             AccountCommand::Unlock {
-                address, password, ..
+                address,
+                password,
+                duration,
             } => {
-                // TODO: Duration
                 println!(
                     "{:#?}",
                     client
                         .wallet
-                        .unlock_account(address, password, None)
+                        .unlock_account(address, password, duration)
                         .await?
                 );
             }
@@ -172,10 +235,64 @@ impl HandleSubcommand for AccountCommand {
                         .await?
                 );
             }
-            AccountCommand::Get { address } => {
+            AccountCommand::ImportMnemonic {
+                password,
+                derivation_path,
+                encryption_password,
+                mnemonic,
+            } => {
+                println!(
+                    "{:#?}",
+                    client
+                        .wallet
+                        .import_mnemonic(mnemonic, derivation_path, password, encryption_password)
+                        .await?
+                );
+            }
+            AccountCommand::DeriveAccount {
+                password,
+                wallet_id,
+                index,
+            } => {
+                println!(
+                    "{:#?}",
+                    client
+                        .wallet
+                        .derive_account(wallet_id, index, password)
+                        .await?
+                );
+            }
+            AccountCommand::ListMnemonics {} => {
+                println!("{:#?}", client.wallet.list_mnemonics().await?);
+            }
+            AccountCommand::ListDerived { wallet_id } => {
+                println!(
+                    "{:#?}",
+                    client.wallet.list_derived_accounts(wallet_id).await?
+                );
+            }
+            AccountCommand::ExportMnemonic {
+                password,
+                encryption_password,
+                wallet_id,
+            } => {
+                let mnemonic = client
+                    .wallet
+                    .export_mnemonic(wallet_id, password, encryption_password)
+                    .await?
+                    .data;
+                println!("{mnemonic}");
+            }
+            AccountCommand::Get {
+                address,
+                block_number,
+            } => {
                 println!(
                     "{:#?}",
-                    client.blockchain.get_account_by_address(address).await?
+                    client
+                        .blockchain
+                        .get_account_by_address(address, block_number)
+                        .await?
                 );
             }
 
@@ -4,7 +4,11 @@ use clap::{ArgGroup, Parser};
 use futures::StreamExt;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
-use nimiq_rpc_interface::{blockchain::BlockchainInterface, types::LogType};
+use nimiq_primitives::coin::Coin;
+use nimiq_rpc_interface::{
+    blockchain::BlockchainInterface,
+    types::{AddressHistoryQuery, AddressTransactionType, LogType, TransactionDirection},
+};
 
 use super::accounts_subcommands::HandleSubcommand;
 use crate::Client;
@@ -84,6 +88,13 @@ pub enum BlockchainCommand {
     /// where the given address is listed as a recipient or as a sender are considered. Reward
     /// transactions are also returned. It has an option to specify the maximum number of transactions/hashes to
     /// fetch, it defaults to 500.
+    /// The transactions can be paged through by passing the returned cursor as `--start-after-hash`
+    /// and filtered by direction, type, block range and minimum value.
+    #[clap(group(
+        ArgGroup::new("start_after")
+        .required(false)
+        .args(&["start_after_hash", "start_after_block_number"]),
+        ))]
     TransactionsByAddress {
         /// The address to query by.
         address: Address,
@@ -95,6 +106,34 @@ pub enum BlockchainCommand {
         /// If set true only the hash of the transactions will be fetched. Otherwise the full transactions will be retrieved.
         #[clap(short = 'h')]
         just_hash: bool,
+
+        /// Start after the transaction with the given hash.
+        #[clap(long)]
+        start_after_hash: Option<Blake2bHash>,
+
+        /// Start with the transactions of the blocks preceding the given block number.
+        #[clap(long)]
+        start_after_block_number: Option<u32>,
+
+        /// Only fetch sent or only fetch received transactions.
+        #[clap(long, value_enum)]
+        direction: Option<TransactionDirection>,
+
+        /// List of transaction types to select. If empty it does not filter by type.
+        #[clap(short = 't', long = "type", value_enum)]
+        types: Vec<AddressTransactionType>,
+
+        /// Only fetch transactions included in the given block or a later one.
+        #[clap(long)]
+        min_block_number: Option<u32>,
+
+        /// Only fetch transactions included in the given block or an earlier one.
+        #[clap(long)]
+        max_block_number: Option<u32>,
+
+        /// Only fetch transactions with at least the given value in NIM.
+        #[clap(long)]
+        min_value: Option<Coin>,
     },
 
     /// Returns the information for the slot owner at the given block height and offset. The
@@ -109,6 +148,14 @@ pub enum BlockchainCommand {
         offset: Option<u32>,
     },
 
+    /// Returns the upcoming slots of a validator until the end of the epoch and the blocks it is
+    /// known to propose, with the expected timestamps of the blocks. The proposers of later blocks
+    /// and the slots of the next epoch are reported as unknown.
+    SlotSchedule {
+        /// The validator address to query by.
+        address: Address,
+    },
+
     /// Returns information about the currently penalized slots or the previous batch. This includes slots that lost rewards
     /// and that were disabled.
     PenalizedSlots {
@@ -121,6 +168,11 @@ pub enum BlockchainCommand {
     ValidatorByAddress {
         /// The address to query by.
         address: Address,
+
+        /// Query the state after the given block instead of the current state. Requires the
+        /// node to keep an accounts archive.
+        #[clap(long)]
+        block_number: Option<u32>,
     },
 
     /// Tries to fetch all validators in the staking contract.
@@ -138,11 +190,28 @@ pub enum BlockchainCommand {
     Staker {
         /// The address to query by.
         address: Address,
+
+        /// Query the state after the given block instead of the current state. Requires the
+        /// node to keep an accounts archive.
+        #[clap(long)]
+        block_number: Option<u32>,
     },
 
     /// Lists the current stakes from the staking contract.
     Stakes {},
 
+    /// Reports the rewards a validator received for the slots it held during an epoch and their
+    /// pro-rata split between its stakers. Requires the node to keep an accounts archive.
+    /// IMPORTANT: This is a very expensive operation, iterating over all historic transactions
+    /// of the epoch and all existing stakers in the contract.
+    ValidatorRewards {
+        /// The validator address to query by.
+        address: Address,
+
+        /// The epoch the rewards were earned in.
+        epoch_number: u32,
+    },
+
     /// Follow the head of the blockchain.
     FollowHead {
         /// Show the full block instead of only the hash.
@@ -211,6 +280,23 @@ impl HandleSubcommand for BlockchainCommand {
                     client.blockchain.get_slot_at(block_number, offset).await?
                 )
             }
+            BlockchainCommand::SlotSchedule { address } => {
+                let schedule = client
+                    .blockchain
+                    .get_validator_slot_schedule(address)
+                    .await?;
+                println!("{:#?}", schedule);
+                if let Some(block) = &schedule.data.proposers_unknown_from {
+                    println!(
+                        "The proposers of blocks #{} to #{} are unknown.",
+                        block.block_number, schedule.data.last_block_number
+                    );
+                }
+                println!(
+                    "The slots from block #{} on are unknown until the election block of epoch {} is final.",
+                    schedule.data.slots_unknown_from.block_number, schedule.data.epoch_number
+                );
+            }
             BlockchainCommand::Transaction { hash } => {
                 println!(
                     "{:#?}",
@@ -266,13 +352,30 @@ impl HandleSubcommand for BlockchainCommand {
                 address,
                 max,
                 just_hash,
+                start_after_hash,
+                start_after_block_number,
+                direction,
+                types,
+                min_block_number,
+                max_block_number,
+                min_value,
             } => {
+                let query = AddressHistoryQuery {
+                    max,
+                    start_after_hash,
+                    start_after_block_number,
+                    direction,
+                    types,
+                    min_block_number,
+                    max_block_number,
+                    min_value,
+                };
                 if just_hash {
                     println!(
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transaction_hashes_by_address(address, max)
+                            .get_transaction_hashes_by_address_paginated(address, query)
                             .await?
                     )
                 } else {
@@ -280,7 +383,7 @@ impl HandleSubcommand for BlockchainCommand {
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transactions_by_address(address, max)
+                            .get_transactions_by_address_paginated(address, query)
                             .await?
                     )
                 }
@@ -298,9 +401,15 @@ impl HandleSubcommand for BlockchainCommand {
                     )
                 }
             }
-            BlockchainCommand::ValidatorByAddress { address } => println!(
+            BlockchainCommand::ValidatorByAddress {
+                address,
+                block_number,
+            } => println!(
                 "{:#?}",
-                client.blockchain.get_validator_by_address(address).await?
+                client
+                    .blockchain
+                    .get_validator_by_address(address, block_number)
+                    .await?
             ),
 
             BlockchainCommand::Validators {} => {
@@ -314,15 +423,31 @@ impl HandleSubcommand for BlockchainCommand {
                     .get_stakers_by_validator_address(address)
                     .await?
             ),
-            BlockchainCommand::Staker { address } => {
+            BlockchainCommand::Staker {
+                address,
+                block_number,
+            } => {
                 println!(
                     "{:#?}",
-                    client.blockchain.get_staker_by_address(address).await?
+                    client
+                        .blockchain
+                        .get_staker_by_address(address, block_number)
+                        .await?
                 )
             }
             BlockchainCommand::Stakes {} => {
                 println!("{:#?}", client.blockchain.get_active_validators().await?);
             }
+            BlockchainCommand::ValidatorRewards {
+                address,
+                epoch_number,
+            } => println!(
+                "{:#?}",
+                client
+                    .blockchain
+                    .get_validator_rewards_by_epoch(address, epoch_number)
+                    .await?
+            ),
 
             BlockchainCommand::FollowHead { block: show_block } => {
                 if show_block {
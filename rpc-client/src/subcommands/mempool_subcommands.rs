@@ -30,6 +30,14 @@ pub enum MempoolCommand {
 
     /// Returns the minimum fee per byte of the local mempool.
     MinFeePerByte {},
+
+    /// Suggests the fee per byte a transaction needs to pay to be included within the given
+    /// number of blocks.
+    FeeEstimate {
+        /// The number of blocks within which the transaction should be included.
+        #[clap(short = 'b', long, default_value_t = 1)]
+        target_blocks: u32,
+    },
 }
 
 #[async_trait]
@@ -66,6 +74,12 @@ impl HandleSubcommand for MempoolCommand {
             MempoolCommand::MinFeePerByte {} => {
                 println!("{:#?}", client.mempool.get_min_fee_per_byte().await?);
             }
+            MempoolCommand::FeeEstimate { target_blocks } => {
+                println!(
+                    "{:#?}",
+                    client.mempool.get_fee_estimate(target_blocks).await?
+                );
+            }
         }
         Ok(client)
     }
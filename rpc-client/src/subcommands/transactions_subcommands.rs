@@ -1,9 +1,9 @@
-use std::str::FromStr;
+use std::{num::NonZeroU8, str::FromStr};
 
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::{Args, Parser};
-use nimiq_keys::Address;
+use nimiq_keys::{Address, Ed25519PublicKey};
 use nimiq_primitives::coin::Coin;
 use nimiq_rpc_interface::{
     consensus::ConsensusInterface,
@@ -328,6 +328,84 @@ pub enum TransactionCommand {
         #[clap(short, long, default_value_t)]
         validity_start_height: ValidityStartHeight,
     },
+
+    /// Simulates a serialized transaction on top of the current head state without sending it.
+    /// Prints the resulting logs, receipts and balance changes. Transactions created with `--dry`
+    /// can be simulated this way.
+    Simulate {
+        /// The transaction as hex string.
+        raw_tx: String,
+    },
+
+    /* Multisig transactions */
+    /// Creates a partially signed transaction sending funds from the multisig address of the given
+    /// public keys to a basic `recipient`. The output is passed to the co-signers, who add their
+    /// commitments with `multisig-commit` and then their partial signatures with `multisig-sign`.
+    MultisigCreate {
+        /// Recipient for this transaction. This must be a basic account.
+        recipient: Address,
+
+        /// The amount of NIM to be sent.
+        value: Coin,
+
+        /// The public key of an owner of the multisig address. Must be given once for every owner.
+        #[clap(short = 'k', long = "public-key", required = true)]
+        public_keys: Vec<Ed25519PublicKey>,
+
+        /// The number of owners required to sign transactions of the multisig address.
+        #[clap(short, long)]
+        min_signatures: NonZeroU8,
+
+        /// The associated transaction fee to be paid. If absent it defaults to 0 NIM.
+        #[clap(short, long, default_value = "0")]
+        fee: Coin,
+
+        /// The block height from which on the transaction could be applied. The maximum amount of blocks the transaction is valid for
+        /// is specified in `TRANSACTION_VALIDITY_WINDOW`.
+        /// If absent it defaults to the current block height at time of processing.
+        #[clap(short, long, default_value_t)]
+        validity_start_height: ValidityStartHeight,
+    },
+
+    /// Adds the commitments of a co-signer to a partially signed transaction. The secret part of
+    /// the commitments stays in the memory of the RPC server until the wallet signs the
+    /// transaction or is locked.
+    MultisigCommit {
+        /// The wallet of the co-signer. It must be unlocked prior to this action.
+        wallet: Address,
+
+        /// The partially signed transaction as hex string.
+        partially_signed_transaction: String,
+    },
+
+    /// Adds the partial signature of a co-signer to a partially signed transaction. Requires the
+    /// commitments of all co-signers to be present.
+    MultisigSign {
+        /// The wallet of the co-signer. It must be unlocked and have added its commitments before.
+        wallet: Address,
+
+        /// The partially signed transaction as hex string.
+        partially_signed_transaction: String,
+    },
+
+    /// Combines the commitments and partial signatures of partially signed versions of the same
+    /// transaction.
+    MultisigCombine {
+        /// The partially signed transactions as hex strings.
+        #[clap(required = true)]
+        partially_signed_transactions: Vec<String>,
+    },
+
+    /// Aggregates the partial signatures of a partially signed transaction and sends the signed
+    /// transaction to the network.
+    MultisigFinalize {
+        /// The partially signed transaction as hex string.
+        partially_signed_transaction: String,
+
+        /// Don't actually send the transaction, but output the transaction as hex string.
+        #[clap(long)]
+        dry: bool,
+    },
 }
 
 impl TransactionCommand {
@@ -828,6 +906,75 @@ impl HandleSubcommand for TransactionCommand {
                     .await?;
                 println!("{tx:#?}");
             }
+            TransactionCommand::Simulate { raw_tx } => {
+                let simulation = client.consensus.simulate_transaction(raw_tx).await?;
+                println!("{simulation:#?}");
+            }
+            TransactionCommand::MultisigCreate {
+                recipient,
+                value,
+                public_keys,
+                min_signatures,
+                fee,
+                validity_start_height,
+            } => {
+                let pst = client
+                    .consensus
+                    .create_multisig_transaction(
+                        public_keys,
+                        min_signatures,
+                        recipient,
+                        value,
+                        fee,
+                        validity_start_height,
+                    )
+                    .await?;
+                println!("{pst:#?}");
+            }
+            TransactionCommand::MultisigCommit {
+                wallet,
+                partially_signed_transaction,
+            } => {
+                let pst = client
+                    .consensus
+                    .add_multisig_commitments(wallet, partially_signed_transaction)
+                    .await?;
+                println!("{pst:#?}");
+            }
+            TransactionCommand::MultisigSign {
+                wallet,
+                partially_signed_transaction,
+            } => {
+                let pst = client
+                    .consensus
+                    .sign_multisig_transaction(wallet, partially_signed_transaction)
+                    .await?;
+                println!("{pst:#?}");
+            }
+            TransactionCommand::MultisigCombine {
+                partially_signed_transactions,
+            } => {
+                let pst = client
+                    .consensus
+                    .combine_multisig_transactions(partially_signed_transactions)
+                    .await?;
+                println!("{pst:#?}");
+            }
+            TransactionCommand::MultisigFinalize {
+                partially_signed_transaction,
+                dry,
+            } => {
+                let tx = client
+                    .consensus
+                    .finalize_multisig_transaction(partially_signed_transaction)
+                    .await?;
+                if dry {
+                    println!("{tx:#?}");
+                } else {
+                    let txid = client.consensus.send_raw_transaction(tx.data).await?;
+                    println!("{txid:#?}");
+                }
+            }
         }
         Ok(client)
     }
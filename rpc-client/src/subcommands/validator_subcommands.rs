@@ -19,6 +19,15 @@ pub enum ValidatorCommand {
         automatic_reactivate: bool,
     },
 
+    /// Returns the extra data included in the micro blocks produced by the local validator.
+    ExtraData {},
+
+    /// Changes the extra data included in the micro blocks produced by the local validator.
+    SetExtraData {
+        /// The extra data. Occurrences of `{version}` are replaced by the client version.
+        extra_data: String,
+    },
+
     /// Returns the address of the local validator.
     ValidatorAddress {},
 
@@ -152,6 +161,15 @@ impl HandleSubcommand for ValidatorCommand {
                 println!("Auto reactivate set to {automatic_reactivate}");
             }
 
+            ValidatorCommand::ExtraData {} => {
+                println!("{:#?}", client.validator.get_extra_data().await?);
+            }
+
+            ValidatorCommand::SetExtraData { extra_data } => {
+                client.validator.set_extra_data(extra_data.clone()).await?;
+                println!("Extra data set to {extra_data}");
+            }
+
             ValidatorCommand::CreateNewValidator {
                 sender_wallet,
                 validator_wallet,
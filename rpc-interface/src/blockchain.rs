@@ -4,8 +4,9 @@ use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 
 use crate::types::{
-    Account, Block, BlockLog, BlockchainState, ExecutedTransaction, Inherent, LogType,
-    PenalizedSlots, RPCData, RPCResult, Slot, Staker, Validator,
+    Account, AddressHistoryPage, AddressHistoryQuery, Block, BlockLog, BlockchainState,
+    ExecutedTransaction, Inherent, LogType, PenalizedSlots, RPCData, RPCResult, Slot, SlotSchedule,
+    Staker, Validator, ValidatorRewards,
 };
 
 #[nimiq_jsonrpc_derive::proxy(name = "BlockchainProxy", rename_all = "camelCase")]
@@ -110,10 +111,33 @@ pub trait BlockchainInterface {
         max: Option<u16>,
     ) -> RPCResult<Vec<ExecutedTransaction>, (), Self::Error>;
 
+    /// Returns a page of the hashes of the transactions for a given address, from the most recent
+    /// to the least recent one. The query can start after a given transaction hash or block
+    /// number and filter the transactions by direction, type, block range and minimum value.
+    /// The returned cursor is used to fetch the next page.
+    async fn get_transaction_hashes_by_address_paginated(
+        &mut self,
+        address: Address,
+        query: AddressHistoryQuery,
+    ) -> RPCResult<AddressHistoryPage<Blake2bHash>, (), Self::Error>;
+
+    /// Returns a page of the transactions for a given address, from the most recent to the least
+    /// recent one. The query can start after a given transaction hash or block number and filter
+    /// the transactions by direction, type, block range and minimum value. The returned cursor is
+    /// used to fetch the next page.
+    async fn get_transactions_by_address_paginated(
+        &mut self,
+        address: Address,
+        query: AddressHistoryQuery,
+    ) -> RPCResult<AddressHistoryPage<ExecutedTransaction>, (), Self::Error>;
+
     /// Tries to fetch the account at the given address.
+    /// If a block number is given, the state after that block is returned. This requires the
+    /// node to keep an accounts archive.
     async fn get_account_by_address(
         &mut self,
         address: Address,
+        block_number: Option<u32>,
     ) -> RPCResult<Account, BlockchainState, Self::Error>;
 
     /// Fetches all accounts in the accounts tree.
@@ -126,6 +150,16 @@ pub trait BlockchainInterface {
         &mut self,
     ) -> RPCResult<Vec<Validator>, BlockchainState, Self::Error>;
 
+    /// Returns the upcoming slots of the validator with the given address, from the next block to
+    /// the last micro block of its epoch, and the blocks it is known to propose, with the expected
+    /// timestamps of the blocks. If the head is an election block, this is the epoch elected by it.
+    /// The blocks whose proposers are not known yet and the first block of the next epoch, whose
+    /// slots are not known yet, are reported as unknown.
+    async fn get_validator_slot_schedule(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<SlotSchedule, BlockchainState, Self::Error>;
+
     /// Returns information about the currently penalized slots. This includes slots that lost rewards
     /// and that were disabled.
     async fn get_current_penalized_slots(
@@ -139,9 +173,12 @@ pub trait BlockchainInterface {
     ) -> RPCResult<PenalizedSlots, BlockchainState, Self::Error>;
 
     /// Tries to fetch a validator information given its address.
+    /// If a block number is given, the state after that block is returned. This requires the
+    /// node to keep an accounts archive.
     async fn get_validator_by_address(
         &mut self,
         address: Address,
+        block_number: Option<u32>,
     ) -> RPCResult<Validator, BlockchainState, Self::Error>;
 
     /// Fetches all validators in the staking contract.
@@ -158,11 +195,26 @@ pub trait BlockchainInterface {
     ) -> RPCResult<Vec<Staker>, BlockchainState, Self::Error>;
 
     /// Tries to fetch a staker information given its address.
+    /// If a block number is given, the state after that block is returned. This requires the
+    /// node to keep an accounts archive.
     async fn get_staker_by_address(
         &mut self,
         address: Address,
+        block_number: Option<u32>,
     ) -> RPCResult<Staker, BlockchainState, Self::Error>;
 
+    /// Returns the rewards the validator with the given address received for the slots it held
+    /// during the given epoch, the penalties and jail events that reduced them and their split
+    /// between its stakers pro-rata to their active balance at the election block.
+    /// This requires the node to keep the history and an accounts archive.
+    /// IMPORTANT: This operation iterates over all historic transactions of the epoch and all
+    /// stakers of the staking contract and thus is extremely computationally expensive.
+    async fn get_validator_rewards_by_epoch(
+        &mut self,
+        address: Address,
+        epoch_number: u32,
+    ) -> RPCResult<ValidatorRewards, (), Self::Error>;
+
     /// Subscribes to new block events (retrieves the full block).
     #[stream]
     async fn subscribe_for_head_block(
@@ -1,10 +1,12 @@
+use std::num::NonZeroU8;
+
 use async_trait::async_trait;
 use nimiq_hash::Blake2bHash;
-use nimiq_keys::Address;
+use nimiq_keys::{Address, Ed25519PublicKey};
 use nimiq_primitives::coin::Coin;
 use nimiq_transaction::account::htlc_contract::{AnyHash, PreImage};
 
-use crate::types::{RPCResult, Transaction, ValidityStartHeight};
+use crate::types::{RPCResult, SimulatedTransaction, Transaction, ValidityStartHeight};
 
 #[nimiq_jsonrpc_derive::proxy(name = "ConsensusProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -28,6 +30,14 @@ pub trait ConsensusInterface {
         raw_tx: String,
     ) -> RPCResult<Blake2bHash, (), Self::Error>;
 
+    /// Simulates the given serialized transaction on top of the current head state, as if it
+    /// was included in the next block. Neither the accounts nor the mempool are changed.
+    /// The node can't push blocks while a simulation is running.
+    async fn simulate_transaction(
+        &mut self,
+        raw_tx: String,
+    ) -> RPCResult<SimulatedTransaction, (), Self::Error>;
+
     /// Returns a serialized basic transaction.
     async fn create_basic_transaction(
         &mut self,
@@ -541,4 +551,62 @@ pub trait ConsensusInterface {
         value: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<Blake2bHash, (), Self::Error>;
+
+    /// Returns a serialized partially signed basic transaction sent from the multisig address of
+    /// the given public keys, which requires `min_signatures` of them to sign. The partially
+    /// signed transaction is passed between the co-signers, who first add their commitments and
+    /// then their partial signatures.
+    async fn create_multisig_transaction(
+        &mut self,
+        public_keys: Vec<Ed25519PublicKey>,
+        min_signatures: NonZeroU8,
+        recipient: Address,
+        value: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Adds the commitments of the given unlocked wallet to a serialized partially signed
+    /// transaction and returns it. The secret nonces are kept in memory until the wallet signs
+    /// the transaction or is locked.
+    async fn add_multisig_commitments(
+        &mut self,
+        wallet: Address,
+        partially_signed_transaction: String,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Adds the partial signature of the given unlocked wallet to a serialized partially signed
+    /// transaction and returns it. Requires the commitments of all co-signers to be present.
+    async fn sign_multisig_transaction(
+        &mut self,
+        wallet: Address,
+        partially_signed_transaction: String,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Combines the commitments and partial signatures of serialized partially signed versions
+    /// of the same transaction.
+    async fn combine_multisig_transactions(
+        &mut self,
+        partially_signed_transactions: Vec<String>,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Aggregates the partial signatures of a serialized partially signed transaction and returns
+    /// the serialized signed transaction, which can be sent with `sendRawTransaction`.
+    async fn finalize_multisig_transaction(
+        &mut self,
+        partially_signed_transaction: String,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    /// Returns the batches in `startBatch..startBatch + count` that may contain transactions or
+    /// inherents of any of the given addresses, according to the compact address filters served by
+    /// `minPeers` history nodes (1 by default). The addresses are not revealed to the peers. The
+    /// result may contain batches that don't touch any of the addresses, and it is only complete if
+    /// at least one of the peers is honest.
+    async fn get_batches_by_addresses(
+        &mut self,
+        addresses: Vec<Address>,
+        start_batch: u32,
+        count: u32,
+        min_peers: Option<usize>,
+    ) -> RPCResult<Vec<u32>, (), Self::Error>;
 }
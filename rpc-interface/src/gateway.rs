@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use crate::types::RPCResult;
+
+#[nimiq_jsonrpc_derive::proxy(name = "GatewayProxy", rename_all = "camelCase")]
+#[async_trait]
+pub trait GatewayInterface {
+    type Error;
+
+    /// Proves to the RPC gateway in front of this server that it is the server the gateway was
+    /// configured with, by answering the given challenge with the shared handshake key.
+    async fn gateway_handshake(&mut self, challenge: String) -> RPCResult<String, (), Self::Error>;
+}
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use nimiq_hash::Blake2bHash;
 use nimiq_transaction::Transaction;
 
-use crate::types::{HashOrTx, MempoolInfo, RPCResult};
+use crate::types::{FeeEstimate, HashOrTx, MempoolInfo, RPCResult};
 
 #[nimiq_jsonrpc_derive::proxy(name = "MempoolProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -31,6 +31,13 @@ pub trait MempoolInterface {
     /// Obtains the minimum fee per byte as per mempool configuration.
     async fn get_min_fee_per_byte(&mut self) -> RPCResult<f64, (), Self::Error>;
 
+    /// Suggests the fee per byte a transaction needs to pay to be included within the given
+    /// number of blocks, based on the fees paid in recent blocks and the backlog of the mempool.
+    async fn get_fee_estimate(
+        &mut self,
+        target_blocks: u32,
+    ) -> RPCResult<FeeEstimate, (), Self::Error>;
+
     /// Tries to obtain the given transaction (using its hash) from the mempool.
     async fn get_transaction_from_mempool(
         &mut self,
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 
-use crate::types::RPCResult;
+use crate::types::{BannedPeer, RPCResult};
 
 #[nimiq_jsonrpc_derive::proxy(name = "NetworkProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -15,4 +15,10 @@ pub trait NetworkInterface {
 
     /// Returns a list with the IDs of all our peers.
     async fn get_peer_list(&mut self) -> RPCResult<Vec<String>, (), Self::Error>;
+
+    /// Returns the peers that were penalized for misbehaving, together with their ban status.
+    async fn get_banned_peers(&mut self) -> RPCResult<Vec<BannedPeer>, (), Self::Error>;
+
+    /// Clears the ban list, such that banned peers can connect again.
+    async fn clear_banned_peers(&mut self) -> RPCResult<(), (), Self::Error>;
 }
@@ -8,8 +8,17 @@ use std::{
 };
 
 use clap::ValueEnum;
-use nimiq_account::{BlockLog as BBlockLog, Log, TransactionLog};
+use nimiq_account::{
+    AccountReceipt, BlockLog as BBlockLog, Log, OperationReceipt,
+    SimulatedTransaction as BSimulatedTransaction, TransactionLog,
+};
 use nimiq_block::{MicroJustification, MultiSignature};
+use nimiq_blockchain::{
+    AddressHistoryCursor, AddressHistoryFilter, AddressTransactionType as BAddressTransactionType,
+    BatchReward as BBatchReward, ScheduledBlock as BScheduledBlock, SlotSchedule as BSlotSchedule,
+    StakerReward as BStakerReward, TransactionDirection as BTransactionDirection,
+    ValidatorEpochRewards,
+};
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError};
 use nimiq_blockchain_proxy::BlockchainReadProxy;
 use nimiq_bls::CompressedPublicKey;
@@ -17,7 +26,8 @@ use nimiq_collections::BitSet;
 use nimiq_hash::{Blake2bHash, Blake2sHash, Hash};
 use nimiq_keys::{Address, Ed25519PublicKey, Ed25519Signature, PrivateKey};
 use nimiq_primitives::{
-    coin::Coin, networks::NetworkId, policy::Policy, slots_allocation::Validators,
+    account::FailReason, coin::Coin, networks::NetworkId, policy::Policy,
+    slots_allocation::Validators,
 };
 use nimiq_serde::Serialize as NimiqSerialize;
 use nimiq_transaction::{
@@ -416,6 +426,74 @@ impl Slots {
     }
 }
 
+/// The upcoming slots of a validator, from the block following the head to the last micro block of
+/// the epoch of that block. The parts of the schedule that can't be determined yet are marked as
+/// unknown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotSchedule {
+    pub validator: Address,
+    pub epoch_number: u32,
+    pub first_slot_number: u16,
+    pub num_slots: u16,
+    pub first_block_number: u32,
+    /// The expected timestamp of the first block, assuming no delays.
+    pub first_block_timestamp: u64,
+    /// The last micro block of the epoch. The election block is not included.
+    pub last_block_number: u32,
+    /// The expected timestamp of the last block, assuming no delays.
+    pub last_block_timestamp: u64,
+    /// The blocks the validator is known to propose, unless it skips them. The proposer of a
+    /// block is selected using the VRF seed of its predecessor, so only the proposer of the next
+    /// block is known in advance.
+    pub proposer_blocks: Vec<ScheduledBlock>,
+    /// The first block whose proposer is unknown. The proposers of all blocks from this one to the
+    /// last block are unknown. `None` if the proposers of all blocks are known.
+    pub proposers_unknown_from: Option<ScheduledBlock>,
+    /// The first block of the following epoch. The slots of the validator from this block on are
+    /// unknown until the election block of this epoch is final.
+    pub slots_unknown_from: ScheduledBlock,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledBlock {
+    pub block_number: u32,
+    /// The expected timestamp of the block, assuming no delays.
+    pub timestamp: u64,
+}
+
+impl From<BScheduledBlock> for ScheduledBlock {
+    fn from(block: BScheduledBlock) -> Self {
+        ScheduledBlock {
+            block_number: block.block_number,
+            timestamp: block.timestamp,
+        }
+    }
+}
+
+impl From<BSlotSchedule> for SlotSchedule {
+    fn from(schedule: BSlotSchedule) -> Self {
+        SlotSchedule {
+            validator: schedule.validator_address,
+            epoch_number: schedule.epoch_number,
+            first_slot_number: schedule.slots.start,
+            num_slots: schedule.slots.len() as u16,
+            first_block_number: schedule.first_block.block_number,
+            first_block_timestamp: schedule.first_block.timestamp,
+            last_block_number: schedule.last_block.block_number,
+            last_block_timestamp: schedule.last_block.timestamp,
+            proposer_blocks: schedule
+                .proposer_blocks
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            proposers_unknown_from: schedule.proposers_unknown_from.map(Into::into),
+            slots_unknown_from: schedule.slots_unknown_from.into(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PenalizedSlots {
@@ -793,6 +871,71 @@ pub struct ReturnAccount {
     pub private_key: PrivateKey,
 }
 
+/// The type of an imported mnemonic.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MnemonicType {
+    /// A legacy mnemonic, which encodes a single private key.
+    Legacy,
+    /// A BIP39 mnemonic, from which accounts are derived.
+    Bip39,
+}
+
+/// An account that was derived from a mnemonic.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnDerivedAccount {
+    /// The index of the account.
+    pub index: u32,
+    /// The derivation path of the account. Not set for legacy mnemonics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// The address of the account.
+    pub address: Address,
+}
+
+/// A mnemonic that was imported into the wallet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnMnemonicWallet {
+    /// The ID of the mnemonic wallet, which is the address of its account with index 0.
+    pub wallet_id: Address,
+    /// The type of the mnemonic.
+    pub mnemonic_type: MnemonicType,
+    /// The path that accounts are derived at. Not set for legacy mnemonics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
+    /// The accounts that have been derived and imported.
+    pub accounts: Vec<ReturnDerivedAccount>,
+}
+
+/// Restrictions on the transactions a wallet account may sign. Restrictions that are not set
+/// don't apply.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningPolicy {
+    /// The maximum value of a single transaction.
+    pub max_value: Option<Coin>,
+    /// The recipients the account may send to. For transactions to the staking contract, these
+    /// are the stakers and validators funds may be staked for. Transactions that don't stake any
+    /// funds, like updates of stakers, aren't restricted. Contract creations are rejected, as the
+    /// address of the new contract can't be allowed upfront.
+    pub allowed_recipients: Option<Vec<Address>>,
+    /// The types of transactions the account may sign.
+    pub allowed_transaction_types: Option<Vec<TransactionKind>>,
+}
+
+/// The type of a transaction, as far as signing policies are concerned. Transactions are typed by
+/// the contract they interact with, either as sender or as recipient.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionKind {
+    Basic,
+    Vesting,
+    Htlc,
+    Staking,
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
@@ -1082,6 +1225,208 @@ pub enum LogType {
     FailedTransaction,
 }
 
+/// The direction of a transaction from the point of view of an address.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransactionDirection {
+    Sent,
+    Received,
+}
+
+impl From<TransactionDirection> for BTransactionDirection {
+    fn from(direction: TransactionDirection) -> Self {
+        match direction {
+            TransactionDirection::Sent => BTransactionDirection::Sent,
+            TransactionDirection::Received => BTransactionDirection::Received,
+        }
+    }
+}
+
+/// The type of a transaction in the history of an address. Transactions are classified by the
+/// contract they interact with, if any.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum AddressTransactionType {
+    Basic,
+    Vesting,
+    Htlc,
+    Staking,
+    Reward,
+}
+
+impl From<AddressTransactionType> for BAddressTransactionType {
+    fn from(ty: AddressTransactionType) -> Self {
+        match ty {
+            AddressTransactionType::Basic => BAddressTransactionType::Basic,
+            AddressTransactionType::Vesting => BAddressTransactionType::Vesting,
+            AddressTransactionType::Htlc => BAddressTransactionType::Htlc,
+            AddressTransactionType::Staking => BAddressTransactionType::Staking,
+            AddressTransactionType::Reward => BAddressTransactionType::Reward,
+        }
+    }
+}
+
+/// Selects a page of the transactions of an address. Transactions are returned from the most
+/// recent to the least recent one and need to meet all of the given criteria.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AddressHistoryQuery {
+    /// The maximum number of transactions to return. Defaults to 500.
+    pub max: Option<u16>,
+    /// Start after the transaction with this hash, usually the `nextCursor` of the previous page.
+    pub start_after_hash: Option<Blake2bHash>,
+    /// Start with the transactions of the blocks preceding this block number.
+    pub start_after_block_number: Option<u32>,
+    /// Only return sent or only return received transactions.
+    pub direction: Option<TransactionDirection>,
+    /// Only return transactions of one of these types. All types are returned if empty.
+    pub types: Vec<AddressTransactionType>,
+    /// Only return transactions included in this block or a later one.
+    pub min_block_number: Option<u32>,
+    /// Only return transactions included in this block or an earlier one.
+    pub max_block_number: Option<u32>,
+    /// Only return transactions with at least this value (in Luna).
+    pub min_value: Option<Coin>,
+}
+
+impl AddressHistoryQuery {
+    /// Returns the position after which the page starts. Fails if both a transaction hash and a
+    /// block number are given.
+    pub fn start_after(&self) -> Result<Option<AddressHistoryCursor>, &'static str> {
+        match (&self.start_after_hash, self.start_after_block_number) {
+            (Some(_), Some(_)) => {
+                Err("Only one of startAfterHash and startAfterBlockNumber can be given")
+            }
+            (Some(hash), None) => Ok(Some(AddressHistoryCursor::Transaction(hash.clone()))),
+            (None, Some(block_number)) => Ok(Some(AddressHistoryCursor::BlockNumber(block_number))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Returns the filter the transactions of the page need to pass.
+    pub fn filter(&self) -> AddressHistoryFilter {
+        AddressHistoryFilter {
+            direction: self.direction.map(Into::into),
+            types: self.types.iter().copied().map(Into::into).collect(),
+            min_block_number: self.min_block_number,
+            max_block_number: self.max_block_number,
+            min_value: self.min_value,
+        }
+    }
+}
+
+/// A page of the transactions of an address.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressHistoryPage<T> {
+    /// The transactions (or their hashes), from the most recent to the least recent one.
+    pub transactions: Vec<T>,
+    /// The hash to pass as `startAfterHash` to fetch the next page. `None` if there are no more
+    /// transactions. As the number of transactions scanned per page is limited, a page may
+    /// contain fewer transactions than requested even though more follow.
+    pub next_cursor: Option<Blake2bHash>,
+}
+
+/// The rewards a validator received for the slots it held during an epoch and their split
+/// between its stakers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorRewards {
+    pub validator_address: Address,
+    pub epoch_number: u32,
+    /// The election block after which the stakes were taken.
+    pub election_block_number: u32,
+    pub first_slot_number: u16,
+    pub num_slots: u16,
+    pub batches: Vec<BatchReward>,
+    pub total_reward: Coin,
+    pub penalties: Vec<ValidatorPunishment>,
+    pub jails: Vec<ValidatorPunishment>,
+    pub total_stake: Coin,
+    pub deposit: Coin,
+    pub stakers: Vec<StakerReward>,
+}
+
+impl From<ValidatorEpochRewards> for ValidatorRewards {
+    fn from(rewards: ValidatorEpochRewards) -> Self {
+        ValidatorRewards {
+            validator_address: rewards.validator_address,
+            epoch_number: rewards.epoch_number,
+            election_block_number: rewards.election_block_number,
+            first_slot_number: rewards.slots.start,
+            num_slots: rewards.slots.len() as u16,
+            batches: rewards.batches.into_iter().map(Into::into).collect(),
+            total_reward: rewards.total_reward,
+            penalties: rewards
+                .penalties
+                .into_iter()
+                .map(|ev| ValidatorPunishment {
+                    offense_event_block: ev.offense_event_block,
+                    first_slot_number: ev.slot,
+                    num_slots: 1,
+                })
+                .collect(),
+            jails: rewards
+                .jails
+                .into_iter()
+                .map(|ev| ValidatorPunishment {
+                    offense_event_block: ev.offense_event_block,
+                    first_slot_number: ev.slots.start,
+                    num_slots: ev.slots.len() as u16,
+                })
+                .collect(),
+            total_stake: rewards.total_stake,
+            deposit: rewards.deposit,
+            stakers: rewards.stakers.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReward {
+    pub batch_number: u32,
+    pub payout_block_number: u32,
+    pub reward: Coin,
+}
+
+impl From<BBatchReward> for BatchReward {
+    fn from(batch: BBatchReward) -> Self {
+        BatchReward {
+            batch_number: batch.batch_number,
+            payout_block_number: batch.payout_block_number,
+            reward: batch.reward,
+        }
+    }
+}
+
+/// A penalty or jail event that reduced the rewards of a validator.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorPunishment {
+    pub offense_event_block: u32,
+    pub first_slot_number: u16,
+    pub num_slots: u16,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakerReward {
+    pub address: Address,
+    pub active_balance: Coin,
+    pub reward: Coin,
+}
+
+impl From<BStakerReward> for StakerReward {
+    fn from(staker: BStakerReward) -> Self {
+        StakerReward {
+            address: staker.address,
+            active_balance: staker.active_balance,
+            reward: staker.reward,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", tag = "type")]
 pub enum BlockLog {
@@ -1155,6 +1500,59 @@ pub fn is_of_log_type_and_related_to_addresses(
     matches_log_types && matches_addresses
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceChange {
+    pub address: Address,
+    pub balance_before: Coin,
+    pub balance_after: Coin,
+}
+
+/// The outcome of a simulated transaction. The receipts are the hex encoded account receipts
+/// that would be stored to revert the transaction.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedTransaction {
+    pub hash: Blake2bHash,
+    pub succeeded: bool,
+    pub fail_reason: Option<FailReason>,
+    pub logs: Vec<Log>,
+    pub sender_receipt: Option<String>,
+    pub recipient_receipt: Option<String>,
+    pub pruned_account: Option<String>,
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+impl SimulatedTransaction {
+    pub fn from_simulated_transaction(simulation: BSimulatedTransaction) -> Self {
+        let (receipt, fail_reason) = match simulation.receipt {
+            OperationReceipt::Ok(receipt) => (receipt, None),
+            OperationReceipt::Err(receipt, fail_reason) => (receipt, Some(fail_reason)),
+        };
+        let to_hex =
+            |receipt: Option<AccountReceipt>| receipt.map(|receipt| hex::encode(receipt.0));
+
+        SimulatedTransaction {
+            hash: simulation.log.tx_hash,
+            succeeded: fail_reason.is_none(),
+            fail_reason,
+            logs: simulation.log.logs,
+            sender_receipt: to_hex(receipt.sender_receipt),
+            recipient_receipt: to_hex(receipt.recipient_receipt),
+            pruned_account: to_hex(receipt.pruned_account),
+            balance_changes: simulation
+                .balance_changes
+                .into_iter()
+                .map(|change| BalanceChange {
+                    address: change.address,
+                    balance_before: change.balance_before,
+                    balance_after: change.balance_after,
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ZKPState {
@@ -1356,3 +1754,32 @@ impl MempoolInfo {
         info
     }
 }
+
+/// A suggested fee for a transaction to be included within a target number of blocks.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimate {
+    pub target_blocks: u32,
+    /// The fee per byte a transaction needs to exceed to be included within the target blocks.
+    pub fee_per_byte: f64,
+    /// The size (in bytes) of the mempool transactions that are expected to be included ahead of
+    /// a transaction paying the suggested fee.
+    pub backlog_size: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// A peer that was penalized for misbehaving
+pub struct BannedPeer {
+    pub peer_id: String,
+    /// The reason of the most recent offense: `invalidBlock`, `invalidMacroChain`, `invalidChunk`
+    /// or `invalidResponse`.
+    pub reason: String,
+    /// The accumulated ban score.
+    pub score: u32,
+    /// Time of the most recent offense in seconds since unix epoch.
+    pub last_offense: u64,
+    /// Time in seconds since unix epoch until which the peer is banned.
+    /// `None` if the ban score didn't reach the threshold.
+    pub banned_until: Option<u64>,
+}
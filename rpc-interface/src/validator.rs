@@ -23,6 +23,14 @@ pub trait ValidatorInterface {
         automatic_reactivate: bool,
     ) -> RPCResult<(), (), Self::Error>;
 
+    /// Returns the extra data included in the micro blocks produced by our validator.
+    async fn get_extra_data(&mut self) -> RPCResult<String, (), Self::Error>;
+
+    /// Updates the extra data included in the micro blocks produced by our validator.
+    /// Occurrences of `{version}` are replaced by the client version. The resulting extra data
+    /// must not exceed 32 bytes.
+    async fn set_extra_data(&mut self, extra_data: String) -> RPCResult<(), (), Self::Error>;
+
     /// Returns if our validator is currently elected.
     async fn is_validator_elected(&mut self) -> RPCResult<bool, (), Self::Error>;
 
@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 use nimiq_keys::{Address, Ed25519PublicKey, Ed25519Signature};
 
-use crate::types::{RPCResult, ReturnAccount, ReturnSignature};
+use crate::types::{
+    RPCResult, ReturnAccount, ReturnDerivedAccount, ReturnMnemonicWallet, ReturnSignature,
+    SigningPolicy,
+};
 
 #[nimiq_jsonrpc_derive::proxy(name = "WalletProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -32,7 +35,8 @@ pub trait WalletInterface {
         passphrase: Option<String>,
     ) -> RPCResult<ReturnAccount, (), Self::Error>;
 
-    /// Unlocks the account.
+    /// Unlocks the account. If a duration in seconds is given, the account is locked again
+    /// automatically once it elapsed.
     async fn unlock_account(
         &mut self,
         address: Address,
@@ -45,6 +49,22 @@ pub trait WalletInterface {
     #[allow(clippy::wrong_self_convention)]
     async fn is_account_unlocked(&mut self, address: Address) -> RPCResult<bool, (), Self::Error>;
 
+    /// Sets the signing policy of an account, which restricts the transactions that are signed
+    /// with it. Passing no policy removes the current one. The passphrase of the account is
+    /// required, such that the policy can't be lifted by anyone who can access the RPC server.
+    async fn set_signing_policy(
+        &mut self,
+        address: Address,
+        policy: Option<SigningPolicy>,
+        passphrase: Option<String>,
+    ) -> RPCResult<(), (), Self::Error>;
+
+    /// Returns the signing policy of an account, if it has one.
+    async fn get_signing_policy(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<Option<SigningPolicy>, (), Self::Error>;
+
     async fn sign(
         &mut self,
         message: String,
@@ -61,4 +81,45 @@ pub trait WalletInterface {
         signature: Ed25519Signature,
         is_hex: bool,
     ) -> RPCResult<bool, (), Self::Error>;
+
+    /// Imports a BIP39 or legacy mnemonic of 24 words and locks it with the passphrase.
+    /// The account with index 0 is derived and imported as well. BIP39 accounts are derived
+    /// at `{derivationPath}/{index}'`, which defaults to `m/44'/242'/0'/{index}'`.
+    /// If an encryption passphrase is given, `mnemonic` is expected to be an encrypted
+    /// mnemonic as returned by `exportMnemonic`. Importing a mnemonic with the same derivation
+    /// path again fails, such that the accounts derived from it are kept.
+    async fn import_mnemonic(
+        &mut self,
+        mnemonic: String,
+        derivation_path: Option<String>,
+        passphrase: Option<String>,
+        encryption_passphrase: Option<String>,
+    ) -> RPCResult<ReturnMnemonicWallet, (), Self::Error>;
+
+    /// Derives the account with the given index from an imported mnemonic and imports it,
+    /// locked with the passphrase of the mnemonic.
+    async fn derive_account(
+        &mut self,
+        wallet_id: Address,
+        index: u32,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnDerivedAccount, (), Self::Error>;
+
+    /// Returns the imported mnemonics together with the accounts derived from them.
+    async fn list_mnemonics(&mut self) -> RPCResult<Vec<ReturnMnemonicWallet>, (), Self::Error>;
+
+    /// Returns the accounts that have been derived from an imported mnemonic.
+    async fn list_derived_accounts(
+        &mut self,
+        wallet_id: Address,
+    ) -> RPCResult<Vec<ReturnDerivedAccount>, (), Self::Error>;
+
+    /// Exports an imported mnemonic. If an encryption passphrase is given, the mnemonic is
+    /// returned encrypted with it in hexadecimal format, otherwise the words are returned.
+    async fn export_mnemonic(
+        &mut self,
+        wallet_id: Address,
+        passphrase: Option<String>,
+        encryption_passphrase: Option<String>,
+    ) -> RPCResult<String, (), Self::Error>;
 }
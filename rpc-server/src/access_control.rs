@@ -0,0 +1,298 @@
+use std::{collections::HashSet, fs::File, io::BufReader, net::IpAddr, path::Path, sync::Arc};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use ipnet::IpNet;
+use nimiq_hash::{Blake2bHash, Hash};
+use subtle::ConstantTimeEq;
+use tokio_rustls::{
+    rustls::{self, pki_types::CertificateDer, ServerConfig},
+    TlsAcceptor,
+};
+
+use crate::{error::Error, rate_limiting::RateLimitConfig};
+
+/// A list of IP networks (in CIDR notation) that are allowed to connect to the RPC server.
+///
+/// Plain IP addresses are accepted as well and are treated as single-host networks.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IpAllowList {
+    networks: Vec<IpNet>,
+}
+
+impl IpAllowList {
+    pub fn new(networks: Vec<IpNet>) -> Self {
+        Self { networks }
+    }
+
+    /// Parses a list of entries, each being either a network in CIDR notation (e.g. `10.0.0.0/8`)
+    /// or a single IP address (e.g. `::1`).
+    pub fn parse<S: AsRef<str>>(entries: &[S]) -> Result<Self, Error> {
+        let networks = entries
+            .iter()
+            .map(|entry| {
+                let entry = entry.as_ref().trim();
+                entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| Error::InvalidIpNetwork(entry.to_owned()))
+            })
+            .collect::<Result<Vec<IpNet>, Error>>()?;
+        Ok(Self::new(networks))
+    }
+
+    pub fn networks(&self) -> &[IpNet] {
+        &self.networks
+    }
+
+    /// Checks whether the given IP address is contained in any of the allowed networks.
+    /// IPv4-mapped IPv6 addresses are matched against the IPv4 networks.
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.networks.iter().any(|network| network.contains(&ip))
+    }
+}
+
+/// The origins that browsers are allowed to send cross-origin requests from.
+///
+/// An entry of `*` allows any origin.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CorsPolicy {
+    origins: Vec<String>,
+    any_origin: bool,
+}
+
+impl CorsPolicy {
+    /// The methods that are announced in responses to preflight requests.
+    pub const ALLOWED_METHODS: &'static str = "GET, POST, OPTIONS";
+    /// The request headers that are announced in responses to preflight requests.
+    pub const ALLOWED_HEADERS: &'static str = "authorization, content-type";
+    /// How long browsers may cache the result of a preflight request, in seconds.
+    pub const MAX_AGE: &'static str = "600";
+
+    pub fn new<I: IntoIterator<Item = S>, S: AsRef<str>>(origins: I) -> Self {
+        let mut any_origin = false;
+        let origins = origins
+            .into_iter()
+            .filter_map(|origin| {
+                let origin = Self::normalize(origin.as_ref());
+                if origin == "*" {
+                    any_origin = true;
+                    None
+                } else {
+                    Some(origin)
+                }
+            })
+            .collect();
+
+        Self {
+            origins,
+            any_origin,
+        }
+    }
+
+    /// Checks whether requests from the given origin (the value of the `Origin` header) are allowed.
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        if self.any_origin {
+            return true;
+        }
+        self.origins.contains(&Self::normalize(origin))
+    }
+
+    fn normalize(origin: &str) -> String {
+        origin.trim().trim_end_matches('/').to_ascii_lowercase()
+    }
+}
+
+/// The certificate chain and private key used to terminate TLS connections to the RPC server.
+#[derive(Clone)]
+pub struct TlsIdentity {
+    acceptor: TlsAcceptor,
+}
+
+impl TlsIdentity {
+    /// Creates a TLS identity from DER-encoded certificates and a DER-encoded private key
+    /// (in either PKCS#8, PKCS#1 or SEC1 format).
+    pub fn new(certificates: Vec<Vec<u8>>, private_key: Vec<u8>) -> Result<Self, Error> {
+        let certificates = certificates.into_iter().map(CertificateDer::from).collect();
+        let private_key = private_key
+            .try_into()
+            .map_err(|e: &str| Error::Tls(e.to_owned()))?;
+        Self::from_der(certificates, private_key)
+    }
+
+    /// Reads the certificates (PEM-encoded X.509) and the private key (PEM-encoded PKCS#8, PKCS#1
+    /// or SEC1) from the given files.
+    pub fn from_pem_files<P: AsRef<Path>>(certificates: P, private_key: P) -> Result<Self, Error> {
+        let certificates = rustls_pemfile::certs(&mut BufReader::new(File::open(certificates)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        if certificates.is_empty() {
+            return Err(Error::Tls("No certificates found".to_owned()));
+        }
+
+        let private_key =
+            rustls_pemfile::private_key(&mut BufReader::new(File::open(private_key)?))?
+                .ok_or_else(|| Error::Tls("No private key found".to_owned()))?;
+
+        Self::from_der(certificates, private_key)
+    }
+
+    fn from_der(
+        certificates: Vec<CertificateDer<'static>>,
+        private_key: rustls::pki_types::PrivateKeyDer<'static>,
+    ) -> Result<Self, Error> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .and_then(|builder| {
+                builder
+                    .with_no_client_auth()
+                    .with_single_cert(certificates, private_key)
+            })
+            .map_err(|e| Error::Tls(e.to_string()))?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+        })
+    }
+
+    pub(crate) fn acceptor(&self) -> &TlsAcceptor {
+        &self.acceptor
+    }
+}
+
+impl std::fmt::Debug for TlsIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsIdentity").finish_non_exhaustive()
+    }
+}
+
+/// The methods a user of the RPC server is allowed to call.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RpcRole {
+    /// The allowed methods, or `None` if all methods are allowed.
+    allowed_methods: Option<HashSet<String>>,
+}
+
+impl RpcRole {
+    /// A role that allows to call only the given methods.
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(allowed_methods: I) -> Self {
+        Self {
+            allowed_methods: Some(allowed_methods.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    /// A role that allows to call all methods.
+    pub fn unrestricted() -> Self {
+        Self {
+            allowed_methods: None,
+        }
+    }
+
+    pub fn is_unrestricted(&self) -> bool {
+        self.allowed_methods.is_none()
+    }
+
+    pub fn allows_method(&self, method: &str) -> bool {
+        match &self.allowed_methods {
+            Some(allowed_methods) => allowed_methods.contains(method),
+            None => true,
+        }
+    }
+}
+
+/// A user that is allowed to access the RPC server with HTTP basic auth.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RpcUser {
+    pub username: String,
+    /// The Blake2b hash of the password.
+    pub password_hash: Blake2bHash,
+    pub role: RpcRole,
+}
+
+impl RpcUser {
+    pub fn new<U: Into<String>>(username: U, password_hash: Blake2bHash, role: RpcRole) -> Self {
+        Self {
+            username: username.into(),
+            password_hash,
+            role,
+        }
+    }
+
+    fn check(&self, username: &str, password_hash: &Blake2bHash) -> bool {
+        (self.username.as_bytes().ct_eq(username.as_bytes())
+            & self.password_hash.ct_eq(password_hash))
+        .into()
+    }
+}
+
+/// The users that are allowed to access the RPC server.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RpcUsers {
+    users: Vec<RpcUser>,
+}
+
+impl RpcUsers {
+    pub fn new(users: Vec<RpcUser>) -> Self {
+        Self { users }
+    }
+
+    pub fn users(&self) -> &[RpcUser] {
+        &self.users
+    }
+
+    /// Returns the user identified by the value of an `Authorization` header using the `Basic`
+    /// scheme, or `None` if the credentials are malformed or don't match any user.
+    pub fn authenticate(&self, authorization: &[u8]) -> Option<&RpcUser> {
+        let credentials = std::str::from_utf8(authorization)
+            .ok()?
+            .strip_prefix("Basic ")?;
+        let credentials = BASE64_STANDARD.decode(credentials.trim()).ok()?;
+        let credentials = String::from_utf8(credentials).ok()?;
+        let (username, password) = credentials.split_once(':')?;
+
+        let password_hash: Blake2bHash = password.hash();
+        self.users
+            .iter()
+            .find(|user| user.check(username, &password_hash))
+    }
+}
+
+/// Access restrictions enforced in front of the JSON-RPC server.
+#[derive(Clone, Debug, Default)]
+pub struct AccessControl {
+    /// If set, only connections from these networks are accepted.
+    pub allowed_ips: Option<IpAllowList>,
+    /// If set, cross-origin requests are only accepted from these origins.
+    pub cors: Option<CorsPolicy>,
+    /// If set, connections are required to use TLS.
+    pub tls: Option<TlsIdentity>,
+    /// If set, the requests of each client are limited to these quotas.
+    pub rate_limit: Option<RateLimitConfig>,
+    /// If set, requests must carry the credentials of one of these users and may only call the
+    /// methods allowed by the role of the user.
+    pub users: Option<RpcUsers>,
+    /// The maximum size of the request bodies the gateway buffers for checking the calls. Larger
+    /// requests are rejected. Defaults to [`AccessControl::DEFAULT_MAX_BODY_SIZE`].
+    pub max_body_size: Option<usize>,
+}
+
+impl AccessControl {
+    /// The default maximum size of request bodies, in bytes.
+    pub const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+    /// Returns the maximum size of request bodies, in bytes.
+    pub fn max_body_size(&self) -> usize {
+        self.max_body_size.unwrap_or(Self::DEFAULT_MAX_BODY_SIZE)
+    }
+
+    /// Whether any restriction is configured, i.e. whether the [`RpcGateway`](crate::gateway::RpcGateway)
+    /// needs to be put in front of the JSON-RPC server.
+    pub fn is_enabled(&self) -> bool {
+        self.allowed_ips.is_some()
+            || self.cors.is_some()
+            || self.tls.is_some()
+            || self.rate_limit.is_some()
+            || self.users.is_some()
+    }
+}
@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 use futures::{future, stream::BoxStream, StreamExt};
 use nimiq_account::{BlockLog as BBlockLog, TransactionLog};
-use nimiq_blockchain::interface::{HistoryIndexInterface, HistoryInterface};
+use nimiq_blockchain::{
+    interface::{HistoryIndexInterface, HistoryInterface},
+    AddressHistoryPage as BAddressHistoryPage, Blockchain,
+};
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent};
 use nimiq_blockchain_proxy::{BlockchainProxy, BlockchainReadProxy};
 use nimiq_hash::Blake2bHash;
@@ -10,14 +13,16 @@ use nimiq_primitives::{key_nibbles::KeyNibbles, policy::Policy};
 use nimiq_rpc_interface::{
     blockchain::BlockchainInterface,
     types::{
-        is_of_log_type_and_related_to_addresses, Account, Block, BlockLog, BlockchainState,
-        ExecutedTransaction, Inherent, LogType, PenalizedSlots, RPCData, RPCResult, Slot, Staker,
-        Validator,
+        is_of_log_type_and_related_to_addresses, Account, AddressHistoryPage, AddressHistoryQuery,
+        Block, BlockLog, BlockchainState, ExecutedTransaction, Inherent, LogType, PenalizedSlots,
+        RPCData, RPCResult, Slot, SlotSchedule, Staker, Validator, ValidatorRewards,
     },
 };
 use tokio_stream::wrappers::BroadcastStream;
 
 use crate::error::Error;
+#[cfg(feature = "metrics")]
+use crate::rpc_metrics::RpcMetrics;
 
 pub struct BlockchainDispatcher {
     blockchain: BlockchainProxy,
@@ -71,6 +76,100 @@ fn get_validator_by_address(
     }
 }
 
+/// Returns the blockchain state after the main chain block with the given number.
+fn get_archived_blockchain_state(
+    blockchain: &Blockchain,
+    block_number: u32,
+) -> Result<BlockchainState, Error> {
+    let block = blockchain
+        .get_block_at(block_number, false, None)
+        .map_err(|_| Error::BlockNotFound(block_number))?;
+    Ok(BlockchainState::new(block_number, block.hash()))
+}
+
+/// Returns the page of the history of the given address that is selected by the query.
+fn get_address_history_page(
+    blockchain: &Blockchain,
+    address: &Address,
+    query: &AddressHistoryQuery,
+) -> Result<BAddressHistoryPage, Error> {
+    let start_after = query
+        .start_after()
+        .map_err(|error| Error::InvalidArgument(error.to_owned()))?;
+
+    blockchain
+        .history_store
+        .history_index()
+        .ok_or(Error::RequiresHistoryIndex)?
+        .get_tx_hashes_by_address_paginated(
+            address,
+            query.max.unwrap_or(500),
+            start_after.as_ref(),
+            &query.filter(),
+            None,
+        )
+        .ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "startAfterHash is not a transaction of address {address}"
+            ))
+        })
+}
+
+/// Fetches the transactions with the given hashes from the history index. Reward inherents are
+/// converted into transactions.
+fn get_executed_transactions(
+    blockchain: &Blockchain,
+    tx_hashes: Vec<Blake2bHash>,
+) -> Result<Vec<ExecutedTransaction>, Error> {
+    let history_index = blockchain
+        .history_store
+        .history_index()
+        .ok_or(Error::RequiresHistoryIndex)?;
+
+    let mut txs = vec![];
+
+    for hash in tx_hashes {
+        // Get all the historic transactions that correspond to this hash.
+        let hist_tx = history_index
+            .get_hist_tx_by_hash(&hash, None)
+            .ok_or_else(|| Error::TransactionNotFound(hash.clone()))?;
+
+        // Convert the historic transaction into a regular transaction. This will also convert
+        // reward inherents.
+        txs.push(
+            ExecutedTransaction::try_from_historic_transaction(
+                hist_tx,
+                Some(blockchain.block_number()),
+            )
+            .ok_or_else(|| Error::TransactionNotFound(hash.clone()))?,
+        )
+    }
+
+    Ok(txs)
+}
+
+/// Returns a stream of the hashes of new head blocks.
+fn head_block_hash_stream(
+    blockchain: &BlockchainProxy,
+) -> BoxStream<'static, RPCData<Blake2bHash, ()>> {
+    blockchain
+        .read()
+        .notifier_as_stream()
+        .filter_map(|event| {
+            let result = match event {
+                BlockchainEvent::Extended(hash) => Some(hash.into()),
+                BlockchainEvent::HistoryAdopted(hash) => Some(hash.into()),
+                BlockchainEvent::Finalized(_) | BlockchainEvent::EpochFinalized(_) => None,
+                BlockchainEvent::Rebranched(_, new_branch) => {
+                    Some(new_branch.into_iter().last().unwrap().0.into())
+                }
+                BlockchainEvent::Stored(_block) => None,
+            };
+            future::ready(result)
+        })
+        .boxed()
+}
+
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
 #[async_trait]
 impl BlockchainInterface for BlockchainDispatcher {
@@ -334,29 +433,43 @@ impl BlockchainInterface for BlockchainDispatcher {
                 .ok_or(Error::RequiresHistoryIndex)?
                 .get_tx_hashes_by_address(&address, max.unwrap_or(500), None);
 
-            let mut txs = vec![];
+            Ok(get_executed_transactions(&blockchain, tx_hashes)?.into())
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
 
-            for hash in tx_hashes {
-                // Get all the historic transactions that correspond to this hash.
-                let hist_tx = blockchain
-                    .history_store
-                    .history_index()
-                    .ok_or(Error::RequiresHistoryIndex)?
-                    .get_hist_tx_by_hash(&hash, None)
-                    .ok_or_else(|| Error::TransactionNotFound(hash.clone()))?;
-
-                // Convert the historic transaction into a regular transaction. This will also convert
-                // reward inherents.
-                txs.push(
-                    ExecutedTransaction::try_from_historic_transaction(
-                        hist_tx,
-                        Some(blockchain.block_number()),
-                    )
-                    .ok_or_else(|| Error::TransactionNotFound(hash.clone()))?,
-                )
+    async fn get_transaction_hashes_by_address_paginated(
+        &mut self,
+        address: Address,
+        query: AddressHistoryQuery,
+    ) -> RPCResult<AddressHistoryPage<Blake2bHash>, (), Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            let page = get_address_history_page(&blockchain, &address, &query)?;
+
+            Ok(AddressHistoryPage {
+                transactions: page.hashes,
+                next_cursor: page.next,
             }
+            .into())
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
 
-            Ok(txs.into())
+    async fn get_transactions_by_address_paginated(
+        &mut self,
+        address: Address,
+        query: AddressHistoryQuery,
+    ) -> RPCResult<AddressHistoryPage<ExecutedTransaction>, (), Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            let page = get_address_history_page(&blockchain, &address, &query)?;
+
+            Ok(AddressHistoryPage {
+                transactions: get_executed_transactions(&blockchain, page.hashes)?,
+                next_cursor: page.next,
+            }
+            .into())
         } else {
             Err(Error::NotSupportedForLightBlockchain)
         }
@@ -365,9 +478,20 @@ impl BlockchainInterface for BlockchainDispatcher {
     async fn get_account_by_address(
         &mut self,
         address: Address,
+        block_number: Option<u32>,
     ) -> RPCResult<Account, BlockchainState, Self::Error> {
         let blockchain_proxy = self.blockchain.read();
         if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
+            if let Some(block_number) = block_number {
+                let db_txn = blockchain.read_transaction();
+                let account = blockchain.get_archived_account(&address, block_number, &db_txn)?;
+                return Ok(Account::from_account_with_state(
+                    address,
+                    account,
+                    get_archived_blockchain_state(blockchain, block_number)?,
+                ));
+            }
+
             let account = blockchain
                 .get_account_if_complete(&address)
                 .ok_or(Error::NoConsensus)?;
@@ -426,6 +550,22 @@ impl BlockchainInterface for BlockchainDispatcher {
         }
     }
 
+    async fn get_validator_slot_schedule(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<SlotSchedule, BlockchainState, Self::Error> {
+        let blockchain_proxy = self.blockchain.read();
+        if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
+            let schedule = blockchain
+                .get_slot_schedule(&address)
+                .ok_or(Error::ValidatorWithoutSlots(address))?;
+
+            Ok(RPCData::with_blockchain(schedule.into(), &blockchain_proxy))
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
     async fn get_current_penalized_slots(
         &mut self,
     ) -> RPCResult<PenalizedSlots, BlockchainState, Self::Error> {
@@ -477,8 +617,27 @@ impl BlockchainInterface for BlockchainDispatcher {
     async fn get_validator_by_address(
         &mut self,
         address: Address,
+        block_number: Option<u32>,
     ) -> RPCResult<Validator, BlockchainState, Self::Error> {
-        get_validator_by_address(&self.blockchain.read(), &address)
+        let blockchain_proxy = self.blockchain.read();
+        match (block_number, &blockchain_proxy) {
+            (Some(block_number), BlockchainReadProxy::Full(blockchain)) => {
+                let db_txn = blockchain.read_transaction();
+                let staking_contract =
+                    blockchain.get_archived_staking_contract(block_number, &db_txn)?;
+                let data_store =
+                    blockchain.get_archived_staking_contract_store(block_number, &db_txn)?;
+                let validator = staking_contract
+                    .get_validator(&data_store, &address)
+                    .ok_or(Error::ValidatorNotFound(address))?;
+
+                Ok(RPCData::new(
+                    Validator::from_validator(&validator),
+                    get_archived_blockchain_state(blockchain, block_number)?,
+                ))
+            }
+            _ => get_validator_by_address(&blockchain_proxy, &address),
+        }
     }
 
     async fn get_validators(&mut self) -> RPCResult<Vec<Validator>, BlockchainState, Self::Error> {
@@ -528,9 +687,26 @@ impl BlockchainInterface for BlockchainDispatcher {
     async fn get_staker_by_address(
         &mut self,
         address: Address,
+        block_number: Option<u32>,
     ) -> RPCResult<Staker, BlockchainState, Self::Error> {
         let blockchain_proxy = self.blockchain.read();
         if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
+            if let Some(block_number) = block_number {
+                let db_txn = blockchain.read_transaction();
+                let staking_contract =
+                    blockchain.get_archived_staking_contract(block_number, &db_txn)?;
+                let data_store =
+                    blockchain.get_archived_staking_contract_store(block_number, &db_txn)?;
+                let staker = staking_contract
+                    .get_staker(&data_store, &address)
+                    .ok_or(Error::StakerNotFound(address))?;
+
+                return Ok(RPCData::new(
+                    Staker::from_staker(&staker),
+                    get_archived_blockchain_state(blockchain, block_number)?,
+                ));
+            }
+
             let staking_contract = blockchain
                 .get_staking_contract_if_complete(None)
                 .ok_or(Error::NoConsensus)?;
@@ -549,17 +725,33 @@ impl BlockchainInterface for BlockchainDispatcher {
         }
     }
 
+    async fn get_validator_rewards_by_epoch(
+        &mut self,
+        address: Address,
+        epoch_number: u32,
+    ) -> RPCResult<ValidatorRewards, (), Self::Error> {
+        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+            let db_txn = blockchain.read_transaction();
+            let rewards =
+                blockchain.get_validator_rewards_for_epoch(&address, epoch_number, &db_txn)?;
+
+            Ok(ValidatorRewards::from(rewards).into())
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
     #[stream]
     async fn subscribe_for_head_block(
         &mut self,
         include_body: Option<bool>,
     ) -> Result<BoxStream<'static, RPCData<Block, ()>>, Self::Error> {
         let blockchain = self.blockchain.clone();
-        let stream = self.subscribe_for_head_block_hash().await?;
+        let stream = head_block_hash_stream(&self.blockchain);
 
         // Uses the stream to receive hashes of blocks and then requests the actual block.
         // If the block was reverted in between these steps, the stream won't emit any event.
-        Ok(stream
+        let stream = stream
             .filter_map(move |rpc_result| {
                 let blockchain_rg = blockchain.read();
                 let result = get_block_by_hash(
@@ -570,28 +762,22 @@ impl BlockchainInterface for BlockchainDispatcher {
                 .ok();
                 future::ready(result)
             })
-            .boxed())
+            .boxed();
+
+        #[cfg(feature = "metrics")]
+        let stream = RpcMetrics::track_subscription("subscribeForHeadBlock", stream);
+        Ok(stream)
     }
 
     #[stream]
     async fn subscribe_for_head_block_hash(
         &mut self,
     ) -> Result<BoxStream<'static, RPCData<Blake2bHash, ()>>, Self::Error> {
-        let stream = self.blockchain.read().notifier_as_stream();
-        Ok(stream
-            .filter_map(|event| {
-                let result = match event {
-                    BlockchainEvent::Extended(hash) => Some(hash.into()),
-                    BlockchainEvent::HistoryAdopted(hash) => Some(hash.into()),
-                    BlockchainEvent::Finalized(_) | BlockchainEvent::EpochFinalized(_) => None,
-                    BlockchainEvent::Rebranched(_, new_branch) => {
-                        Some(new_branch.into_iter().last().unwrap().0.into())
-                    }
-                    BlockchainEvent::Stored(_block) => None,
-                };
-                future::ready(result)
-            })
-            .boxed())
+        let stream = head_block_hash_stream(&self.blockchain);
+
+        #[cfg(feature = "metrics")]
+        let stream = RpcMetrics::track_subscription("subscribeForHeadBlockHash", stream);
+        Ok(stream)
     }
 
     #[stream]
@@ -602,7 +788,7 @@ impl BlockchainInterface for BlockchainDispatcher {
         let blockchain = self.blockchain.clone();
         let stream = self.blockchain.read().notifier_as_stream();
 
-        Ok(stream
+        let stream = stream
             .filter_map(move |event| {
                 let result = match event {
                     BlockchainEvent::EpochFinalized(..) => {
@@ -613,7 +799,12 @@ impl BlockchainInterface for BlockchainDispatcher {
                 };
                 future::ready(result)
             })
-            .boxed())
+            .boxed();
+
+        #[cfg(feature = "metrics")]
+        let stream =
+            RpcMetrics::track_subscription("subscribeForValidatorElectionByAddress", stream);
+        Ok(stream)
     }
 
     #[stream]
@@ -625,118 +816,124 @@ impl BlockchainInterface for BlockchainDispatcher {
         if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
             let stream = BroadcastStream::new(blockchain.log_notifier.subscribe());
 
-            if addresses.is_empty() && log_types.is_empty() {
-                Ok(Box::pin(stream.boxed().filter_map(|event| {
-                    let result = match event {
-                        Ok(event) => Some(RPCData::with_block_log(event)),
-                        Err(_) => None,
-                    };
-                    future::ready(result)
-                })))
-            } else {
-                Ok(stream
-                    .filter_map(move |event| {
+            let stream: BoxStream<'static, RPCData<BlockLog, BlockchainState>> =
+                if addresses.is_empty() && log_types.is_empty() {
+                    Box::pin(stream.boxed().filter_map(|event| {
                         let result = match event {
-                            Ok(BBlockLog::AppliedBlock {
-                                mut inherent_logs,
-                                block_hash,
-                                block_number,
-                                timestamp,
-                                tx_logs,
-                                total_tx_size: _,
-                            }) => {
-                                // Collects the inherents that are related to any of the addresses specified and of any of the log types provided.
-                                inherent_logs.retain(|log| {
-                                    is_of_log_type_and_related_to_addresses(
-                                        log, &addresses, &log_types,
-                                    )
-                                });
-                                // Since each TransactionLog has its own vec of logs, we iterate over each tx_logs and filter their logs,
-                                // if a tx_log has no logs after filtering, it will be filtered out completely.
-                                let tx_logs: Vec<TransactionLog> = tx_logs
-                                    .into_iter()
-                                    .filter_map(|mut tx_log| {
-                                        tx_log.logs.retain(|log| {
-                                            is_of_log_type_and_related_to_addresses(
-                                                log, &addresses, &log_types,
-                                            )
-                                        });
-                                        if tx_log.logs.is_empty() {
-                                            None
-                                        } else {
-                                            Some(tx_log)
-                                        }
-                                    })
-                                    .collect();
-
-                                // If this block has no transaction logs or inherent logs of interest, we return None. Otherwise, we return the filtered BlockLog.
-                                // This way the stream only emits an event if a block has at least one log fulfilling the specified criteria.
-                                if !inherent_logs.is_empty() || !tx_logs.is_empty() {
-                                    Some(RPCData::new(
-                                        BlockLog::AppliedBlock {
-                                            inherent_logs,
-                                            timestamp,
-                                            tx_logs,
-                                        },
-                                        BlockchainState {
-                                            block_number,
-                                            block_hash,
-                                        },
-                                    ))
-                                } else {
-                                    None
-                                }
-                            }
-                            Ok(BBlockLog::RevertedBlock {
-                                mut inherent_logs,
-                                block_hash,
-                                block_number,
-                                tx_logs,
-                                total_tx_size: _,
-                            }) => {
-                                // Filters the inherents and tx_logs the same way as the AppliedBlock
-                                inherent_logs.retain(|log| {
-                                    is_of_log_type_and_related_to_addresses(
-                                        log, &addresses, &log_types,
-                                    )
-                                });
-                                let tx_logs: Vec<TransactionLog> = tx_logs
-                                    .into_iter()
-                                    .filter_map(|mut tx_log| {
-                                        tx_log.logs.retain(|log| {
-                                            is_of_log_type_and_related_to_addresses(
-                                                log, &addresses, &log_types,
-                                            )
-                                        });
-                                        if tx_log.logs.is_empty() {
-                                            None
-                                        } else {
-                                            Some(tx_log)
-                                        }
-                                    })
-                                    .collect();
-
-                                if !inherent_logs.is_empty() || !tx_logs.is_empty() {
-                                    Some(RPCData::new(
-                                        BlockLog::RevertedBlock {
-                                            inherent_logs,
-                                            tx_logs,
-                                        },
-                                        BlockchainState {
-                                            block_number,
-                                            block_hash,
-                                        },
-                                    ))
-                                } else {
-                                    None
-                                }
-                            }
+                            Ok(event) => Some(RPCData::with_block_log(event)),
                             Err(_) => None,
                         };
                         future::ready(result)
-                    })
-                    .boxed())
-            }
+                    }))
+                } else {
+                    stream
+                        .filter_map(move |event| {
+                            let result = match event {
+                                Ok(BBlockLog::AppliedBlock {
+                                    mut inherent_logs,
+                                    block_hash,
+                                    block_number,
+                                    timestamp,
+                                    tx_logs,
+                                    total_tx_size: _,
+                                }) => {
+                                    // Collects the inherents that are related to any of the addresses specified and of any of the log types provided.
+                                    inherent_logs.retain(|log| {
+                                        is_of_log_type_and_related_to_addresses(
+                                            log, &addresses, &log_types,
+                                        )
+                                    });
+                                    // Since each TransactionLog has its own vec of logs, we iterate over each tx_logs and filter their logs,
+                                    // if a tx_log has no logs after filtering, it will be filtered out completely.
+                                    let tx_logs: Vec<TransactionLog> = tx_logs
+                                        .into_iter()
+                                        .filter_map(|mut tx_log| {
+                                            tx_log.logs.retain(|log| {
+                                                is_of_log_type_and_related_to_addresses(
+                                                    log, &addresses, &log_types,
+                                                )
+                                            });
+                                            if tx_log.logs.is_empty() {
+                                                None
+                                            } else {
+                                                Some(tx_log)
+                                            }
+                                        })
+                                        .collect();
+
+                                    // If this block has no transaction logs or inherent logs of interest, we return None. Otherwise, we return the filtered BlockLog.
+                                    // This way the stream only emits an event if a block has at least one log fulfilling the specified criteria.
+                                    if !inherent_logs.is_empty() || !tx_logs.is_empty() {
+                                        Some(RPCData::new(
+                                            BlockLog::AppliedBlock {
+                                                inherent_logs,
+                                                timestamp,
+                                                tx_logs,
+                                            },
+                                            BlockchainState {
+                                                block_number,
+                                                block_hash,
+                                            },
+                                        ))
+                                    } else {
+                                        None
+                                    }
+                                }
+                                Ok(BBlockLog::RevertedBlock {
+                                    mut inherent_logs,
+                                    block_hash,
+                                    block_number,
+                                    tx_logs,
+                                    total_tx_size: _,
+                                }) => {
+                                    // Filters the inherents and tx_logs the same way as the AppliedBlock
+                                    inherent_logs.retain(|log| {
+                                        is_of_log_type_and_related_to_addresses(
+                                            log, &addresses, &log_types,
+                                        )
+                                    });
+                                    let tx_logs: Vec<TransactionLog> = tx_logs
+                                        .into_iter()
+                                        .filter_map(|mut tx_log| {
+                                            tx_log.logs.retain(|log| {
+                                                is_of_log_type_and_related_to_addresses(
+                                                    log, &addresses, &log_types,
+                                                )
+                                            });
+                                            if tx_log.logs.is_empty() {
+                                                None
+                                            } else {
+                                                Some(tx_log)
+                                            }
+                                        })
+                                        .collect();
+
+                                    if !inherent_logs.is_empty() || !tx_logs.is_empty() {
+                                        Some(RPCData::new(
+                                            BlockLog::RevertedBlock {
+                                                inherent_logs,
+                                                tx_logs,
+                                            },
+                                            BlockchainState {
+                                                block_number,
+                                                block_hash,
+                                            },
+                                        ))
+                                    } else {
+                                        None
+                                    }
+                                }
+                                Err(_) => None,
+                            };
+                            future::ready(result)
+                        })
+                        .boxed()
+                };
+
+            #[cfg(feature = "metrics")]
+            let stream =
+                RpcMetrics::track_subscription("subscribeForLogsByAddressesAndTypes", stream);
+            Ok(stream)
         } else {
             Err(Error::NotSupportedForLightBlockchain)
         }
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{num::NonZeroU8, sync::Arc};
 
 use async_trait::async_trait;
 use nimiq_blockchain_interface::AbstractBlockchain;
@@ -6,12 +6,16 @@ use nimiq_blockchain_proxy::BlockchainReadProxy;
 use nimiq_bls::{KeyPair as BlsKeyPair, SecretKey as BlsSecretKey};
 use nimiq_consensus::ConsensusProxy;
 use nimiq_hash::{Blake2bHash, Hash};
-use nimiq_keys::{Address, Ed25519PublicKey, KeyPair, PrivateKey};
+use nimiq_keys::{
+    multisig::{commitment::CommitmentPair, MUSIG2_PARAMETER_V},
+    Address, Ed25519PublicKey, KeyPair, PrivateKey, SecureGenerate,
+};
+use nimiq_mempool::verify::VerifyErr;
 use nimiq_network_libp2p::Network;
-use nimiq_primitives::{coin::Coin, networks::NetworkId};
+use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
 use nimiq_rpc_interface::{
     consensus::ConsensusInterface,
-    types::{RPCResult, Transaction as RPCTransaction, ValidityStartHeight},
+    types::{RPCResult, SimulatedTransaction, Transaction as RPCTransaction, ValidityStartHeight},
 };
 use nimiq_serde::{Deserialize, Serialize};
 use nimiq_transaction::{
@@ -19,10 +23,15 @@ use nimiq_transaction::{
     SignatureProof, Transaction,
 };
 use nimiq_transaction_builder::TransactionBuilder;
+use nimiq_wallet::{Beneficiary, PartiallySignedTransaction, TransactionKind};
 use parking_lot::RwLock;
 
 use crate::{error::Error, wallets::UnlockedWallets};
 
+/// The maximum number of batches whose filters are requested by a single `getBatchesByAddresses`
+/// call.
+const MAX_BATCHES_BY_ADDRESSES: u32 = 1024;
+
 pub struct ConsensusDispatcher {
     consensus: ConsensusProxy<Network>,
     unlocked_wallets: Option<Arc<RwLock<UnlockedWallets>>>,
@@ -52,6 +61,23 @@ impl ConsensusDispatcher {
             .clone())
     }
 
+    /// Checks a transaction against the signing policy of a wallet. This needs to happen before
+    /// the wallet signs the transaction.
+    fn check_signing_policy(
+        &self,
+        wallet: &Address,
+        beneficiary: &Beneficiary,
+        value: Coin,
+        kind: TransactionKind,
+    ) -> Result<(), Error> {
+        if let Some(ref unlocked_wallets) = self.unlocked_wallets {
+            if let Some(policy) = unlocked_wallets.read().get_policy(wallet) {
+                policy.check_parameters(beneficiary, value, kind)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Returns the network ID for our current blockchain.
     fn get_network_id(&self) -> NetworkId {
         self.consensus.blockchain.read().network_id()
@@ -68,6 +94,20 @@ fn transaction_to_hex_string(transaction: &Transaction) -> String {
     hex::encode(transaction.serialize_to_vec())
 }
 
+fn partially_signed_transaction_from_hex_string(
+    partially_signed_transaction: &str,
+) -> Result<PartiallySignedTransaction, Error> {
+    Ok(PartiallySignedTransaction::from_bytes(&hex::decode(
+        partially_signed_transaction,
+    )?)?)
+}
+
+fn partially_signed_transaction_to_hex_string(
+    partially_signed_transaction: &PartiallySignedTransaction,
+) -> String {
+    hex::encode(partially_signed_transaction.serialize_to_vec())
+}
+
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
 #[async_trait]
 impl ConsensusInterface for ConsensusDispatcher {
@@ -98,6 +138,35 @@ impl ConsensusInterface for ConsensusDispatcher {
         }
     }
 
+    async fn simulate_transaction(
+        &mut self,
+        raw_tx: String,
+    ) -> RPCResult<SimulatedTransaction, (), Self::Error> {
+        let mut transaction = Transaction::deserialize_from_vec(&hex::decode(raw_tx)?)?;
+
+        if let BlockchainReadProxy::Full(blockchain) = self.consensus.blockchain.read() {
+            // Apply the same checks as the mempool before touching the accounts.
+            transaction
+                .verify_mut(blockchain.network_id())
+                .map_err(|e| Error::InvalidTransaction(VerifyErr::InvalidTransaction(e)))?;
+            if !transaction.is_valid_at(blockchain.block_number() + 1) {
+                return Err(Error::InvalidTransaction(VerifyErr::InvalidBlockNumber));
+            }
+            if blockchain
+                .contains_tx_in_validity_window(&transaction.hash::<Blake2bHash>().into(), None)
+            {
+                return Err(Error::InvalidTransaction(VerifyErr::AlreadyIncluded));
+            }
+
+            let simulation = blockchain
+                .simulate_transaction(&transaction)
+                .ok_or(Error::NoConsensus)??;
+            Ok(SimulatedTransaction::from_simulated_transaction(simulation).into())
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
     async fn create_basic_transaction(
         &mut self,
         wallet: Address,
@@ -106,6 +175,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &wallet,
+            &Beneficiary::Account(recipient.clone()),
+            value,
+            TransactionKind::Basic,
+        )?;
+
         let transaction = TransactionBuilder::new_basic(
             &self.get_wallet_keypair(&wallet)?,
             recipient,
@@ -142,6 +218,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &wallet,
+            &Beneficiary::Account(recipient.clone()),
+            value,
+            TransactionKind::Basic,
+        )?;
+
         let transaction = TransactionBuilder::new_basic_with_data(
             &self.get_wallet_keypair(&wallet)?,
             recipient,
@@ -189,6 +272,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &wallet,
+            &Beneficiary::NewContract,
+            value,
+            TransactionKind::Vesting,
+        )?;
+
         let transaction = TransactionBuilder::new_create_vesting(
             &self.get_wallet_keypair(&wallet)?,
             owner,
@@ -240,6 +330,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &wallet,
+            &Beneficiary::Account(recipient.clone()),
+            value,
+            TransactionKind::Vesting,
+        )?;
+
         let transaction = TransactionBuilder::new_redeem_vesting(
             &self.get_wallet_keypair(&wallet)?,
             contract_address,
@@ -288,6 +385,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &wallet,
+            &Beneficiary::NewContract,
+            value,
+            TransactionKind::Htlc,
+        )?;
+
         let transaction = TransactionBuilder::new_create_htlc(
             &self.get_wallet_keypair(&wallet)?,
             htlc_sender,
@@ -345,6 +449,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &wallet,
+            &Beneficiary::Account(recipient.clone()),
+            value,
+            TransactionKind::Htlc,
+        )?;
+
         let transaction = TransactionBuilder::new_redeem_htlc_regular(
             &self.get_wallet_keypair(&wallet)?,
             contract_address,
@@ -399,6 +510,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &wallet,
+            &Beneficiary::Account(recipient.clone()),
+            value,
+            TransactionKind::Htlc,
+        )?;
+
         let transaction = TransactionBuilder::new_redeem_htlc_timeout(
             &self.get_wallet_keypair(&wallet)?,
             contract_address,
@@ -500,6 +618,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &wallet,
+            &Beneficiary::Account(recipient.clone()),
+            value,
+            TransactionKind::Htlc,
+        )?;
+
         let sig = TransactionBuilder::sign_htlc_early(
             &self.get_wallet_keypair(&wallet)?,
             contract_address,
@@ -522,6 +647,19 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &sender_wallet,
+            &Beneficiary::Account(staker_wallet.clone()),
+            value,
+            TransactionKind::Staking,
+        )?;
+        self.check_signing_policy(
+            &staker_wallet,
+            &Beneficiary::Account(staker_wallet.clone()),
+            value,
+            TransactionKind::Staking,
+        )?;
+
         let transaction = TransactionBuilder::new_create_staker(
             &self.get_wallet_keypair(&sender_wallet)?,
             &self.get_wallet_keypair(&staker_wallet)?,
@@ -566,6 +704,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &sender_wallet,
+            &Beneficiary::Account(staker_address.clone()),
+            value,
+            TransactionKind::Staking,
+        )?;
+
         let transaction = TransactionBuilder::new_add_stake(
             &self.get_wallet_keypair(&sender_wallet)?,
             staker_address,
@@ -609,9 +754,18 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        for wallet in sender_wallet.iter().chain([&staker_wallet]) {
+            self.check_signing_policy(
+                wallet,
+                &Beneficiary::None,
+                Coin::ZERO,
+                TransactionKind::Staking,
+            )?;
+        }
+
         let sender_key = match sender_wallet {
             None => None,
-            Some(address) => Some(self.get_wallet_keypair(&address)?),
+            Some(ref address) => Some(self.get_wallet_keypair(address)?),
         };
 
         let transaction = TransactionBuilder::new_update_staker(
@@ -658,9 +812,18 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        for wallet in sender_wallet.iter().chain([&staker_wallet]) {
+            self.check_signing_policy(
+                wallet,
+                &Beneficiary::None,
+                Coin::ZERO,
+                TransactionKind::Staking,
+            )?;
+        }
+
         let sender_key = match sender_wallet {
             None => None,
-            Some(address) => Some(self.get_wallet_keypair(&address)?),
+            Some(ref address) => Some(self.get_wallet_keypair(address)?),
         };
 
         let transaction = TransactionBuilder::new_set_active_stake(
@@ -704,9 +867,18 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        for wallet in sender_wallet.iter().chain([&staker_wallet]) {
+            self.check_signing_policy(
+                wallet,
+                &Beneficiary::None,
+                Coin::ZERO,
+                TransactionKind::Staking,
+            )?;
+        }
+
         let sender_key = match sender_wallet {
             None => None,
-            Some(address) => Some(self.get_wallet_keypair(&address)?),
+            Some(ref address) => Some(self.get_wallet_keypair(address)?),
         };
 
         let transaction = TransactionBuilder::new_retire_stake(
@@ -750,6 +922,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &staker_wallet,
+            &Beneficiary::Account(recipient.clone()),
+            value,
+            TransactionKind::Staking,
+        )?;
+
         let transaction = TransactionBuilder::new_remove_stake(
             &self.get_wallet_keypair(&staker_wallet)?,
             recipient,
@@ -819,6 +998,20 @@ impl ConsensusInterface for ConsensusDispatcher {
             )
         };
 
+        let deposit = Coin::from_u64_unchecked(Policy::VALIDATOR_DEPOSIT);
+        self.check_signing_policy(
+            &sender_wallet,
+            &Beneficiary::Account(validator_wallet.clone()),
+            deposit,
+            TransactionKind::Staking,
+        )?;
+        self.check_signing_policy(
+            &validator_wallet,
+            &Beneficiary::Account(validator_wallet.clone()),
+            deposit,
+            TransactionKind::Staking,
+        )?;
+
         let transaction = TransactionBuilder::new_create_validator(
             &self.get_wallet_keypair(&sender_wallet)?,
             &self.get_wallet_keypair(&validator_wallet)?,
@@ -910,6 +1103,19 @@ impl ConsensusInterface for ConsensusDispatcher {
             }
         };
 
+        self.check_signing_policy(
+            &sender_wallet,
+            &Beneficiary::None,
+            Coin::ZERO,
+            TransactionKind::Staking,
+        )?;
+        self.check_signing_policy(
+            &validator_wallet,
+            &Beneficiary::None,
+            Coin::ZERO,
+            TransactionKind::Staking,
+        )?;
+
         let transaction = TransactionBuilder::new_update_validator(
             &self.get_wallet_keypair(&sender_wallet)?,
             &self.get_wallet_keypair(&validator_wallet)?,
@@ -965,6 +1171,13 @@ impl ConsensusInterface for ConsensusDispatcher {
 
         let signing_key_pair = KeyPair::from(secret_key);
 
+        self.check_signing_policy(
+            &sender_wallet,
+            &Beneficiary::None,
+            Coin::ZERO,
+            TransactionKind::Staking,
+        )?;
+
         let transaction = TransactionBuilder::new_deactivate_validator(
             &self.get_wallet_keypair(&sender_wallet)?,
             validator_address,
@@ -1010,6 +1223,13 @@ impl ConsensusInterface for ConsensusDispatcher {
             .map_err(|_| Error::InvalidArgument("Signing Key".to_string()))?;
         let signing_key_pair = KeyPair::from(secret_key);
 
+        self.check_signing_policy(
+            &sender_wallet,
+            &Beneficiary::None,
+            Coin::ZERO,
+            TransactionKind::Staking,
+        )?;
+
         let transaction = TransactionBuilder::new_reactivate_validator(
             &self.get_wallet_keypair(&sender_wallet)?,
             validator_address,
@@ -1074,6 +1294,19 @@ impl ConsensusInterface for ConsensusDispatcher {
         fee: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &sender_wallet,
+            &Beneficiary::None,
+            Coin::ZERO,
+            TransactionKind::Staking,
+        )?;
+        self.check_signing_policy(
+            &validator_wallet,
+            &Beneficiary::None,
+            Coin::ZERO,
+            TransactionKind::Staking,
+        )?;
+
         let transaction = TransactionBuilder::new_retire_validator(
             &self.get_wallet_keypair(&sender_wallet)?,
             &self.get_wallet_keypair(&validator_wallet)?,
@@ -1134,6 +1367,13 @@ impl ConsensusInterface for ConsensusDispatcher {
         value: Coin,
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<String, (), Self::Error> {
+        self.check_signing_policy(
+            &validator_wallet,
+            &Beneficiary::Account(recipient.clone()),
+            value,
+            TransactionKind::Staking,
+        )?;
+
         let transaction = TransactionBuilder::new_delete_validator(
             recipient,
             &self.get_wallet_keypair(&validator_wallet)?,
@@ -1166,4 +1406,156 @@ impl ConsensusInterface for ConsensusDispatcher {
             .data;
         self.send_raw_transaction(raw_tx).await
     }
+
+    async fn create_multisig_transaction(
+        &mut self,
+        public_keys: Vec<Ed25519PublicKey>,
+        min_signatures: NonZeroU8,
+        recipient: Address,
+        value: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<String, (), Self::Error> {
+        let validity_start_height = self.validity_start_height(validity_start_height);
+        let network_id = self.get_network_id();
+        let partially_signed_transaction =
+            PartiallySignedTransaction::with_transaction(&public_keys, min_signatures, |sender| {
+                Transaction::new_basic(
+                    sender,
+                    recipient,
+                    value,
+                    fee,
+                    validity_start_height,
+                    network_id,
+                )
+            })?;
+
+        Ok(partially_signed_transaction_to_hex_string(&partially_signed_transaction).into())
+    }
+
+    async fn add_multisig_commitments(
+        &mut self,
+        wallet: Address,
+        partially_signed_transaction: String,
+    ) -> RPCResult<String, (), Self::Error> {
+        let mut partially_signed_transaction =
+            partially_signed_transaction_from_hex_string(&partially_signed_transaction)?;
+        let key_pair = self.get_wallet_keypair(&wallet)?;
+        let transaction_hash = partially_signed_transaction
+            .transaction()
+            .hash::<Blake2bHash>();
+
+        let mut unlocked_wallets = self
+            .unlocked_wallets
+            .as_ref()
+            .ok_or_else(|| Error::UnlockedWalletNotFound(wallet.clone()))?
+            .write();
+
+        // Reuse the commitments if the wallet already committed to sign the transaction, as the
+        // nonces are only ever used for a single partial signature.
+        let (commitment_pairs, is_new) =
+            match unlocked_wallets.get_multisig_commitments(&wallet, &transaction_hash) {
+                Some(commitment_pairs) => (*commitment_pairs, false),
+                None => (
+                    [(); MUSIG2_PARAMETER_V].map(|_| CommitmentPair::generate_default_csprng()),
+                    true,
+                ),
+            };
+
+        partially_signed_transaction.add_commitments(
+            key_pair.public,
+            CommitmentPair::to_commitments(&commitment_pairs),
+        )?;
+        if is_new
+            && !unlocked_wallets.insert_multisig_commitments(
+                &wallet,
+                transaction_hash,
+                commitment_pairs,
+            )
+        {
+            return Err(Error::UnlockedWalletNotFound(wallet));
+        }
+
+        Ok(partially_signed_transaction_to_hex_string(&partially_signed_transaction).into())
+    }
+
+    async fn sign_multisig_transaction(
+        &mut self,
+        wallet: Address,
+        partially_signed_transaction: String,
+    ) -> RPCResult<String, (), Self::Error> {
+        let mut partially_signed_transaction =
+            partially_signed_transaction_from_hex_string(&partially_signed_transaction)?;
+        let key_pair = self.get_wallet_keypair(&wallet)?;
+        if let Some(ref unlocked_wallets) = self.unlocked_wallets {
+            if let Some(policy) = unlocked_wallets.read().get_policy(&wallet) {
+                policy.check(partially_signed_transaction.transaction())?;
+            }
+        }
+        let transaction_hash = partially_signed_transaction
+            .transaction()
+            .hash::<Blake2bHash>();
+
+        let mut unlocked_wallets = self
+            .unlocked_wallets
+            .as_ref()
+            .ok_or_else(|| Error::UnlockedWalletNotFound(wallet.clone()))?
+            .write();
+        let commitment_pairs = *unlocked_wallets
+            .get_multisig_commitments(&wallet, &transaction_hash)
+            .ok_or_else(|| Error::MultisigCommitmentsNotFound(wallet.clone()))?;
+
+        partially_signed_transaction.sign(&key_pair, &commitment_pairs)?;
+        unlocked_wallets.remove_multisig_commitments(&wallet, &transaction_hash);
+
+        Ok(partially_signed_transaction_to_hex_string(&partially_signed_transaction).into())
+    }
+
+    async fn combine_multisig_transactions(
+        &mut self,
+        partially_signed_transactions: Vec<String>,
+    ) -> RPCResult<String, (), Self::Error> {
+        let mut partially_signed_transactions = partially_signed_transactions
+            .iter()
+            .map(|pst| partially_signed_transaction_from_hex_string(pst));
+        let mut combined = partially_signed_transactions.next().ok_or_else(|| {
+            Error::InvalidArgument("No partially signed transactions given".to_string())
+        })??;
+        for partially_signed_transaction in partially_signed_transactions {
+            combined.combine(&partially_signed_transaction?)?;
+        }
+
+        Ok(partially_signed_transaction_to_hex_string(&combined).into())
+    }
+
+    async fn finalize_multisig_transaction(
+        &mut self,
+        partially_signed_transaction: String,
+    ) -> RPCResult<String, (), Self::Error> {
+        let transaction =
+            partially_signed_transaction_from_hex_string(&partially_signed_transaction)?
+                .finalize()?;
+
+        Ok(transaction_to_hex_string(&transaction).into())
+    }
+
+    async fn get_batches_by_addresses(
+        &mut self,
+        addresses: Vec<Address>,
+        start_batch: u32,
+        count: u32,
+        min_peers: Option<usize>,
+    ) -> RPCResult<Vec<u32>, (), Self::Error> {
+        if count > MAX_BATCHES_BY_ADDRESSES {
+            return Err(Error::InvalidArgument(format!(
+                "At most {MAX_BATCHES_BY_ADDRESSES} batches can be queried at once"
+            )));
+        }
+
+        Ok(self
+            .consensus
+            .request_batches_by_addresses(addresses, start_batch, count, min_peers.unwrap_or(1))
+            .await?
+            .into())
+    }
 }
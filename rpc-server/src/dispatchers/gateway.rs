@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use nimiq_rpc_interface::{gateway::GatewayInterface, types::RPCResult};
+
+use crate::{error::Error, gateway::UpstreamSecret};
+
+/// Answers the handshake of the [`RpcGateway`](crate::gateway::RpcGateway) in front of the server.
+///
+/// The gateway rejects the handshake method in the requests it forwards, but can't inspect the
+/// calls made over WebSocket connections. As the gateway verifies the server only once, before it
+/// accepts any connections, only the first handshake is answered.
+pub struct GatewayDispatcher {
+    secret: UpstreamSecret,
+    answered: bool,
+}
+
+impl GatewayDispatcher {
+    pub fn new(secret: UpstreamSecret) -> Self {
+        GatewayDispatcher {
+            secret,
+            answered: false,
+        }
+    }
+}
+
+#[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
+#[async_trait]
+impl GatewayInterface for GatewayDispatcher {
+    type Error = Error;
+
+    async fn gateway_handshake(&mut self, challenge: String) -> RPCResult<String, (), Self::Error> {
+        if self.answered {
+            return Err(Error::HandshakeAlreadyAnswered);
+        }
+        self.answered = true;
+        Ok(self.secret.handshake_proof(&challenge).into())
+    }
+}
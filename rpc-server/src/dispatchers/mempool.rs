@@ -5,7 +5,7 @@ use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_mempool::{mempool::Mempool, mempool_transactions::TxPriority};
 use nimiq_rpc_interface::{
     mempool::MempoolInterface,
-    types::{HashOrTx, MempoolInfo, RPCResult},
+    types::{FeeEstimate, HashOrTx, MempoolInfo, RPCResult},
 };
 use nimiq_serde::Deserialize;
 use nimiq_transaction::Transaction;
@@ -84,6 +84,20 @@ impl MempoolInterface for MempoolDispatcher {
         Ok(self.mempool.get_rules().tx_fee_per_byte.into())
     }
 
+    async fn get_fee_estimate(
+        &mut self,
+        target_blocks: u32,
+    ) -> RPCResult<FeeEstimate, (), Self::Error> {
+        let estimate = self.mempool.estimate_fee(target_blocks);
+
+        Ok(FeeEstimate {
+            target_blocks: estimate.target_blocks,
+            fee_per_byte: estimate.fee_per_byte,
+            backlog_size: estimate.backlog_size as u64,
+        }
+        .into())
+    }
+
     async fn get_transaction_from_mempool(
         &mut self,
         hash: Blake2bHash,
@@ -3,7 +3,10 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use nimiq_network_interface::network::Network as InterfaceNetwork;
 use nimiq_network_libp2p::Network;
-use nimiq_rpc_interface::{network::NetworkInterface, types::RPCResult};
+use nimiq_rpc_interface::{
+    network::NetworkInterface,
+    types::{BannedPeer, RPCResult},
+};
 
 use crate::error::Error;
 
@@ -39,4 +42,25 @@ impl NetworkInterface for NetworkDispatcher {
             .collect::<Vec<_>>()
             .into())
     }
+
+    async fn get_banned_peers(&mut self) -> RPCResult<Vec<BannedPeer>, (), Self::Error> {
+        Ok(self
+            .network
+            .get_banned_peers()
+            .into_iter()
+            .map(|(peer_id, info)| BannedPeer {
+                peer_id: peer_id.to_string(),
+                reason: info.reason.as_str().to_owned(),
+                score: info.score,
+                last_offense: info.last_offense,
+                banned_until: info.banned_until,
+            })
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn clear_banned_peers(&mut self) -> RPCResult<(), (), Self::Error> {
+        self.network.clear_banned_peers().await;
+        Ok(().into())
+    }
 }
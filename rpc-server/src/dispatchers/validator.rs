@@ -6,20 +6,27 @@ use nimiq_keys::Address;
 use nimiq_network_libp2p::Network;
 use nimiq_rpc_interface::{types::RPCResult, validator::ValidatorInterface};
 use nimiq_serde::Serialize;
-use nimiq_validator::validator::ValidatorProxy;
+use nimiq_validator::{extra_data::expand_extra_data, validator::ValidatorProxy};
 
 use crate::error::Error;
 
 pub struct ValidatorDispatcher {
     validator: ValidatorProxy,
     consensus: ConsensusProxy<Network>,
+    /// The client version that replaces the placeholder in extra data templates.
+    client_version: &'static str,
 }
 
 impl ValidatorDispatcher {
-    pub fn new(validator: ValidatorProxy, consensus: ConsensusProxy<Network>) -> Self {
+    pub fn new(
+        validator: ValidatorProxy,
+        consensus: ConsensusProxy<Network>,
+        client_version: &'static str,
+    ) -> Self {
         ValidatorDispatcher {
             validator,
             consensus,
+            client_version,
         }
     }
 }
@@ -60,6 +67,18 @@ impl ValidatorInterface for ValidatorDispatcher {
         Ok(().into())
     }
 
+    async fn get_extra_data(&mut self) -> RPCResult<String, (), Self::Error> {
+        let extra_data = self.validator.extra_data.read();
+        Ok(String::from_utf8_lossy(&extra_data).into_owned().into())
+    }
+
+    async fn set_extra_data(&mut self, extra_data: String) -> RPCResult<(), (), Self::Error> {
+        *self.validator.extra_data.write() = expand_extra_data(&extra_data, self.client_version)?;
+
+        log::debug!("Extra data set to {}.", extra_data);
+        Ok(().into())
+    }
+
     async fn is_validator_elected(&mut self) -> RPCResult<bool, (), Self::Error> {
         let is_elected = self.validator.slot_band.read().is_some();
         Ok(is_elected.into())
@@ -1,15 +1,29 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 use nimiq_database::traits::WriteTransaction;
 use nimiq_keys::{Address, Ed25519PublicKey, Ed25519Signature, KeyPair, PrivateKey};
 use nimiq_rpc_interface::{
-    types::{RPCResult, ReturnAccount, ReturnSignature},
+    types::{
+        MnemonicType, RPCResult, ReturnAccount, ReturnDerivedAccount, ReturnMnemonicWallet,
+        ReturnSignature, SigningPolicy, TransactionKind,
+    },
     wallet::WalletInterface,
 };
-use nimiq_serde::Deserialize;
-use nimiq_utils::otp::Locked;
-use nimiq_wallet::{WalletAccount, WalletStore};
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_time::sleep;
+use nimiq_utils::{
+    otp::{Locked, Unlocked},
+    spawn,
+};
+use nimiq_wallet::{
+    account_path, DerivedAccount, MnemonicKind, MnemonicSecret, MnemonicWallet,
+    SigningPolicy as WalletSigningPolicy, TransactionKind as WalletTransactionKind, WalletAccount,
+    WalletStore, DEFAULT_DERIVATION_PATH,
+};
 use parking_lot::RwLock;
 
 use crate::{error::Error, wallets::UnlockedWallets};
@@ -22,6 +36,70 @@ fn message_from_maybe_hex(s: String, is_hex: bool) -> Result<Vec<u8>, Error> {
     }
 }
 
+fn derived_accounts(wallet: &MnemonicWallet) -> Vec<ReturnDerivedAccount> {
+    wallet
+        .accounts
+        .iter()
+        .map(|account| ReturnDerivedAccount {
+            index: account.index,
+            path: match wallet.kind {
+                MnemonicKind::Legacy => None,
+                MnemonicKind::Bip39 => account_path(&wallet.derivation_path, account.index).ok(),
+            },
+            address: account.address.clone(),
+        })
+        .collect()
+}
+
+fn wallet_signing_policy(policy: SigningPolicy) -> WalletSigningPolicy {
+    WalletSigningPolicy {
+        max_value: policy.max_value,
+        allowed_recipients: policy.allowed_recipients,
+        allowed_transaction_types: policy.allowed_transaction_types.map(|kinds| {
+            kinds
+                .into_iter()
+                .map(|kind| match kind {
+                    TransactionKind::Basic => WalletTransactionKind::Basic,
+                    TransactionKind::Vesting => WalletTransactionKind::Vesting,
+                    TransactionKind::Htlc => WalletTransactionKind::Htlc,
+                    TransactionKind::Staking => WalletTransactionKind::Staking,
+                })
+                .collect()
+        }),
+    }
+}
+
+fn signing_policy(policy: WalletSigningPolicy) -> SigningPolicy {
+    SigningPolicy {
+        max_value: policy.max_value,
+        allowed_recipients: policy.allowed_recipients,
+        allowed_transaction_types: policy.allowed_transaction_types.map(|kinds| {
+            kinds
+                .into_iter()
+                .map(|kind| match kind {
+                    WalletTransactionKind::Basic => TransactionKind::Basic,
+                    WalletTransactionKind::Vesting => TransactionKind::Vesting,
+                    WalletTransactionKind::Htlc => TransactionKind::Htlc,
+                    WalletTransactionKind::Staking => TransactionKind::Staking,
+                })
+                .collect()
+        }),
+    }
+}
+
+fn mnemonic_wallet(wallet_id: Address, wallet: &MnemonicWallet) -> ReturnMnemonicWallet {
+    let (mnemonic_type, derivation_path) = match wallet.kind {
+        MnemonicKind::Legacy => (MnemonicType::Legacy, None),
+        MnemonicKind::Bip39 => (MnemonicType::Bip39, Some(wallet.derivation_path.clone())),
+    };
+    ReturnMnemonicWallet {
+        wallet_id,
+        mnemonic_type,
+        derivation_path,
+        accounts: derived_accounts(wallet),
+    }
+}
+
 pub struct WalletDispatcher {
     wallet_store: Arc<WalletStore>,
     pub unlocked_wallets: Arc<RwLock<UnlockedWallets>>,
@@ -99,24 +177,39 @@ impl WalletInterface for WalletDispatcher {
         .into())
     }
 
-    // # TODO The duration parameter is ignored.
     async fn unlock_account(
         &mut self,
         address: Address,
         passphrase: Option<String>,
-        _duration: Option<u64>,
+        duration: Option<u64>,
     ) -> RPCResult<bool, (), Self::Error> {
         let passphrase = passphrase.unwrap_or_default();
         let account = self
             .wallet_store
             .get(&address, None)
-            .ok_or(Error::AccountNotFound(address))?;
+            .ok_or_else(|| Error::AccountNotFound(address.clone()))?;
 
         let unlocked_account = account
             .unlock(passphrase.as_bytes())
             .map_err(|_locked| Error::WrongPassphrase)?;
 
-        self.unlocked_wallets.write().insert(unlocked_account);
+        let duration = duration.map(Duration::from_secs);
+        let expires_at = duration.and_then(|duration| Instant::now().checked_add(duration));
+        let policy = self.wallet_store.get_policy(&address, None);
+        self.unlocked_wallets
+            .write()
+            .insert(unlocked_account, expires_at, policy);
+
+        // Remove the account from memory once the unlock expired.
+        if let Some(duration) = duration.filter(|_| expires_at.is_some()) {
+            let unlocked_wallets = Arc::downgrade(&self.unlocked_wallets);
+            spawn(async move {
+                sleep(duration).await;
+                if let Some(unlocked_wallets) = unlocked_wallets.upgrade() {
+                    unlocked_wallets.write().remove_expired();
+                }
+            });
+        }
 
         Ok(true.into())
     }
@@ -128,6 +221,41 @@ impl WalletInterface for WalletDispatcher {
         Ok(is_unlocked.into())
     }
 
+    async fn set_signing_policy(
+        &mut self,
+        address: Address,
+        policy: Option<SigningPolicy>,
+        passphrase: Option<String>,
+    ) -> RPCResult<(), (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        self.wallet_store
+            .get(&address, None)
+            .ok_or_else(|| Error::AccountNotFound(address.clone()))?
+            .unlock(passphrase.as_bytes())
+            .map_err(|_locked| Error::WrongPassphrase)?;
+
+        let policy = policy.map(wallet_signing_policy);
+        let mut txn = self.wallet_store.create_write_transaction();
+        self.wallet_store
+            .put_policy(&address, policy.as_ref(), &mut txn);
+        txn.commit();
+
+        self.unlocked_wallets.write().set_policy(&address, policy);
+
+        Ok(().into())
+    }
+
+    async fn get_signing_policy(
+        &mut self,
+        address: Address,
+    ) -> RPCResult<Option<SigningPolicy>, (), Self::Error> {
+        Ok(self
+            .wallet_store
+            .get_policy(&address, None)
+            .map(signing_policy)
+            .into())
+    }
+
     async fn sign(
         &mut self,
         message: String,
@@ -176,4 +304,150 @@ impl WalletInterface for WalletDispatcher {
         let message = message_from_maybe_hex(message, is_hex)?;
         Ok(WalletAccount::verify_message(&public_key, &message, &signature).into())
     }
+
+    async fn import_mnemonic(
+        &mut self,
+        mnemonic: String,
+        derivation_path: Option<String>,
+        passphrase: Option<String>,
+        encryption_passphrase: Option<String>,
+    ) -> RPCResult<ReturnMnemonicWallet, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let derivation_path =
+            derivation_path.unwrap_or_else(|| DEFAULT_DERIVATION_PATH.to_string());
+
+        let secret = match encryption_passphrase {
+            Some(encryption_passphrase) => {
+                let locked =
+                    Locked::<MnemonicSecret>::deserialize_from_vec(&hex::decode(mnemonic)?)?;
+                let unlocked = locked
+                    .unlock(encryption_passphrase.as_bytes())
+                    .map_err(|_locked| Error::WrongPassphrase)?;
+                Unlocked::into_unlocked_data(unlocked)
+            }
+            None => MnemonicSecret::new(&mnemonic)?,
+        };
+        let kind = secret.kind()?;
+
+        // The first account identifies the mnemonic.
+        let account = secret.derive_account(&derivation_path, 0)?;
+        let wallet_id = account.address.clone();
+        if self.wallet_store.get_mnemonic(&wallet_id, None).is_some() {
+            return Err(Error::MnemonicAlreadyImported(wallet_id));
+        }
+
+        let mut wallet = MnemonicWallet {
+            secret: Locked::with_defaults(secret, passphrase.as_bytes())?,
+            kind,
+            derivation_path,
+            accounts: vec![],
+        };
+        wallet.add_account(DerivedAccount {
+            index: 0,
+            address: wallet_id.clone(),
+        });
+        let locked_account = Locked::with_defaults(account, passphrase.as_bytes())?;
+
+        let mut txn = self.wallet_store.create_write_transaction();
+        self.wallet_store.put(&wallet_id, &locked_account, &mut txn);
+        self.wallet_store
+            .put_mnemonic(&wallet_id, &wallet, &mut txn);
+        txn.commit();
+
+        Ok(mnemonic_wallet(wallet_id, &wallet).into())
+    }
+
+    async fn derive_account(
+        &mut self,
+        wallet_id: Address,
+        index: u32,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnDerivedAccount, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let mut wallet = self
+            .wallet_store
+            .get_mnemonic(&wallet_id, None)
+            .ok_or_else(|| Error::MnemonicNotFound(wallet_id.clone()))?;
+
+        let secret = wallet
+            .secret
+            .unlock(passphrase.as_bytes())
+            .map_err(|_locked| Error::WrongPassphrase)?;
+        let account = secret.derive_account(&wallet.derivation_path, index)?;
+        wallet.secret = Unlocked::lock(secret);
+
+        let address = account.address.clone();
+        let locked_account = Locked::with_defaults(account, passphrase.as_bytes())?;
+        wallet.add_account(DerivedAccount {
+            index,
+            address: address.clone(),
+        });
+
+        let mut txn = self.wallet_store.create_write_transaction();
+        self.wallet_store.put(&address, &locked_account, &mut txn);
+        self.wallet_store
+            .put_mnemonic(&wallet_id, &wallet, &mut txn);
+        txn.commit();
+
+        Ok(derived_accounts(&wallet)
+            .into_iter()
+            .find(|account| account.index == index)
+            .expect("Derived account must be recorded")
+            .into())
+    }
+
+    async fn list_mnemonics(&mut self) -> RPCResult<Vec<ReturnMnemonicWallet>, (), Self::Error> {
+        let txn = self.wallet_store.create_read_transaction();
+        Ok(self
+            .wallet_store
+            .list_mnemonics(Some(&txn))
+            .into_iter()
+            .filter_map(|wallet_id| {
+                let wallet = self.wallet_store.get_mnemonic(&wallet_id, Some(&txn))?;
+                Some(mnemonic_wallet(wallet_id, &wallet))
+            })
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn list_derived_accounts(
+        &mut self,
+        wallet_id: Address,
+    ) -> RPCResult<Vec<ReturnDerivedAccount>, (), Self::Error> {
+        let wallet = self
+            .wallet_store
+            .get_mnemonic(&wallet_id, None)
+            .ok_or(Error::MnemonicNotFound(wallet_id))?;
+
+        Ok(derived_accounts(&wallet).into())
+    }
+
+    async fn export_mnemonic(
+        &mut self,
+        wallet_id: Address,
+        passphrase: Option<String>,
+        encryption_passphrase: Option<String>,
+    ) -> RPCResult<String, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let secret = self
+            .wallet_store
+            .get_mnemonic(&wallet_id, None)
+            .ok_or(Error::MnemonicNotFound(wallet_id))?
+            .secret
+            .unlock(passphrase.as_bytes())
+            .map_err(|_locked| Error::WrongPassphrase)?;
+
+        let exported = match encryption_passphrase {
+            Some(encryption_passphrase) => {
+                let locked = Locked::with_defaults(
+                    Unlocked::unlocked_data(&secret).clone(),
+                    encryption_passphrase.as_bytes(),
+                )?;
+                hex::encode(locked.serialize_to_vec())
+            }
+            None => secret.words().to_string(),
+        };
+
+        Ok(exported.into())
+    }
 }
@@ -12,9 +12,27 @@ pub enum Error {
     #[error("{0}")]
     NetworkError(#[from] nimiq_network_libp2p::NetworkError),
 
+    #[error("{0}")]
+    Request(#[from] nimiq_network_interface::request::RequestError),
+
     #[error("Mempool rejected transaction: {0}")]
     MempoolError(VerifyErr),
 
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(VerifyErr),
+
+    #[error("{0}")]
+    Accounts(#[from] nimiq_account::AccountsError),
+
+    #[error("{0}")]
+    Archive(#[from] nimiq_blockchain::ArchiveError),
+
+    #[error("{0}")]
+    ValidatorRewards(#[from] nimiq_blockchain::ValidatorRewardsError),
+
+    #[error("{0}")]
+    ExtraData(#[from] nimiq_validator::extra_data::ExtraDataError),
+
     #[error("Block not found: {0}")]
     BlockNotFound(u32),
 
@@ -51,6 +69,9 @@ pub enum Error {
     #[error("No validator with address: {0}")]
     ValidatorNotFound(Address),
 
+    #[error("Validator with address {0} holds no slots in the current epoch")]
+    ValidatorWithoutSlots(Address),
+
     #[error("Validator with address {0} is already {1}")]
     ValidatorAlreadyInState(Address, String),
 
@@ -66,6 +87,24 @@ pub enum Error {
     #[error("No unlocked wallet with address: {0}")]
     UnlockedWalletNotFound(Address),
 
+    #[error("No mnemonic with wallet ID: {0}")]
+    MnemonicNotFound(Address),
+
+    #[error("Mnemonic with wallet ID {0} is already imported")]
+    MnemonicAlreadyImported(Address),
+
+    #[error("{0}")]
+    Mnemonic(#[from] nimiq_wallet::MnemonicError),
+
+    #[error("Signing policy violated: {0}")]
+    SigningPolicy(#[from] nimiq_wallet::PolicyViolation),
+
+    #[error("No multisig commitments of wallet {0} for this transaction")]
+    MultisigCommitmentsNotFound(Address),
+
+    #[error("{0}")]
+    PartiallySignedTransaction(#[from] nimiq_wallet::PartiallySignedTransactionError),
+
     #[error("Invalid hex: {0}")]
     HexError(#[from] hex::FromHexError),
 
@@ -89,10 +128,85 @@ pub enum Error {
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("Invalid IP address or network: {0}")]
+    InvalidIpNetwork(String),
+
+    #[error("TLS configuration error: {0}")]
+    Tls(String),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] hyper::Error),
+
+    #[error("HTTP client error: {0}")]
+    HttpClient(#[from] hyper_util::client::legacy::Error),
+
+    #[error("The JSON-RPC server behind the gateway failed to prove its identity")]
+    UpstreamVerification,
+
+    #[error("The gateway handshake was already answered")]
+    HandshakeAlreadyAnswered,
+}
+
+impl Error {
+    /// Returns the name of the error variant, e.g. for use as a metrics label.
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Error::Core(..) => "Core",
+            Error::NetworkError(..) => "NetworkError",
+            Error::Request(..) => "Request",
+            Error::MempoolError(..) => "MempoolError",
+            Error::InvalidTransaction(..) => "InvalidTransaction",
+            Error::Accounts(..) => "Accounts",
+            Error::Archive(..) => "Archive",
+            Error::ValidatorRewards(..) => "ValidatorRewards",
+            Error::ExtraData(..) => "ExtraData",
+            Error::BlockNotFound(..) => "BlockNotFound",
+            Error::BlockNotFoundByHash(..) => "BlockNotFoundByHash",
+            Error::BlockNumberBeforeGenesis => "BlockNumberBeforeGenesis",
+            Error::UnexpectedMacroBlock(..) => "UnexpectedMacroBlock",
+            Error::UnexpectedMacroBlockByHash(..) => "UnexpectedMacroBlockByHash",
+            Error::NotImplemented => "NotImplemented",
+            Error::NotSupportedForLightBlockchain => "NotSupportedForLightBlockchain",
+            Error::RequiresHistoryIndex => "RequiresHistoryIndex",
+            Error::InvalidTransactionParameters => "InvalidTransactionParameters",
+            Error::TransactionBuilder(..) => "TransactionBuilder",
+            Error::AccountNotFound(..) => "AccountNotFound",
+            Error::ValidatorNotFound(..) => "ValidatorNotFound",
+            Error::ValidatorWithoutSlots(..) => "ValidatorWithoutSlots",
+            Error::ValidatorAlreadyInState(..) => "ValidatorAlreadyInState",
+            Error::ValidatorRetired(..) => "ValidatorRetired",
+            Error::StakerNotFound(..) => "StakerNotFound",
+            Error::WrongPassphrase => "WrongPassphrase",
+            Error::UnlockedWalletNotFound(..) => "UnlockedWalletNotFound",
+            Error::MnemonicNotFound(..) => "MnemonicNotFound",
+            Error::MnemonicAlreadyImported(..) => "MnemonicAlreadyImported",
+            Error::Mnemonic(..) => "Mnemonic",
+            Error::SigningPolicy(..) => "SigningPolicy",
+            Error::MultisigCommitmentsNotFound(..) => "MultisigCommitmentsNotFound",
+            Error::PartiallySignedTransaction(..) => "PartiallySignedTransaction",
+            Error::HexError(..) => "HexError",
+            Error::Serialization(..) => "Serialization",
+            Error::Argon2(..) => "Argon2",
+            Error::TransactionNotFound(..) => "TransactionNotFound",
+            Error::MultipleTransactionsFound(..) => "MultipleTransactionsFound",
+            Error::InvalidArgument(..) => "InvalidArgument",
+            Error::NoConsensus => "NoConsensus",
+            Error::Io(..) => "Io",
+            Error::InvalidIpNetwork(..) => "InvalidIpNetwork",
+            Error::Tls(..) => "Tls",
+            Error::Http(..) => "Http",
+            Error::HttpClient(..) => "HttpClient",
+            Error::UpstreamVerification => "UpstreamVerification",
+            Error::HandshakeAlreadyAnswered => "HandshakeAlreadyAnswered",
+        }
+    }
 }
 
 impl From<Error> for RpcError {
     fn from(e: Error) -> Self {
+        #[cfg(feature = "metrics")]
+        crate::rpc_metrics::RpcMetrics::note_error(&e);
         RpcError::internal_error(Some(serde_json::value::Value::String(e.to_string())))
     }
 }
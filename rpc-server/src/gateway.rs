@@ -0,0 +1,734 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener as StdTcpListener},
+    sync::Arc,
+    time::Duration,
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bytes::Bytes;
+use http_body_util::{combinators::BoxBody, BodyExt, Full, LengthLimitError, Limited};
+use hyper::{
+    body::Incoming,
+    header::{self, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+    upgrade::OnUpgrade,
+    Method, Request, Response, StatusCode, Uri,
+};
+use hyper_util::{
+    client::legacy::{connect::HttpConnector, Client},
+    rt::{TokioExecutor, TokioIo},
+};
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_time::sleep;
+use nimiq_utils::spawn;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+};
+
+use crate::{
+    access_control::{AccessControl, CorsPolicy, RpcRole},
+    error::Error,
+    rate_limiting::{RateLimitConfig, RateLimiter},
+};
+
+/// The JSON-RPC error code used when a request is rejected by the gateway.
+pub const ACCESS_DENIED_ERROR_CODE: i64 = -32_001;
+/// The JSON-RPC error code used when a request exceeds the quota of the client.
+pub const RATE_LIMITED_ERROR_CODE: i64 = -32_005;
+/// The JSON-RPC method the gateway calls to verify the JSON-RPC server behind it.
+pub const HANDSHAKE_METHOD: &str = "gatewayHandshake";
+
+/// How long the gateway waits for the JSON-RPC server to come up.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// The initial and the maximum delay after the listener failed to accept a connection.
+const ACCEPT_BACKOFF: (Duration, Duration) = (Duration::from_millis(10), Duration::from_secs(1));
+
+type GatewayBody = BoxBody<Bytes, hyper::Error>;
+
+/// The secret the gateway authenticates with at the JSON-RPC server behind it.
+///
+/// The JSON-RPC server listens on a loopback port that every local process can connect to, so it
+/// must only accept requests carrying this secret. The gateway authenticates the clients itself
+/// and replaces their credentials with the secret when forwarding their requests.
+///
+/// Another process could take the loopback port before the JSON-RPC server binds to it, so the
+/// JSON-RPC server additionally proves that it knows the handshake key (see [`HANDSHAKE_METHOD`])
+/// before the gateway forwards any requests to it. The handshake key is never sent.
+#[derive(Clone)]
+pub struct UpstreamSecret {
+    password: String,
+    handshake_key: String,
+}
+
+impl UpstreamSecret {
+    /// The username the gateway authenticates with at the JSON-RPC server.
+    pub const USERNAME: &'static str = "gateway";
+
+    /// Creates a new random secret.
+    pub fn random() -> Self {
+        Self {
+            password: hex::encode(rand::random::<[u8; 32]>()),
+            handshake_key: hex::encode(rand::random::<[u8; 32]>()),
+        }
+    }
+
+    /// The Blake2b hash of the password, for configuring the HTTP basic auth of the JSON-RPC server.
+    pub fn password_hash(&self) -> Blake2bHash {
+        self.password.hash()
+    }
+
+    /// The value of the `Authorization` header the gateway sends to the JSON-RPC server.
+    pub fn authorization(&self) -> HeaderValue {
+        let credentials = format!("{}:{}", Self::USERNAME, self.password);
+        HeaderValue::try_from(format!("Basic {}", BASE64_STANDARD.encode(credentials)))
+            .expect("Base64 is a valid header value")
+    }
+
+    /// The answer of the JSON-RPC server to the given handshake challenge.
+    pub fn handshake_proof(&self, challenge: &str) -> String {
+        format!("{}{}", self.handshake_key, challenge)
+            .hash::<Blake2bHash>()
+            .to_hex()
+    }
+}
+
+impl std::fmt::Debug for UpstreamSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UpstreamSecret").finish_non_exhaustive()
+    }
+}
+
+/// The JSON-RPC server behind the gateway.
+struct Upstream {
+    addr: SocketAddr,
+    secret: UpstreamSecret,
+    /// Keeps the connections to the JSON-RPC server alive to reuse them for later requests.
+    client: Client<HttpConnector, GatewayBody>,
+}
+
+impl Upstream {
+    fn new(addr: SocketAddr, secret: UpstreamSecret) -> Self {
+        Self {
+            addr,
+            secret,
+            client: Client::builder(TokioExecutor::new()).build_http(),
+        }
+    }
+
+    /// Forwards the request to the JSON-RPC server over a pooled connection. Upgraded connections
+    /// are not returned to the pool.
+    async fn forward(
+        &self,
+        mut request: Request<GatewayBody>,
+    ) -> Result<Response<Incoming>, Error> {
+        // The client needs the absolute URI to pick a connection from the pool.
+        let path_and_query = request
+            .uri()
+            .path_and_query()
+            .map_or("/", |path_and_query| path_and_query.as_str());
+        *request.uri_mut() = Uri::builder()
+            .scheme("http")
+            .authority(self.addr.to_string())
+            .path_and_query(path_and_query)
+            .build()
+            .expect("Upstream URI is valid");
+
+        Ok(self.client.request(request).await?)
+    }
+
+    /// Challenges the JSON-RPC server to prove that it knows the handshake key, retrying while it
+    /// isn't listening yet.
+    async fn verify(&self) -> Result<(), Error> {
+        let challenge = hex::encode(rand::random::<[u8; 32]>());
+        let mut waited = Duration::ZERO;
+        let mut delay = ACCEPT_BACKOFF.0;
+        let proof = loop {
+            match self.handshake(&challenge).await {
+                Err(Error::HttpClient(error))
+                    if error.is_connect() && waited < HANDSHAKE_TIMEOUT =>
+                {
+                    log::debug!(%error, "Waiting for the JSON-RPC server to come up");
+                    sleep(delay).await;
+                    waited += delay;
+                    delay = (delay * 2).min(ACCEPT_BACKOFF.1);
+                }
+                result => break result?,
+            }
+        };
+
+        if proof.as_deref() != Some(self.secret.handshake_proof(&challenge).as_str()) {
+            log::error!(
+                address = %self.addr,
+                "The JSON-RPC server behind the RPC gateway failed to prove its identity"
+            );
+            return Err(Error::UpstreamVerification);
+        }
+        Ok(())
+    }
+
+    /// Sends the handshake challenge and returns the proof the JSON-RPC server answered with.
+    async fn handshake(&self, challenge: &str) -> Result<Option<String>, Error> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": HANDSHAKE_METHOD,
+            "params": [challenge],
+            "id": 1,
+        });
+        let request = Request::post("/")
+            .header(header::HOST, self.addr.to_string())
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::AUTHORIZATION, self.secret.authorization())
+            .body(full(Bytes::from(body.to_string())))
+            .expect("Handshake request is valid");
+
+        let response = self.forward(request).await?;
+        let body = response.into_body().collect().await?.to_bytes();
+        let proof = serde_json::from_slice::<Value>(&body)
+            .ok()
+            .and_then(|response| response["result"]["data"].as_str().map(str::to_owned));
+        Ok(proof)
+    }
+}
+
+/// A reverse proxy in front of the JSON-RPC server that enforces the IP allow-list, the CORS
+/// policy, the user permissions and the rate limits and optionally terminates TLS, for both plain
+/// HTTP requests and WebSocket connections. If users or rate limits are configured, only users
+/// with an unrestricted role may open WebSocket connections, as the calls made over them are
+/// neither checked nor limited. Upgrading a connection counts as a single call towards the rate
+/// limits.
+///
+/// The JSON-RPC server itself should be bound to a loopback address (see [`unused_loopback_addr`])
+/// and only accept requests authenticated with the [`UpstreamSecret`] of the gateway, such that it
+/// is only reached through the gateway. It answers the handshake of the gateway with the
+/// [`GatewayDispatcher`](crate::dispatchers::GatewayDispatcher).
+pub struct RpcGateway {
+    listener: StdTcpListener,
+    upstream: Arc<Upstream>,
+    access_control: Arc<AccessControl>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl RpcGateway {
+    /// Binds the gateway to `bind_to`. Requests are forwarded to the JSON-RPC server listening at
+    /// `upstream`, authenticated with the given secret.
+    pub fn bind(
+        bind_to: SocketAddr,
+        upstream: SocketAddr,
+        secret: UpstreamSecret,
+        access_control: AccessControl,
+    ) -> Result<Self, Error> {
+        let listener = StdTcpListener::bind(bind_to)?;
+        listener.set_nonblocking(true)?;
+
+        let rate_limiter = access_control
+            .rate_limit
+            .clone()
+            .map(|config| Arc::new(RateLimiter::new(config)));
+
+        Ok(Self {
+            listener,
+            upstream: Arc::new(Upstream::new(upstream, secret)),
+            access_control: Arc::new(access_control),
+            rate_limiter,
+        })
+    }
+
+    /// The address the gateway is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Waits for the JSON-RPC server to prove its identity and then accepts connections. Fails
+    /// if the JSON-RPC server doesn't come up or fails to prove its identity.
+    pub async fn run(self) -> Result<(), Error> {
+        let listener = TcpListener::from_std(self.listener)?;
+        self.upstream.verify().await?;
+        log::info!(
+            address = %listener.local_addr()?,
+            tls = self.access_control.tls.is_some(),
+            "RPC gateway listening",
+        );
+
+        let mut backoff = ACCEPT_BACKOFF.0;
+        loop {
+            // Accepting fails e.g. when running out of file descriptors, which resolves itself
+            // once other connections are closed.
+            let (stream, peer) = match listener.accept().await {
+                Ok(connection) => {
+                    backoff = ACCEPT_BACKOFF.0;
+                    connection
+                }
+                Err(error) => {
+                    log::warn!(%error, ?backoff, "RPC gateway failed to accept connection");
+                    sleep(backoff).await;
+                    backoff = (backoff * 2).min(ACCEPT_BACKOFF.1);
+                    continue;
+                }
+            };
+            let upstream = Arc::clone(&self.upstream);
+            let access_control = Arc::clone(&self.access_control);
+            let rate_limiter = self.rate_limiter.clone();
+
+            spawn(async move {
+                match access_control.tls.as_ref() {
+                    Some(tls) => match tls.acceptor().accept(stream).await {
+                        Ok(stream) => {
+                            serve(stream, peer, upstream, access_control, rate_limiter).await
+                        }
+                        Err(error) => {
+                            log::debug!(%peer, %error, "RPC gateway TLS handshake failed")
+                        }
+                    },
+                    None => serve(stream, peer, upstream, access_control, rate_limiter).await,
+                }
+            });
+        }
+    }
+}
+
+/// Returns a loopback address with a port that is currently not in use, for binding the JSON-RPC
+/// server behind the gateway. The port might be taken by another process in the meantime, which
+/// the gateway detects with the handshake (see [`UpstreamSecret`]).
+pub fn unused_loopback_addr() -> Result<SocketAddr, Error> {
+    let listener = StdTcpListener::bind((IpAddr::V4(Ipv4Addr::LOCALHOST), 0))?;
+    Ok(listener.local_addr()?)
+}
+
+async fn serve<S>(
+    stream: S,
+    peer: SocketAddr,
+    upstream: Arc<Upstream>,
+    access_control: Arc<AccessControl>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let service = service_fn(move |request| {
+        let upstream = Arc::clone(&upstream);
+        let access_control = Arc::clone(&access_control);
+        let rate_limiter = rate_limiter.clone();
+        async move {
+            Ok::<_, Infallible>(
+                handle(
+                    request,
+                    peer,
+                    &upstream,
+                    &access_control,
+                    rate_limiter.as_deref(),
+                )
+                .await,
+            )
+        }
+    });
+
+    if let Err(error) = http1::Builder::new()
+        .serve_connection(TokioIo::new(stream), service)
+        .with_upgrades()
+        .await
+    {
+        log::debug!(%peer, %error, "RPC gateway connection error");
+    }
+}
+
+async fn handle(
+    request: Request<Incoming>,
+    peer: SocketAddr,
+    upstream: &Upstream,
+    access_control: &AccessControl,
+    rate_limiter: Option<&RateLimiter>,
+) -> Response<GatewayBody> {
+    if let Some(allowed_ips) = &access_control.allowed_ips {
+        if !allowed_ips.is_allowed(peer.ip()) {
+            log::debug!(%peer, "RPC gateway rejected request from disallowed IP");
+            return reject(
+                StatusCode::FORBIDDEN,
+                format!("IP address {} is not allowed", peer.ip()),
+            );
+        }
+    }
+
+    let origin = request.headers().get(header::ORIGIN).cloned();
+    if let (Some(cors), Some(origin)) = (&access_control.cors, &origin) {
+        if !origin
+            .to_str()
+            .is_ok_and(|origin| cors.allows_origin(origin))
+        {
+            log::debug!(%peer, ?origin, "RPC gateway rejected request from disallowed origin");
+            return reject(
+                StatusCode::FORBIDDEN,
+                format!(
+                    "Origin {} is not allowed",
+                    String::from_utf8_lossy(origin.as_bytes())
+                ),
+            );
+        }
+
+        if request.method() == Method::OPTIONS {
+            return preflight(origin.clone());
+        }
+    }
+
+    let role = match &access_control.users {
+        Some(users) => {
+            let user = request
+                .headers()
+                .get(header::AUTHORIZATION)
+                .and_then(|authorization| users.authenticate(authorization.as_bytes()));
+            match user {
+                Some(user) => Some(&user.role),
+                None => {
+                    log::debug!(%peer, "RPC gateway rejected request with invalid credentials");
+                    let mut response = unauthorized();
+                    if let (Some(_), Some(origin)) = (&access_control.cors, origin) {
+                        add_cors_headers(response.headers_mut(), origin);
+                    }
+                    return response;
+                }
+            }
+        }
+        None => None,
+    };
+
+    let max_body_size = access_control.max_body_size();
+    let mut request = match check_calls(request, peer, role, rate_limiter, max_body_size).await {
+        Ok(request) => request,
+        Err(mut response) => {
+            if let (Some(_), Some(origin)) = (&access_control.cors, origin) {
+                add_cors_headers(response.headers_mut(), origin);
+            }
+            return response;
+        }
+    };
+
+    // The credentials of the client have been checked above, the JSON-RPC server only accepts the
+    // ones of the gateway.
+    request
+        .headers_mut()
+        .insert(header::AUTHORIZATION, upstream.secret.authorization());
+
+    let is_upgrade = request.headers().contains_key(header::UPGRADE);
+    let downstream_upgrade = is_upgrade.then(|| hyper::upgrade::on(&mut request));
+
+    let mut response = match upstream.forward(request).await {
+        Ok(response) => response,
+        Err(error) => {
+            log::warn!(%error, "RPC gateway failed to reach the JSON-RPC server");
+            return reject(
+                StatusCode::BAD_GATEWAY,
+                "JSON-RPC server unavailable".to_owned(),
+            );
+        }
+    };
+
+    if let Some(downstream_upgrade) = downstream_upgrade {
+        if response.status() == StatusCode::SWITCHING_PROTOCOLS {
+            let upstream_upgrade = hyper::upgrade::on(&mut response);
+            spawn(tunnel(downstream_upgrade, upstream_upgrade));
+        }
+    }
+
+    if access_control.cors.is_some() {
+        if let Some(origin) = origin {
+            add_cors_headers(response.headers_mut(), origin);
+        }
+    }
+
+    response.map(BodyExt::boxed)
+}
+
+/// Checks the calls of the request against the role of the user and takes their cost from the
+/// quotas of the client. The body of plain HTTP requests is buffered to determine the calls, up to
+/// `max_body_size` bytes.
+///
+/// Requests calling [`HANDSHAKE_METHOD`] or any method that isn't allowed by the role are rejected
+/// as a whole. As the calls made over WebSocket connections can neither be checked nor rate
+/// limited, only users with an unrestricted role may open them if any restriction applies.
+async fn check_calls(
+    request: Request<Incoming>,
+    peer: SocketAddr,
+    role: Option<&RpcRole>,
+    rate_limiter: Option<&RateLimiter>,
+    max_body_size: usize,
+) -> Result<Request<GatewayBody>, Response<GatewayBody>> {
+    let is_unrestricted = role.is_some_and(RpcRole::is_unrestricted);
+    let role = role.filter(|role| !role.is_unrestricted());
+    let is_restricted = role.is_some() || rate_limiter.is_some();
+
+    let credentials = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .map(|credentials| credentials.as_bytes().to_vec());
+
+    let (request, calls) = if request.headers().contains_key(header::UPGRADE) {
+        if is_restricted && !is_unrestricted {
+            log::debug!(%peer, "RPC gateway rejected WebSocket upgrade of restricted client");
+            return Err(reject(
+                StatusCode::FORBIDDEN,
+                "WebSocket connections require access to all methods".to_owned(),
+            ));
+        }
+        (request.map(BodyExt::boxed), JsonRpcCalls::default())
+    } else {
+        let (parts, body) = request.into_parts();
+        let body = match Limited::new(body, max_body_size).collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(error) if error.is::<LengthLimitError>() => {
+                log::debug!(%peer, max_body_size, "RPC gateway rejected oversized request body");
+                return Err(reject(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    format!("Request body exceeds {max_body_size} bytes"),
+                ));
+            }
+            Err(error) => {
+                log::debug!(%peer, %error, "RPC gateway failed to read request body");
+                return Err(reject(
+                    StatusCode::BAD_REQUEST,
+                    "Failed to read request body".to_owned(),
+                ));
+            }
+        };
+        let calls = JsonRpcCalls::parse(&body);
+        (Request::from_parts(parts, full(body)), calls)
+    };
+
+    // The handshake is reserved for the gateway itself, which sends it to the JSON-RPC server
+    // directly when verifying it.
+    if calls
+        .calls
+        .iter()
+        .any(|(method, _)| method == HANDSHAKE_METHOD)
+    {
+        log::debug!(%peer, "RPC gateway rejected call of the handshake method");
+        return Err(calls_error(
+            &calls,
+            StatusCode::FORBIDDEN,
+            ACCESS_DENIED_ERROR_CODE,
+            &format!("Method {HANDSHAKE_METHOD} is not allowed"),
+        ));
+    }
+
+    if let Some(role) = role {
+        if calls.calls.is_empty() {
+            return Err(reject(
+                StatusCode::FORBIDDEN,
+                "Invalid JSON-RPC request".to_owned(),
+            ));
+        }
+        if let Some((method, _)) = calls
+            .calls
+            .iter()
+            .find(|(method, _)| !role.allows_method(method))
+        {
+            log::debug!(%peer, method, "RPC gateway rejected call of disallowed method");
+            return Err(calls_error(
+                &calls,
+                StatusCode::FORBIDDEN,
+                ACCESS_DENIED_ERROR_CODE,
+                &format!("Method {method} is not allowed"),
+            ));
+        }
+    }
+
+    if let Some(rate_limiter) = rate_limiter {
+        let cost = calls.cost(rate_limiter.config());
+        if let Err(retry_after) = rate_limiter.check(peer.ip(), credentials.as_deref(), cost) {
+            log::debug!(%peer, cost, ?retry_after, "RPC gateway rate limited request");
+            return Err(rate_limited(&calls, retry_after));
+        }
+    }
+
+    Ok(request)
+}
+
+/// The calls of a JSON-RPC request, as far as they are needed for access control and rate limiting.
+#[derive(Default)]
+struct JsonRpcCalls {
+    is_batch: bool,
+    /// The method and the ID of each call.
+    calls: Vec<(String, Value)>,
+}
+
+impl JsonRpcCalls {
+    /// Parses the calls from a request body. Bodies that aren't valid JSON result in no calls and
+    /// calls without a method get an empty method name. The JSON-RPC server takes care of
+    /// rejecting them.
+    fn parse(body: &[u8]) -> Self {
+        let call = |value: &Value| {
+            let method = value
+                .get("method")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_owned();
+            let id = value.get("id").cloned().unwrap_or(Value::Null);
+            (method, id)
+        };
+
+        match serde_json::from_slice(body) {
+            Ok(Value::Array(values)) => JsonRpcCalls {
+                is_batch: true,
+                calls: values.iter().map(call).collect(),
+            },
+            Ok(value) => JsonRpcCalls {
+                is_batch: false,
+                calls: vec![call(&value)],
+            },
+            Err(_) => JsonRpcCalls::default(),
+        }
+    }
+
+    /// Returns the total cost of the calls. Requests without any valid call count as a single
+    /// call.
+    fn cost(&self, config: &RateLimitConfig) -> u32 {
+        if self.calls.is_empty() {
+            return RateLimitConfig::DEFAULT_METHOD_COST;
+        }
+        self.calls
+            .iter()
+            .map(|(method, _)| config.method_cost(method))
+            .fold(0, u32::saturating_add)
+    }
+}
+
+/// Copies data between both sides of an upgraded (i.e. WebSocket) connection.
+async fn tunnel(downstream: OnUpgrade, upstream: OnUpgrade) {
+    match (downstream.await, upstream.await) {
+        (Ok(downstream), Ok(upstream)) => {
+            let mut downstream = TokioIo::new(downstream);
+            let mut upstream = TokioIo::new(upstream);
+            if let Err(error) = tokio::io::copy_bidirectional(&mut downstream, &mut upstream).await
+            {
+                log::debug!(%error, "RPC gateway WebSocket tunnel closed");
+            }
+        }
+        (Err(error), _) | (_, Err(error)) => {
+            log::debug!(%error, "RPC gateway failed to upgrade connection");
+        }
+    }
+}
+
+fn preflight(origin: HeaderValue) -> Response<GatewayBody> {
+    let mut response = Response::new(empty());
+    *response.status_mut() = StatusCode::NO_CONTENT;
+
+    let headers = response.headers_mut();
+    add_cors_headers(headers, origin);
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static(CorsPolicy::ALLOWED_METHODS),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+        HeaderValue::from_static(CorsPolicy::ALLOWED_HEADERS),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_MAX_AGE,
+        HeaderValue::from_static(CorsPolicy::MAX_AGE),
+    );
+
+    response
+}
+
+fn add_cors_headers(headers: &mut header::HeaderMap, origin: HeaderValue) {
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+        HeaderValue::from_static("true"),
+    );
+    headers.append(header::VARY, HeaderValue::from_static("Origin"));
+}
+
+/// Builds a JSON-RPC error response for requests that are rejected by the gateway.
+fn reject(status: StatusCode, message: String) -> Response<GatewayBody> {
+    json_response(
+        status,
+        json_rpc_error(ACCESS_DENIED_ERROR_CODE, &message, Value::Null),
+    )
+}
+
+/// Builds the JSON-RPC error response for requests without valid credentials.
+fn unauthorized() -> Response<GatewayBody> {
+    let mut response = reject(StatusCode::UNAUTHORIZED, "Invalid credentials".to_owned());
+    response.headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        HeaderValue::from_static("Basic realm=\"JSON-RPC\""),
+    );
+    response
+}
+
+/// Builds the JSON-RPC error response for requests that exceed the quota of the client.
+fn rate_limited(calls: &JsonRpcCalls, retry_after: Duration) -> Response<GatewayBody> {
+    let retry_after = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let mut response = calls_error(
+        calls,
+        StatusCode::TOO_MANY_REQUESTS,
+        RATE_LIMITED_ERROR_CODE,
+        &format!("Rate limit exceeded, retry after {retry_after} seconds"),
+    );
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+/// Builds a JSON-RPC error response for the given calls, with an error for each call of a batch
+/// request.
+fn calls_error(
+    calls: &JsonRpcCalls,
+    status: StatusCode,
+    code: i64,
+    message: &str,
+) -> Response<GatewayBody> {
+    let body = if calls.is_batch && !calls.calls.is_empty() {
+        Value::Array(
+            calls
+                .calls
+                .iter()
+                .map(|(_, id)| json_rpc_error(code, message, id.clone()))
+                .collect(),
+        )
+    } else {
+        let id = calls
+            .calls
+            .first()
+            .map(|(_, id)| id.clone())
+            .unwrap_or(Value::Null);
+        json_rpc_error(code, message, id)
+    };
+
+    json_response(status, body)
+}
+
+fn json_rpc_error(code: i64, message: &str, id: Value) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": code,
+            "message": message,
+        },
+        "id": id,
+    })
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<GatewayBody> {
+    let mut response = Response::new(full(body.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+fn full<B: Into<Bytes>>(body: B) -> GatewayBody {
+    Full::new(body.into())
+        .map_err(|never| match never {})
+        .boxed()
+}
+
+fn empty() -> GatewayBody {
+    full(String::new())
+}
@@ -1,6 +1,11 @@
 pub use error::Error;
 pub use nimiq_jsonrpc_server::{Config, Server};
 
+pub mod access_control;
 pub mod dispatchers;
 pub mod error;
+pub mod gateway;
+pub mod rate_limiting;
+#[cfg(feature = "metrics")]
+pub mod rpc_metrics;
 pub mod wallets;
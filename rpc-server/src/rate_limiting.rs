@@ -0,0 +1,334 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv6Addr},
+    time::{Duration, Instant},
+};
+
+use nimiq_hash::{Blake2bHash, Blake2bHasher, Hasher};
+use parking_lot::Mutex;
+
+/// How often buckets that are full again are removed.
+const CLEAN_UP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The size of a token bucket and the rate at which it is refilled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenBucketConfig {
+    /// The maximum number of tokens in the bucket, i.e. the maximum burst of request costs.
+    pub capacity: u32,
+    /// The number of tokens added to the bucket per second.
+    pub refill_per_second: f64,
+}
+
+/// The request quotas enforced by the [`RpcGateway`](crate::gateway::RpcGateway).
+///
+/// Every request costs a number of tokens depending on the method that is called (see
+/// [`RateLimitConfig::DEFAULT_METHOD_COSTS`], 1 for all other methods unless configured otherwise)
+/// and is only accepted if all buckets it is subject to hold enough tokens. For batch requests, the
+/// costs of all calls in the batch are added up.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RateLimitConfig {
+    /// If set, requests are limited per client IP address. IPv6 addresses are limited per /64
+    /// network, as a single host usually has a whole /64 network at its disposal.
+    pub per_ip: Option<TokenBucketConfig>,
+    /// If set, requests carrying an `Authorization` header are additionally limited per
+    /// credentials.
+    pub per_credential: Option<TokenBucketConfig>,
+    /// The costs of the methods that are more expensive than others. They take precedence over
+    /// the default costs.
+    pub method_costs: HashMap<String, u32>,
+}
+
+impl RateLimitConfig {
+    /// The cost of a method that has neither a configured nor a default cost.
+    pub const DEFAULT_METHOD_COST: u32 = 1;
+
+    /// The costs of the methods that are expensive for the node, unless configured otherwise.
+    /// `simulateTransaction` holds the write lock of the database while it runs, which delays
+    /// the pushing of blocks.
+    pub const DEFAULT_METHOD_COSTS: &'static [(&'static str, u32)] = &[("simulateTransaction", 20)];
+
+    /// Returns the number of tokens a call of the given method costs.
+    pub fn method_cost(&self, method: &str) -> u32 {
+        self.method_costs.get(method).copied().unwrap_or_else(|| {
+            Self::DEFAULT_METHOD_COSTS
+                .iter()
+                .find(|(name, _)| *name == method)
+                .map_or(Self::DEFAULT_METHOD_COST, |(_, cost)| *cost)
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum RateLimitKey {
+    Ip(IpAddr),
+    /// The hash of the value of the `Authorization` header.
+    Credentials(Blake2bHash),
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(config: &TokenBucketConfig, now: Instant) -> Self {
+        TokenBucket {
+            tokens: config.capacity as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, config: &TokenBucketConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * config.refill_per_second)
+            .min(config.capacity as f64);
+        self.last_refill = now;
+    }
+
+    fn is_full(&self, config: &TokenBucketConfig) -> bool {
+        self.tokens >= config.capacity as f64
+    }
+
+    /// Returns how long it takes until the bucket holds `cost` tokens. Costs are capped at the
+    /// capacity of the bucket, such that expensive calls are possible with a full bucket.
+    fn time_until_available(&self, config: &TokenBucketConfig, cost: u32) -> Duration {
+        let missing = cost.min(config.capacity) as f64 - self.tokens;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing / config.refill_per_second)
+        }
+    }
+
+    fn consume(&mut self, config: &TokenBucketConfig, cost: u32) {
+        self.tokens -= cost.min(config.capacity) as f64;
+    }
+}
+
+/// Keeps the token buckets of all clients and decides whether their requests are accepted.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    state: Mutex<RateLimiterState>,
+}
+
+#[derive(Debug)]
+struct RateLimiterState {
+    buckets: HashMap<RateLimitKey, TokenBucket>,
+    last_clean_up: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        RateLimiter {
+            config,
+            state: Mutex::new(RateLimiterState {
+                buckets: HashMap::new(),
+                last_clean_up: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Takes `cost` tokens from the buckets of the given client if all of them hold enough
+    /// tokens. Otherwise, no tokens are taken and the time after which the request would be
+    /// accepted is returned.
+    pub fn check(&self, ip: IpAddr, credentials: Option<&[u8]>, cost: u32) -> Result<(), Duration> {
+        self.check_at(Instant::now(), ip, credentials, cost)
+    }
+
+    fn check_at(
+        &self,
+        now: Instant,
+        ip: IpAddr,
+        credentials: Option<&[u8]>,
+        cost: u32,
+    ) -> Result<(), Duration> {
+        let mut keys = Vec::with_capacity(2);
+        if let Some(config) = &self.config.per_ip {
+            keys.push((RateLimitKey::Ip(ip_key(ip)), config));
+        }
+        if let (Some(config), Some(credentials)) = (&self.config.per_credential, credentials) {
+            let hash = Blake2bHasher::default().digest(credentials);
+            keys.push((RateLimitKey::Credentials(hash), config));
+        }
+
+        let mut state = self.state.lock();
+        state.clean_up(&self.config, now);
+
+        let mut retry_after = Duration::ZERO;
+        for (key, config) in &keys {
+            let bucket = state
+                .buckets
+                .entry(key.clone())
+                .or_insert_with(|| TokenBucket::new(config, now));
+            bucket.refill(config, now);
+            retry_after = retry_after.max(bucket.time_until_available(config, cost));
+        }
+        if !retry_after.is_zero() {
+            return Err(retry_after);
+        }
+
+        for (key, config) in &keys {
+            if let Some(bucket) = state.buckets.get_mut(key) {
+                bucket.consume(config, cost);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RateLimiterState {
+    /// Removes the buckets that are full again, as they are equivalent to new ones.
+    fn clean_up(&mut self, config: &RateLimitConfig, now: Instant) {
+        if now.saturating_duration_since(self.last_clean_up) < CLEAN_UP_INTERVAL {
+            return;
+        }
+        self.last_clean_up = now;
+
+        self.buckets.retain(|key, bucket| {
+            let bucket_config = match key {
+                RateLimitKey::Ip(_) => config.per_ip.as_ref(),
+                RateLimitKey::Credentials(_) => config.per_credential.as_ref(),
+            };
+            bucket_config.is_some_and(|bucket_config| {
+                bucket.refill(bucket_config, now);
+                !bucket.is_full(bucket_config)
+            })
+        });
+    }
+}
+
+/// Returns the address requests from the given IP address are accounted to.
+fn ip_key(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => IpAddr::V4(ip),
+        IpAddr::V6(ip) => {
+            let network = u128::from(ip) & !(u128::MAX >> 64);
+            IpAddr::V6(Ipv6Addr::from(network))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use nimiq_test_log::test;
+
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    const OTHER_CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+    fn rate_limiter(per_ip: Option<u32>, per_credential: Option<u32>) -> RateLimiter {
+        let bucket = |capacity| TokenBucketConfig {
+            capacity,
+            refill_per_second: 1.0,
+        };
+        RateLimiter::new(RateLimitConfig {
+            per_ip: per_ip.map(bucket),
+            per_credential: per_credential.map(bucket),
+            method_costs: HashMap::from([("getAccounts".to_owned(), 5)]),
+        })
+    }
+
+    #[test]
+    fn it_limits_requests_per_ip() {
+        let limiter = rate_limiter(Some(3), None);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.check_at(now, CLIENT, None, 1), Ok(()));
+        }
+        assert_eq!(
+            limiter.check_at(now, CLIENT, None, 1),
+            Err(Duration::from_secs(1))
+        );
+        assert_eq!(limiter.check_at(now, OTHER_CLIENT, None, 1), Ok(()));
+
+        // The bucket is refilled over time.
+        let later = now + Duration::from_secs(2);
+        assert_eq!(limiter.check_at(later, CLIENT, None, 2), Ok(()));
+        assert!(limiter.check_at(later, CLIENT, None, 1).is_err());
+    }
+
+    #[test]
+    fn it_groups_ipv6_addresses_by_network() {
+        let limiter = rate_limiter(Some(1), None);
+        let now = Instant::now();
+
+        assert_eq!(
+            limiter.check_at(now, "2001:db8::1".parse().unwrap(), None, 1),
+            Ok(())
+        );
+        assert!(limiter
+            .check_at(now, "2001:db8::2".parse().unwrap(), None, 1)
+            .is_err());
+        assert_eq!(
+            limiter.check_at(now, "2001:db8:0:1::1".parse().unwrap(), None, 1),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_requires_all_buckets_to_hold_enough_tokens() {
+        let limiter = rate_limiter(Some(10), Some(2));
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at(now, CLIENT, Some(b"alice"), 2), Ok(()));
+        assert!(limiter
+            .check_at(now, OTHER_CLIENT, Some(b"alice"), 1)
+            .is_err());
+        assert_eq!(limiter.check_at(now, OTHER_CLIENT, Some(b"bob"), 2), Ok(()));
+
+        // Rejected requests don't take tokens from the per-IP bucket.
+        for _ in 0..8 {
+            assert_eq!(limiter.check_at(now, CLIENT, None, 1), Ok(()));
+        }
+        assert!(limiter.check_at(now, CLIENT, None, 1).is_err());
+    }
+
+    #[test]
+    fn it_caps_costs_at_the_capacity() {
+        let limiter = rate_limiter(Some(3), None);
+        let now = Instant::now();
+
+        let cost = limiter.config().method_cost("getAccounts");
+        assert_eq!(cost, 5);
+        assert_eq!(limiter.check_at(now, CLIENT, None, cost), Ok(()));
+        assert_eq!(
+            limiter.check_at(now, CLIENT, None, cost),
+            Err(Duration::from_secs(3))
+        );
+        assert_eq!(limiter.config().method_cost("getBlockNumber"), 1);
+    }
+
+    #[test]
+    fn it_applies_default_method_costs() {
+        let mut config = RateLimitConfig::default();
+        assert_eq!(config.method_cost("simulateTransaction"), 20);
+
+        config
+            .method_costs
+            .insert("simulateTransaction".to_owned(), 2);
+        assert_eq!(config.method_cost("simulateTransaction"), 2);
+    }
+
+    #[test]
+    fn it_removes_full_buckets() {
+        let limiter = rate_limiter(Some(3), None);
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at(now, CLIENT, None, 1), Ok(()));
+        assert_eq!(limiter.state.lock().buckets.len(), 1);
+
+        let later = now + CLEAN_UP_INTERVAL * 2;
+        assert_eq!(limiter.check_at(later, OTHER_CLIENT, None, 1), Ok(()));
+        assert_eq!(limiter.state.lock().buckets.len(), 1);
+    }
+}
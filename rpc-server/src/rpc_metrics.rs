@@ -0,0 +1,319 @@
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt};
+use nimiq_jsonrpc_core::{Request, Response};
+use nimiq_jsonrpc_server::{Dispatcher, Message};
+use prometheus_client::{
+    encoding::EncodeLabelSet,
+    metrics::{counter::Counter, family::Family, gauge::Gauge, histogram::Histogram},
+    registry::Registry,
+};
+use tokio::sync::mpsc;
+
+use crate::error::Error;
+
+tokio::task_local! {
+    /// The metrics of the server whose request is currently being dispatched. Errors and
+    /// subscriptions are created deep inside the dispatchers, which don't know about the server.
+    static CURRENT_METRICS: Arc<RpcMetrics>;
+}
+
+pub struct RpcMetrics {
+    requests: Family<MethodLabels, Counter>,
+    failed_requests: Family<MethodLabels, Counter>,
+    request_durations: Family<MethodLabels, Histogram, fn() -> Histogram>,
+    errors: Family<ErrorLabels, Counter>,
+    rejected_requests: Counter,
+    unknown_method_requests: Counter,
+    active_subscriptions: Family<MethodLabels, Gauge>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct MethodLabels {
+    method: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct ErrorLabels {
+    error: String,
+}
+
+fn request_duration_histogram() -> Histogram {
+    Histogram::new([0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0].into_iter())
+}
+
+impl Default for RpcMetrics {
+    fn default() -> Self {
+        RpcMetrics {
+            requests: Default::default(),
+            failed_requests: Default::default(),
+            request_durations: Family::new_with_constructor(request_duration_histogram),
+            errors: Default::default(),
+            rejected_requests: Default::default(),
+            unknown_method_requests: Default::default(),
+            active_subscriptions: Default::default(),
+        }
+    }
+}
+
+impl RpcMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "requests",
+            "Number of requests per method",
+            self.requests.clone(),
+        );
+
+        registry.register(
+            "failed_requests",
+            "Number of requests per method that returned an error",
+            self.failed_requests.clone(),
+        );
+
+        registry.register(
+            "request_durations",
+            "Time it took to handle a request per method",
+            self.request_durations.clone(),
+        );
+
+        registry.register(
+            "errors",
+            "Number of errors returned per error kind",
+            self.errors.clone(),
+        );
+
+        registry.register(
+            "rejected_requests",
+            "Number of requests for methods that are not in the allowed methods",
+            self.rejected_requests.clone(),
+        );
+
+        registry.register(
+            "unknown_method_requests",
+            "Number of requests for methods that don't exist",
+            self.unknown_method_requests.clone(),
+        );
+
+        registry.register(
+            "active_subscriptions",
+            "Number of currently open subscriptions per method",
+            self.active_subscriptions.clone(),
+        );
+    }
+
+    fn note_request(&self, method: &str, duration: Duration, failed: bool) {
+        let labels = MethodLabels {
+            method: method.to_owned(),
+        };
+        self.requests.get_or_create(&labels).inc();
+        if failed {
+            self.failed_requests.get_or_create(&labels).inc();
+        }
+        self.request_durations
+            .get_or_create(&labels)
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Notes an error returned by a dispatcher. Does nothing if the request isn't dispatched
+    /// through a [`MetricsDispatcher`].
+    pub(crate) fn note_error(error: &Error) {
+        let _ = CURRENT_METRICS.try_with(|metrics| {
+            metrics
+                .errors
+                .get_or_create(&ErrorLabels {
+                    error: error.variant_name().to_owned(),
+                })
+                .inc();
+        });
+    }
+
+    /// Counts the given subscription stream as active until it is dropped. Does nothing if the
+    /// request isn't dispatched through a [`MetricsDispatcher`].
+    pub(crate) fn track_subscription<T: Send + 'static>(
+        method: &str,
+        stream: BoxStream<'static, T>,
+    ) -> BoxStream<'static, T> {
+        match CURRENT_METRICS.try_with(|metrics| {
+            metrics
+                .active_subscriptions
+                .get_or_create(&MethodLabels {
+                    method: method.to_owned(),
+                })
+                .clone()
+        }) {
+            Ok(gauge) => {
+                let guard = SubscriptionGuard::new(gauge);
+                stream
+                    .map(move |item| {
+                        let _guard = &guard;
+                        item
+                    })
+                    .boxed()
+            }
+            Err(_) => stream,
+        }
+    }
+}
+
+/// Keeps a subscription counted as active as long as it is alive.
+struct SubscriptionGuard {
+    gauge: Gauge,
+}
+
+impl SubscriptionGuard {
+    fn new(gauge: Gauge) -> Self {
+        gauge.inc();
+        SubscriptionGuard { gauge }
+    }
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        self.gauge.dec();
+    }
+}
+
+/// A dispatcher that records metrics about the requests dispatched by the inner dispatcher.
+///
+/// It is meant to wrap the [`AllowListDispatcher`](nimiq_jsonrpc_server::AllowListDispatcher),
+/// such that rejected requests are recorded as well. Per-method metrics are only recorded for
+/// methods that exist and are allowed, so arbitrary method names can't inflate the number of
+/// label values.
+pub struct MetricsDispatcher<D: Dispatcher> {
+    inner: D,
+    metrics: Arc<RpcMetrics>,
+    known_methods: HashSet<String>,
+    allowed_methods: Option<HashSet<String>>,
+}
+
+impl<D: Dispatcher> MetricsDispatcher<D> {
+    /// Wraps the given dispatcher. `allowed_methods` must be the methods allowed by the inner
+    /// dispatcher, or `None` if all methods are allowed.
+    pub fn new(
+        inner: D,
+        metrics: Arc<RpcMetrics>,
+        allowed_methods: Option<HashSet<String>>,
+    ) -> Self {
+        let known_methods = inner.method_names().into_iter().map(String::from).collect();
+        MetricsDispatcher {
+            inner,
+            metrics,
+            known_methods,
+            allowed_methods,
+        }
+    }
+}
+
+#[async_trait]
+impl<D: Dispatcher> Dispatcher for MetricsDispatcher<D> {
+    async fn dispatch(
+        &mut self,
+        request: Request,
+        tx: Option<&mpsc::Sender<Message>>,
+        id: u64,
+    ) -> Option<Response> {
+        if self
+            .allowed_methods
+            .as_ref()
+            .is_some_and(|allowed_methods| !allowed_methods.contains(&request.method))
+        {
+            self.metrics.rejected_requests.inc();
+            return self.inner.dispatch(request, tx, id).await;
+        }
+        if !self.known_methods.contains(&request.method) {
+            self.metrics.unknown_method_requests.inc();
+            return self.inner.dispatch(request, tx, id).await;
+        }
+
+        let method = request.method.clone();
+        let start = Instant::now();
+        let response = CURRENT_METRICS
+            .scope(
+                Arc::clone(&self.metrics),
+                self.inner.dispatch(request, tx, id),
+            )
+            .await;
+
+        let failed = response
+            .as_ref()
+            .is_some_and(|response| response.error.is_some());
+        self.metrics.note_request(&method, start.elapsed(), failed);
+
+        response
+    }
+
+    fn match_method(&self, name: &str) -> bool {
+        self.inner.match_method(name)
+    }
+
+    fn method_names(&self) -> Vec<&str> {
+        self.inner.method_names()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+    use nimiq_test_log::test;
+
+    use super::*;
+
+    fn active_subscriptions(metrics: &RpcMetrics, method: &str) -> i64 {
+        metrics
+            .active_subscriptions
+            .get_or_create(&MethodLabels {
+                method: method.to_owned(),
+            })
+            .get()
+    }
+
+    #[test(tokio::test)]
+    async fn it_tracks_subscriptions_until_dropped() {
+        let metrics = Arc::new(RpcMetrics::default());
+
+        let subscription = CURRENT_METRICS
+            .scope(Arc::clone(&metrics), async {
+                RpcMetrics::track_subscription("subscribe", stream::iter([1, 2]).boxed())
+            })
+            .await;
+        assert_eq!(active_subscriptions(&metrics, "subscribe"), 1);
+
+        assert_eq!(subscription.collect::<Vec<_>>().await, vec![1, 2]);
+        assert_eq!(active_subscriptions(&metrics, "subscribe"), 0);
+
+        // Outside of a dispatch, nothing is tracked.
+        let _subscription =
+            RpcMetrics::track_subscription("subscribe", stream::empty::<u8>().boxed());
+        assert_eq!(active_subscriptions(&metrics, "subscribe"), 0);
+    }
+
+    #[test(tokio::test)]
+    async fn it_counts_errors_by_variant() {
+        let metrics = Arc::new(RpcMetrics::default());
+
+        CURRENT_METRICS
+            .scope(Arc::clone(&metrics), async {
+                RpcMetrics::note_error(&Error::NotImplemented);
+                RpcMetrics::note_error(&Error::NotImplemented);
+                RpcMetrics::note_error(&Error::WrongPassphrase);
+            })
+            .await;
+        RpcMetrics::note_error(&Error::NotImplemented);
+
+        let errors = |error: &str| {
+            metrics
+                .errors
+                .get_or_create(&ErrorLabels {
+                    error: error.to_owned(),
+                })
+                .get()
+        };
+        assert_eq!(errors("NotImplemented"), 2);
+        assert_eq!(errors("WrongPassphrase"), 1);
+    }
+}
@@ -1,28 +1,161 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
 
-use nimiq_keys::Address;
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::{
+    multisig::{commitment::CommitmentPair, MUSIG2_PARAMETER_V},
+    Address,
+};
 use nimiq_utils::otp::Unlocked;
-use nimiq_wallet::WalletAccount;
+use nimiq_wallet::{SigningPolicy, WalletAccount};
+
+/// The maximum number of multisig transactions an unlocked wallet can have outstanding commitments
+/// for. Once it is reached, the commitments of the oldest transaction are discarded.
+pub const MAX_MULTISIG_COMMITMENTS: usize = 64;
+
+struct UnlockedWallet {
+    wallet: Unlocked<WalletAccount>,
+    /// The wallet is locked again once this deadline passed.
+    expires_at: Option<Instant>,
+    policy: Option<SigningPolicy>,
+    /// The secret commitment pairs for multisig transactions the wallet committed to sign,
+    /// with the hash of the transaction, oldest first. They are discarded when the wallet is
+    /// locked.
+    multisig_commitments: VecDeque<(Blake2bHash, [CommitmentPair; MUSIG2_PARAMETER_V])>,
+}
+
+impl UnlockedWallet {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
 
 #[derive(Default)]
 pub struct UnlockedWallets {
-    pub unlocked_wallets: HashMap<Address, Unlocked<WalletAccount>>,
+    unlocked_wallets: HashMap<Address, UnlockedWallet>,
 }
 
 impl UnlockedWallets {
-    pub fn insert(&mut self, wallet: Unlocked<WalletAccount>) {
+    /// Inserts an unlocked wallet, which expires at the given deadline if there is one.
+    /// Signing with the wallet is restricted by the policy if one is given.
+    pub fn insert(
+        &mut self,
+        wallet: Unlocked<WalletAccount>,
+        expires_at: Option<Instant>,
+        policy: Option<SigningPolicy>,
+    ) {
         log::info!("Unlocking {:?}", &wallet.address);
-        self.unlocked_wallets.insert(wallet.address.clone(), wallet);
+        self.remove_expired();
+        self.unlocked_wallets.insert(
+            wallet.address.clone(),
+            UnlockedWallet {
+                wallet,
+                expires_at,
+                policy,
+                multisig_commitments: VecDeque::new(),
+            },
+        );
     }
 
     pub fn get(&self, address: &Address) -> Option<&WalletAccount> {
         log::info!("Accessing {:?}", address);
-        self.unlocked_wallets
-            .get(address)
-            .map(Unlocked::unlocked_data)
+        self.get_unexpired(address)
+            .map(|unlocked| Unlocked::unlocked_data(&unlocked.wallet))
+    }
+
+    /// Returns the signing policy of an unlocked wallet.
+    pub fn get_policy(&self, address: &Address) -> Option<&SigningPolicy> {
+        self.get_unexpired(address)?.policy.as_ref()
+    }
+
+    /// Replaces the signing policy of a wallet if it is unlocked.
+    pub fn set_policy(&mut self, address: &Address, policy: Option<SigningPolicy>) {
+        if let Some(unlocked) = self.unlocked_wallets.get_mut(address) {
+            unlocked.policy = policy;
+        }
+    }
+
+    /// Returns the commitment pairs an unlocked wallet created to sign the multisig transaction
+    /// with the given hash.
+    pub fn get_multisig_commitments(
+        &self,
+        address: &Address,
+        transaction_hash: &Blake2bHash,
+    ) -> Option<&[CommitmentPair; MUSIG2_PARAMETER_V]> {
+        self.get_unexpired(address)?
+            .multisig_commitments
+            .iter()
+            .find(|(hash, _)| hash == transaction_hash)
+            .map(|(_, commitment_pairs)| commitment_pairs)
+    }
+
+    /// Stores the commitment pairs of an unlocked wallet for signing the multisig transaction
+    /// with the given hash, replacing any previous ones for the same transaction. If the wallet
+    /// already has [`MAX_MULTISIG_COMMITMENTS`] outstanding commitments, the oldest ones are
+    /// discarded. Returns `false` if the wallet isn't unlocked.
+    pub fn insert_multisig_commitments(
+        &mut self,
+        address: &Address,
+        transaction_hash: Blake2bHash,
+        commitment_pairs: [CommitmentPair; MUSIG2_PARAMETER_V],
+    ) -> bool {
+        self.remove_expired();
+        let Some(unlocked) = self.unlocked_wallets.get_mut(address) else {
+            return false;
+        };
+
+        let commitments = &mut unlocked.multisig_commitments;
+        commitments.retain(|(hash, _)| *hash != transaction_hash);
+        if commitments.len() >= MAX_MULTISIG_COMMITMENTS {
+            if let Some((evicted, _)) = commitments.pop_front() {
+                log::debug!(
+                    wallet = %address,
+                    transaction_hash = %evicted,
+                    "Discarding oldest multisig commitments"
+                );
+            }
+        }
+        commitments.push_back((transaction_hash, commitment_pairs));
+        true
+    }
+
+    /// Discards the commitment pairs for the multisig transaction with the given hash once they
+    /// have been used for a partial signature, as they must never be used twice.
+    pub fn remove_multisig_commitments(
+        &mut self,
+        address: &Address,
+        transaction_hash: &Blake2bHash,
+    ) {
+        if let Some(unlocked) = self.unlocked_wallets.get_mut(address) {
+            unlocked
+                .multisig_commitments
+                .retain(|(hash, _)| hash != transaction_hash);
+        }
     }
 
     pub fn remove(&mut self, address: &Address) -> Option<Unlocked<WalletAccount>> {
-        self.unlocked_wallets.remove(address)
+        self.unlocked_wallets
+            .remove(address)
+            .map(|unlocked| unlocked.wallet)
+    }
+
+    /// Locks all wallets whose unlock duration elapsed.
+    pub fn remove_expired(&mut self) {
+        let now = Instant::now();
+        self.unlocked_wallets.retain(|address, unlocked| {
+            let expired = unlocked.is_expired(now);
+            if expired {
+                log::info!("Locking {:?} after its unlock expired", address);
+            }
+            !expired
+        });
+    }
+
+    fn get_unexpired(&self, address: &Address) -> Option<&UnlockedWallet> {
+        self.unlocked_wallets
+            .get(address)
+            .filter(|unlocked| !unlocked.is_expired(Instant::now()))
     }
 }
@@ -0,0 +1,793 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bytes::Bytes;
+use http_body_util::{BodyExt, Empty, Full};
+use hyper::{
+    body::Incoming, header, server::conn::http1, service::service_fn, Method, Request, Response,
+    StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use nimiq_hash::Hash;
+use nimiq_rpc_server::{
+    access_control::{
+        AccessControl, CorsPolicy, IpAllowList, RpcRole, RpcUser, RpcUsers, TlsIdentity,
+    },
+    gateway::{
+        unused_loopback_addr, RpcGateway, UpstreamSecret, ACCESS_DENIED_ERROR_CODE,
+        HANDSHAKE_METHOD, RATE_LIMITED_ERROR_CODE,
+    },
+    rate_limiting::{RateLimitConfig, TokenBucketConfig},
+    Error,
+};
+use nimiq_test_log::test;
+use nimiq_time::sleep;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use tokio_rustls::{
+    rustls::{self, pki_types::ServerName, ClientConfig, RootCertStore},
+    TlsConnector,
+};
+
+const RPC_RESPONSE: &str = r#"{"jsonrpc":"2.0","result":42,"id":1}"#;
+const RPC_REQUEST: &str = r#"{"jsonrpc":"2.0","method":"getBlockNumber","params":[],"id":1}"#;
+
+/// Starts a minimal stand-in for the JSON-RPC server. It answers the handshake of the gateway,
+/// every other HTTP request carrying the secret with `RPC_RESPONSE` and echoes back everything it
+/// receives on upgraded connections.
+async fn start_upstream(secret: UpstreamSecret) -> SocketAddr {
+    let addr = unused_loopback_addr().unwrap();
+    start_upstream_at(addr, secret).await;
+    addr
+}
+
+/// Starts the stand-in for the JSON-RPC server at the given address and returns the number of
+/// connections it accepted.
+async fn start_upstream_at(addr: SocketAddr, secret: UpstreamSecret) -> Arc<AtomicUsize> {
+    let listener = TcpListener::bind(addr).await.unwrap();
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    let accepted = Arc::clone(&connections);
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            accepted.fetch_add(1, Ordering::Relaxed);
+            let secret = secret.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |mut request: Request<Incoming>| {
+                    let authorized = request.headers().get(header::AUTHORIZATION)
+                        == Some(&secret.authorization());
+                    let secret = secret.clone();
+                    async move {
+                        if !authorized {
+                            return Ok::<_, Infallible>(
+                                Response::builder()
+                                    .status(StatusCode::UNAUTHORIZED)
+                                    .body(Full::new(Bytes::new()))
+                                    .unwrap(),
+                            );
+                        }
+                        if request.headers().contains_key(header::UPGRADE) {
+                            tokio::spawn(async move {
+                                let upgraded = hyper::upgrade::on(&mut request).await.unwrap();
+                                let (mut reader, mut writer) =
+                                    tokio::io::split(TokioIo::new(upgraded));
+                                tokio::io::copy(&mut reader, &mut writer).await.ok();
+                            });
+                            return Ok(Response::builder()
+                                .status(StatusCode::SWITCHING_PROTOCOLS)
+                                .header(header::CONNECTION, "upgrade")
+                                .header(header::UPGRADE, "websocket")
+                                .body(Full::new(Bytes::new()))
+                                .unwrap());
+                        }
+                        let body = request.into_body().collect().await.unwrap().to_bytes();
+                        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                        if body["method"] == HANDSHAKE_METHOD {
+                            let proof = secret.handshake_proof(body["params"][0].as_str().unwrap());
+                            let response = serde_json::json!({
+                                "jsonrpc": "2.0",
+                                "result": { "data": proof, "metadata": null },
+                                "id": body["id"],
+                            });
+                            return Ok(Response::new(Full::new(Bytes::from(response.to_string()))));
+                        }
+                        Ok(Response::new(Full::new(Bytes::from(RPC_RESPONSE))))
+                    }
+                });
+                http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .with_upgrades()
+                    .await
+                    .ok();
+            });
+        }
+    });
+
+    connections
+}
+
+async fn start_gateway(access_control: AccessControl) -> SocketAddr {
+    start_gateway_with_upstream(access_control).await.0
+}
+
+/// Starts the gateway and returns its address and the address of the JSON-RPC server behind it.
+async fn start_gateway_with_upstream(access_control: AccessControl) -> (SocketAddr, SocketAddr) {
+    let secret = UpstreamSecret::random();
+    let upstream = start_upstream(secret.clone()).await;
+    let gateway = RpcGateway::bind(
+        (IpAddr::V4(Ipv4Addr::LOCALHOST), 0).into(),
+        upstream,
+        secret,
+        access_control,
+    )
+    .unwrap();
+    let addr = gateway.local_addr().unwrap();
+    tokio::spawn(gateway.run());
+    (addr, upstream)
+}
+
+async fn send<S>(
+    stream: S,
+    request: Request<Full<Bytes>>,
+) -> (StatusCode, header::HeaderMap, String)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .unwrap();
+    tokio::spawn(connection);
+
+    let response = sender.send_request(request).await.unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn rpc_request(origin: Option<&str>) -> Request<Full<Bytes>> {
+    rpc_request_with_body(RPC_REQUEST, origin)
+}
+
+fn rpc_request_with_body(body: &'static str, origin: Option<&str>) -> Request<Full<Bytes>> {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri("/")
+        .header(header::HOST, "localhost")
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(origin) = origin {
+        builder = builder.header(header::ORIGIN, origin);
+    }
+    builder.body(Full::new(Bytes::from(body))).unwrap()
+}
+
+fn basic_auth(username: &str, password: &str) -> String {
+    format!(
+        "Basic {}",
+        BASE64_STANDARD.encode(format!("{username}:{password}"))
+    )
+}
+
+fn users() -> RpcUsers {
+    RpcUsers::new(vec![
+        RpcUser::new("admin", "secret".hash(), RpcRole::unrestricted()),
+        RpcUser::new(
+            "explorer",
+            "public".hash(),
+            RpcRole::new(["getBlockNumber"]),
+        ),
+    ])
+}
+
+fn assert_access_denied(status: StatusCode, body: &str) {
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["error"]["code"], ACCESS_DENIED_ERROR_CODE);
+}
+
+#[test]
+fn ip_allow_list_parses_networks_and_addresses() {
+    let allow_list = IpAllowList::parse(&["10.0.0.0/8", "192.168.1.7", "fd00::/8"]).unwrap();
+
+    assert!(allow_list.is_allowed(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3))));
+    assert!(allow_list.is_allowed(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 7))));
+    assert!(!allow_list.is_allowed(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 8))));
+    assert!(allow_list.is_allowed("fd12::1".parse().unwrap()));
+    assert!(!allow_list.is_allowed(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+
+    // IPv4-mapped IPv6 addresses are matched against the IPv4 networks.
+    assert!(allow_list.is_allowed(IpAddr::V6(Ipv4Addr::new(10, 0, 0, 1).to_ipv6_mapped())));
+}
+
+#[test]
+fn ip_allow_list_rejects_invalid_entries() {
+    assert!(matches!(
+        IpAllowList::parse(&["10.0.0.0/33"]),
+        Err(Error::InvalidIpNetwork(_))
+    ));
+    assert!(matches!(
+        IpAllowList::parse(&["localhost"]),
+        Err(Error::InvalidIpNetwork(_))
+    ));
+}
+
+#[test]
+fn users_are_authenticated_with_basic_auth() {
+    let users = users();
+
+    let user = users.authenticate(basic_auth("explorer", "public").as_bytes());
+    assert_eq!(user.map(|user| user.username.as_str()), Some("explorer"));
+    assert!(!user.unwrap().role.allows_method("getPeerCount"));
+
+    assert!(users
+        .authenticate(basic_auth("admin", "secret").as_bytes())
+        .unwrap()
+        .role
+        .is_unrestricted());
+    assert!(users
+        .authenticate(basic_auth("explorer", "secret").as_bytes())
+        .is_none());
+    assert!(users.authenticate(b"Basic invalid").is_none());
+    assert!(users.authenticate(b"Bearer token").is_none());
+}
+
+#[test]
+fn cors_policy_matches_origins() {
+    let cors = CorsPolicy::new(["https://Dashboard.example.com/"]);
+    assert!(cors.allows_origin("https://dashboard.example.com"));
+    assert!(!cors.allows_origin("http://dashboard.example.com"));
+    assert!(!cors.allows_origin("https://evil.example.com"));
+
+    let cors = CorsPolicy::new(["*"]);
+    assert!(cors.allows_origin("https://evil.example.com"));
+}
+
+#[test(tokio::test)]
+async fn it_forwards_requests_from_allowed_networks() {
+    let addr = start_gateway(AccessControl {
+        allowed_ips: Some(IpAllowList::parse(&["127.0.0.0/8"]).unwrap()),
+        ..Default::default()
+    })
+    .await;
+
+    let (status, _, body) = send(TcpStream::connect(addr).await.unwrap(), rpc_request(None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+}
+
+#[test(tokio::test)]
+async fn it_rejects_requests_from_disallowed_networks() {
+    let addr = start_gateway(AccessControl {
+        allowed_ips: Some(IpAllowList::parse(&["10.0.0.0/8"]).unwrap()),
+        ..Default::default()
+    })
+    .await;
+
+    let (status, _, body) = send(TcpStream::connect(addr).await.unwrap(), rpc_request(None)).await;
+    assert_access_denied(status, &body);
+}
+
+#[test(tokio::test)]
+async fn it_applies_the_ip_allow_list_to_websocket_upgrades() {
+    let websocket_request = || {
+        Request::builder()
+            .method(Method::GET)
+            .uri("/ws")
+            .header(header::HOST, "localhost")
+            .header(header::CONNECTION, "upgrade")
+            .header(header::UPGRADE, "websocket")
+            .body(Empty::<Bytes>::new())
+            .unwrap()
+    };
+
+    // Disallowed
+    let addr = start_gateway(AccessControl {
+        allowed_ips: Some(IpAllowList::parse(&["10.0.0.0/8"]).unwrap()),
+        ..Default::default()
+    })
+    .await;
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .unwrap();
+    tokio::spawn(connection.with_upgrades());
+    let response = sender.send_request(websocket_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Allowed
+    let addr = start_gateway(AccessControl {
+        allowed_ips: Some(IpAllowList::parse(&["127.0.0.1"]).unwrap()),
+        ..Default::default()
+    })
+    .await;
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .unwrap();
+    tokio::spawn(connection.with_upgrades());
+    let response = sender.send_request(websocket_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+
+    let mut upgraded = TokioIo::new(hyper::upgrade::on(response).await.unwrap());
+    upgraded.write_all(b"ping").await.unwrap();
+    let mut buffer = [0u8; 4];
+    upgraded.read_exact(&mut buffer).await.unwrap();
+    assert_eq!(&buffer, b"ping");
+}
+
+#[test(tokio::test)]
+async fn it_answers_cors_preflight_requests() {
+    let addr = start_gateway(AccessControl {
+        cors: Some(CorsPolicy::new(["https://dashboard.example.com"])),
+        ..Default::default()
+    })
+    .await;
+
+    let preflight = |origin: &str| {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/")
+            .header(header::HOST, "localhost")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Full::new(Bytes::new()))
+            .unwrap()
+    };
+
+    let (status, headers, _) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        preflight("https://dashboard.example.com"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "https://dashboard.example.com"
+    );
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_METHODS],
+        CorsPolicy::ALLOWED_METHODS
+    );
+
+    let (status, _, body) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        preflight("https://evil.example.com"),
+    )
+    .await;
+    assert_access_denied(status, &body);
+}
+
+#[test(tokio::test)]
+async fn it_checks_the_origin_of_cross_origin_requests() {
+    let addr = start_gateway(AccessControl {
+        cors: Some(CorsPolicy::new(["https://dashboard.example.com"])),
+        ..Default::default()
+    })
+    .await;
+
+    let (status, headers, body) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        rpc_request(Some("https://dashboard.example.com")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "https://dashboard.example.com"
+    );
+
+    let (status, _, body) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        rpc_request(Some("https://evil.example.com")),
+    )
+    .await;
+    assert_access_denied(status, &body);
+
+    // Requests that don't come from a browser don't carry an origin and are not affected.
+    let (status, headers, body) =
+        send(TcpStream::connect(addr).await.unwrap(), rpc_request(None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+    assert!(!headers.contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+}
+
+#[test(tokio::test)]
+async fn it_terminates_tls() {
+    let certificate = rcgen::generate_simple_self_signed(vec!["localhost".to_owned()]).unwrap();
+    let certificate_der = certificate.serialize_der().unwrap();
+    let identity = TlsIdentity::new(
+        vec![certificate_der.clone()],
+        certificate.serialize_private_key_der(),
+    )
+    .unwrap();
+
+    let addr = start_gateway(AccessControl {
+        tls: Some(identity),
+        ..Default::default()
+    })
+    .await;
+
+    let mut root_store = RootCertStore::empty();
+    root_store.add(certificate_der.into()).unwrap();
+    let client_config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+
+    let stream = TlsConnector::from(Arc::new(client_config))
+        .connect(
+            ServerName::try_from("localhost").unwrap(),
+            TcpStream::connect(addr).await.unwrap(),
+        )
+        .await
+        .unwrap();
+    let (status, _, body) = send(stream, rpc_request(None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+
+    // Plain HTTP is not accepted.
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .unwrap();
+    tokio::spawn(connection);
+    assert!(sender.send_request(rpc_request(None)).await.is_err());
+}
+
+#[test(tokio::test)]
+async fn it_rate_limits_requests() {
+    let addr = start_gateway(AccessControl {
+        rate_limit: Some(RateLimitConfig {
+            per_ip: Some(TokenBucketConfig {
+                capacity: 3,
+                refill_per_second: 0.001,
+            }),
+            method_costs: HashMap::from([("getBlockNumber".to_owned(), 2)]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .await;
+
+    let (status, _, body) = send(TcpStream::connect(addr).await.unwrap(), rpc_request(None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+
+    let (status, headers, body) =
+        send(TcpStream::connect(addr).await.unwrap(), rpc_request(None)).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(headers.contains_key(header::RETRY_AFTER));
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["error"]["code"], RATE_LIMITED_ERROR_CODE);
+    assert_eq!(body["id"], 1);
+
+    // Batch requests are charged the costs of all their calls and get an error for each call.
+    let batch = r#"[
+        {"jsonrpc":"2.0","method":"getPeerCount","params":[],"id":2},
+        {"jsonrpc":"2.0","method":"getPeerCount","params":[],"id":3}
+    ]"#;
+    let (status, _, body) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        rpc_request_with_body(batch, None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body[0]["id"], 2);
+    assert_eq!(body[1]["id"], 3);
+    assert_eq!(body[1]["error"]["code"], RATE_LIMITED_ERROR_CODE);
+
+    let cheap_call = r#"{"jsonrpc":"2.0","method":"getPeerCount","params":[],"id":4}"#;
+    let (status, _, _) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        rpc_request_with_body(cheap_call, None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[test(tokio::test)]
+async fn it_checks_the_permissions_of_users() {
+    let addr = start_gateway(AccessControl {
+        users: Some(users()),
+        ..Default::default()
+    })
+    .await;
+
+    let request = |body, credentials: Option<(&str, &str)>| {
+        let mut request = rpc_request_with_body(body, None);
+        if let Some((username, password)) = credentials {
+            request.headers_mut().insert(
+                header::AUTHORIZATION,
+                basic_auth(username, password).parse().unwrap(),
+            );
+        }
+        request
+    };
+    let peer_count = r#"{"jsonrpc":"2.0","method":"getPeerCount","params":[],"id":2}"#;
+
+    for credentials in [None, Some(("explorer", "secret"))] {
+        let (status, headers, _) = send(
+            TcpStream::connect(addr).await.unwrap(),
+            request(RPC_REQUEST, credentials),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(headers.contains_key(header::WWW_AUTHENTICATE));
+    }
+
+    let (status, _, body) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        request(RPC_REQUEST, Some(("explorer", "public"))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+
+    let (status, _, body) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        request(peer_count, Some(("explorer", "public"))),
+    )
+    .await;
+    assert_access_denied(status, &body);
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["id"], 2);
+
+    // Restricted users can't open WebSocket connections.
+    let mut upgrade = request("", Some(("explorer", "public")));
+    *upgrade.method_mut() = Method::GET;
+    upgrade
+        .headers_mut()
+        .insert(header::CONNECTION, "upgrade".parse().unwrap());
+    upgrade
+        .headers_mut()
+        .insert(header::UPGRADE, "websocket".parse().unwrap());
+    let (status, _, body) = send(TcpStream::connect(addr).await.unwrap(), upgrade).await;
+    assert_access_denied(status, &body);
+
+    let (status, _, body) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        request(peer_count, Some(("admin", "secret"))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+}
+
+#[test(tokio::test)]
+async fn it_authenticates_at_the_upstream_with_its_own_secret() {
+    let (addr, upstream) = start_gateway_with_upstream(AccessControl {
+        users: Some(users()),
+        ..Default::default()
+    })
+    .await;
+
+    // The credentials of the users are replaced by the secret of the gateway.
+    let mut request = rpc_request(None);
+    request.headers_mut().insert(
+        header::AUTHORIZATION,
+        basic_auth("admin", "secret").parse().unwrap(),
+    );
+    let (status, _, body) = send(TcpStream::connect(addr).await.unwrap(), request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+
+    // The JSON-RPC server can't be used without going through the gateway, not even with the
+    // credentials of a user.
+    let mut request = rpc_request(None);
+    request.headers_mut().insert(
+        header::AUTHORIZATION,
+        basic_auth("admin", "secret").parse().unwrap(),
+    );
+    let (status, _, _) = send(TcpStream::connect(upstream).await.unwrap(), request).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[test(tokio::test)]
+async fn it_waits_for_the_upstream_to_come_up() {
+    let secret = UpstreamSecret::random();
+    let upstream = unused_loopback_addr().unwrap();
+    let gateway = RpcGateway::bind(
+        (IpAddr::V4(Ipv4Addr::LOCALHOST), 0).into(),
+        upstream,
+        secret.clone(),
+        AccessControl::default(),
+    )
+    .unwrap();
+    let addr = gateway.local_addr().unwrap();
+    tokio::spawn(gateway.run());
+
+    sleep(Duration::from_millis(100)).await;
+    start_upstream_at(upstream, secret).await;
+
+    let (status, _, body) = send(TcpStream::connect(addr).await.unwrap(), rpc_request(None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+}
+
+#[test(tokio::test)]
+async fn it_rejects_calls_of_the_handshake_method() {
+    const HANDSHAKE_REQUEST: &str =
+        r#"{"jsonrpc":"2.0","method":"gatewayHandshake","params":["challenge"],"id":1}"#;
+    const BATCH_REQUEST: &str = r#"[{"jsonrpc":"2.0","method":"getBlockNumber","params":[],"id":1},{"jsonrpc":"2.0","method":"gatewayHandshake","params":["challenge"],"id":2}]"#;
+
+    // Not even unrestricted users may call it.
+    let addr = start_gateway(AccessControl {
+        users: Some(users()),
+        ..Default::default()
+    })
+    .await;
+    for body in [HANDSHAKE_REQUEST, BATCH_REQUEST] {
+        let mut request = rpc_request_with_body(body, None);
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            basic_auth("admin", "secret").parse().unwrap(),
+        );
+        let (status, _, body) = send(TcpStream::connect(addr).await.unwrap(), request).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(body.contains(HANDSHAKE_METHOD));
+    }
+
+    // It is also rejected if no restrictions are configured.
+    let addr = start_gateway(AccessControl::default()).await;
+    let (status, _, body) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        rpc_request_with_body(HANDSHAKE_REQUEST, None),
+    )
+    .await;
+    assert_access_denied(status, &body);
+}
+
+#[test(tokio::test)]
+async fn it_reuses_upstream_connections() {
+    let secret = UpstreamSecret::random();
+    let upstream = unused_loopback_addr().unwrap();
+    let connections = start_upstream_at(upstream, secret.clone()).await;
+    let gateway = RpcGateway::bind(
+        (IpAddr::V4(Ipv4Addr::LOCALHOST), 0).into(),
+        upstream,
+        secret,
+        AccessControl::default(),
+    )
+    .unwrap();
+    let addr = gateway.local_addr().unwrap();
+    tokio::spawn(gateway.run());
+
+    for _ in 0..3 {
+        let (status, _, body) =
+            send(TcpStream::connect(addr).await.unwrap(), rpc_request(None)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, RPC_RESPONSE);
+        // Give the gateway a moment to return the connection to its pool.
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    // The connection of the handshake is used for all requests.
+    assert_eq!(connections.load(Ordering::Relaxed), 1);
+}
+
+#[test(tokio::test)]
+async fn it_refuses_to_forward_requests_to_an_unverified_upstream() {
+    // Another process took the port of the JSON-RPC server.
+    let impostor = start_upstream(UpstreamSecret::random()).await;
+    let gateway = RpcGateway::bind(
+        (IpAddr::V4(Ipv4Addr::LOCALHOST), 0).into(),
+        impostor,
+        UpstreamSecret::random(),
+        AccessControl::default(),
+    )
+    .unwrap();
+
+    assert!(matches!(
+        gateway.run().await,
+        Err(Error::UpstreamVerification)
+    ));
+}
+
+#[test(tokio::test)]
+async fn it_only_opens_websocket_connections_of_unrestricted_users_if_rate_limited() {
+    let addr = start_gateway(AccessControl {
+        rate_limit: Some(RateLimitConfig {
+            per_ip: Some(TokenBucketConfig {
+                capacity: 100,
+                refill_per_second: 1.0,
+            }),
+            ..Default::default()
+        }),
+        users: Some(users()),
+        ..Default::default()
+    })
+    .await;
+
+    let upgrade = |credentials: (&str, &str)| {
+        Request::builder()
+            .method(Method::GET)
+            .uri("/ws")
+            .header(header::HOST, "localhost")
+            .header(header::CONNECTION, "upgrade")
+            .header(header::UPGRADE, "websocket")
+            .header(
+                header::AUTHORIZATION,
+                basic_auth(credentials.0, credentials.1),
+            )
+            .body(Full::new(Bytes::new()))
+            .unwrap()
+    };
+
+    let (status, _, body) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        upgrade(("explorer", "public")),
+    )
+    .await;
+    assert_access_denied(status, &body);
+
+    let (status, _, _) = send(
+        TcpStream::connect(addr).await.unwrap(),
+        upgrade(("admin", "secret")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::SWITCHING_PROTOCOLS);
+
+    // Without users, nobody can open a WebSocket connection that bypasses the rate limits.
+    let addr = start_gateway(AccessControl {
+        rate_limit: Some(RateLimitConfig {
+            per_ip: Some(TokenBucketConfig {
+                capacity: 100,
+                refill_per_second: 1.0,
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .await;
+    let mut request = upgrade(("admin", "secret"));
+    request.headers_mut().remove(header::AUTHORIZATION);
+    let (status, _, body) = send(TcpStream::connect(addr).await.unwrap(), request).await;
+    assert_access_denied(status, &body);
+}
+
+#[test(tokio::test)]
+async fn it_rejects_oversized_request_bodies() {
+    let addr = start_gateway(AccessControl {
+        rate_limit: Some(RateLimitConfig {
+            per_ip: Some(TokenBucketConfig {
+                capacity: 100,
+                refill_per_second: 1.0,
+            }),
+            ..Default::default()
+        }),
+        max_body_size: Some(RPC_REQUEST.len()),
+        ..Default::default()
+    })
+    .await;
+
+    let (status, _, body) = send(TcpStream::connect(addr).await.unwrap(), rpc_request(None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, RPC_RESPONSE);
+
+    let oversized = format!("{RPC_REQUEST} ");
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/")
+        .header(header::HOST, "localhost")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(oversized)))
+        .unwrap();
+    let (status, _, _) = send(TcpStream::connect(addr).await.unwrap(), request).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
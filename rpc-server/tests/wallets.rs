@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_keys::{
+    multisig::{commitment::CommitmentPair, MUSIG2_PARAMETER_V},
+    Address, SecureGenerate,
+};
+use nimiq_primitives::coin::Coin;
+use nimiq_rpc_server::wallets::{UnlockedWallets, MAX_MULTISIG_COMMITMENTS};
+use nimiq_test_log::test;
+use nimiq_utils::otp::Unlocked;
+use nimiq_wallet::{SigningPolicy, WalletAccount};
+
+fn insert_wallet(
+    unlocked_wallets: &mut UnlockedWallets,
+    expires_at: Option<Instant>,
+    policy: Option<SigningPolicy>,
+) -> Address {
+    let wallet = Unlocked::with_defaults(WalletAccount::generate(), b"password").unwrap();
+    let address = wallet.address.clone();
+    unlocked_wallets.insert(wallet, expires_at, policy);
+    address
+}
+
+#[test]
+fn it_locks_wallets_once_their_unlock_expired() {
+    let mut unlocked_wallets = UnlockedWallets::default();
+    let policy = SigningPolicy {
+        max_value: Some(Coin::from_u64_unchecked(1)),
+        ..Default::default()
+    };
+
+    let unexpired = insert_wallet(
+        &mut unlocked_wallets,
+        Some(Instant::now() + Duration::from_secs(3600)),
+        Some(policy.clone()),
+    );
+    let permanent = insert_wallet(&mut unlocked_wallets, None, None);
+    let expired = insert_wallet(
+        &mut unlocked_wallets,
+        Some(Instant::now()),
+        Some(policy.clone()),
+    );
+
+    // An expired wallet can't be used anymore, even before it is removed.
+    assert!(unlocked_wallets.get(&expired).is_none());
+    assert!(unlocked_wallets.get_policy(&expired).is_none());
+    assert!(unlocked_wallets.get(&unexpired).is_some());
+    assert_eq!(unlocked_wallets.get_policy(&unexpired), Some(&policy));
+    assert!(unlocked_wallets.get(&permanent).is_some());
+    assert!(unlocked_wallets.get_policy(&permanent).is_none());
+
+    unlocked_wallets.remove_expired();
+    assert!(unlocked_wallets.remove(&expired).is_none());
+    assert!(unlocked_wallets.remove(&unexpired).is_some());
+    assert!(unlocked_wallets.remove(&permanent).is_some());
+}
+
+#[test]
+fn it_discards_the_oldest_multisig_commitments() {
+    let mut unlocked_wallets = UnlockedWallets::default();
+    let address = insert_wallet(&mut unlocked_wallets, None, None);
+    let transaction_hash = |i: usize| i.to_string().hash::<Blake2bHash>();
+    let commitment_pairs =
+        || [(); MUSIG2_PARAMETER_V].map(|_| CommitmentPair::generate_default_csprng());
+
+    for i in 0..=MAX_MULTISIG_COMMITMENTS {
+        assert!(unlocked_wallets.insert_multisig_commitments(
+            &address,
+            transaction_hash(i),
+            commitment_pairs()
+        ));
+    }
+
+    // The commitments of the first transaction were discarded to make room for the last one.
+    assert!(unlocked_wallets
+        .get_multisig_commitments(&address, &transaction_hash(0))
+        .is_none());
+    for i in 1..=MAX_MULTISIG_COMMITMENTS {
+        assert!(unlocked_wallets
+            .get_multisig_commitments(&address, &transaction_hash(i))
+            .is_some());
+    }
+
+    // Removing commitments makes room for new ones.
+    unlocked_wallets.remove_multisig_commitments(&address, &transaction_hash(1));
+    assert!(unlocked_wallets.insert_multisig_commitments(
+        &address,
+        transaction_hash(0),
+        commitment_pairs()
+    ));
+    assert!(unlocked_wallets
+        .get_multisig_commitments(&address, &transaction_hash(2))
+        .is_some());
+
+    // Commitments can only be stored for unlocked wallets.
+    assert!(!unlocked_wallets.insert_multisig_commitments(
+        &Address::from([0x77; 20]),
+        transaction_hash(0),
+        commitment_pairs()
+    ));
+}
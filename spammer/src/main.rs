@@ -150,7 +150,9 @@ async fn main_inner() -> Result<(), Error> {
         passive: false,
         sync_mode: None,
         network: None,
+        migrations_dry_run: false,
         prove: false,
+        command: None,
     };
 
     // Parse config file - this will obey the `--config` command line option.
@@ -219,12 +221,20 @@ async fn main_inner() -> Result<(), Error> {
     log::info!("Client initialized");
 
     // Initialize RPC server
-    if let Some(rpc_config) = rpc_config {
+    let rpc_metrics = rpc_config.map(|rpc_config| {
         use nimiq::extras::rpc_server::initialize_rpc_server;
         let rpc_server = initialize_rpc_server(&client, rpc_config, client.wallet_store())
             .expect("Failed to initialize RPC server");
-        spawn(async move { rpc_server.run().await });
-    }
+        let rpc_metrics = rpc_server.metrics();
+        spawn(async move {
+            // The RPC server is unreachable without its gateway, so we stop the spammer.
+            if let Err(error) = rpc_server.run().await {
+                log_error_cause_chain(&error);
+                std::process::exit(1);
+            }
+        });
+        rpc_metrics
+    });
 
     // Start consensus.
     let consensus = client.take_consensus().unwrap();
@@ -245,6 +255,8 @@ async fn main_inner() -> Result<(), Error> {
             metrics_config.addr,
             client.blockchain(),
             client.mempool(),
+            client.validator_proxy(),
+            rpc_metrics,
             client.consensus_proxy(),
             client.network(),
             &[],
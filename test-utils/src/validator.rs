@@ -55,6 +55,7 @@ where
             signing_key,
             voting_key,
             fee_key,
+            vec![],
             MempoolConfig::default(),
         ),
         consensus,
@@ -2,6 +2,7 @@ use nimiq_bls::KeyPair as BlsKeyPair;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::{Address, Ed25519PublicKey, KeyPair};
 use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
+use nimiq_serde::Serialize;
 use nimiq_transaction::{
     account::htlc_contract::{AnyHash, PreImage},
     SignatureProof, Transaction,
@@ -68,6 +69,12 @@ pub enum TransactionBuilderError {
     /// [`signaling transaction`]: struct.TransactionBuilder.html#method.with_value
     #[error("The value must be zero for signaling transactions and cannot be zero for others.")]
     InvalidValue,
+    /// The fee per byte set by [`with_fee_per_byte`] is negative or not finite, or the resulting
+    /// fee exceeds the maximum amount of coins.
+    ///
+    /// [`with_fee_per_byte`]: struct.TransactionBuilder.html#method.with_fee_per_byte
+    #[error("The fee per byte is invalid.")]
+    InvalidFeePerByte,
 }
 
 /// A helper to build arbitrary transactions.
@@ -94,6 +101,7 @@ pub struct TransactionBuilder {
     sender: Option<Sender>,
     value: Option<Coin>,
     fee: Option<Coin>,
+    fee_per_byte: Option<f64>,
     recipient: Option<Recipient>,
     validity_start_height: Option<u32>,
     network_id: Option<NetworkId>,
@@ -221,6 +229,46 @@ impl TransactionBuilder {
     /// ```
     pub fn with_fee(&mut self, fee: Coin) -> &mut Self {
         self.fee = Some(fee);
+        self.fee_per_byte = None;
+        self
+    }
+
+    /// Sets the transaction `fee` such that the transaction pays more than the given fee per byte,
+    /// e.g. a fee estimate of the mempool. This replaces a fee set by [`with_fee`].
+    ///
+    /// The fee is computed for the size of the transaction including a signature proof of a
+    /// single Ed25519 key. Transactions with larger proofs pay a bit less per byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nimiq_transaction_builder::{TransactionBuilder, Recipient, Sender};
+    /// use nimiq_keys::Address;
+    /// use nimiq_primitives::coin::Coin;
+    /// use nimiq_primitives::networks::NetworkId;
+    ///
+    /// let sender = Sender::new_basic(Address::from_any_str("NQ46 MNYU LQ93 GYYS P5DC YA51 L5JP UPUT KR62").unwrap());
+    /// let recipient = Recipient::new_basic(
+    ///     Address::from_any_str("NQ25 B7NR A1HC V4R2 YRKD 20PR RPGS MNV7 D812").unwrap()
+    /// );
+    /// let mut builder = TransactionBuilder::with_required(
+    ///     sender,
+    ///     recipient,
+    ///     Coin::from_u64_unchecked(100),
+    ///     1,
+    ///     NetworkId::Main
+    /// );
+    /// builder.with_fee_per_byte(2.5);
+    ///
+    /// let proof_builder = builder.generate().unwrap();
+    /// let transaction = proof_builder.preliminary_transaction();
+    /// assert!(transaction.fee > Coin::ZERO);
+    /// ```
+    ///
+    /// [`with_fee`]: struct.TransactionBuilder.html#method.with_fee
+    pub fn with_fee_per_byte(&mut self, fee_per_byte: f64) -> &mut Self {
+        self.fee = None;
+        self.fee_per_byte = Some(fee_per_byte);
         self
     }
 
@@ -373,7 +421,7 @@ impl TransactionBuilder {
         }
 
         // Currently, the flags for creation & signaling can never occur at the same time.
-        let tx = if recipient.is_creation() {
+        let mut tx = if recipient.is_creation() {
             Transaction::new_contract_creation(
                 sender.address(),
                 sender.account_type(),
@@ -411,8 +459,23 @@ impl TransactionBuilder {
             )
         };
 
+        if let Some(fee_per_byte) = self.fee_per_byte {
+            tx.fee = Self::fee_exceeding(fee_per_byte, &tx)?;
+        }
+
         Ok(TransactionProofBuilder::new(tx))
     }
+
+    /// Returns the smallest fee for which the given transaction pays more than the given fee per
+    /// byte once it is signed by a single Ed25519 key.
+    fn fee_exceeding(fee_per_byte: f64, tx: &Transaction) -> Result<Coin, TransactionBuilderError> {
+        let mut signed_tx = tx.clone();
+        signed_tx.proof =
+            SignatureProof::from_ed25519(Default::default(), Default::default()).serialize_to_vec();
+
+        Transaction::fee_exceeding(fee_per_byte, signed_tx.serialized_size())
+            .ok_or(TransactionBuilderError::InvalidFeePerByte)
+    }
 }
 
 // Convenience functionality.
@@ -0,0 +1,78 @@
+use nimiq_keys::{Address, KeyPair, PrivateKey};
+use nimiq_primitives::{coin::Coin, networks::NetworkId};
+use nimiq_serde::Deserialize;
+use nimiq_test_log::test;
+use nimiq_transaction_builder::{Recipient, Sender, TransactionBuilder, TransactionBuilderError};
+
+fn builder(key_pair: &KeyPair) -> TransactionBuilder {
+    TransactionBuilder::with_required(
+        Sender::new_basic(Address::from(key_pair)),
+        Recipient::new_basic(Address::from([2u8; 20])),
+        Coin::from_u64_unchecked(100),
+        1,
+        NetworkId::UnitAlbatross,
+    )
+}
+
+#[test]
+fn it_can_set_the_fee_per_byte() {
+    let key_pair = KeyPair::from(
+        PrivateKey::deserialize_from_vec(
+            &hex::decode("9d5bd02379e7e45cf515c788048f5cf3c454ffabd3e83bd1d7667716c325c3c0")
+                .unwrap(),
+        )
+        .unwrap(),
+    );
+
+    for fee_per_byte in [0.0, 1.0, 2.5, 1000.0] {
+        let mut builder = builder(&key_pair);
+        builder.with_fee_per_byte(fee_per_byte);
+        let mut proof_builder = builder.generate().unwrap().unwrap_basic();
+        proof_builder.sign_with_key_pair(&key_pair);
+        let tx = proof_builder.generate().unwrap();
+
+        // The fee is computed for the extended format, so basic transactions pay slightly more.
+        assert!(tx.fee_per_byte() > fee_per_byte);
+        assert!(tx.fee_per_byte() < fee_per_byte * 1.5 + 1.0);
+    }
+
+    // A fixed fee replaces the fee per byte and vice versa.
+    let mut builder = builder(&key_pair);
+    builder
+        .with_fee_per_byte(1.0)
+        .with_fee(Coin::from_u64_unchecked(7));
+    assert_eq!(
+        builder
+            .clone()
+            .generate()
+            .unwrap()
+            .preliminary_transaction()
+            .fee,
+        Coin::from_u64_unchecked(7)
+    );
+    builder.with_fee_per_byte(0.0);
+    assert_eq!(
+        builder.generate().unwrap().preliminary_transaction().fee,
+        Coin::from_u64_unchecked(1)
+    );
+}
+
+#[test]
+fn it_rejects_invalid_fees_per_byte() {
+    let key_pair = KeyPair::from(
+        PrivateKey::deserialize_from_vec(
+            &hex::decode("9d5bd02379e7e45cf515c788048f5cf3c454ffabd3e83bd1d7667716c325c3c0")
+                .unwrap(),
+        )
+        .unwrap(),
+    );
+
+    for fee_per_byte in [-1.0, f64::NAN, f64::INFINITY, f64::MAX, 1e15] {
+        let mut builder = builder(&key_pair);
+        builder.with_fee_per_byte(fee_per_byte);
+        assert!(matches!(
+            builder.generate(),
+            Err(TransactionBuilderError::InvalidFeePerByte)
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+mod fee;
 mod htlc_contract;
 mod staking_contract;
 mod vesting_contract;
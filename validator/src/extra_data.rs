@@ -0,0 +1,63 @@
+use nimiq_primitives::policy::Policy;
+use thiserror::Error;
+
+/// Placeholder in an extra data template that is replaced by the client version.
+pub const VERSION_PLACEHOLDER: &str = "{version}";
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ExtraDataError {
+    #[error(
+        "Extra data must not exceed {max} bytes, but has {0} bytes",
+        max = Policy::MAX_EXTRA_DATA_SIZE
+    )]
+    TooLarge(usize),
+}
+
+/// Builds the extra data that is included in the micro blocks we produce from the given template.
+/// All occurrences of `{version}` are replaced by the given client version, the rest of the
+/// template is used as is.
+pub fn expand_extra_data(template: &str, client_version: &str) -> Result<Vec<u8>, ExtraDataError> {
+    let extra_data = template
+        .replace(VERSION_PLACEHOLDER, client_version)
+        .into_bytes();
+
+    if extra_data.len() > Policy::MAX_EXTRA_DATA_SIZE {
+        return Err(ExtraDataError::TooLarge(extra_data.len()));
+    }
+    Ok(extra_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_test_log::test;
+
+    use super::*;
+
+    const VERSION: &str = "1.2.3";
+
+    #[test]
+    fn it_expands_the_version() {
+        assert_eq!(
+            expand_extra_data("my-pool", VERSION).unwrap(),
+            b"my-pool".to_vec()
+        );
+        assert_eq!(
+            expand_extra_data("my-pool/{version}", VERSION).unwrap(),
+            b"my-pool/1.2.3".to_vec()
+        );
+        assert_eq!(expand_extra_data("", VERSION).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn it_rejects_too_large_extra_data() {
+        let template = "a".repeat(Policy::MAX_EXTRA_DATA_SIZE);
+        assert!(expand_extra_data(&template, VERSION).is_ok());
+
+        // The size is checked after expanding the version.
+        let template = format!("{}{{version}}", "a".repeat(Policy::MAX_EXTRA_DATA_SIZE - 2));
+        assert_eq!(
+            expand_extra_data(&template, VERSION),
+            Err(ExtraDataError::TooLarge(Policy::MAX_EXTRA_DATA_SIZE + 3))
+        );
+    }
+}
@@ -57,6 +57,15 @@ impl EquivocationProofPool {
         }
     }
 
+    /// Returns the number of equivocation proofs in the pool.
+    pub fn len(&self) -> usize {
+        self.equivocation_proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.equivocation_proofs.is_empty()
+    }
+
     /// Returns a list of current equivocation proofs.
     pub fn get_equivocation_proofs_for_block(&self, max_size: usize) -> Vec<EquivocationProof> {
         let mut proofs = Vec::new();
@@ -2,9 +2,13 @@
 extern crate log;
 
 pub mod aggregation;
+pub mod extra_data;
 mod jail;
 mod r#macro;
 mod micro;
 mod proposal_buffer;
 pub mod tendermint;
 pub mod validator;
+/// Validator metrics
+#[cfg(feature = "metrics")]
+mod validator_metrics;
@@ -1,3 +1,5 @@
+#[cfg(feature = "metrics")]
+use std::time::Instant;
 use std::{
     cmp,
     pin::Pin,
@@ -18,6 +20,8 @@ use nimiq_vrf::VrfSeed;
 use parking_lot::RwLock;
 
 use crate::aggregation::skip_block::SkipBlockAggregation;
+#[cfg(feature = "metrics")]
+use crate::validator_metrics::ValidatorMetrics;
 
 // Ignoring this clippy warning since size difference is not that much (320
 // bytes) and we probably don't want the performance penalty of the allocation.
@@ -34,10 +38,13 @@ struct NextProduceMicroBlockEvent<TValidatorNetwork> {
     block_producer: BlockProducer,
     validator_slot_band: u16,
     equivocation_proofs: Vec<EquivocationProof>,
+    extra_data: Vec<u8>,
     prev_seed: VrfSeed,
     block_number: u32,
     producer_timeout: Duration,
     block_separation_time: Duration,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<ValidatorMetrics>,
 }
 
 impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<TValidatorNetwork> {
@@ -51,10 +58,12 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
         block_producer: BlockProducer,
         validator_slot_band: u16,
         equivocation_proofs: Vec<EquivocationProof>,
+        extra_data: Vec<u8>,
         prev_seed: VrfSeed,
         block_number: u32,
         producer_timeout: Duration,
         block_separation_time: Duration,
+        #[cfg(feature = "metrics")] metrics: Arc<ValidatorMetrics>,
     ) -> Self {
         Self {
             blockchain,
@@ -63,10 +72,13 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
             block_producer,
             validator_slot_band,
             equivocation_proofs,
+            extra_data,
             prev_seed,
             block_number,
             producer_timeout,
             block_separation_time,
+            #[cfg(feature = "metrics")]
+            metrics,
         }
     }
 
@@ -228,6 +240,9 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
             vrf_entropy: self.prev_seed.entropy(),
         };
 
+        #[cfg(feature = "metrics")]
+        let aggregation_start = Instant::now();
+
         let (_, skip_block_proof) = SkipBlockAggregation::start(
             skip_block_info.clone(),
             self.block_producer.voting_key.clone(),
@@ -237,6 +252,10 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
         )
         .await;
 
+        #[cfg(feature = "metrics")]
+        self.metrics
+            .note_skip_block_aggregation(aggregation_start.elapsed());
+
         let result = {
             // Acquire blockchain.upgradable_read() to prevent further changes to the blockchain while
             // we're constructing the block. Check if we're still in the correct state, abort otherwise.
@@ -253,7 +272,7 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
                     timestamp,
                     vec![],
                     vec![],
-                    vec![], // TODO: Allow validators to set extra data field.
+                    vec![], // Skip blocks must not contain extra data.
                     Some(skip_block_proof),
                 );
 
@@ -349,7 +368,7 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
             timestamp,
             self.equivocation_proofs.clone(),
             transactions,
-            vec![], // TODO: Allow validators to set extra data field.
+            self.extra_data.clone(),
             None,
         )
     }
@@ -386,10 +405,12 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> ProduceMicroBlock<TValidator
         block_producer: BlockProducer,
         validator_slot_band: u16,
         equivocation_proofs: Vec<EquivocationProof>,
+        extra_data: Vec<u8>,
         prev_seed: VrfSeed,
         block_number: u32,
         producer_timeout: Duration,
         block_separation_time: Duration,
+        #[cfg(feature = "metrics")] metrics: Arc<ValidatorMetrics>,
     ) -> Self {
         let next_event = NextProduceMicroBlockEvent::new(
             blockchain,
@@ -398,10 +419,13 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> ProduceMicroBlock<TValidator
             block_producer,
             validator_slot_band,
             equivocation_proofs,
+            extra_data,
             prev_seed,
             block_number,
             producer_timeout,
             block_separation_time,
+            #[cfg(feature = "metrics")]
+            metrics,
         )
         .next()
         .boxed();
@@ -42,6 +42,8 @@ use parking_lot::RwLock;
 use tokio_metrics::TaskMonitor;
 use tokio_stream::wrappers::BroadcastStream;
 
+#[cfg(feature = "metrics")]
+use crate::validator_metrics::ValidatorMetrics;
 use crate::{
     aggregation::tendermint::{proposal::RequestProposal, state::MacroState},
     jail::EquivocationProofPool,
@@ -61,6 +63,13 @@ pub struct ConsensusState {
     equivocation_proofs: EquivocationProofPool,
 }
 
+impl ConsensusState {
+    /// Returns the number of equivocation proofs that were collected but not yet included in a block.
+    pub fn num_equivocation_proofs(&self) -> usize {
+        self.equivocation_proofs.len()
+    }
+}
+
 /// Validator inactivity
 struct InactivityState {
     inactive_tx_hash: Blake2bHash,
@@ -75,6 +84,9 @@ pub struct ValidatorProxy {
     pub automatic_reactivate: Arc<AtomicBool>,
     pub slot_band: Arc<RwLock<Option<u16>>>,
     pub consensus_state: Arc<RwLock<ConsensusState>>,
+    pub extra_data: Arc<RwLock<Vec<u8>>>,
+    #[cfg(feature = "metrics")]
+    pub metrics: Arc<ValidatorMetrics>,
 }
 
 impl Clone for ValidatorProxy {
@@ -87,6 +99,9 @@ impl Clone for ValidatorProxy {
             automatic_reactivate: Arc::clone(&self.automatic_reactivate),
             slot_band: Arc::clone(&self.slot_band),
             consensus_state: Arc::clone(&self.consensus_state),
+            extra_data: Arc::clone(&self.extra_data),
+            #[cfg(feature = "metrics")]
+            metrics: Arc::clone(&self.metrics),
         }
     }
 }
@@ -119,12 +134,17 @@ where
     consensus_state: Arc<RwLock<ConsensusState>>,
     validator_state: Option<InactivityState>,
     automatic_reactivate: Arc<AtomicBool>,
+    /// The extra data included in the micro blocks we produce.
+    extra_data: Arc<RwLock<Vec<u8>>>,
 
     macro_producer: Option<ProduceMacroBlock<TValidatorNetwork>>,
     macro_state: Arc<RwLock<Option<MacroState>>>,
 
     micro_producer: Option<ProduceMicroBlock<TValidatorNetwork>>,
 
+    #[cfg(feature = "metrics")]
+    metrics: Arc<ValidatorMetrics>,
+
     pub mempool_task: MempoolTask<TValidatorNetwork::NetworkType>,
 }
 
@@ -146,6 +166,7 @@ where
         signing_key: SchnorrKeyPair,
         voting_key: BlsKeyPair,
         fee_key: SchnorrKeyPair,
+        extra_data: Vec<u8>,
         mempool_config: MempoolConfig,
     ) -> Self {
         let consensus_event_rx = consensus.subscribe_events();
@@ -174,7 +195,12 @@ where
             consensus.proxy(),
         );
 
-        let mempool = MempoolTask::new(consensus, Arc::clone(&blockchain), mempool_config);
+        let mempool = MempoolTask::new(
+            consensus,
+            Arc::clone(&blockchain),
+            env.clone(),
+            mempool_config,
+        );
 
         let automatic_reactivate = Arc::new(AtomicBool::new(automatic_reactivate));
 
@@ -213,12 +239,16 @@ where
             consensus_state: Arc::new(RwLock::new(blockchain_state)),
             validator_state: None,
             automatic_reactivate,
+            extra_data: Arc::new(RwLock::new(extra_data)),
 
             macro_producer: None,
             macro_state: Arc::clone(&macro_state),
 
             micro_producer: None,
 
+            #[cfg(feature = "metrics")]
+            metrics: Default::default(),
+
             mempool_task: mempool,
         }
     }
@@ -383,10 +413,13 @@ where
                     block_producer,
                     self.validator_slot_band(),
                     equivocation_proofs,
+                    self.extra_data.read().clone(),
                     prev_seed,
                     next_block_number,
                     Self::PRODUCER_TIMEOUT,
                     Self::BLOCK_SEPARATION_TIME,
+                    #[cfg(feature = "metrics")]
+                    Arc::clone(&self.metrics),
                 ));
             }
         }
@@ -433,6 +466,9 @@ where
             .equivocation_proofs
             .apply_block(&block);
 
+        #[cfg(feature = "metrics")]
+        self.note_block_metrics(hash, &block, false);
+
         self.check_reactivate(block.block_number());
         self.init_block_producer(Some(hash));
     }
@@ -452,10 +488,49 @@ where
         }
         drop(consensus_state);
 
+        #[cfg(feature = "metrics")]
+        {
+            for (hash, block) in old_chain.iter() {
+                self.note_block_metrics(hash, block, true);
+            }
+            for (hash, block) in new_chain.iter() {
+                self.note_block_metrics(hash, block, false);
+            }
+        }
+
         let head_hash = &new_chain.last().expect("new_chain must not be empty").0;
         self.init_block_producer(Some(head_hash));
     }
 
+    /// Updates the block production metrics with a block that was added to the main chain or, if
+    /// `reverted` is set, that was removed from it by a rebranch.
+    #[cfg(feature = "metrics")]
+    fn note_block_metrics(&self, hash: &Blake2bHash, block: &Block, reverted: bool) {
+        let blockchain = self.blockchain.read();
+        let validators = match blockchain
+            .get_validators_for_epoch(Policy::epoch_at(block.block_number()), None)
+        {
+            Ok(validators) => validators,
+            Err(_) => return,
+        };
+        let slot_band = match validators.get_slot_band_by_address(&self.validator_address()) {
+            Some(slot_band) => slot_band,
+            None => return,
+        };
+        let proposer = match blockchain.get_proposer_of(hash, None) {
+            Ok(proposer) => proposer,
+            Err(_) => return,
+        };
+
+        let is_proposer = proposer.band == slot_band;
+        let slots = &validators.get_validator_by_slot_band(slot_band).slots;
+        if reverted {
+            self.metrics.revert_block(block, is_proposer, slots);
+        } else {
+            self.metrics.note_block(block, is_proposer, slots);
+        }
+    }
+
     fn on_fork_event(&mut self, event: ForkEvent) {
         match event {
             ForkEvent::Detected(fork_proof) => self.on_equivocation_proof(fork_proof.into()),
@@ -725,9 +800,18 @@ where
             automatic_reactivate: Arc::clone(&self.automatic_reactivate),
             slot_band: Arc::clone(&self.slot_band),
             consensus_state: Arc::clone(&self.consensus_state),
+            extra_data: Arc::clone(&self.extra_data),
+            #[cfg(feature = "metrics")]
+            metrics: Arc::clone(&self.metrics),
         }
     }
 
+    /// Returns the validator metrics.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Arc<ValidatorMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
     #[cfg(feature = "metrics")]
     pub fn get_mempool_monitor(&self) -> TaskMonitor {
         self.mempool_task.get_mempool_monitor()
@@ -0,0 +1,221 @@
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use nimiq_block::{Block, MicroJustification};
+use parking_lot::RwLock;
+use prometheus_client::{
+    encoding::{EncodeLabelSet, EncodeLabelValue},
+    metrics::{family::Family, gauge::Gauge, histogram::Histogram},
+    registry::Registry,
+};
+
+/// The block counts are gauges rather than counters, as blocks that are reverted by a rebranch are
+/// subtracted again.
+pub struct ValidatorMetrics {
+    produced_blocks: Family<BlockTypeLabels, Gauge>,
+    expected_blocks: Family<BlockTypeLabels, Gauge>,
+    missed_slots: Gauge,
+    skip_block_contributions: Gauge,
+    tendermint_rounds: Histogram,
+    skip_block_aggregation_durations: Histogram,
+    /// The time at which we last produced a block, or the time the validator was started if we
+    /// did not produce a block yet.
+    last_produced_block: RwLock<Instant>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct BlockTypeLabels {
+    block_type: BlockTypeLabel,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelValue)]
+enum BlockTypeLabel {
+    Micro,
+    Macro,
+}
+
+impl Default for ValidatorMetrics {
+    fn default() -> Self {
+        ValidatorMetrics {
+            produced_blocks: Default::default(),
+            expected_blocks: Default::default(),
+            missed_slots: Default::default(),
+            skip_block_contributions: Default::default(),
+            tendermint_rounds: Histogram::new([1.0, 2.0, 3.0, 4.0, 5.0, 10.0].into_iter()),
+            skip_block_aggregation_durations: Histogram::new(
+                [0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0].into_iter(),
+            ),
+            last_produced_block: RwLock::new(Instant::now()),
+        }
+    }
+}
+
+impl ValidatorMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "produced_blocks",
+            "Number of blocks produced by this validator",
+            self.produced_blocks.clone(),
+        );
+
+        registry.register(
+            "expected_blocks",
+            "Number of blocks this validator was the designated producer of",
+            self.expected_blocks.clone(),
+        );
+
+        registry.register(
+            "missed_slots",
+            "Number of micro blocks of this validator that were replaced by a skip block",
+            self.missed_slots.clone(),
+        );
+
+        registry.register(
+            "skip_block_contributions",
+            "Number of skip blocks this validator contributed a signature to",
+            self.skip_block_contributions.clone(),
+        );
+
+        registry.register(
+            "tendermint_rounds",
+            "Number of Tendermint rounds needed per macro block",
+            self.tendermint_rounds.clone(),
+        );
+
+        registry.register(
+            "skip_block_aggregation_durations",
+            "Time the Handel aggregation of skip block signatures took",
+            self.skip_block_aggregation_durations.clone(),
+        );
+    }
+
+    /// Notes a block of the main chain. `is_proposer` states whether we were the designated
+    /// producer of the block and `slots` are the slots we own in the block's epoch. Skip blocks
+    /// are attributed to the validator whose micro block they replace.
+    pub(crate) fn note_block(&self, block: &Block, is_proposer: bool, slots: &Range<u16>) {
+        if let Block::Macro(macro_block) = block {
+            self.tendermint_rounds
+                .observe((macro_block.header.round + 1) as f64);
+        }
+        if is_proposer && !block.is_skip() {
+            *self.last_produced_block.write() = Instant::now();
+        }
+        self.count_block(block, is_proposer, slots, 1);
+    }
+
+    /// Notes a block that was reverted by a rebranch, subtracting it from the block counts. Only
+    /// micro blocks can be reverted, as macro blocks are final.
+    pub(crate) fn revert_block(&self, block: &Block, is_proposer: bool, slots: &Range<u16>) {
+        self.count_block(block, is_proposer, slots, -1);
+    }
+
+    fn count_block(&self, block: &Block, is_proposer: bool, slots: &Range<u16>, delta: i64) {
+        let block_type = match block {
+            Block::Micro(micro_block) => {
+                if let Some(MicroJustification::Skip(ref proof)) = micro_block.justification {
+                    if slots
+                        .clone()
+                        .any(|slot| proof.sig.signers.contains(slot as usize))
+                    {
+                        self.skip_block_contributions.inc_by(delta);
+                    }
+                }
+                BlockTypeLabel::Micro
+            }
+            Block::Macro(_) => BlockTypeLabel::Macro,
+        };
+
+        if !is_proposer {
+            return;
+        }
+
+        let labels = BlockTypeLabels { block_type };
+        self.expected_blocks.get_or_create(&labels).inc_by(delta);
+        if block.is_skip() {
+            self.missed_slots.inc_by(delta);
+        } else {
+            self.produced_blocks.get_or_create(&labels).inc_by(delta);
+        }
+    }
+
+    pub(crate) fn note_skip_block_aggregation(&self, duration: Duration) {
+        self.skip_block_aggregation_durations
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Returns the time since we last produced a block, or since the validator was started if we
+    /// did not produce a block yet.
+    pub fn time_since_last_produced_block(&self) -> Duration {
+        self.last_produced_block.read().elapsed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nimiq_block::{Block, MicroJustification};
+    use nimiq_collections::BitSet;
+    use nimiq_test_log::test;
+    use nimiq_test_utils::block_production::TemporaryBlockProducer;
+
+    use super::{BlockTypeLabel, BlockTypeLabels, ValidatorMetrics};
+
+    fn count(metrics: &ValidatorMetrics, block_type: BlockTypeLabel) -> (i64, i64) {
+        let labels = BlockTypeLabels { block_type };
+        (
+            metrics.produced_blocks.get_or_create(&labels).get(),
+            metrics.expected_blocks.get_or_create(&labels).get(),
+        )
+    }
+
+    #[test]
+    fn it_subtracts_reverted_blocks() {
+        let producer = TemporaryBlockProducer::new();
+        let metrics = ValidatorMetrics::default();
+        let slots = 0..1;
+
+        let micro_block = producer.next_block(vec![], false);
+        let skip_block = producer.next_block(vec![], true);
+        metrics.note_block(&micro_block, true, &slots);
+        metrics.note_block(&skip_block, true, &slots);
+        assert_eq!(count(&metrics, BlockTypeLabel::Micro), (1, 2));
+        assert_eq!(metrics.missed_slots.get(), 1);
+        assert_eq!(metrics.skip_block_contributions.get(), 1);
+
+        // Blocks we are not the proposer of only count our skip block contributions.
+        let other_skip_block = producer.next_block(vec![], true);
+        metrics.note_block(&other_skip_block, false, &slots);
+        assert_eq!(count(&metrics, BlockTypeLabel::Micro), (1, 2));
+        assert_eq!(metrics.missed_slots.get(), 1);
+        assert_eq!(metrics.skip_block_contributions.get(), 2);
+
+        metrics.revert_block(&other_skip_block, false, &slots);
+        metrics.revert_block(&skip_block, true, &slots);
+        metrics.revert_block(&micro_block, true, &slots);
+        assert_eq!(count(&metrics, BlockTypeLabel::Micro), (0, 0));
+        assert_eq!(metrics.missed_slots.get(), 0);
+        assert_eq!(metrics.skip_block_contributions.get(), 0);
+    }
+
+    #[test]
+    fn it_counts_skip_block_contributions_of_any_slot() {
+        let producer = TemporaryBlockProducer::new();
+        let metrics = ValidatorMetrics::default();
+
+        // Only the third slot of the validator signed the skip block.
+        let mut skip_block = producer.next_block_no_push(vec![], true);
+        if let Block::Micro(ref mut micro_block) = skip_block {
+            if let Some(MicroJustification::Skip(ref mut proof)) = micro_block.justification {
+                let mut signers = BitSet::new();
+                signers.insert(6);
+                proof.sig.signers = signers;
+            }
+        }
+
+        metrics.note_block(&skip_block, false, &(0..4));
+        assert_eq!(metrics.skip_block_contributions.get(), 0);
+        metrics.note_block(&skip_block, false, &(4..8));
+        assert_eq!(metrics.skip_block_contributions.get(), 1);
+    }
+}
@@ -1,9 +1,20 @@
+pub use mnemonic_wallet::{
+    account_path, DerivedAccount, MnemonicError, MnemonicKind, MnemonicSecret, MnemonicWallet,
+    DEFAULT_DERIVATION_PATH,
+};
 pub use multisig_account::MultiSigAccount;
+pub use partially_signed_transaction::{
+    CoSigner, PartiallySignedTransaction, PartiallySignedTransactionError,
+};
+pub use signing_policy::{Beneficiary, PolicyViolation, SigningPolicy, TransactionKind};
 pub use wallet_account::WalletAccount;
 #[cfg(feature = "store")]
 pub use wallet_store::WalletStore;
 
+mod mnemonic_wallet;
 mod multisig_account;
+mod partially_signed_transaction;
+mod signing_policy;
 mod wallet_account;
 #[cfg(feature = "store")]
 mod wallet_store;
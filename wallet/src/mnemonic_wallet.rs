@@ -0,0 +1,152 @@
+use nimiq_database_value_derive::DbSerializable;
+use nimiq_key_derivation::ExtendedPrivateKey;
+use nimiq_keys::{Address, KeyPair, PrivateKey};
+use nimiq_mnemonic::{key_derivation::ToExtendedPrivateKey, Mnemonic, MnemonicType, WORDLIST_EN};
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_utils::otp::{Locked, Verify};
+use thiserror::Error;
+
+use crate::wallet_account::WalletAccount;
+
+/// The default derivation path of Nimiq accounts. The index of an account is appended to it
+/// as a hardened segment, i.e. the first account is derived at `m/44'/242'/0'/0'`.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/242'/0'";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MnemonicError {
+    #[error("Invalid mnemonic")]
+    InvalidMnemonic,
+    #[error("Invalid derivation path: {0}")]
+    InvalidDerivationPath(String),
+    #[error("Legacy mnemonics only encode the account with index 0")]
+    InvalidLegacyIndex,
+    #[error("Failed to compute the seed of the mnemonic")]
+    InvalidSeed,
+    #[error("Mnemonic is valid both as a legacy and as a BIP39 mnemonic")]
+    AmbiguousMnemonic,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MnemonicKind {
+    /// Legacy mnemonics encode a single private key and use a CRC8 checksum.
+    Legacy,
+    /// BIP39 mnemonics encode a seed from which accounts are derived.
+    Bip39,
+}
+
+/// The words of a mnemonic. This is the part of a [`MnemonicWallet`] that is stored encrypted.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MnemonicSecret {
+    words: String,
+}
+
+impl Verify for MnemonicSecret {
+    fn verify(&self) -> bool {
+        self.kind().is_ok()
+    }
+}
+
+impl MnemonicSecret {
+    /// Parses a mnemonic of 24 words. Mnemonics that are valid both as legacy and as BIP39
+    /// mnemonics are rejected, since it's unclear which accounts they encode.
+    pub fn new(words: &str) -> Result<Self, MnemonicError> {
+        let secret = Self {
+            words: words.split_whitespace().collect::<Vec<_>>().join(" "),
+        };
+        secret.kind()?;
+        Ok(secret)
+    }
+
+    pub fn words(&self) -> &str {
+        &self.words
+    }
+
+    pub fn kind(&self) -> Result<MnemonicKind, MnemonicError> {
+        match self.mnemonic().get_type(WORDLIST_EN) {
+            MnemonicType::LEGACY => Ok(MnemonicKind::Legacy),
+            MnemonicType::BIP39 => Ok(MnemonicKind::Bip39),
+            MnemonicType::UNKNOWN => Err(MnemonicError::AmbiguousMnemonic),
+            MnemonicType::INVALID => Err(MnemonicError::InvalidMnemonic),
+        }
+    }
+
+    /// Derives the account with the given index. BIP39 accounts are derived at
+    /// `{derivation_path}/{index}'`, legacy mnemonics only contain the account with index 0.
+    pub fn derive_account(
+        &self,
+        derivation_path: &str,
+        index: u32,
+    ) -> Result<WalletAccount, MnemonicError> {
+        let mnemonic = self.mnemonic();
+        let private_key = match self.kind()? {
+            MnemonicKind::Legacy => {
+                if index != 0 {
+                    return Err(MnemonicError::InvalidLegacyIndex);
+                }
+                let entropy = mnemonic
+                    .to_entropy_legacy(WORDLIST_EN)
+                    .ok_or(MnemonicError::InvalidMnemonic)?;
+                PrivateKey::from(entropy.0)
+            }
+            MnemonicKind::Bip39 => {
+                let path = account_path(derivation_path, index)?;
+                mnemonic
+                    .to_master_key(None)
+                    .map_err(|_| MnemonicError::InvalidSeed)?
+                    .derive_path(&path)
+                    .ok_or(MnemonicError::InvalidDerivationPath(path))?
+                    .into_private_key()
+            }
+        };
+
+        Ok(WalletAccount::from(KeyPair::from(private_key)))
+    }
+
+    fn mnemonic(&self) -> Mnemonic {
+        // Parsing a mnemonic never fails.
+        self.words.parse().unwrap()
+    }
+}
+
+/// Returns the derivation path of the account with the given index.
+pub fn account_path(derivation_path: &str, index: u32) -> Result<String, MnemonicError> {
+    let path = format!("{derivation_path}/{index}'");
+    if index >= 0x8000_0000 || !ExtendedPrivateKey::is_valid_path(&path) {
+        return Err(MnemonicError::InvalidDerivationPath(path));
+    }
+    Ok(path)
+}
+
+/// An account that was derived from a mnemonic and imported into the wallet store.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DerivedAccount {
+    pub index: u32,
+    pub address: Address,
+}
+
+/// A mnemonic as it is kept in the wallet store. The mnemonic itself is encrypted, while the
+/// derivation path and the derived accounts can be listed without the passphrase.
+#[derive(Serialize, Deserialize, DbSerializable)]
+pub struct MnemonicWallet {
+    pub secret: Locked<MnemonicSecret>,
+    pub kind: MnemonicKind,
+    pub derivation_path: String,
+    pub accounts: Vec<DerivedAccount>,
+}
+
+impl MnemonicWallet {
+    /// Returns the derived account with the given index, if it was derived before.
+    pub fn get_account(&self, index: u32) -> Option<&DerivedAccount> {
+        self.accounts.iter().find(|account| account.index == index)
+    }
+
+    /// Records a derived account, keeping the accounts sorted by index.
+    pub fn add_account(&mut self, account: DerivedAccount) {
+        if let Err(pos) = self
+            .accounts
+            .binary_search_by_key(&account.index, |account| account.index)
+        {
+            self.accounts.insert(pos, account);
+        }
+    }
+}
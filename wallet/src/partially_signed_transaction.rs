@@ -0,0 +1,408 @@
+use std::num::NonZeroU8;
+
+use nimiq_hash::Blake2bHasher;
+use nimiq_keys::{
+    multisig::{
+        address::{combine_public_keys, compute_address},
+        commitment::{Commitment, CommitmentPair},
+        partial_signature::PartialSignature,
+        CommitmentsBuilder, CommitmentsData, MUSIG2_PARAMETER_V,
+    },
+    Address, Ed25519PublicKey, KeyPair, PublicKey, Signature,
+};
+use nimiq_serde::{Deserialize, DeserializeError, Serialize};
+use nimiq_transaction::{SignatureProof, Transaction};
+use nimiq_utils::merkle::Blake2bMerklePath;
+use thiserror::Error;
+
+/// Possible errors when building a partially signed transaction.
+#[derive(Debug, Error)]
+pub enum PartiallySignedTransactionError {
+    #[error("Unsupported partially signed transaction version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Failed to deserialize partially signed transaction: {0}")]
+    Deserialize(#[from] DeserializeError),
+    #[error("The minimum number of signatures exceeds the number of public keys")]
+    InvalidMinSignatures,
+    #[error("Multisig addresses of more than {0} public keys are not supported")]
+    TooManyPublicKeys(usize),
+    #[error("Multisig addresses with more than {0} combinations of public keys are not supported")]
+    TooManyKeyCombinations(u64),
+    #[error("The sender {0} is not the multisig address of the public keys")]
+    SenderMismatch(Address),
+    #[error("The partially signed transactions are not for the same transaction")]
+    TransactionMismatch,
+    #[error("{0} is not one of the co-signers")]
+    UnknownSigner(Ed25519PublicKey),
+    #[error("{0} already provided different commitments")]
+    ConflictingCommitments(Ed25519PublicKey),
+    #[error("The commitments of {0} don't match the provided commitment pairs")]
+    CommitmentsMismatch(Ed25519PublicKey),
+    #[error("All {0} co-signers already provided their commitments")]
+    TooManySigners(u8),
+    #[error("Only {0} of {1} co-signers provided their commitments")]
+    MissingCommitments(usize, u8),
+    #[error("Only {0} of {1} co-signers provided their partial signature")]
+    MissingSignatures(usize, u8),
+    #[error("Invalid partial signature of {0}")]
+    InvalidPartialSignature(Ed25519PublicKey),
+}
+
+/// A co-signer of a partially signed transaction, identified by its public key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoSigner {
+    /// The public key of the co-signer, which is one of the owners of the multisig address.
+    pub public_key: Ed25519PublicKey,
+    /// The public commitments of the co-signer. The corresponding nonces never leave the
+    /// co-signer and must be used for a single signature only.
+    pub commitments: [Commitment; MUSIG2_PARAMETER_V],
+    /// The partial signature of the co-signer, once all commitments are known.
+    pub partial_signature: Option<PartialSignature>,
+}
+
+/// A container for a transaction sent from a multisig address that is passed between the
+/// co-signers to collect their commitments and partial signatures (MuSig2).
+///
+/// Signing happens in two rounds: First, each of the `min_signatures` co-signers adds its
+/// commitments. Once all commitments are present, each co-signer adds its partial signature.
+/// Containers of the same transaction can be combined, such that the co-signers can work on
+/// copies in parallel. Once all partial signatures are present, the container is finalized into
+/// a signed transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartiallySignedTransaction {
+    version: u8,
+    transaction: Transaction,
+    /// The public keys of all owners of the multisig address, sorted.
+    public_keys: Vec<Ed25519PublicKey>,
+    min_signatures: NonZeroU8,
+    /// The co-signers that provided their commitments, sorted by public key.
+    signers: Vec<CoSigner>,
+}
+
+impl PartiallySignedTransaction {
+    /// The current version of the serialization format.
+    pub const VERSION: u8 = 1;
+
+    /// The maximum number of owners of a multisig address.
+    pub const MAX_PUBLIC_KEYS: usize = 100;
+    /// The maximum number of combinations of `min_signatures` out of all public keys. The multisig
+    /// address commits to all of them, so computing it is expensive for large numbers.
+    pub const MAX_KEY_COMBINATIONS: u64 = 10_000;
+
+    /// Creates a new partially signed transaction without any commitments. The sender of the
+    /// transaction must be the multisig address of the given public keys requiring
+    /// `min_signatures` signatures.
+    pub fn new(
+        transaction: Transaction,
+        public_keys: &[Ed25519PublicKey],
+        min_signatures: NonZeroU8,
+    ) -> Result<Self, PartiallySignedTransactionError> {
+        Self::with_transaction(public_keys, min_signatures, |_| transaction)
+    }
+
+    /// Creates a new partially signed transaction without any commitments for the transaction
+    /// built from the multisig address of the given public keys requiring `min_signatures`
+    /// signatures.
+    pub fn with_transaction<F: FnOnce(Address) -> Transaction>(
+        public_keys: &[Ed25519PublicKey],
+        min_signatures: NonZeroU8,
+        build_transaction: F,
+    ) -> Result<Self, PartiallySignedTransactionError> {
+        let mut public_keys = public_keys.to_vec();
+        public_keys.sort();
+        public_keys.dedup();
+        if min_signatures.get() as usize > public_keys.len() {
+            return Err(PartiallySignedTransactionError::InvalidMinSignatures);
+        }
+        if public_keys.len() > Self::MAX_PUBLIC_KEYS {
+            return Err(PartiallySignedTransactionError::TooManyPublicKeys(
+                Self::MAX_PUBLIC_KEYS,
+            ));
+        }
+        if num_combinations(public_keys.len(), min_signatures.get() as usize)
+            .map_or(true, |combinations| {
+                combinations > Self::MAX_KEY_COMBINATIONS
+            })
+        {
+            return Err(PartiallySignedTransactionError::TooManyKeyCombinations(
+                Self::MAX_KEY_COMBINATIONS,
+            ));
+        }
+
+        let address = compute_address(&combine_public_keys(
+            public_keys.clone(),
+            min_signatures.get() as usize,
+        ));
+        let transaction = build_transaction(address.clone());
+        if transaction.sender != address {
+            return Err(PartiallySignedTransactionError::SenderMismatch(
+                transaction.sender,
+            ));
+        }
+
+        Ok(Self {
+            version: Self::VERSION,
+            transaction,
+            public_keys,
+            min_signatures,
+            signers: vec![],
+        })
+    }
+
+    /// Deserializes a partially signed transaction and checks its commitments and partial
+    /// signatures.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PartiallySignedTransactionError> {
+        let received = Self::deserialize_from_vec(bytes)?;
+        if received.version != Self::VERSION {
+            return Err(PartiallySignedTransactionError::UnsupportedVersion(
+                received.version,
+            ));
+        }
+
+        let mut checked = Self::new(
+            received.transaction.clone(),
+            &received.public_keys,
+            received.min_signatures,
+        )?;
+        checked.combine(&received)?;
+        Ok(checked)
+    }
+
+    /// The transaction to be signed.
+    pub fn transaction(&self) -> &Transaction {
+        &self.transaction
+    }
+
+    /// The public keys of all owners of the multisig address.
+    pub fn public_keys(&self) -> &[Ed25519PublicKey] {
+        &self.public_keys
+    }
+
+    /// The number of co-signers required to sign the transaction.
+    pub fn min_signatures(&self) -> NonZeroU8 {
+        self.min_signatures
+    }
+
+    /// The co-signers that provided their commitments so far.
+    pub fn signers(&self) -> &[CoSigner] {
+        &self.signers
+    }
+
+    /// Returns whether all co-signers provided their partial signatures, such that the
+    /// transaction can be finalized.
+    pub fn is_complete(&self) -> bool {
+        self.signers.len() == self.min_signatures.get() as usize
+            && self
+                .signers
+                .iter()
+                .all(|signer| signer.partial_signature.is_some())
+    }
+
+    /// Adds the commitments of a co-signer. Adding the same commitments again has no effect.
+    pub fn add_commitments(
+        &mut self,
+        public_key: Ed25519PublicKey,
+        commitments: [Commitment; MUSIG2_PARAMETER_V],
+    ) -> Result<(), PartiallySignedTransactionError> {
+        if !self.public_keys.contains(&public_key) {
+            return Err(PartiallySignedTransactionError::UnknownSigner(public_key));
+        }
+
+        if let Some(signer) = self.signer(&public_key) {
+            return if signer.commitments == commitments {
+                Ok(())
+            } else {
+                Err(PartiallySignedTransactionError::ConflictingCommitments(
+                    public_key,
+                ))
+            };
+        }
+
+        if self.signers.len() >= self.min_signatures.get() as usize {
+            return Err(PartiallySignedTransactionError::TooManySigners(
+                self.min_signatures.get(),
+            ));
+        }
+
+        self.signers.push(CoSigner {
+            public_key,
+            commitments,
+            partial_signature: None,
+        });
+        self.signers.sort_by(|a, b| a.public_key.cmp(&b.public_key));
+        Ok(())
+    }
+
+    /// Adds the partial signature of a co-signer after verifying it. Requires the commitments of
+    /// all co-signers to be present.
+    pub fn add_partial_signature(
+        &mut self,
+        public_key: Ed25519PublicKey,
+        partial_signature: PartialSignature,
+    ) -> Result<(), PartiallySignedTransactionError> {
+        let signer = self
+            .signer(&public_key)
+            .ok_or(PartiallySignedTransactionError::UnknownSigner(public_key))?;
+        let commitments_data = self.commitments_data(signer, None)?;
+        if !public_key.verify_partial(
+            &commitments_data,
+            &partial_signature,
+            &self.transaction.serialize_content(),
+        ) {
+            return Err(PartiallySignedTransactionError::InvalidPartialSignature(
+                public_key,
+            ));
+        }
+
+        self.signer_mut(&public_key)
+            .expect("Signer was found before")
+            .partial_signature = Some(partial_signature);
+        Ok(())
+    }
+
+    /// Creates and adds the partial signature of the given key pair. The commitment pairs must
+    /// be the ones whose commitments were added for the key pair before. They must not be used
+    /// for any other signature.
+    pub fn sign(
+        &mut self,
+        key_pair: &KeyPair,
+        own_commitments: &[CommitmentPair; MUSIG2_PARAMETER_V],
+    ) -> Result<(), PartiallySignedTransactionError> {
+        let signer =
+            self.signer(&key_pair.public)
+                .ok_or(PartiallySignedTransactionError::UnknownSigner(
+                    key_pair.public,
+                ))?;
+        if CommitmentPair::to_commitments(own_commitments) != signer.commitments {
+            return Err(PartiallySignedTransactionError::CommitmentsMismatch(
+                key_pair.public,
+            ));
+        }
+
+        let commitments_data = self.commitments_data(signer, Some(*own_commitments))?;
+        let partial_signature = key_pair
+            .partial_sign(&commitments_data, &self.transaction.serialize_content())
+            .expect("Nonces are present");
+
+        self.signer_mut(&key_pair.public)
+            .expect("Signer was found before")
+            .partial_signature = Some(partial_signature);
+        Ok(())
+    }
+
+    /// Merges the commitments and partial signatures of another partially signed version of the
+    /// same transaction into this one.
+    pub fn combine(&mut self, other: &Self) -> Result<(), PartiallySignedTransactionError> {
+        if self.transaction.serialize_content() != other.transaction.serialize_content()
+            || self.public_keys != other.public_keys
+            || self.min_signatures != other.min_signatures
+        {
+            return Err(PartiallySignedTransactionError::TransactionMismatch);
+        }
+
+        // Partial signatures can only be verified once all commitments are known.
+        for signer in &other.signers {
+            self.add_commitments(signer.public_key, signer.commitments)?;
+        }
+        for signer in &other.signers {
+            if let Some(partial_signature) = signer.partial_signature {
+                self.add_partial_signature(signer.public_key, partial_signature)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Aggregates the partial signatures into a signature proof and returns the signed
+    /// transaction.
+    pub fn finalize(&self) -> Result<Transaction, PartiallySignedTransactionError> {
+        let num_signed = self
+            .signers
+            .iter()
+            .filter(|signer| signer.partial_signature.is_some())
+            .count();
+        if !self.is_complete() {
+            return Err(PartiallySignedTransactionError::MissingSignatures(
+                num_signed,
+                self.min_signatures.get(),
+            ));
+        }
+
+        let commitments_data = self.commitments_data(&self.signers[0], None)?;
+        let aggregated_signature: PartialSignature = self
+            .signers
+            .iter()
+            .filter_map(|signer| signer.partial_signature)
+            .sum();
+        let multisig_keys =
+            combine_public_keys(self.public_keys.clone(), self.min_signatures.get() as usize);
+
+        let proof = SignatureProof {
+            merkle_path: Blake2bMerklePath::new::<Blake2bHasher, _>(
+                &multisig_keys,
+                &commitments_data.aggregate_public_key,
+            ),
+            public_key: PublicKey::Ed25519(commitments_data.aggregate_public_key),
+            signature: Signature::Ed25519(
+                aggregated_signature.to_signature(&commitments_data.aggregate_commitment),
+            ),
+            webauthn_fields: None,
+        };
+
+        let mut signed_transaction = self.transaction.clone();
+        signed_transaction.proof = proof.serialize_to_vec();
+        Ok(signed_transaction)
+    }
+
+    fn signer(&self, public_key: &Ed25519PublicKey) -> Option<&CoSigner> {
+        self.signers
+            .iter()
+            .find(|signer| signer.public_key == *public_key)
+    }
+
+    fn signer_mut(&mut self, public_key: &Ed25519PublicKey) -> Option<&mut CoSigner> {
+        self.signers
+            .iter_mut()
+            .find(|signer| signer.public_key == *public_key)
+    }
+
+    /// Builds the commitments data from the point of view of the given co-signer. The nonces
+    /// are only required for signing.
+    fn commitments_data(
+        &self,
+        signer: &CoSigner,
+        own_commitments: Option<[CommitmentPair; MUSIG2_PARAMETER_V]>,
+    ) -> Result<CommitmentsData, PartiallySignedTransactionError> {
+        if self.signers.len() < self.min_signatures.get() as usize {
+            return Err(PartiallySignedTransactionError::MissingCommitments(
+                self.signers.len(),
+                self.min_signatures.get(),
+            ));
+        }
+
+        let mut builder = match own_commitments {
+            Some(own_commitments) => {
+                CommitmentsBuilder::with_private_commitments(signer.public_key, own_commitments)
+            }
+            None => {
+                CommitmentsBuilder::with_public_commitments(signer.public_key, signer.commitments)
+            }
+        };
+        for other in &self.signers {
+            if other.public_key != signer.public_key {
+                builder.push_signer(other.public_key, other.commitments);
+            }
+        }
+        Ok(builder.build(&self.transaction.serialize_content()))
+    }
+}
+
+/// Returns the number of combinations of `k` out of `n` elements, or `None` if it overflows.
+fn num_combinations(n: usize, k: usize) -> Option<u64> {
+    let k = k.min(n - k) as u64;
+    let mut combinations = 1u64;
+    for i in 0..k {
+        // The product of `i + 1` consecutive numbers is divisible by `(i + 1)!`.
+        combinations = combinations.checked_mul(n as u64 - i)? / (i + 1);
+    }
+    Some(combinations)
+}
@@ -0,0 +1,149 @@
+use nimiq_database_value_derive::DbSerializable;
+use nimiq_keys::Address;
+use nimiq_primitives::{account::AccountType, coin::Coin};
+use nimiq_serde::{Deserialize, Serialize};
+use nimiq_transaction::{
+    account::staking_contract::IncomingStakingTransactionData, Transaction, TransactionFlags,
+};
+use thiserror::Error;
+
+/// The type of a transaction, as far as signing policies are concerned. Transactions are typed
+/// by the contract they interact with, either as sender or as recipient.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionKind {
+    Basic,
+    Vesting,
+    Htlc,
+    Staking,
+}
+
+impl TransactionKind {
+    pub fn of(transaction: &Transaction) -> Self {
+        let account_types = [transaction.sender_type, transaction.recipient_type];
+        if account_types.contains(&AccountType::Staking) {
+            TransactionKind::Staking
+        } else if account_types.contains(&AccountType::Vesting) {
+            TransactionKind::Vesting
+        } else if account_types.contains(&AccountType::HTLC) {
+            TransactionKind::Htlc
+        } else {
+            TransactionKind::Basic
+        }
+    }
+}
+
+/// The account a transaction credits with its value, as far as signing policies are concerned.
+///
+/// For transactions to the staking contract, this is the staker or validator the funds are staked
+/// for rather than the staking contract itself. Transactions to the staking contract that don't
+/// stake any funds, like updates of stakers or validators, don't credit any account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Beneficiary {
+    /// The recipient of a transfer or the staker or validator funds are staked for.
+    Account(Address),
+    /// A contract created by the transaction. Its address can't be allowed upfront.
+    NewContract,
+    /// The transaction doesn't move any funds apart from the fee.
+    None,
+}
+
+impl Beneficiary {
+    pub fn of(transaction: &Transaction) -> Self {
+        if transaction
+            .flags
+            .contains(TransactionFlags::CONTRACT_CREATION)
+        {
+            return Beneficiary::NewContract;
+        }
+        if transaction.recipient_type != AccountType::Staking {
+            return Beneficiary::Account(transaction.recipient.clone());
+        }
+
+        match IncomingStakingTransactionData::parse(transaction) {
+            Ok(IncomingStakingTransactionData::CreateValidator { proof, .. })
+            | Ok(IncomingStakingTransactionData::CreateStaker { proof, .. }) => {
+                Beneficiary::Account(proof.compute_signer())
+            }
+            Ok(IncomingStakingTransactionData::AddStake { staker_address }) => {
+                Beneficiary::Account(staker_address)
+            }
+            Ok(_) => Beneficiary::None,
+            // Transactions that can't be parsed are checked against the staking contract itself.
+            Err(_) => Beneficiary::Account(transaction.recipient.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PolicyViolation {
+    #[error("Value {value} exceeds the maximum of {max_value}")]
+    ValueExceeded { value: Coin, max_value: Coin },
+    #[error("Recipient {0} is not allowed")]
+    RecipientNotAllowed(Address),
+    #[error("Contract creations are not allowed if the recipients are restricted")]
+    ContractCreation,
+    #[error("Transaction type {0:?} is not allowed")]
+    TransactionKindNotAllowed(TransactionKind),
+}
+
+/// Restrictions on the transactions a wallet account may sign. Restrictions that are not set
+/// don't apply.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, DbSerializable)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningPolicy {
+    /// The maximum value of a single transaction.
+    pub max_value: Option<Coin>,
+    /// The recipients the account may send to.
+    pub allowed_recipients: Option<Vec<Address>>,
+    /// The types of transactions the account may sign.
+    pub allowed_transaction_types: Option<Vec<TransactionKind>>,
+}
+
+impl SigningPolicy {
+    /// Checks whether a transaction complies with this policy.
+    pub fn check(&self, transaction: &Transaction) -> Result<(), PolicyViolation> {
+        self.check_parameters(
+            &Beneficiary::of(transaction),
+            transaction.value,
+            TransactionKind::of(transaction),
+        )
+    }
+
+    /// Checks whether a transaction with the given parameters complies with this policy. This
+    /// allows to check a transaction before it is signed.
+    ///
+    /// The allowed recipients restrict the beneficiary of the transaction. Thus, staking requires
+    /// the staker or validator to be allowed, and contract creations are rejected if the
+    /// recipients are restricted.
+    pub fn check_parameters(
+        &self,
+        beneficiary: &Beneficiary,
+        value: Coin,
+        kind: TransactionKind,
+    ) -> Result<(), PolicyViolation> {
+        if let Some(max_value) = self.max_value {
+            if value > max_value {
+                return Err(PolicyViolation::ValueExceeded { value, max_value });
+            }
+        }
+
+        if let Some(ref allowed_recipients) = self.allowed_recipients {
+            match beneficiary {
+                Beneficiary::Account(address) if !allowed_recipients.contains(address) => {
+                    return Err(PolicyViolation::RecipientNotAllowed(address.clone()));
+                }
+                Beneficiary::NewContract => return Err(PolicyViolation::ContractCreation),
+                _ => {}
+            }
+        }
+
+        if let Some(ref allowed_transaction_types) = self.allowed_transaction_types {
+            if !allowed_transaction_types.contains(&kind) {
+                return Err(PolicyViolation::TransactionKindNotAllowed(kind));
+            }
+        }
+
+        Ok(())
+    }
+}
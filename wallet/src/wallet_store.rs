@@ -6,23 +6,35 @@ use nimiq_database::{
 use nimiq_keys::Address;
 use nimiq_utils::otp::Locked;
 
-use crate::wallet_account::WalletAccount;
+use crate::{
+    mnemonic_wallet::MnemonicWallet, signing_policy::SigningPolicy, wallet_account::WalletAccount,
+};
 
 declare_table!(WalletTable, "Wallet", Address => Locked<WalletAccount>);
+declare_table!(MnemonicTable, "Mnemonic", Address => MnemonicWallet);
+declare_table!(SigningPolicyTable, "SigningPolicy", Address => SigningPolicy);
 
 #[derive(Debug)]
 pub struct WalletStore {
     env: MdbxDatabase,
     table: WalletTable,
+    mnemonic_table: MnemonicTable,
+    policy_table: SigningPolicyTable,
 }
 
 impl WalletStore {
     pub fn new(env: MdbxDatabase) -> Self {
         let wallet_table = WalletTable;
+        let mnemonic_table = MnemonicTable;
+        let policy_table = SigningPolicyTable;
         env.create_regular_table(&wallet_table);
+        env.create_regular_table(&mnemonic_table);
+        env.create_regular_table(&policy_table);
         WalletStore {
             env,
             table: wallet_table,
+            mnemonic_table,
+            policy_table,
         }
     }
 
@@ -61,4 +73,55 @@ impl WalletStore {
     ) {
         txn.put_reserve(&self.table, address, wallet);
     }
+
+    /// Lists the IDs of the imported mnemonics, which are the addresses of their first account.
+    pub fn list_mnemonics(&self, txn_option: Option<&MdbxReadTransaction>) -> Vec<Address> {
+        let txn = txn_option.or_new(&self.env);
+
+        let cursor = txn.cursor(&self.mnemonic_table);
+        cursor
+            .into_iter_start()
+            .map(|(wallet_id, _)| wallet_id)
+            .collect()
+    }
+
+    pub fn get_mnemonic(
+        &self,
+        wallet_id: &Address,
+        txn_option: Option<&MdbxReadTransaction>,
+    ) -> Option<MnemonicWallet> {
+        let txn = txn_option.or_new(&self.env);
+        txn.get(&self.mnemonic_table, wallet_id)
+    }
+
+    pub fn put_mnemonic(
+        &self,
+        wallet_id: &Address,
+        wallet: &MnemonicWallet,
+        txn: &mut MdbxWriteTransaction,
+    ) {
+        txn.put_reserve(&self.mnemonic_table, wallet_id, wallet);
+    }
+
+    pub fn get_policy(
+        &self,
+        address: &Address,
+        txn_option: Option<&MdbxReadTransaction>,
+    ) -> Option<SigningPolicy> {
+        let txn = txn_option.or_new(&self.env);
+        txn.get(&self.policy_table, address)
+    }
+
+    /// Sets the signing policy of an account. Passing `None` removes the policy.
+    pub fn put_policy(
+        &self,
+        address: &Address,
+        policy: Option<&SigningPolicy>,
+        txn: &mut MdbxWriteTransaction,
+    ) {
+        match policy {
+            Some(policy) => txn.put_reserve(&self.policy_table, address, policy),
+            None => txn.remove(&self.policy_table, address),
+        }
+    }
 }
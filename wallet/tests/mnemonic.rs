@@ -0,0 +1,128 @@
+use nimiq_key_derivation::ExtendedPrivateKey;
+use nimiq_keys::PrivateKey;
+use nimiq_mnemonic::{Entropy, Mnemonic, WORDLIST_EN};
+use nimiq_test_log::test;
+use nimiq_utils::otp::Locked;
+use nimiq_wallet::{
+    DerivedAccount, MnemonicError, MnemonicKind, MnemonicSecret, MnemonicWallet,
+    DEFAULT_DERIVATION_PATH,
+};
+
+const ENTROPY: &str = "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f";
+
+#[test]
+fn it_derives_bip39_accounts() {
+    let mnemonic = Entropy::from(ENTROPY).to_mnemonic(WORDLIST_EN);
+    let secret = MnemonicSecret::new(&mnemonic.to_string()).unwrap();
+    assert_eq!(secret.kind(), Ok(MnemonicKind::Bip39));
+
+    let master_key = ExtendedPrivateKey::from_seed(mnemonic.to_seed(None).unwrap());
+    for index in 0..3 {
+        let account = secret
+            .derive_account(DEFAULT_DERIVATION_PATH, index)
+            .unwrap();
+        let expected = master_key
+            .derive_path(&format!("m/44'/242'/0'/{index}'"))
+            .unwrap();
+        assert_eq!(account.address, expected.to_address());
+    }
+
+    // A custom derivation path changes the accounts.
+    let account = secret.derive_account("m/44'/242'/1'", 0).unwrap();
+    let default_account = secret.derive_account(DEFAULT_DERIVATION_PATH, 0).unwrap();
+    assert_ne!(account.address, default_account.address);
+
+    assert!(matches!(
+        secret.derive_account("m/44/242", 0),
+        Err(MnemonicError::InvalidDerivationPath(_))
+    ));
+}
+
+#[test]
+#[allow(deprecated)]
+fn it_derives_legacy_accounts() {
+    let entropy = Entropy::from(ENTROPY);
+    let mnemonic = entropy.to_legacy_mnemonic(WORDLIST_EN);
+    let secret = MnemonicSecret::new(&mnemonic.to_string()).unwrap();
+    assert_eq!(secret.kind(), Ok(MnemonicKind::Legacy));
+
+    // Legacy mnemonics encode the private key directly.
+    let account = secret.derive_account(DEFAULT_DERIVATION_PATH, 0).unwrap();
+    assert_eq!(account.key_pair.private, PrivateKey::from(entropy.0));
+
+    assert_eq!(
+        secret
+            .derive_account(DEFAULT_DERIVATION_PATH, 1)
+            .unwrap_err(),
+        MnemonicError::InvalidLegacyIndex
+    );
+}
+
+#[test]
+fn it_rejects_invalid_mnemonics() {
+    // Only mnemonics of 24 words are supported.
+    assert_eq!(
+        MnemonicSecret::new(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        ),
+        Err(MnemonicError::InvalidMnemonic)
+    );
+    assert_eq!(
+        MnemonicSecret::new("not a mnemonic"),
+        Err(MnemonicError::InvalidMnemonic)
+    );
+
+    // A mnemonic with a valid checksum both as a legacy and as a BIP39 mnemonic.
+    let entropy = (0u32..)
+        .map(|i| {
+            let mut entropy = [0u8; 32];
+            entropy[..4].copy_from_slice(&i.to_be_bytes());
+            Entropy::from(entropy)
+        })
+        .find(Entropy::is_colliding_checksum)
+        .unwrap();
+    assert_eq!(
+        MnemonicSecret::new(&entropy.to_mnemonic(WORDLIST_EN).to_string()),
+        Err(MnemonicError::AmbiguousMnemonic)
+    );
+}
+
+#[test]
+fn it_locks_mnemonics() {
+    let mnemonic: Mnemonic = Entropy::from(ENTROPY).to_mnemonic(WORDLIST_EN);
+    let secret = MnemonicSecret::new(&mnemonic.to_string()).unwrap();
+
+    let mut wallet = MnemonicWallet {
+        secret: Locked::with_defaults(secret.clone(), b"password").unwrap(),
+        kind: MnemonicKind::Bip39,
+        derivation_path: DEFAULT_DERIVATION_PATH.to_string(),
+        accounts: vec![],
+    };
+    for index in [2, 0, 2] {
+        let address = secret
+            .derive_account(DEFAULT_DERIVATION_PATH, index)
+            .unwrap()
+            .address;
+        wallet.add_account(DerivedAccount { index, address });
+    }
+    assert_eq!(
+        wallet
+            .accounts
+            .iter()
+            .map(|account| account.index)
+            .collect::<Vec<_>>(),
+        vec![0, 2]
+    );
+    assert!(wallet.get_account(1).is_none());
+
+    let locked = wallet
+        .secret
+        .unlock(b"wrong password")
+        .err()
+        .expect("Unlocking with the wrong password must fail");
+    let unlocked = locked
+        .unlock(b"password")
+        .ok()
+        .expect("Unlocking with the password must succeed");
+    assert_eq!(unlocked.words(), mnemonic.to_string());
+}
@@ -0,0 +1,217 @@
+use std::num::NonZeroU8;
+
+use hex::FromHex;
+use nimiq_keys::{multisig::commitment::CommitmentPair, Address, KeyPair, PrivateKey};
+use nimiq_primitives::{coin::Coin, networks::NetworkId};
+use nimiq_serde::Serialize;
+use nimiq_test_log::test;
+use nimiq_transaction::Transaction;
+use nimiq_wallet::{MultiSigAccount, PartiallySignedTransaction, PartiallySignedTransactionError};
+
+static PRIVATE_KEYS: &[&str] = &[
+    "37f485f69a33e942b18b79602edb07481880d0b33a7d46adf693633bba7e85e0",
+    "fb7789860ab2165b623cb4bda92f99247582320306ed1417bd6283d57d3694ed",
+    "122eb25a770f0dc0a1505fd540f518b72b8592b25fcac120f9c6e87ceb1e0274",
+];
+
+fn key_pairs() -> Vec<KeyPair> {
+    PRIVATE_KEYS
+        .iter()
+        .map(|key| KeyPair::from(PrivateKey::from_hex(key).unwrap()))
+        .collect()
+}
+
+/// Returns the 2-of-3 multisig accounts of all owners and an unsigned transaction sent from it.
+fn two_of_three() -> (Vec<MultiSigAccount>, Transaction) {
+    let key_pairs = key_pairs();
+    let public_keys: Vec<_> = key_pairs.iter().map(|kp| kp.public).collect();
+    let accounts: Vec<_> = key_pairs
+        .iter()
+        .map(|kp| {
+            MultiSigAccount::from_public_keys(kp, NonZeroU8::new(2).unwrap(), &public_keys).unwrap()
+        })
+        .collect();
+    let transaction = accounts[0].create_transaction(
+        Address::from_any_str("NQ68 D40E KU4Q V8JV E96E X1M1 5NL6 KUYC SQXS").unwrap(),
+        Coin::from_u64_unchecked(1),
+        Coin::ZERO,
+        1,
+        NetworkId::UnitAlbatross,
+    );
+    (accounts, transaction)
+}
+
+/// Simulates sending the container to another co-signer.
+fn transfer(pst: &PartiallySignedTransaction) -> PartiallySignedTransaction {
+    PartiallySignedTransaction::from_bytes(&pst.serialize_to_vec()).unwrap()
+}
+
+#[test]
+fn it_can_sign_through_the_container() {
+    let (accounts, transaction) = two_of_three();
+    let public_keys: Vec<_> = accounts.iter().map(|a| a.key_pair.public).collect();
+
+    // The first co-signer creates the container, the third one signs along.
+    let mut pst = PartiallySignedTransaction::new(
+        transaction.clone(),
+        &public_keys,
+        NonZeroU8::new(2).unwrap(),
+    )
+    .unwrap();
+    let pairs1 = accounts[0].create_commitments();
+    pst.add_commitments(
+        accounts[0].key_pair.public,
+        CommitmentPair::to_commitments(&pairs1),
+    )
+    .unwrap();
+
+    let mut pst = transfer(&pst);
+    let pairs3 = accounts[2].create_commitments();
+    pst.add_commitments(
+        accounts[2].key_pair.public,
+        CommitmentPair::to_commitments(&pairs3),
+    )
+    .unwrap();
+
+    // Only the required number of co-signers can take part.
+    let pairs2 = accounts[1].create_commitments();
+    assert!(matches!(
+        pst.add_commitments(
+            accounts[1].key_pair.public,
+            CommitmentPair::to_commitments(&pairs2),
+        ),
+        Err(PartiallySignedTransactionError::TooManySigners(2))
+    ));
+
+    // Both co-signers sign their own copy, then the copies are combined.
+    let mut pst1 = transfer(&pst);
+    pst1.sign(&accounts[0].key_pair, &pairs1).unwrap();
+    assert!(matches!(
+        pst1.finalize(),
+        Err(PartiallySignedTransactionError::MissingSignatures(1, 2))
+    ));
+
+    let mut pst3 = transfer(&pst);
+    assert!(matches!(
+        pst3.sign(&accounts[2].key_pair, &pairs1),
+        Err(PartiallySignedTransactionError::CommitmentsMismatch(_))
+    ));
+    pst3.sign(&accounts[2].key_pair, &pairs3).unwrap();
+
+    let mut combined = transfer(&pst1);
+    combined.combine(&transfer(&pst3)).unwrap();
+    assert!(combined.is_complete());
+
+    let signed = combined.finalize().unwrap();
+    assert_eq!(signed.serialize_content(), transaction.serialize_content());
+    assert!(signed.verify(NetworkId::UnitAlbatross).is_ok());
+}
+
+#[test]
+fn it_rejects_invalid_containers() {
+    let (accounts, transaction) = two_of_three();
+    let public_keys: Vec<_> = accounts.iter().map(|a| a.key_pair.public).collect();
+
+    // The sender must be the multisig address of the public keys.
+    assert!(matches!(
+        PartiallySignedTransaction::new(
+            transaction.clone(),
+            &public_keys[..2],
+            NonZeroU8::new(2).unwrap(),
+        ),
+        Err(PartiallySignedTransactionError::SenderMismatch(_))
+    ));
+    assert!(matches!(
+        PartiallySignedTransaction::new(
+            transaction.clone(),
+            &public_keys,
+            NonZeroU8::new(4).unwrap(),
+        ),
+        Err(PartiallySignedTransactionError::InvalidMinSignatures)
+    ));
+
+    let mut pst =
+        PartiallySignedTransaction::new(transaction, &public_keys, NonZeroU8::new(2).unwrap())
+            .unwrap();
+    let outsider = KeyPair::from(PrivateKey::from([1u8; PrivateKey::SIZE]));
+    let pairs = accounts[0].create_commitments();
+    assert!(matches!(
+        pst.add_commitments(outsider.public, CommitmentPair::to_commitments(&pairs)),
+        Err(PartiallySignedTransactionError::UnknownSigner(_))
+    ));
+
+    // Commitments can't be replaced, as that would allow to extract the private key.
+    pst.add_commitments(
+        accounts[0].key_pair.public,
+        CommitmentPair::to_commitments(&pairs),
+    )
+    .unwrap();
+    assert!(matches!(
+        pst.add_commitments(
+            accounts[0].key_pair.public,
+            CommitmentPair::to_commitments(&accounts[0].create_commitments()),
+        ),
+        Err(PartiallySignedTransactionError::ConflictingCommitments(_))
+    ));
+
+    // Signing requires the commitments of all co-signers.
+    assert!(matches!(
+        pst.sign(&accounts[0].key_pair, &pairs),
+        Err(PartiallySignedTransactionError::MissingCommitments(1, 2))
+    ));
+
+    // Unknown versions are rejected.
+    let mut bytes = pst.serialize_to_vec();
+    bytes[0] = PartiallySignedTransaction::VERSION + 1;
+    assert!(matches!(
+        PartiallySignedTransaction::from_bytes(&bytes),
+        Err(PartiallySignedTransactionError::UnsupportedVersion(_))
+    ));
+}
+
+#[test]
+fn it_rejects_multisig_addresses_with_too_many_key_combinations() {
+    let (_, transaction) = two_of_three();
+    let public_keys: Vec<_> = (1..=60u8)
+        .map(|i| KeyPair::from(PrivateKey::from([i; PrivateKey::SIZE])).public)
+        .collect();
+
+    // 60 choose 30 combinations would take forever to compute.
+    assert!(matches!(
+        PartiallySignedTransaction::new(
+            transaction.clone(),
+            &public_keys,
+            NonZeroU8::new(30).unwrap(),
+        ),
+        Err(PartiallySignedTransactionError::TooManyKeyCombinations(_))
+    ));
+
+    let public_keys: Vec<_> = (0..=PartiallySignedTransaction::MAX_PUBLIC_KEYS)
+        .map(|i| {
+            let mut private_key = [1u8; PrivateKey::SIZE];
+            private_key[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            KeyPair::from(PrivateKey::from(private_key)).public
+        })
+        .collect();
+    assert!(matches!(
+        PartiallySignedTransaction::new(transaction, &public_keys, NonZeroU8::new(1).unwrap()),
+        Err(PartiallySignedTransactionError::TooManyPublicKeys(_))
+    ));
+}
+
+#[test]
+fn it_builds_the_transaction_from_the_multisig_address() {
+    let (accounts, transaction) = two_of_three();
+    let public_keys: Vec<_> = accounts.iter().map(|a| a.key_pair.public).collect();
+
+    let pst = PartiallySignedTransaction::with_transaction(
+        &public_keys,
+        NonZeroU8::new(2).unwrap(),
+        |sender| {
+            assert_eq!(sender, transaction.sender);
+            transaction.clone()
+        },
+    )
+    .unwrap();
+    assert_eq!(pst.transaction(), &transaction);
+}
@@ -0,0 +1,187 @@
+use nimiq_keys::Address;
+use nimiq_primitives::{account::AccountType, coin::Coin, networks::NetworkId, policy::Policy};
+use nimiq_serde::Serialize;
+use nimiq_test_log::test;
+use nimiq_transaction::{
+    account::staking_contract::IncomingStakingTransactionData, SignatureProof, Transaction,
+};
+use nimiq_wallet::{Beneficiary, PolicyViolation, SigningPolicy, TransactionKind};
+
+fn address(byte: u8) -> Address {
+    Address::from([byte; Address::SIZE])
+}
+
+fn basic_transaction(recipient: Address, value: u64) -> Transaction {
+    Transaction::new_basic(
+        address(1),
+        recipient,
+        Coin::from_u64_unchecked(value),
+        Coin::ZERO,
+        1,
+        NetworkId::UnitAlbatross,
+    )
+}
+
+#[test]
+fn empty_policy_allows_everything() {
+    let policy = SigningPolicy::default();
+    assert_eq!(
+        policy.check(&basic_transaction(address(2), 1_000_000)),
+        Ok(())
+    );
+}
+
+#[test]
+fn it_limits_the_value() {
+    let policy = SigningPolicy {
+        max_value: Some(Coin::from_u64_unchecked(100)),
+        ..Default::default()
+    };
+
+    assert_eq!(policy.check(&basic_transaction(address(2), 100)), Ok(()));
+    assert_eq!(
+        policy.check(&basic_transaction(address(2), 101)),
+        Err(PolicyViolation::ValueExceeded {
+            value: Coin::from_u64_unchecked(101),
+            max_value: Coin::from_u64_unchecked(100),
+        })
+    );
+}
+
+#[test]
+fn it_limits_the_recipients() {
+    let policy = SigningPolicy {
+        allowed_recipients: Some(vec![address(2)]),
+        ..Default::default()
+    };
+
+    assert_eq!(policy.check(&basic_transaction(address(2), 1)), Ok(()));
+    assert_eq!(
+        policy.check(&basic_transaction(address(3), 1)),
+        Err(PolicyViolation::RecipientNotAllowed(address(3)))
+    );
+
+    // The recipient of a contract creation can't be checked.
+    let transaction = Transaction::new_contract_creation(
+        address(1),
+        AccountType::Basic,
+        vec![],
+        AccountType::HTLC,
+        vec![],
+        Coin::from_u64_unchecked(1),
+        Coin::ZERO,
+        1,
+        NetworkId::UnitAlbatross,
+    );
+    assert_eq!(
+        policy.check(&transaction),
+        Err(PolicyViolation::ContractCreation)
+    );
+}
+
+fn staking_transaction(data: IncomingStakingTransactionData, value: u64) -> Transaction {
+    Transaction::new_extended(
+        address(1),
+        AccountType::Basic,
+        vec![],
+        Policy::STAKING_CONTRACT_ADDRESS,
+        AccountType::Staking,
+        data.serialize_to_vec(),
+        Coin::from_u64_unchecked(value),
+        Coin::ZERO,
+        1,
+        NetworkId::UnitAlbatross,
+    )
+}
+
+#[test]
+fn it_limits_the_stakers_funds_are_staked_for() {
+    let policy = SigningPolicy {
+        allowed_recipients: Some(vec![address(2)]),
+        ..Default::default()
+    };
+
+    // Staking is checked against the staker, not the staking contract.
+    let transaction = staking_transaction(
+        IncomingStakingTransactionData::AddStake {
+            staker_address: address(2),
+        },
+        100,
+    );
+    assert_eq!(
+        Beneficiary::of(&transaction),
+        Beneficiary::Account(address(2))
+    );
+    assert_eq!(policy.check(&transaction), Ok(()));
+
+    let transaction = staking_transaction(
+        IncomingStakingTransactionData::AddStake {
+            staker_address: address(3),
+        },
+        100,
+    );
+    assert_eq!(
+        policy.check(&transaction),
+        Err(PolicyViolation::RecipientNotAllowed(address(3)))
+    );
+
+    // Creating a staker credits the staker that signed the proof.
+    let proof = SignatureProof::default();
+    let transaction = staking_transaction(
+        IncomingStakingTransactionData::CreateStaker {
+            delegation: None,
+            proof: proof.clone(),
+        },
+        100,
+    );
+    assert_eq!(
+        policy.check(&transaction),
+        Err(PolicyViolation::RecipientNotAllowed(proof.compute_signer()))
+    );
+
+    // Updates don't stake any funds and thus aren't restricted by the recipients.
+    let transaction = staking_transaction(
+        IncomingStakingTransactionData::UpdateStaker {
+            new_delegation: Some(address(3)),
+            reactivate_all_stake: false,
+            proof,
+        },
+        0,
+    );
+    assert_eq!(Beneficiary::of(&transaction), Beneficiary::None);
+    assert_eq!(policy.check(&transaction), Ok(()));
+    assert_eq!(
+        policy.check_parameters(&Beneficiary::None, Coin::ZERO, TransactionKind::Staking),
+        Ok(())
+    );
+}
+
+#[test]
+fn it_limits_the_transaction_types() {
+    let policy = SigningPolicy {
+        allowed_transaction_types: Some(vec![TransactionKind::Basic]),
+        ..Default::default()
+    };
+
+    assert_eq!(policy.check(&basic_transaction(address(2), 1)), Ok(()));
+
+    let transaction = Transaction::new_extended(
+        address(1),
+        AccountType::Basic,
+        vec![],
+        address(3),
+        AccountType::Staking,
+        vec![],
+        Coin::from_u64_unchecked(1),
+        Coin::ZERO,
+        1,
+        NetworkId::UnitAlbatross,
+    );
+    assert_eq!(TransactionKind::of(&transaction), TransactionKind::Staking);
+    assert_eq!(
+        policy.check(&transaction),
+        Err(PolicyViolation::TransactionKindNotAllowed(
+            TransactionKind::Staking
+        ))
+    );
+}